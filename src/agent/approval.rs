@@ -0,0 +1,146 @@
+//! Approval of tool calls before they run.
+//!
+//! Tools whose [`Tool::requires_approval`](crate::agent::tools::Tool::requires_approval)
+//! returns true are only executed by
+//! [`execute_with_timeout`](crate::agent::tools::execute_with_timeout) once the
+//! [`ApprovalHandler`] of the call's [`ToolContext`](crate::agent::tools::ToolContext)
+//! has approved it; otherwise the call fails with `ToolError::Denied`. The chat
+//! sets a [`PermissionPrompt`], which queues the call in the
+//! [`PermissionManager`] shown by the permission dialog.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::agent::permissions::{
+    PermissionDecision, PermissionLevel, PermissionManager, PermissionRequest, PermissionResult,
+};
+
+/// How long the permission manager waits for the user before giving up
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Outcome of an approval request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalDecision {
+    Approved,
+    Denied,
+    /// Nobody answered in time; the call is not executed
+    TimedOut,
+}
+
+impl ApprovalDecision {
+    pub fn is_approved(self) -> bool {
+        self == ApprovalDecision::Approved
+    }
+}
+
+/// Decides whether a tool call that requires approval may run.
+#[async_trait]
+pub trait ApprovalHandler: Send + Sync {
+    /// Ask for approval of `tool_name` called with `params`. `preview` is a
+    /// short human-readable summary of what the call is about to do.
+    async fn request_approval(
+        &self,
+        tool_name: &str,
+        params: &Value,
+        preview: &str,
+    ) -> ApprovalDecision;
+}
+
+/// Approves every call (headless runs, tests, "auto-approve all tools").
+pub struct AutoApprove;
+
+#[async_trait]
+impl ApprovalHandler for AutoApprove {
+    async fn request_approval(
+        &self,
+        _tool_name: &str,
+        _params: &Value,
+        _preview: &str,
+    ) -> ApprovalDecision {
+        ApprovalDecision::Approved
+    }
+}
+
+/// Asks the user about a call to a tool of permission `level`
+/// (`PermissionLevel::for_tool`).
+///
+/// Queues the call as a pending [`PermissionRequest`] (shown by the UI) and
+/// waits for the user's decision, unless `level` is already allowed by
+/// default. The request is withdrawn if the call is stopped or nobody
+/// answers in time.
+pub struct PermissionPrompt {
+    pub manager: Arc<PermissionManager>,
+    pub level: PermissionLevel,
+}
+
+#[async_trait]
+impl ApprovalHandler for PermissionPrompt {
+    async fn request_approval(
+        &self,
+        tool_name: &str,
+        params: &Value,
+        preview: &str,
+    ) -> ApprovalDecision {
+        let request = PermissionRequest {
+            id: Uuid::new_v4(),
+            tool_name: tool_name.to_string(),
+            operation: "execute".to_string(),
+            target: preview.to_string(),
+            level: self.level,
+            params: params.clone(),
+            timestamp: Utc::now(),
+        };
+        let id = request.id;
+
+        match self.manager.request_permission(request).await {
+            PermissionResult::Approved => ApprovalDecision::Approved,
+            PermissionResult::Denied => ApprovalDecision::Denied,
+            PermissionResult::Pending => {
+                let _pending = Withdraw {
+                    manager: &self.manager,
+                    id,
+                };
+                match self.manager.wait_for_decision(id, APPROVAL_TIMEOUT).await {
+                    Some(PermissionDecision::Approved) => ApprovalDecision::Approved,
+                    Some(PermissionDecision::Denied) => ApprovalDecision::Denied,
+                    None => ApprovalDecision::TimedOut,
+                }
+            }
+        }
+    }
+}
+
+/// Withdraws a still pending request when dropped (the waiting call was
+/// cancelled or gave up)
+struct Withdraw<'a> {
+    manager: &'a PermissionManager,
+    id: Uuid,
+}
+
+impl Drop for Withdraw<'_> {
+    fn drop(&mut self) {
+        self.manager.withdraw(self.id);
+    }
+}
+
+/// One-line summary of a tool call for the approval prompt: its main target
+/// (path, command, url...) or, failing that, its compact parameters.
+pub fn approval_preview(tool_name: &str, params: &Value) -> String {
+    let target = ["path", "command", "url", "source", "destination"]
+        .iter()
+        .find_map(|key| params.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| params.to_string());
+    let target = if target.len() > 200 {
+        format!("{}...", crate::truncate_str(&target, 200))
+    } else {
+        target
+    };
+    format!("{}: {}", tool_name, target)
+}
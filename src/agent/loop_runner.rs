@@ -13,9 +13,15 @@ use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use crate::agent::tools::{ToolRegistry, ToolResult, ToolError};
+use crate::agent::approval::{ApprovalHandler, AutoApprove};
+use crate::agent::tools::{
+    execute_with_timeout, ToolContext, ToolError, ToolRegistry, ToolResult,
+    DEFAULT_MAX_OUTPUT_BYTES,
+};
+use crate::storage::audit::ToolAuditLog;
 use crate::agent::planning::{TaskPlan, TaskStatus, PlanManager};
 use crate::agent::runner::{ToolCall, extract_tool_call};
 
@@ -38,6 +44,10 @@ pub struct AgentLoopConfig {
     pub enable_retry: bool,
     /// Maximum retries per tool call
     pub max_retries: usize,
+    /// Time limit for a tool call (seconds), unless the tool sets its own
+    pub tool_timeout_secs: u64,
+    /// Budget for a tool result (bytes of JSON), unless the tool sets its own
+    pub max_tool_output_bytes: usize,
 }
 
 impl Default for AgentLoopConfig {
@@ -51,6 +61,8 @@ impl Default for AgentLoopConfig {
             min_iteration_delay_ms: 100,
             enable_retry: true,
             max_retries: 2,
+            tool_timeout_secs: 120,
+            max_tool_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
         }
     }
 }
@@ -465,6 +477,9 @@ pub struct ToolHistoryEntry {
     pub params: Value,
     pub result: Option<ToolResult>,
     pub error: Option<String>,
+    /// `ToolError::code()` of the failure, to branch on without parsing `error`
+    #[serde(default)]
+    pub error_code: Option<String>,
     pub timestamp: u64,
     pub duration_ms: u64,
 }
@@ -474,16 +489,41 @@ pub struct AgentLoop {
     pub config: AgentLoopConfig,
     pub tool_registry: Arc<ToolRegistry>,
     pub plan_manager: PlanManager,
+    /// Passed to tool calls (approval, default timeout, and the token
+    /// cancelled to abort the call in flight from the stop button)
+    pub tool_context: ToolContext,
 }
 
 impl AgentLoop {
     pub fn new(config: AgentLoopConfig, tool_registry: Arc<ToolRegistry>) -> Self {
+        let tool_context = ToolContext::default()
+            .with_timeout(Duration::from_secs(config.tool_timeout_secs))
+            .with_max_output_bytes(config.max_tool_output_bytes);
         Self {
             config,
             tool_registry,
             plan_manager: PlanManager::new(),
+            tool_context,
         }
     }
+
+    /// Abort tool calls when `cancel` is triggered
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.tool_context.cancel = cancel;
+        self
+    }
+
+    /// Use `handler` to approve tools that require approval
+    pub fn with_approval_handler(mut self, handler: Arc<dyn ApprovalHandler>) -> Self {
+        self.tool_context.approval = Some(handler);
+        self
+    }
+
+    /// Record tool calls in `audit`, when there is one
+    pub fn with_audit_log(mut self, audit: Option<Arc<ToolAuditLog>>) -> Self {
+        self.tool_context.audit = audit;
+        self
+    }
     
     /// Analyze LLM response and determine next action
     pub fn analyze_response(&self, response: &str, ctx: &AgentContext) -> IterationResult {
@@ -572,21 +612,34 @@ impl AgentLoop {
         ctx: &mut AgentContext,
         event_tx: &mpsc::Sender<AgentEvent>,
     ) -> Result<ToolResult, ToolError> {
-        let tool = self.tool_registry.get(&tool_call.tool)
-            .ok_or_else(|| ToolError::NotFound(tool_call.tool.clone()))?;
+        let tool = self.tool_registry.get_enabled(&tool_call.tool)?;
+        // Approval is asked for by the first attempt; retries run the call
+        // the user already approved
+        let retry_context = self
+            .tool_context
+            .clone()
+            .with_approval(Arc::new(AutoApprove));
         
         let mut retry_count = 0;
         let max_retries = if self.config.enable_retry { self.config.max_retries } else { 0 };
         
         loop {
             let start = Instant::now();
-            
-            let _ = event_tx.send(AgentEvent::ToolCallStarted {
-                tool: tool_call.tool.clone(),
-                params: tool_call.params.clone(),
-            }).await;
-            
-            match tool.execute(tool_call.params.clone()).await {
+
+            let _ = event_tx
+                .send(AgentEvent::ToolCallStarted {
+                    tool: tool_call.tool.clone(),
+                    params: tool_call.params.clone(),
+                })
+                .await;
+
+            let tool_context = if retry_count == 0 {
+                &self.tool_context
+            } else {
+                &retry_context
+            };
+            match execute_with_timeout(tool.as_ref(), tool_call.params.clone(), tool_context).await
+            {
                 Ok(result) => {
                     let duration_ms = start.elapsed().as_millis() as u64;
                     
@@ -596,6 +649,7 @@ impl AgentLoop {
                         params: tool_call.params.clone(),
                         result: Some(result.clone()),
                         error: None,
+                        error_code: None,
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .map(|d| d.as_secs())
@@ -610,6 +664,8 @@ impl AgentLoop {
                     
                     return Ok(result);
                 }
+                // Stopped by the user: no retry, and nothing to record
+                Err(ToolError::Cancelled) => return Err(ToolError::Cancelled),
                 Err(e) => {
                     retry_count += 1;
                     
@@ -619,13 +675,15 @@ impl AgentLoop {
                         retry_count,
                     }).await;
                     
-                    if retry_count > max_retries {
+                    // A missing file or a stale hash fails the same way again
+                    if retry_count > max_retries || !e.is_retryable() {
                         // Record failure in history
                         ctx.tool_history.push(ToolHistoryEntry {
                             tool_name: tool_call.tool.clone(),
                             params: tool_call.params.clone(),
                             result: None,
                             error: Some(e.to_string()),
+                            error_code: Some(e.code().to_string()),
                             timestamp: std::time::SystemTime::now()
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .map(|d| d.as_secs())
@@ -741,6 +799,228 @@ fn is_final_response(response: &str, ctx: &AgentContext) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::approval::ApprovalDecision;
+    use crate::agent::tools::Tool;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mutating tool that counts its executions
+    struct RecordingTool {
+        runs: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Tool for RecordingTool {
+        fn name(&self) -> &str {
+            "mutate"
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn requires_approval(&self) -> bool {
+            true
+        }
+
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _params: Value) -> Result<ToolResult, ToolError> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolResult {
+                success: true,
+                data: Value::Null,
+                message: "done".into(),
+            })
+        }
+    }
+
+    /// Answers every request with `decision` and remembers the previews
+    struct FixedApproval {
+        decision: ApprovalDecision,
+        previews: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ApprovalHandler for FixedApproval {
+        async fn request_approval(
+            &self,
+            _tool_name: &str,
+            _params: &Value,
+            preview: &str,
+        ) -> ApprovalDecision {
+            self.previews.lock().unwrap().push(preview.to_string());
+            self.decision
+        }
+    }
+
+    /// Read-only tool that takes far longer than any test timeout
+    struct SlowTool {
+        own_timeout: Option<Duration>,
+    }
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            self.own_timeout
+        }
+
+        async fn execute(&self, _params: Value) -> Result<ToolResult, ToolError> {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(ToolResult {
+                success: true,
+                data: Value::Null,
+                message: "too late".into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_tools_time_out() {
+        // The executor's default applies when the tool has no timeout of its own
+        let slow = SlowTool { own_timeout: None };
+        let start = Instant::now();
+        let err = execute_with_timeout(
+            &slow,
+            Value::Null,
+            &ToolContext::default().with_timeout(Duration::from_millis(50)),
+        )
+            .await
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        let ToolError::Timeout { elapsed_ms } = err else {
+            panic!("expected a timeout, got {}", err);
+        };
+        assert!((50..5000).contains(&elapsed_ms), "{}", elapsed_ms);
+
+        // A tool's own timeout wins over the loop's tool_timeout_secs
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register_sync(Arc::new(SlowTool {
+            own_timeout: Some(Duration::from_millis(20)),
+        }));
+        let config = AgentLoopConfig {
+            enable_retry: false,
+            tool_timeout_secs: 600,
+            ..Default::default()
+        };
+        let runner = AgentLoop::new(config, registry);
+        let call = ToolCall {
+            tool: "slow".into(),
+            params: serde_json::json!({}),
+        };
+        let (tx, _rx) = mpsc::channel(16);
+        let mut ctx = AgentContext::new();
+        let start = Instant::now();
+        let err = runner
+            .execute_tool_with_retry(&call, &mut ctx, &tx)
+            .await
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(
+            matches!(err, ToolError::Timeout { elapsed_ms } if (20..5000).contains(&elapsed_ms)),
+            "{}",
+            err
+        );
+        assert_eq!(ctx.tool_history.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_aborts_tool_call() {
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register_sync(Arc::new(SlowTool { own_timeout: None }));
+        let cancel = CancellationToken::new();
+        let runner =
+            AgentLoop::new(AgentLoopConfig::default(), registry).with_cancellation(cancel.clone());
+        let call = ToolCall {
+            tool: "slow".into(),
+            params: serde_json::json!({}),
+        };
+        let (tx, _rx) = mpsc::channel(16);
+        let mut ctx = AgentContext::new();
+
+        let stopper = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel.cancel();
+        });
+        let start = Instant::now();
+        let err = runner
+            .execute_tool_with_retry(&call, &mut ctx, &tx)
+            .await
+            .unwrap_err();
+        stopper.await.unwrap();
+
+        assert!(matches!(err, ToolError::Cancelled));
+        // Neither retried nor recorded
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(ctx.tool_history.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tools_requiring_approval_are_gated() {
+        let tool = Arc::new(RecordingTool {
+            runs: AtomicUsize::new(0),
+        });
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register_sync(tool.clone());
+        let call = ToolCall {
+            tool: "mutate".into(),
+            params: serde_json::json!({ "path": "notes.txt" }),
+        };
+        let config = AgentLoopConfig {
+            enable_retry: false,
+            ..Default::default()
+        };
+        let (tx, _rx) = mpsc::channel(16);
+        let mut ctx = AgentContext::new();
+
+        // No handler: refused without running
+        let runner = AgentLoop::new(config.clone(), registry.clone());
+        let err = runner
+            .execute_tool_with_retry(&call, &mut ctx, &tx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Denied(_)));
+
+        let denied = Arc::new(FixedApproval {
+            decision: ApprovalDecision::Denied,
+            previews: Default::default(),
+        });
+        let runner =
+            AgentLoop::new(config.clone(), registry.clone()).with_approval_handler(denied.clone());
+        let err = runner
+            .execute_tool_with_retry(&call, &mut ctx, &tx)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Denied: mutate was not approved (Denied)");
+        assert_eq!(
+            *denied.previews.lock().unwrap(),
+            vec!["mutate: notes.txt".to_string()]
+        );
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 0);
+
+        let runner = AgentLoop::new(config, registry)
+            .with_approval_handler(Arc::new(crate::agent::approval::AutoApprove));
+        let result = runner
+            .execute_tool_with_retry(&call, &mut ctx, &tx)
+            .await
+            .unwrap();
+        assert_eq!(result.message, "done");
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 1);
+    }
     
     #[test]
     fn test_agent_context_new() {
@@ -775,6 +1055,7 @@ mod tests {
                 params: serde_json::json!({"query": "test"}),
                 result: None,
                 error: None,
+                error_code: None,
                 timestamp: 0,
                 duration_ms: 100,
             });
@@ -7,6 +7,7 @@
 //! - Dynamic prompts with context injection
 //! - Multiple specialized tools (web search, code search, file operations, etc.)
 
+pub mod approval;
 pub mod permissions;
 pub mod tools;
 pub mod skills;
@@ -16,14 +17,19 @@ pub mod planning;
 pub mod prompts;
 pub mod mcp_config;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use skills::{SkillRegistry, loader::SkillLoader};
+use crate::storage::audit::ToolAuditLog;
 
 pub use permissions::{
     PermissionLevel, PermissionManager, PermissionRequest, PermissionResult,
     PermissionPolicy, PermissionSignals, PermissionDecision, PermissionNotification,
 };
-pub use tools::{Tool, ToolRegistry, ToolResult, ToolError, ToolInfo};
+pub use approval::{ApprovalDecision, ApprovalHandler, AutoApprove, PermissionPrompt};
+pub use tools::{Tool, ToolCategory, ToolContext, ToolRegistry, ToolResult, ToolError, ToolInfo};
+pub use tools::hashline::{HashAlgorithm, HashlineConfig};
+pub use tools::messages::{set_locale, Locale};
 pub use tools::exa::{ExaSearchTool, ExaSearchConfig, create_exa_tools};
 pub use tools::mcp_client::{McpServerConfig, McpTransport, McpServerManager};
 pub use tools::mcp_presets::{McpPreset, McpCategory, get_all_presets};
@@ -51,6 +57,9 @@ pub struct AgentConfig {
     pub enable_file_write: bool,
     /// Whether to enable bash/shell execution (full access)
     pub enable_bash: bool,
+    /// Directory `shell_exec`, `directory_list`, `git_status` and `git_diff`
+    /// work in and may not leave; the process's working directory when unset
+    pub workspace_dir: Option<PathBuf>,
     /// Whether to enable git operations
     pub enable_git: bool,
     /// Whether to enable web fetch/download
@@ -61,12 +70,18 @@ pub struct AgentConfig {
     pub enable_system_tools: bool,
     /// Maximum tool execution time in seconds
     pub tool_timeout_secs: u64,
+    /// Hashline hash settings, shared by file_read and file_edit
+    pub hashline: HashlineConfig,
+    /// Whether file_edit saves a `.localm.bak` copy unless told otherwise
+    pub backup_before_edit: bool,
     /// Agent loop configuration
     pub loop_config: AgentLoopConfig,
     /// MCP server configurations
     pub mcp_servers: Vec<McpServerConfig>,
     /// List of disabled MCP server IDs
     pub disabled_mcp_servers: Vec<String>,
+    /// Tools that stay registered but may not run (`ToolError::Disabled`)
+    pub disabled_tools: Vec<String>,
 }
 
 impl Default for AgentConfig {
@@ -80,14 +95,18 @@ impl Default for AgentConfig {
             enable_commands: false,
             enable_file_write: true,
             enable_bash: true,
+            workspace_dir: None,
             enable_git: true,
             enable_web_fetch: true,
             enable_dev_tools: true,
             enable_system_tools: true,
             tool_timeout_secs: 120,
+            hashline: HashlineConfig::default(),
+            backup_before_edit: false,
             loop_config: AgentLoopConfig::default(),
             mcp_servers: Vec::new(),
             disabled_mcp_servers: Vec::new(),
+            disabled_tools: Vec::new(),
         }
     }
 }
@@ -99,13 +118,17 @@ pub struct Agent {
     pub permission_manager: Arc<PermissionManager>,
     pub plan_manager: PlanManager,
     pub skill_registry: Arc<SkillRegistry>,
+    /// Where tool calls are recorded; `None` when the data directory is unavailable
+    pub audit_log: Option<Arc<ToolAuditLog>>,
 }
 
 impl Agent {
     pub fn new(config: AgentConfig) -> Self {
         let tool_registry = Arc::new(ToolRegistry::new());
+        tool_registry.set_disabled(&config.disabled_tools);
         let permission_manager = Arc::new(PermissionManager::new(config.default_permission));
         let skill_registry = Arc::new(SkillRegistry::new());
+        let audit_log = ToolAuditLog::in_data_dir().ok().map(Arc::new);
         
         Self {
             config,
@@ -113,9 +136,17 @@ impl Agent {
             permission_manager,
             plan_manager: PlanManager::new(),
             skill_registry,
+            audit_log,
         }
     }
     
+    /// Directory the workspace-bound tools work in
+    fn workspace_dir(&self) -> PathBuf {
+        self.config.workspace_dir.clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+    
     /// Initialize all tools based on configuration
     pub async fn initialize_tools(&self) -> Result<(), Box<dyn std::error::Error>> {
         use tools::builtins;
@@ -124,6 +155,7 @@ impl Agent {
         use tools::git;
         use tools::dev;
         use tools::system;
+        use tools::web;
         use tools::skill_create;
         use tools::skill_invoke;
         use tools::skill_list;
@@ -163,13 +195,15 @@ impl Agent {
         // File system tools (read-only)
         // ============================================================
         if self.config.enable_filesystem {
-            self.tool_registry.register(Arc::new(builtins::FileReadTool)).await;
+            self.tool_registry.register(Arc::new(builtins::FileReadTool::new(self.config.hashline))).await;
             self.tool_registry.register(Arc::new(builtins::FileListTool)).await;
             self.tool_registry.register(Arc::new(builtins::GrepTool)).await;
             self.tool_registry.register(Arc::new(builtins::GlobTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileInfoTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileSearchContentTool)).await;
-            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search)");
+            self.tool_registry.register(Arc::new(filesystem::DirectoryTreeTool::new(self.workspace_dir()))).await;
+            self.tool_registry.register(Arc::new(filesystem::DirectoryListTool::new(self.workspace_dir()))).await;
+            tracing::info!("Filesystem read tools registered (file_read, file_list, grep, glob, file_info, file_search, directory_tree, directory_list)");
         }
         
         // ============================================================
@@ -177,13 +211,18 @@ impl Agent {
         // ============================================================
         if self.config.enable_file_write {
             self.tool_registry.register(Arc::new(builtins::FileWriteTool)).await;
-            self.tool_registry.register(Arc::new(filesystem::FileEditTool)).await;
+            self.tool_registry.register(Arc::new(
+                filesystem::FileEditTool::new(self.config.hashline)
+                    .with_backup_by_default(self.config.backup_before_edit),
+            )).await;
             self.tool_registry.register(Arc::new(filesystem::FileCreateTool)).await;
+            self.tool_registry.register(Arc::new(filesystem::FileAppendTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileDeleteTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileMoveTool)).await;
             self.tool_registry.register(Arc::new(filesystem::FileCopyTool)).await;
+            self.tool_registry.register(Arc::new(filesystem::FilePermissionsTool)).await;
             self.tool_registry.register(Arc::new(filesystem::DirectoryCreateTool)).await;
-            tracing::info!("Filesystem write tools registered (file_write, file_edit, file_create, file_delete, file_move, file_copy, directory_create)");
+            tracing::info!("Filesystem write tools registered (file_write, file_edit, file_create, file_append, file_delete, file_move, file_copy, file_chmod, directory_create)");
         }
         
         // ============================================================
@@ -192,7 +231,8 @@ impl Agent {
         if self.config.enable_bash {
             self.tool_registry.register(Arc::new(shell::BashTool)).await;
             self.tool_registry.register(Arc::new(shell::BashBackgroundTool)).await;
-            tracing::info!("Shell tools registered (bash, bash_background)");
+            self.tool_registry.register(Arc::new(shell::ShellExecTool::new(self.workspace_dir()))).await;
+            tracing::info!("Shell tools registered (bash, bash_background, shell_exec)");
         }
         
         // Legacy safe command tool
@@ -201,12 +241,20 @@ impl Agent {
             tracing::info!("Legacy command tool registered");
         }
         
+        // ============================================================
+        // Web fetch (network, requires permission)
+        // ============================================================
+        if self.config.enable_web_fetch {
+            self.tool_registry.register(Arc::new(web::WebFetchTool)).await;
+            tracing::info!("Web fetch tool registered (web_fetch)");
+        }
+        
         // ============================================================
         // Git tools
         // ============================================================
         if self.config.enable_git {
-            self.tool_registry.register(Arc::new(git::GitStatusTool)).await;
-            self.tool_registry.register(Arc::new(git::GitDiffTool)).await;
+            self.tool_registry.register(Arc::new(git::GitStatusTool::new(self.workspace_dir()))).await;
+            self.tool_registry.register(Arc::new(git::GitDiffTool::new(self.workspace_dir()))).await;
             self.tool_registry.register(Arc::new(git::GitLogTool)).await;
             self.tool_registry.register(Arc::new(git::GitCommitTool)).await;
             self.tool_registry.register(Arc::new(git::GitBranchTool)).await;
@@ -312,14 +360,6 @@ impl Agent {
         Ok(())
     }
     
-    /// Create an agent loop runner
-    pub fn create_loop(&self) -> AgentLoop {
-        AgentLoop::new(
-            self.config.loop_config.clone(),
-            self.tool_registry.clone(),
-        )
-    }
-    
     /// Get list of all available tools
     pub fn list_tools(&self) -> Vec<ToolInfo> {
         self.tool_registry.list_tools()
@@ -335,47 +375,6 @@ impl Agent {
     }
 }
 
-/// Quick helper to determine permission level for a tool
-pub fn get_tool_permission(tool_name: &str) -> PermissionLevel {
-    match tool_name {
-        // Read-only tools (no side effects)
-        "file_read" | "file_list" | "grep" | "glob" | "think" | "todo_write"
-        | "file_info" | "file_search" | "diff" | "wc" | "tree"
-        | "process_list" | "environment" | "system_info" | "which"
-        | "git_status" | "git_diff" | "git_log" | "git_branch"
-        | "pdf_read"
-        | "skill_list" | "skill_invoke" 
-        | "mcp_list_servers" => {
-            PermissionLevel::ReadOnly
-        }
-        // Network tools (external requests)
-        "web_search" | "code_search" | "company_research" 
-        | "deep_research_start" | "deep_research_check" | "web_crawl"
-        | "web_fetch" | "web_download" | "ai_consult" => {
-            PermissionLevel::Network
-        }
-        // Write tools (file modifications)
-        "file_write" | "file_edit" | "file_create" | "file_delete" 
-        | "file_move" | "file_copy" | "directory_create"
-        | "find_replace" | "patch"
-        | "pdf_create" | "pdf_add_page" | "pdf_merge"
-        | "skill_create" 
-        | "mcp_add_server" | "mcp_remove_server" => {
-            PermissionLevel::WriteFile
-        }
-        // Safe command execution
-        "command" => PermissionLevel::ExecuteSafe,
-        // Unsafe execution (full shell, git writes)
-        "bash" | "bash_background" | "git_commit" | "git_stash" => {
-            PermissionLevel::ExecuteUnsafe
-        }
-        // MCP tools (from external servers)
-        name if name.starts_with("mcp_") => PermissionLevel::Network,
-        // Default to read-only
-        _ => PermissionLevel::ReadOnly,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -396,29 +395,26 @@ mod tests {
     
     #[test]
     fn test_tool_permissions() {
+        use tools::{builtins, filesystem, git, shell, web};
+        use tools::hashline::HashlineConfig;
+
+        let workspace = std::env::temp_dir();
+        let level = |tool: &dyn Tool| PermissionLevel::for_tool(tool);
         // Read-only
-        assert_eq!(get_tool_permission("file_read"), PermissionLevel::ReadOnly);
-        assert_eq!(get_tool_permission("grep"), PermissionLevel::ReadOnly);
-        assert_eq!(get_tool_permission("git_status"), PermissionLevel::ReadOnly);
-        assert_eq!(get_tool_permission("tree"), PermissionLevel::ReadOnly);
-        assert_eq!(get_tool_permission("diff"), PermissionLevel::ReadOnly);
+        assert_eq!(level(&builtins::FileReadTool::new(HashlineConfig::default())), PermissionLevel::ReadOnly);
+        assert_eq!(level(&builtins::GrepTool), PermissionLevel::ReadOnly);
+        assert_eq!(level(&git::GitStatusTool::new(&workspace)), PermissionLevel::ReadOnly);
         // Network
-        assert_eq!(get_tool_permission("web_search"), PermissionLevel::Network);
-        assert_eq!(get_tool_permission("web_fetch"), PermissionLevel::Network);
+        assert_eq!(level(&web::WebFetchTool), PermissionLevel::Network);
+        assert_eq!(level(&web::WebDownloadTool), PermissionLevel::Network);
         // Write
-        assert_eq!(get_tool_permission("file_write"), PermissionLevel::WriteFile);
-        assert_eq!(get_tool_permission("file_edit"), PermissionLevel::WriteFile);
-        assert_eq!(get_tool_permission("file_create"), PermissionLevel::WriteFile);
-        assert_eq!(get_tool_permission("find_replace"), PermissionLevel::WriteFile);
+        assert_eq!(level(&builtins::FileWriteTool), PermissionLevel::WriteFile);
+        assert_eq!(level(&filesystem::FileDeleteTool), PermissionLevel::WriteFile);
+        assert_eq!(level(&git::GitBranchTool), PermissionLevel::WriteFile);
         // Execute
-        assert_eq!(get_tool_permission("command"), PermissionLevel::ExecuteSafe);
-        assert_eq!(get_tool_permission("bash"), PermissionLevel::ExecuteUnsafe);
-        assert_eq!(get_tool_permission("git_commit"), PermissionLevel::ExecuteUnsafe);
-        // Skill tools
-        assert_eq!(get_tool_permission("skill_invoke"), PermissionLevel::ReadOnly);
-        assert_eq!(get_tool_permission("skill_list"), PermissionLevel::ReadOnly);
-        // MCP
-        assert_eq!(get_tool_permission("mcp_github_list_repos"), PermissionLevel::Network);
+        assert_eq!(level(&builtins::CommandTool), PermissionLevel::ExecuteUnsafe);
+        assert_eq!(level(&shell::ShellExecTool::new(&workspace)), PermissionLevel::ExecuteUnsafe);
+        assert_eq!(level(&git::GitCommitTool), PermissionLevel::WriteFile);
     }
     
     #[tokio::test]
@@ -445,14 +441,18 @@ mod tests {
         assert!(names.contains(&"grep"));
         assert!(names.contains(&"glob"));
         assert!(names.contains(&"file_info"));
+        assert!(names.contains(&"directory_list"));
         // Write tools
         assert!(names.contains(&"file_edit"));
         assert!(names.contains(&"file_create"));
         assert!(names.contains(&"file_delete"));
         // Shell tools
         assert!(names.contains(&"bash"));
+        assert!(names.contains(&"shell_exec"));
         // Git tools
         assert!(names.contains(&"git_status"));
+        // Web tools
+        assert!(names.contains(&"web_fetch"));
         assert!(names.contains(&"git_diff"));
         assert!(names.contains(&"git_log"));
         // Dev tools
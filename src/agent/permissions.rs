@@ -8,13 +8,15 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Utc};
-use dioxus::prelude::{Signal, Writable};
+use dioxus::prelude::{Signal, SyncSignal, Writable};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 use uuid::Uuid;
 use tokio::time::{sleep, Duration, Instant};
 
+use crate::agent::tools::{Tool, ToolCategory};
+
 /// Permission level for agent operations.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum PermissionLevel {
@@ -46,6 +48,18 @@ impl std::fmt::Display for PermissionLevel {
 }
 
 impl PermissionLevel {
+    /// Level of a call to `tool`, from its category. A tool that requires
+    /// approval is never `ReadOnly`, which is allowed by default.
+    pub fn for_tool(tool: &dyn Tool) -> Self {
+        match tool.category() {
+            ToolCategory::ReadOnly if !tool.requires_approval() => PermissionLevel::ReadOnly,
+            ToolCategory::ReadOnly => PermissionLevel::ReadWrite,
+            ToolCategory::Mutating => PermissionLevel::WriteFile,
+            ToolCategory::Network => PermissionLevel::Network,
+            ToolCategory::Shell => PermissionLevel::ExecuteUnsafe,
+        }
+    }
+
     fn rank(self) -> u8 {
         match self {
             PermissionLevel::ReadOnly => 0,
@@ -137,10 +151,13 @@ pub enum PermissionError {
 }
 
 /// Dioxus signals for UI notification.
+///
+/// Thread-safe signals, so the manager can be shared with tool calls through
+/// a `PermissionPrompt`.
 #[derive(Clone)]
 pub struct PermissionSignals {
-    pub pending_requests: Signal<Vec<PermissionRequest>>,
-    pub last_decision: Signal<Option<PermissionNotification>>,
+    pub pending_requests: SyncSignal<Vec<PermissionRequest>>,
+    pub last_decision: SyncSignal<Option<PermissionNotification>>,
 }
 
 /// Permission manager for request tracking and decisions.
@@ -154,8 +171,8 @@ pub struct PermissionManager {
 
 impl PermissionManager {
     pub fn new(default_level: PermissionLevel) -> Self {
-        let pending = Signal::new(Vec::new());
-        let last_decision = Signal::new(None);
+        let pending = Signal::new_maybe_sync(Vec::new());
+        let last_decision = Signal::new_maybe_sync(None);
         Self {
             pending: Arc::new(Mutex::new(Vec::new())),
             approved: Arc::new(Mutex::new(HashSet::new())),
@@ -216,6 +233,16 @@ impl PermissionManager {
         Ok(())
     }
 
+    /// Drops a pending request nobody waits for anymore (the call was
+    /// stopped or timed out), so the dialog stops showing it.
+    pub fn withdraw(&self, request_id: Uuid) -> bool {
+        let removed = self.remove_pending(request_id);
+        if removed {
+            self.sync_pending_signal();
+        }
+        removed
+    }
+
     /// Checks whether a permission level is allowed by default.
     pub fn check_permission(&self, _tool: &str, level: PermissionLevel) -> bool {
         level.rank() <= self.default_level.rank()
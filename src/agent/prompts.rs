@@ -166,13 +166,14 @@ Use this for simple, single-line queries like searches.
 
 When you read a file with `file_read`, each line includes a **hash tag**:
 ```
-   1|a3| fn main() {
-   2|f1|     println!("hello");
-   3|0e| }
+   1|a3f0| fn main() {
+   2|f17c|     println!("hello");
+   3|0e42| }
 ```
 
 **Use hashes for more reliable edits!** Instead of reproducing the exact old_string:
 - Use `line_number` + `hash` + `new_string` in `file_edit`
+- For a whole block, add `end_line` + `hashes` (one per line) to replace it in one call
 - The hash validates the line hasn't changed since you read it
 - This prevents "String not found" errors
 
@@ -183,7 +184,7 @@ When you read a file with `file_read`, each line includes a **hash tag**:
 
 **Example - After (Hashline - MORE RELIABLE):**
 ```json
-{"tool": "file_edit", "params": {"path": "main.rs", "line_number": 1, "hash": "a3", "new_string": "fn main() -> Result<(), Error> {"}}
+{"tool": "file_edit", "params": {"path": "main.rs", "line_number": 1, "hash": "a3f0", "new_string": "fn main() -> Result<(), Error> {"}}
 ```
 
 **Benefits:**
@@ -245,6 +246,9 @@ Before giving your final answer, ask yourself:
     for tool in tools {
         out.push_str(&format!("**{}**\n", tool.name));
         out.push_str(&format!("  Description: {}\n", tool.description));
+        if tool.requires_approval {
+            out.push_str("  Requires user approval before it runs\n");
+        }
 
         // Add schema info
         if let Some(props) = tool.parameters_schema.get("properties") {
@@ -293,6 +297,12 @@ fn get_tool_example(tool_name: &str) -> Option<&'static str> {
         "file_search" => Some(
             r#"{"tool": "file_search", "params": {"query": "TODO", "path": "./src", "file_pattern": "rs"}}"#,
         ),
+        "directory_tree" => {
+            Some(r#"{"tool": "directory_tree", "params": {"path": ".", "max_depth": 2}}"#)
+        }
+        "directory_list" => {
+            Some(r#"{"tool": "directory_list", "params": {"path": "src", "recursive": true}}"#)
+        }
         // File write/edit tools
         "file_write" => Some(
             r#"<use_tool name="file_write">
@@ -312,6 +322,9 @@ Line 3</param>
         "file_create" => Some(
             r#"{"tool": "file_create", "params": {"path": "src/new_file.rs", "content": "//! New module\n"}}"#,
         ),
+        "file_append" => Some(
+            r#"{"tool": "file_append", "params": {"path": "notes.md", "content": "- [ ] Add tests\n"}}"#,
+        ),
         "file_delete" => Some(r#"{"tool": "file_delete", "params": {"path": "temp_file.txt"}}"#),
         "file_move" => Some(
             r#"{"tool": "file_move", "params": {"source": "old.rs", "destination": "new.rs"}}"#,
@@ -319,6 +332,9 @@ Line 3</param>
         "file_copy" => Some(
             r#"{"tool": "file_copy", "params": {"source": "template.rs", "destination": "new_module.rs"}}"#,
         ),
+        "file_chmod" => {
+            Some(r#"{"tool": "file_chmod", "params": {"path": "scripts/build.sh", "mode": "755"}}"#)
+        }
         "directory_create" => {
             Some(r#"{"tool": "directory_create", "params": {"path": "src/new_module"}}"#)
         }
@@ -332,6 +348,9 @@ Line 3</param>
         "bash_background" => {
             Some(r#"{"tool": "bash_background", "params": {"command": "cargo watch -x run"}}"#)
         }
+        "shell_exec" => Some(
+            r#"{"tool": "shell_exec", "params": {"command": "cargo test 2>&1", "timeout_secs": 300}}"#,
+        ),
         // Git tools
         "git_status" => Some(r#"{"tool": "git_status", "params": {}}"#),
         "git_diff" => Some(r#"{"tool": "git_diff", "params": {"staged": false}}"#),
@@ -344,9 +363,9 @@ Line 3</param>
             Some(r#"{"tool": "git_stash", "params": {"action": "save", "message": "WIP"}}"#)
         }
         // Web tools
-        "web_fetch" => {
-            Some(r#"{"tool": "web_fetch", "params": {"url": "https://api.example.com/data"}}"#)
-        }
+        "web_fetch" => Some(
+            r#"{"tool": "web_fetch", "params": {"url": "https://docs.rs/regex", "as_text": true}}"#,
+        ),
         "web_download" => Some(
             r#"{"tool": "web_download", "params": {"url": "https://example.com/file.zip", "path": "downloads/file.zip"}}"#,
         ),
@@ -615,6 +634,7 @@ pub fn build_incremental_summary_prompt(
                 Role::User => "User",
                 Role::Assistant => "Assistant",
                 Role::System => "System",
+                Role::Tool => "Tool",
             };
             format!("{}: {}", role_str, msg.content)
         })
@@ -702,6 +722,7 @@ pub fn build_compression_summary_prompt(messages: &[Message]) -> String {
                 Role::User => "User",
                 Role::Assistant => "Assistant",
                 Role::System => "System",
+                Role::Tool => "Tool",
             };
             format!("{}: {}", role_str, msg.content)
         })
@@ -748,6 +769,7 @@ pub fn build_title_generation_prompt(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::tools::ToolCategory;
     use serde_json::json;
 
     #[test]
@@ -761,10 +783,14 @@ mod tests {
                     "query": {"type": "string", "description": "Search query"}
                 }
             }),
+            category: ToolCategory::Network,
+            requires_approval: false,
+            enabled: true,
         }];
 
         let instructions = build_tool_instructions_advanced(&tools);
         assert!(instructions.contains("web_search"));
         assert!(instructions.contains("Search the web"));
+        assert!(!instructions.contains("Requires user approval"));
     }
 }
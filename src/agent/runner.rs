@@ -34,6 +34,9 @@ Available tools:\n",
         out.push_str(&tool.name);
         out.push_str(": ");
         out.push_str(&tool.description);
+        if tool.requires_approval {
+            out.push_str(" (requires user approval)");
+        }
         out.push_str("\n  params_schema: ");
         out.push_str(&schema);
         out.push('\n');
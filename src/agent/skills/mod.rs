@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
-use crate::agent::tools::{Tool, ToolResult, ToolError};
+use crate::agent::tools::{Tool, ToolCategory, ToolResult, ToolError};
 use tokio::process::Command;
 
 pub mod loader;
@@ -42,6 +42,10 @@ impl Tool for SkillTool {
         &self.skill.description
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Shell
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -1,23 +1,16 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use dashmap::DashMap;
+use std::time::Duration;
+use dashmap::{DashMap, DashSet};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
-/// Compute a short hash (2 chars) for a line of content
-/// This is used for Hashline - see https://github.com/0xZKnw/oh-my-pi
-/// Hashline improves edit success rates by 10-68% for various models
-fn compute_line_hash(line: &str) -> String {
-    // Simple FNV-like hash (not cryptographic, just for content identification)
-    let mut hash: u32 = 2166136261u32;
-    for byte in line.bytes() {
-        hash = hash.wrapping_mul(16777619u32);
-        hash ^= byte as u32;
-    }
-    // Take 2 hex characters (enough for uniqueness in small files)
-    format!("{:02x}", hash & 0xFFF)
-}
+use crate::agent::approval::{approval_preview, ApprovalHandler};
+use crate::storage::audit::{AuditEntry, ToolAuditLog};
 
 /// Tool trait - all tools must implement this
 #[async_trait]
@@ -26,6 +19,317 @@ pub trait Tool: Send + Sync {
     fn description(&self) -> &str;
     fn parameters_schema(&self) -> Value;
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError>;
+
+    /// Run with the runtime's context. Long-running tools override this to
+    /// check `ctx.cancel` between units of work and stop with
+    /// `ToolError::Cancelled`; the default ignores the context.
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        _ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        self.execute(params).await
+    }
+
+    /// Run with the runtime's context, sending partial output to `progress`
+    /// as it comes (a search's matches as they are found) so it can be shown
+    /// before the call is over. The final `ToolResult` still holds all of
+    /// it. The default streams nothing and runs `execute_with_context`.
+    async fn execute_streaming(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+        _progress: ToolProgress,
+    ) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, ctx).await
+    }
+
+    /// What kind of effect a call has (used to group and gate tools)
+    fn category(&self) -> ToolCategory {
+        ToolCategory::ReadOnly
+    }
+
+    /// Whether a call must be approved (see `ToolContext::authorize`) before
+    /// it runs. By default, mutating and shell tools do.
+    fn requires_approval(&self) -> bool {
+        matches!(
+            self.category(),
+            ToolCategory::Mutating | ToolCategory::Shell
+        )
+    }
+
+    /// Why this particular call must be confirmed by the user even where the
+    /// tool is otherwise approved automatically (a destructive shell command,
+    /// say). Shown at the start of the approval prompt.
+    fn confirmation_reason(&self, _params: &Value) -> Option<String> {
+        None
+    }
+
+    /// How long a call may run before the executor gives up on it.
+    /// `None` uses the context's default (`ToolContext::timeout`).
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Largest serialized result the executor hands back for this tool.
+    /// `None` uses the context's budget (`ToolContext::max_output_bytes`).
+    fn max_output_bytes(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Run `tool`, giving up with `ToolError::Timeout` after its own timeout or
+/// else the context's, or as soon as `ctx.cancel` is triggered (the user
+/// pressed stop).
+///
+/// Giving up drops the tool's future, so it stops at its next `.await` and
+/// whatever it had gathered so far is discarded. Work a tool moved off the
+/// async runtime (a blocking directory walk, say) only stops if the tool
+/// checks the token itself, see `Tool::execute_with_context`. Tools that
+/// write files go through a temp file and a rename, so a call cut short
+/// leaves the target as it was.
+///
+/// Each call runs in a `tool_call` tracing span that records its
+/// `duration_ms` and whether it succeeded, and a successful result carries
+/// the duration in `data.metrics.duration_ms`, next to any metrics the tool
+/// reported itself (`files_scanned`, `bytes_read`, ...).
+///
+/// A tool that requires approval first waits for `ctx.approval` (see
+/// `ToolContext::authorize`); that wait counts against cancellation but not
+/// against the timeout, and a refusal fails the call with `ToolError::Denied`.
+///
+/// With `ctx.audit` set, the call is appended to that log once it is over,
+/// whether it succeeded, failed or was cancelled.
+pub async fn execute_with_timeout(
+    tool: &dyn Tool,
+    params: Value,
+    ctx: &ToolContext,
+) -> Result<ToolResult, ToolError> {
+    let span = tracing::info_span!(
+        "tool_call",
+        tool = tool.name(),
+        duration_ms = tracing::field::Empty,
+        success = tracing::field::Empty,
+    );
+    let limit = tool.timeout().unwrap_or(ctx.timeout);
+    let audited_params = ctx.audit.as_ref().map(|_| params.clone());
+    let start = std::time::Instant::now();
+    let outcome = async {
+        schema::validate_params(&tool.parameters_schema(), &params)?;
+        tokio::select! {
+            biased;
+            _ = ctx.cancel.cancelled() => Err(ToolError::Cancelled),
+            outcome = async {
+                ctx.authorize(tool, &params).await?;
+                let started = std::time::Instant::now();
+                match tokio::time::timeout(limit, async {
+                    match &ctx.progress {
+                        Some(progress) => tool.execute_streaming(params, ctx, progress.clone()).await,
+                        None => tool.execute_with_context(params, ctx).await,
+                    }
+                })
+                .await
+                {
+                    Ok(result) => result,
+                    Err(_) => Err(ToolError::Timeout {
+                        elapsed_ms: started.elapsed().as_millis() as u64,
+                    }),
+                }
+            } => outcome,
+        }
+    }
+    .instrument(span.clone())
+    .await;
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    span.record("duration_ms", duration_ms);
+    span.record("success", outcome.as_ref().is_ok_and(|r| r.success));
+    match &outcome {
+        Ok(_) => tracing::debug!(parent: &span, "tool call finished"),
+        Err(e) => tracing::debug!(parent: &span, error = %e, "tool call failed"),
+    }
+    if let (Some(audit), Some(params)) = (&ctx.audit, audited_params) {
+        let entry = AuditEntry {
+            timestamp: chrono::Utc::now(),
+            tool: tool.name().to_string(),
+            params,
+            success: outcome.as_ref().is_ok_and(|r| r.success),
+            duration_ms,
+            message: match &outcome {
+                Ok(result) => result.message.clone(),
+                Err(e) => e.to_string(),
+            },
+        };
+        if let Err(e) = audit.append(&entry) {
+            tracing::warn!("Failed to write the tool audit log: {}", e);
+        }
+    }
+
+    let max_output_bytes = tool.max_output_bytes().unwrap_or(ctx.max_output_bytes);
+    outcome.map(|mut result| {
+        result.record_duration(duration_ms);
+        result.cap_output(max_output_bytes);
+        result
+    })
+}
+
+/// `path` (relative to `workspace`, or absolute) resolved to an existing
+/// location inside `workspace`; `None` is the workspace itself. Symlinks are
+/// resolved first, so one pointing out of the workspace is refused too.
+pub fn resolve_in_workspace(workspace: &Path, path: Option<&str>) -> Result<PathBuf, ToolError> {
+    let root = std::fs::canonicalize(workspace)
+        .map_err(|e| ToolError::io(&e, messages::tr("path.missing", &[&workspace.display()])))?;
+    let Some(path) = path else {
+        return Ok(root);
+    };
+    let resolved = std::fs::canonicalize(root.join(path))
+        .map_err(|e| ToolError::io(&e, messages::tr("path.missing", &[&path])))?;
+    if !resolved.starts_with(&root) {
+        return Err(ToolError::PermissionDenied(messages::tr(
+            "workspace.outside",
+            &[&path, &root.display()],
+        )));
+    }
+    Ok(resolved)
+}
+
+/// How long a call may run when neither the tool nor the settings say
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where a tool sends partial output while it runs, see
+/// `Tool::execute_streaming`
+pub type ToolProgress = tokio::sync::mpsc::UnboundedSender<Value>;
+
+/// Largest serialized `ToolResult::data` handed back by the executor
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// What the runtime provides around a tool call, beyond its parameters
+#[derive(Clone)]
+pub struct ToolContext {
+    /// Asked before running a tool that requires approval.
+    /// Without one, such tools are denied.
+    pub approval: Option<Arc<dyn ApprovalHandler>>,
+    /// How long a call may run unless the tool sets its own `Tool::timeout`
+    pub timeout: Duration,
+    /// Triggered when the user stops the agent; tools stop at the next
+    /// file or directory boundary
+    pub cancel: CancellationToken,
+    /// Results are cut down to this many bytes of JSON (`ToolResult::cap_output`)
+    /// unless the tool sets its own `Tool::max_output_bytes`
+    pub max_output_bytes: usize,
+    /// Every call run through `execute_with_timeout` is recorded here
+    pub audit: Option<Arc<ToolAuditLog>>,
+    /// Set for one call to receive its partial output; calls then go
+    /// through `Tool::execute_streaming`
+    pub progress: Option<ToolProgress>,
+}
+
+impl Default for ToolContext {
+    fn default() -> Self {
+        Self {
+            approval: None,
+            timeout: DEFAULT_TOOL_TIMEOUT,
+            cancel: CancellationToken::new(),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            audit: None,
+            progress: None,
+        }
+    }
+}
+
+impl ToolContext {
+    /// Ask `handler` for approval
+    pub fn with_approval(mut self, handler: Arc<dyn ApprovalHandler>) -> Self {
+        self.approval = Some(handler);
+        self
+    }
+
+    /// Give calls `timeout` unless the tool sets its own
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Cut results down to `max_bytes` of JSON
+    pub fn with_max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = max_bytes;
+        self
+    }
+
+    /// Stop calls when `cancel` is triggered
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Record calls in `audit`, when there is one
+    pub fn with_audit_log(mut self, audit: Option<Arc<ToolAuditLog>>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Send the partial output of streaming tools to `progress`
+    pub fn with_progress(mut self, progress: ToolProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// `Err(ToolError::Cancelled)` once the call has been cancelled
+    pub fn check_cancelled(&self) -> Result<(), ToolError> {
+        if self.cancel.is_cancelled() {
+            Err(ToolError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Wait for approval of `tool` called with `params` when the tool
+    /// requires it, or the call has a `Tool::confirmation_reason`. Fails with
+    /// `ToolError::Denied` when the call was refused, nobody answered in
+    /// time, or there is no one to ask.
+    pub async fn authorize(&self, tool: &dyn Tool, params: &Value) -> Result<(), ToolError> {
+        let reason = tool.confirmation_reason(params);
+        if !tool.requires_approval() && reason.is_none() {
+            return Ok(());
+        }
+        let Some(handler) = &self.approval else {
+            return Err(ToolError::Denied(format!(
+                "{} requires approval and no approval handler is set",
+                tool.name()
+            )));
+        };
+        let preview = match reason {
+            Some(reason) => format!("⚠️ {}. {}", reason, approval_preview(tool.name(), params)),
+            None => approval_preview(tool.name(), params),
+        };
+        let decision = handler
+            .request_approval(tool.name(), params, &preview)
+            .await;
+        if decision.is_approved() {
+            Ok(())
+        } else {
+            Err(ToolError::Denied(format!(
+                "{} was not approved ({:?})",
+                tool.name(),
+                decision
+            )))
+        }
+    }
+}
+
+/// Risk class of a tool
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCategory {
+    /// Only reads (files, system info, the agent's own state)
+    #[default]
+    ReadOnly,
+    /// Creates, modifies or deletes files
+    Mutating,
+    /// Talks to the network
+    Network,
+    /// Runs arbitrary commands
+    Shell,
 }
 
 /// Tool execution result
@@ -36,6 +340,194 @@ pub struct ToolResult {
     pub message: String,
 }
 
+impl ToolResult {
+    /// `data` as an object, wrapping anything else as `{"value": data}`
+    /// (`null` becomes `{}`) so fields can be added to it
+    fn data_object(&mut self) -> &mut serde_json::Map<String, Value> {
+        match self.data.take() {
+            Value::Object(fields) => self.data = Value::Object(fields),
+            Value::Null => self.data = serde_json::json!({}),
+            other => self.data = serde_json::json!({ "value": other }),
+        }
+        match &mut self.data {
+            Value::Object(fields) => fields,
+            _ => unreachable!("data was just made an object"),
+        }
+    }
+
+    /// Add `duration_ms` to `data.metrics`, keeping the tool's own metrics
+    pub fn record_duration(&mut self, duration_ms: u64) {
+        let metrics = self
+            .data_object()
+            .entry("metrics")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Value::Object(metrics) = metrics {
+            metrics.insert("duration_ms".into(), duration_ms.into());
+        }
+    }
+
+    /// Shrink the result until its message and `data` serialize to at most
+    /// `max_bytes` together, so a huge result cannot flood the model's
+    /// context or the UI.
+    ///
+    /// A message over half the budget loses its middle. Then the largest
+    /// array in `data` with more than one item loses items from its end,
+    /// repeatedly, until the data fits; when no array is left to shorten,
+    /// the longest string loses its middle instead. Cut strings keep their
+    /// head and tail around a `...(truncated N bytes)` marker. A result whose
+    /// data had to shrink gets `truncated: true` and `omitted` (the number of
+    /// array items dropped); data that is not an object is first wrapped as
+    /// `{"value": data}`.
+    pub fn cap_output(&mut self, max_bytes: usize) {
+        let message_size = json_size(&Value::String(self.message.clone()));
+        if message_size + json_size(&self.data) <= max_bytes {
+            return;
+        }
+        if message_size > max_bytes / 2 {
+            truncate_middle(&mut self.message, max_bytes / 2);
+        }
+        let message_size = json_size(&Value::String(self.message.clone()));
+        if message_size + json_size(&self.data) <= max_bytes {
+            return;
+        }
+        // Room for the two flags added at the end
+        let budget = max_bytes.saturating_sub(message_size + 40);
+        let mut omitted = 0usize;
+        loop {
+            let size = json_size(&self.data);
+            if size <= budget {
+                break;
+            }
+            let excess = size - budget;
+
+            if let Some(items) = largest_node(&self.data, true)
+                .and_then(|path| self.data.pointer_mut(&path))
+                .and_then(Value::as_array_mut)
+            {
+                let mut shed = 0;
+                while items.len() > 1 && shed < excess {
+                    if let Some(item) = items.pop() {
+                        // The item and its comma
+                        shed += json_size(&item) + 1;
+                        omitted += 1;
+                    }
+                }
+                continue;
+            }
+
+            let Some(text) = largest_node(&self.data, false)
+                .and_then(|path| self.data.pointer_mut(&path))
+                .and_then(|v| match v {
+                    Value::String(text) => Some(text),
+                    _ => None,
+                })
+            else {
+                break;
+            };
+            let target = json_size(&Value::String(text.clone())).saturating_sub(excess);
+            if truncate_middle(text, target) == 0 {
+                break;
+            }
+        }
+
+        let data = self.data_object();
+        data.insert("truncated".into(), Value::Bool(true));
+        data.insert("omitted".into(), omitted.into());
+    }
+}
+
+/// Cut the middle out of `text` until it serializes to at most `max_bytes`
+/// of JSON, keeping its head and tail around a `...(truncated N bytes)`
+/// marker. Returns the number of bytes cut: 0 when the text already fits or
+/// is too short for the cut to gain anything.
+///
+/// The one head-and-tail cut for tool output: tools that clip their own
+/// output (shell_exec) use it too, within `ToolContext::max_output_bytes`, so
+/// `ToolResult::cap_output` finds nothing left to cut.
+pub fn truncate_middle(text: &mut String, max_bytes: usize) -> usize {
+    // Room for the marker
+    const MARKER_BYTES: usize = 32;
+    if json_size(&Value::String(text.clone())) <= max_bytes || text.len() <= MARKER_BYTES {
+        return 0;
+    }
+    // The kept head and tail, and the quotes, share what the marker leaves
+    let budget = max_bytes.saturating_sub(MARKER_BYTES + 2);
+    let mut used = 0;
+    let mut head = 0;
+    for (i, c) in text.char_indices() {
+        if used + escaped_len(c) > budget / 2 {
+            break;
+        }
+        used += escaped_len(c);
+        head = i + c.len_utf8();
+    }
+    let mut tail = text.len();
+    for (i, c) in text[head..].char_indices().rev() {
+        if used + escaped_len(c) > budget {
+            break;
+        }
+        used += escaped_len(c);
+        tail = head + i;
+    }
+    let dropped = tail - head;
+    *text = format!(
+        "{}...(truncated {} bytes){}",
+        &text[..head],
+        dropped,
+        &text[tail..]
+    );
+    dropped
+}
+
+/// Bytes `c` takes in a JSON string
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// Length of `value` serialized as compact JSON
+fn json_size(value: &Value) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
+
+/// JSON pointer to the largest array with more than one item (`arrays`), or
+/// to the longest string, anywhere in `root`
+fn largest_node(root: &Value, arrays: bool) -> Option<String> {
+    fn visit(value: &Value, path: String, arrays: bool, best: &mut Option<(usize, String)>) {
+        let size = match value {
+            Value::Array(items) if arrays && items.len() > 1 => json_size(value),
+            Value::String(text) if !arrays => text.len(),
+            _ => 0,
+        };
+        if size > 0 && best.as_ref().is_none_or(|(largest, _)| size > *largest) {
+            *best = Some((size, path.clone()));
+        }
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    visit(item, format!("{}/{}", path, i), arrays, best);
+                }
+            }
+            Value::Object(fields) => {
+                for (key, field) in fields {
+                    let key = key.replace('~', "~0").replace('/', "~1");
+                    visit(field, format!("{}/{}", path, key), arrays, best);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut best = None;
+    visit(root, String::new(), arrays, &mut best);
+    best.map(|(_, path)| path)
+}
+
 impl PartialEq for ToolResult {
     fn eq(&self, other: &Self) -> bool {
         self.success == other.success && self.message == other.message
@@ -43,6 +535,9 @@ impl PartialEq for ToolResult {
 }
 
 /// Tool errors
+///
+/// Each variant has a stable `code()`, so retry and repair logic can branch
+/// on the kind of failure instead of parsing the (often French) message.
 #[derive(Debug, Error)]
 pub enum ToolError {
     #[error("Invalid parameters: {0}")]
@@ -51,10 +546,97 @@ pub enum ToolError {
     ExecutionFailed(String),
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
+    /// The call needed approval and did not get it
+    #[error("Denied: {0}")]
+    Denied(String),
+    /// No tool is registered under this name
     #[error("Tool not found: {0}")]
+    UnknownTool(String),
+    /// A file, directory or piece of text the call needs is not there
+    #[error("Not found: {0}")]
     NotFound(String),
-    #[error("Timeout")]
-    Timeout,
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+    /// A hashline no longer matches the line: the file changed since it was read
+    #[error("Hash mismatch: {0}")]
+    HashMismatch(String),
+    /// The text to replace appears `count` times where one match was needed
+    #[error("{message}")]
+    NotUnique { count: usize, message: String },
+    /// Any other I/O failure
+    #[error("{message}")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// The tool is registered but switched off in the settings
+    #[error("Disabled: {0} is turned off in the settings")]
+    Disabled(String),
+    #[error("Timed out after {elapsed_ms} ms")]
+    Timeout { elapsed_ms: u64 },
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+impl ToolError {
+    /// `error`, with `message` for display, as the variant matching its kind
+    pub fn io(error: &std::io::Error, message: String) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound(message),
+            std::io::ErrorKind::AlreadyExists => Self::AlreadyExists(message),
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied(message),
+            kind => Self::Io { kind, message },
+        }
+    }
+
+    /// Stable, machine-readable name of the variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidParameters(_) => "invalid_parameters",
+            Self::ExecutionFailed(_) => "execution_failed",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::Denied(_) => "denied",
+            Self::UnknownTool(_) => "unknown_tool",
+            Self::NotFound(_) => "not_found",
+            Self::AlreadyExists(_) => "already_exists",
+            Self::HashMismatch(_) => "hash_mismatch",
+            Self::NotUnique { .. } => "not_unique",
+            Self::Io { .. } => "io",
+            Self::Disabled(_) => "disabled",
+            Self::Timeout { .. } => "timeout",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether running the same call again may succeed. Missing files, bad
+    /// parameters or a stale hash need a different call, not a retry.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ExecutionFailed(_) | Self::Timeout { .. } => true,
+            Self::Io { kind, .. } => matches!(
+                kind,
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ResourceBusy
+            ),
+            _ => false,
+        }
+    }
+
+    /// `{"code", "message"}`, plus `count` or `kind` when the variant has one
+    pub fn to_json(&self) -> Value {
+        let mut json = serde_json::json!({
+            "code": self.code(),
+            "message": self.to_string(),
+        });
+        match self {
+            Self::NotUnique { count, .. } => json["count"] = (*count).into(),
+            Self::Io { kind, .. } => json["kind"] = kind.to_string().into(),
+            _ => {}
+        }
+        json
+    }
 }
 
 /// Tool information for listing
@@ -63,17 +645,32 @@ pub struct ToolInfo {
     pub name: String,
     pub description: String,
     pub parameters_schema: Value,
+    #[serde(default)]
+    pub category: ToolCategory,
+    #[serde(default)]
+    pub requires_approval: bool,
+    /// Switched off tools are kept registered but cannot run
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 /// Tool registry - singleton pattern
 pub struct ToolRegistry {
     tools: DashMap<String, Arc<dyn Tool>>,
+    /// Names of the tools turned off in the settings. A name may be listed
+    /// before its tool is registered (MCP tools connect later).
+    disabled: DashSet<String>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: DashMap::new(),
+            disabled: DashSet::new(),
         }
     }
     
@@ -92,16 +689,108 @@ impl ToolRegistry {
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
         self.tools.get(name).map(|t| t.clone())
     }
+
+    /// The tool registered as `name`, if it may run: `ToolError::UnknownTool`
+    /// or `ToolError::Disabled` otherwise.
+    pub fn get_enabled(&self, name: &str) -> Result<Arc<dyn Tool>, ToolError> {
+        let tool = self
+            .get(name)
+            .ok_or_else(|| ToolError::UnknownTool(name.to_string()))?;
+        if self.disabled.contains(name) {
+            return Err(ToolError::Disabled(name.to_string()));
+        }
+        Ok(tool)
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.contains(name)
+    }
+
+    /// Turn the tool called `name` on or off
+    pub fn set_enabled(&self, name: &str, enabled: bool) {
+        if enabled {
+            self.disabled.remove(name);
+        } else {
+            self.disabled.insert(name.to_string());
+        }
+    }
+
+    /// Turn off exactly the tools in `names` (`AppSettings::disabled_tools`)
+    pub fn set_disabled(&self, names: &[String]) {
+        self.disabled.clear();
+        for name in names {
+            self.disabled.insert(name.clone());
+        }
+    }
+
+    /// Run the tool registered as `name` under `ctx` (approval, timeout and
+    /// cancellation, see `execute_with_timeout`).
+    /// A disabled tool fails before anyone is asked to approve it.
+    pub async fn execute_with_context(
+        &self,
+        name: &str,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let tool = self.get_enabled(name)?;
+        execute_with_timeout(tool.as_ref(), params, ctx).await
+    }
+
+    /// Run the tool registered as `name`.
+    /// No approval, timeout or cancellation here: see `execute_with_context`.
+    pub async fn execute_by_name(
+        &self,
+        name: &str,
+        params: Value,
+    ) -> Result<ToolResult, ToolError> {
+        // Clone the tool out so the map isn't locked while it runs
+        let tool = self.get_enabled(name)?;
+        schema::validate_params(&tool.parameters_schema(), &params)?;
+        tool.execute(params).await
+    }
+
+    /// JSON array of every tool's schema, sorted by name, in the function
+    /// calling format sent to the model:
+    /// `[{"name", "description", "parameters", "category", "requires_approval"}]`
+    pub fn schemas(&self) -> Value {
+        let tools = self.list_tools();
+        Value::Array(
+            tools
+                .into_iter()
+                .map(|tool| {
+                    serde_json::json!({
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters_schema,
+                        "category": tool.category,
+                        "requires_approval": tool.requires_approval,
+                    })
+                })
+                .collect(),
+        )
+    }
     
-    pub fn list_tools(&self) -> Vec<ToolInfo> {
-        self.tools
+    /// Every registered tool, enabled or not, sorted by name
+    pub fn list(&self) -> Vec<ToolInfo> {
+        let mut tools: Vec<ToolInfo> = self
+            .tools
             .iter()
             .map(|entry| ToolInfo {
                 name: entry.name().to_string(),
                 description: entry.description().to_string(),
                 parameters_schema: entry.parameters_schema(),
+                category: entry.category(),
+                requires_approval: entry.requires_approval(),
+                enabled: self.is_enabled(entry.key()),
             })
-            .collect()
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// The tools the model may call: disabled ones are left out
+    pub fn list_tools(&self) -> Vec<ToolInfo> {
+        self.list().into_iter().filter(|t| t.enabled).collect()
     }
     
     pub fn count(&self) -> usize {
@@ -121,6 +810,21 @@ pub mod exa;
 /// File system tools (edit, create, delete, move, info, mkdir, copy, search)
 pub mod filesystem;
 
+/// Hashline line hashes shared by file_read and file_edit
+pub mod hashline;
+
+/// Text file helpers (binary detection) shared by the file tools
+pub mod text;
+
+/// Parameter validation against each tool's schema, before it runs
+pub mod schema;
+
+/// Built-in file templates rendered by file_create
+pub mod templates;
+
+/// Localized tool messages (follows settings.language)
+pub mod messages;
+
 /// Shell execution tools (bash, background)
 pub mod shell;
 
@@ -159,14 +863,25 @@ pub mod mcp_management;
 /// Builtin tools module
 pub mod builtins {
     use super::*;
+    use super::hashline::HashlineConfig;
     use tokio::process::Command;
     use tokio::time::{timeout, Duration};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use glob::glob as glob_match;
     use regex::Regex;
     
     /// File read tool - improved with line numbers and range support
-    pub struct FileReadTool;
+    #[derive(Default)]
+    pub struct FileReadTool {
+        hashline: HashlineConfig,
+    }
+
+    impl FileReadTool {
+        /// Use the same `hashline` config as `FileEditTool` so hashes verify
+        pub fn new(hashline: HashlineConfig) -> Self {
+            Self { hashline }
+        }
+    }
     
     #[async_trait]
     impl Tool for FileReadTool {
@@ -193,6 +908,10 @@ pub mod builtins {
                     "end_line": {
                         "type": "integer",
                         "description": "Optional end line number (1-indexed)"
+                    },
+                    "encoding": {
+                        "type": "string",
+                        "description": "Text encoding (e.g. windows-1252, utf-16le). Detected automatically when omitted."
                     }
                 },
                 "required": ["path"]
@@ -207,9 +926,11 @@ pub mod builtins {
             let start_line = params["start_line"].as_u64().map(|n| n as usize);
             let end_line = params["end_line"].as_u64().map(|n| n as usize);
             
-            match tokio::fs::read_to_string(&path).await {
-                Ok(content) => {
-                    let lines: Vec<&str> = content.lines().collect();
+            let encoding = super::text::encoding_param(&params)?;
+            
+            match super::text::read_text(&path, encoding).await {
+                Ok(file) => {
+                    let lines: Vec<&str> = file.text.lines().collect();
                     let total_lines = lines.len();
                     
                     let (output, range_info) = match (start_line, end_line) {
@@ -219,7 +940,7 @@ pub mod builtins {
                             let selected: Vec<String> = lines[start..end]
                                 .iter()
                                 .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", start + i + 1, compute_line_hash(l), l))
+                                .map(|(i, l)| self.hashline.format_line(start + i + 1, l))
                                 .collect();
                             (selected.join("\n"), format!(" (lignes {}-{})", start + 1, end))
                         }
@@ -228,7 +949,7 @@ pub mod builtins {
                             let selected: Vec<String> = lines[start..]
                                 .iter()
                                 .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", start + i + 1, compute_line_hash(l), l))
+                                .map(|(i, l)| self.hashline.format_line(start + i + 1, l))
                                 .collect();
                             (selected.join("\n"), format!(" (depuis ligne {})", start + 1))
                         }
@@ -237,7 +958,7 @@ pub mod builtins {
                             let numbered: Vec<String> = lines
                                 .iter()
                                 .enumerate()
-                                .map(|(i, l)| format!("{:>4}|{}| {}", i + 1, compute_line_hash(l), l))
+                                .map(|(i, l)| self.hashline.format_line(i + 1, l))
                                 .collect();
                             (numbered.join("\n"), String::new())
                         }
@@ -248,13 +969,14 @@ pub mod builtins {
                         data: serde_json::json!({ 
                             "content": output,
                             "total_lines": total_lines,
-                            "path": path.display().to_string()
+                            "path": path.display().to_string(),
+                            "encoding": file.encoding.name()
                         }),
                         message: format!("Fichier lu: {} ({} lignes){}",
                             path.display(), total_lines, range_info),
                     })
                 }
-                Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur lecture fichier: {}", e))),
+                Err(e) => Err(e),
             }
         }
     }
@@ -269,9 +991,13 @@ pub mod builtins {
         }
         
         fn description(&self) -> &str {
-            "Write content to a file. Creates the file if it doesn't exist. REQUIRES APPROVAL."
+            "Write content to a file. Creates the file if it doesn't exist."
         }
         
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Mutating
+        }
+
         fn parameters_schema(&self) -> Value {
             serde_json::json!({
                 "type": "object",
@@ -306,8 +1032,9 @@ pub mod builtins {
             // Create parent directories if needed
             if let Some(parent) = path.parent() {
                 if !parent.exists() {
-                    tokio::fs::create_dir_all(parent).await
-                        .map_err(|e| ToolError::ExecutionFailed(format!("Erreur création dossier: {}", e)))?;
+                    tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                        ToolError::io(&e, format!("Erreur création dossier: {}", e))
+                    })?;
                 }
             }
             
@@ -318,7 +1045,7 @@ pub mod builtins {
                     .append(true)
                     .open(&path)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Erreur ouverture fichier: {}", e)))?;
+                    .map_err(|e| ToolError::io(&e, format!("Erreur ouverture fichier: {}", e)))?;
                 file.write_all(content.as_bytes()).await
             } else {
                 tokio::fs::write(&path, content).await
@@ -340,7 +1067,7 @@ pub mod builtins {
                             path.display(), bytes, lines),
                     })
                 }
-                Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur écriture: {}", e))),
+                Err(e) => Err(ToolError::io(&e, format!("Erreur écriture: {}", e))),
             }
         }
     }
@@ -433,7 +1160,7 @@ pub mod builtins {
                     message: format!("{} éléments dans {}", files.len(), path.display()),
                 })
             }
-            Err(e) => Err(ToolError::ExecutionFailed(format!("Erreur lecture dossier: {}", e))),
+            Err(e) => Err(ToolError::io(&e, format!("Erreur lecture dossier: {}", e))),
         }
     }
     
@@ -605,22 +1332,22 @@ pub mod builtins {
                         if truncated { " (résultats tronqués)" } else { "" }),
                 })
             } else {
-                Err(ToolError::InvalidParameters("Path does not exist".to_string()))
+                Err(ToolError::NotFound("Path does not exist".to_string()))
             }
         }
     }
     
     async fn search_file(
-        path: &PathBuf,
+        path: &Path,
         regex: &Regex,
         context_lines: usize,
         results: &mut Vec<Value>,
         total_matches: &mut usize,
         max_results: usize,
     ) -> Result<(), ToolError> {
-        let content = match tokio::fs::read_to_string(path).await {
+        let content = match super::text::read_text_file(path).await {
             Ok(c) => c,
-            Err(_) => return Ok(()), // Skip unreadable files
+            Err(_) => return Ok(()), // Skip unreadable and binary files
         };
         
         let lines: Vec<&str> = content.lines().collect();
@@ -657,16 +1384,16 @@ pub mod builtins {
     }
     
     async fn search_file_async(
-        path: &PathBuf,
+        path: &Path,
         regex: &Regex,
         context_lines: usize,
         results: &std::sync::Arc<tokio::sync::Mutex<Vec<Value>>>,
         total_matches: &std::sync::Arc<std::sync::atomic::AtomicUsize>,
         max_results: usize,
     ) -> Result<(), ToolError> {
-        let content = match tokio::fs::read_to_string(path).await {
+        let content = match super::text::read_text_file(path).await {
             Ok(c) => c,
-            Err(_) => return Ok(()), // Skip unreadable files
+            Err(_) => return Ok(()), // Skip unreadable and binary files
         };
         
         let lines: Vec<&str> = content.lines().collect();
@@ -990,7 +1717,11 @@ pub mod builtins {
         }
         
         fn description(&self) -> &str {
-            "Execute a shell command (requires approval). Only safe read-only commands allowed."
+            "Execute a shell command. Only safe read-only commands allowed."
+        }
+
+        fn category(&self) -> ToolCategory {
+            ToolCategory::Shell
         }
         
         fn parameters_schema(&self) -> Value {
@@ -1078,8 +1809,492 @@ pub mod builtins {
                     })
                 }
                 Ok(Err(e)) => Err(ToolError::ExecutionFailed(format!("Erreur exécution: {}", e))),
-                Err(_) => Err(ToolError::Timeout),
+                Err(_) => Err(ToolError::Timeout {
+                    elapsed_ms: timeout_secs * 1000,
+                }),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_registry_executes_by_name_and_exports_schemas() {
+        let registry = ToolRegistry::new();
+        registry.register_sync(Arc::new(builtins::ThinkTool));
+        registry.register_sync(Arc::new(filesystem::FileInfoTool));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("a.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+        let result = registry
+            .execute_by_name("file_info", serde_json::json!({ "path": file }))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.data["line_count"], 2);
+
+        let missing = registry
+            .execute_by_name("no_such_tool", serde_json::json!({}))
+            .await;
+        assert!(matches!(missing, Err(ToolError::UnknownTool(name)) if name == "no_such_tool"));
+
+        let schemas = registry.schemas();
+        let names: Vec<&str> = schemas
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["file_info", "think"]);
+        assert_eq!(schemas[0]["parameters"]["required"][0], "path");
+        assert_eq!(schemas[0]["category"], "read_only");
+        assert_eq!(schemas[0]["requires_approval"], false);
+    }
+
+    #[test]
+    fn test_giant_results_are_capped() {
+        let matches: Vec<Value> = (0..20_000)
+            .map(|i| {
+                serde_json::json!({
+                    "file": format!("src/module_{}.rs", i / 10),
+                    "line_number": i,
+                    "content": "let needle = compute_something_expensive(input);"
+                })
+            })
+            .collect();
+        let mut result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "matches": matches, "total": 20_000, "query": "needle" }),
+            message: "20000 matches".into(),
+        };
+        result.cap_output(16 * 1024);
+
+        assert!(
+            json_size(&result.data) <= 16 * 1024,
+            "{}",
+            json_size(&result.data)
+        );
+        assert_eq!(result.data["truncated"], true);
+        let kept = result.data["matches"].as_array().unwrap().len();
+        let omitted = result.data["omitted"].as_u64().unwrap() as usize;
+        assert!(kept > 100, "{}", kept);
+        assert_eq!(kept + omitted, 20_000);
+        // The first matches are the ones kept, the rest is untouched
+        assert_eq!(result.data["matches"][0]["line_number"], 0);
+        assert_eq!(result.data["total"], 20_000);
+
+        // A single huge string keeps its head and tail around a marker
+        let mut result = ToolResult {
+            success: true,
+            data: Value::String(format!("start{}end", "é".repeat(50_000))),
+            message: String::new(),
+        };
+        result.cap_output(1024);
+        assert!(json_size(&result.data) <= 1024);
+        let text = result.data["value"].as_str().unwrap();
+        assert!(text.starts_with("startéé"), "{}", text);
+        assert!(text.ends_with("éééend"), "{}", text);
+        assert!(text.contains("...(truncated "), "{}", text);
+        assert_eq!(result.data["omitted"], 0);
+
+        // The message counts toward the budget and is cut in the middle too
+        let mut result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "stdout": "x".repeat(10_000) }),
+            message: format!("head{}tail", "y".repeat(10_000)),
+        };
+        result.cap_output(2048);
+        let total = json_size(&Value::String(result.message.clone())) + json_size(&result.data);
+        assert!(total <= 2048, "{}", total);
+        assert!(result.message.starts_with("heady") && result.message.ends_with("ytail"));
+        assert!(result.message.contains("...(truncated "));
+
+        // Small results are left alone
+        let mut result = ToolResult {
+            success: true,
+            data: serde_json::json!({ "matches": [1, 2, 3] }),
+            message: String::new(),
+        };
+        result.cap_output(1024);
+        assert!(result.data.get("truncated").is_none());
+    }
+
+    #[test]
+    fn test_truncate_middle_keeps_both_ends() {
+        let mut short = "short".to_string();
+        assert_eq!(truncate_middle(&mut short, 100), 0);
+        assert_eq!(short, "short");
+
+        let mut text = format!("start{}é-end", "x\n".repeat(5_000));
+        let cut = truncate_middle(&mut text, 1000);
+        assert!(text.starts_with("start") && text.ends_with("é-end"));
+        assert!(text.contains(&format!("...(truncated {} bytes)", cut)));
+        // Measured as JSON, where each newline takes two bytes
+        assert!(json_size(&Value::String(text.clone())) <= 1000);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_tools_cannot_run() {
+        let registry = ToolRegistry::new();
+        // Disabling ahead of registration works too (MCP tools come later)
+        registry.set_disabled(&["file_info".to_string()]);
+        registry.register_sync(Arc::new(builtins::ThinkTool));
+        registry.register_sync(Arc::new(filesystem::FileInfoTool));
+
+        let err = registry
+            .execute_by_name("file_info", serde_json::json!({ "path": "." }))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolError::Disabled(name) if name == "file_info"),
+            "{}",
+            err
+        );
+        let err = registry
+            .execute_with_context("file_info", serde_json::json!({}), &ToolContext::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Disabled(_)));
+
+        // Still listed for the settings, but not offered to the model
+        let all: Vec<(String, bool)> = registry
+            .list()
+            .into_iter()
+            .map(|t| (t.name, t.enabled))
+            .collect();
+        assert_eq!(all, [("file_info".into(), false), ("think".into(), true)]);
+        assert_eq!(registry.schemas().as_array().unwrap().len(), 1);
+
+        registry.set_enabled("file_info", true);
+        assert!(registry.get_enabled("file_info").is_ok());
+        assert_eq!(registry.list_tools().len(), 2);
+    }
+    #[tokio::test]
+    async fn test_tool_results_carry_metrics() {
+        let registry = ToolRegistry::new();
+        registry.register_sync(Arc::new(builtins::ThinkTool));
+        registry.register_sync(Arc::new(filesystem::FileSearchContentTool));
+        let ctx = ToolContext::default();
+
+        let result = registry
+            .execute_with_context("think", serde_json::json!({ "thought": "hm" }), &ctx)
+            .await
+            .unwrap();
+        assert!(result.data["metrics"]["duration_ms"].is_u64());
+        assert_eq!(result.data["recorded"], true);
+
+        // The executor's duration is merged with the tool's own metrics
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "needle\n").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "hay\n").unwrap();
+        let params = serde_json::json!({
+            "path": tmp.path().to_string_lossy(),
+            "query": "needle"
+        });
+        let result = registry
+            .execute_with_context("file_search", params, &ctx)
+            .await
+            .unwrap();
+        let metrics = &result.data["metrics"];
+        assert_eq!(metrics["files_scanned"], 2);
+        assert_eq!(metrics["bytes_read"], 11);
+        assert!(metrics["duration_ms"].is_u64());
+    }
+
+    /// `file_search`, with a budget of its own
+    struct SmallSearch(filesystem::FileSearchContentTool);
+
+    #[async_trait]
+    impl Tool for SmallSearch {
+        fn name(&self) -> &str {
+            "small_search"
+        }
+        fn description(&self) -> &str {
+            self.0.description()
+        }
+        fn parameters_schema(&self) -> Value {
+            self.0.parameters_schema()
+        }
+        async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+            self.0.execute(params).await
+        }
+        fn max_output_bytes(&self) -> Option<usize> {
+            Some(2 * 1024)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_huge_search_results_are_clamped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let line = "let needle = compute_something_expensive(input);\n";
+        for i in 0..20 {
+            std::fs::write(tmp.path().join(format!("f{}.rs", i)), line.repeat(100)).unwrap();
+        }
+        let params = serde_json::json!({
+            "path": tmp.path().to_string_lossy(),
+            "query": "needle",
+            "max_results": 2000
+        });
+        let result_size = |result: &ToolResult| {
+            json_size(&Value::String(result.message.clone())) + json_size(&result.data)
+        };
+
+        let ctx = ToolContext::default().with_max_output_bytes(8 * 1024);
+        let search = filesystem::FileSearchContentTool;
+        let result = execute_with_timeout(&search, params.clone(), &ctx)
+            .await
+            .unwrap();
+        assert!(result_size(&result) <= 8 * 1024, "{}", result_size(&result));
+        assert_eq!(result.data["truncated"], true);
+        assert!(result.data["omitted"].as_u64().unwrap() > 0);
+
+        // A tool's own budget wins over the context's
+        let result = execute_with_timeout(&SmallSearch(search), params, &ctx)
+            .await
+            .unwrap();
+        assert!(result_size(&result) <= 2 * 1024, "{}", result_size(&result));
+        assert_eq!(result.data["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_audited() {
+        let registry = ToolRegistry::new();
+        registry.register_sync(Arc::new(builtins::ThinkTool));
+        registry.register_sync(Arc::new(filesystem::FileInfoTool));
+        let tmp = tempfile::tempdir().unwrap();
+        let audit = Arc::new(ToolAuditLog::new(tmp.path().join("audit.jsonl")));
+        let ctx = ToolContext::default().with_audit_log(Some(audit.clone()));
+
+        registry
+            .execute_with_context("think", serde_json::json!({ "thought": "hm" }), &ctx)
+            .await
+            .unwrap();
+        let missing = tmp.path().join("missing.txt");
+        registry
+            .execute_with_context(
+                "file_info",
+                serde_json::json!({ "path": missing.to_string_lossy() }),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+
+        let entries = audit.recent(10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].tool, "file_info");
+        assert!(!entries[0].success);
+        assert!(!entries[0].message.is_empty());
+        assert_eq!(entries[1].tool, "think");
+        assert!(entries[1].success);
+        assert_eq!(entries[1].params["thought"], "hm");
+    }
+    /// Never answers, like a user who leaves the dialog open
+    struct NoAnswer;
+
+    #[async_trait]
+    impl ApprovalHandler for NoAnswer {
+        async fn request_approval(
+            &self,
+            _tool_name: &str,
+            _params: &Value,
+            _preview: &str,
+        ) -> crate::agent::approval::ApprovalDecision {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_executor_asks_for_approval() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("made");
+        let params = serde_json::json!({ "path": dir.to_string_lossy() });
+        let tool = filesystem::DirectoryCreateTool;
+
+        let err = execute_with_timeout(&tool, params.clone(), &ToolContext::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Denied(_)), "{}", err);
+        assert!(!dir.exists());
+
+        // Stopping the agent ends the wait for an answer
+        let ctx = ToolContext::default().with_approval(Arc::new(NoAnswer));
+        ctx.cancel.cancel();
+        let err = execute_with_timeout(&tool, params.clone(), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Cancelled), "{}", err);
+        assert!(!dir.exists());
+
+        // Waiting for the user does not count against the timeout
+        let ctx = ToolContext::default()
+            .with_approval(Arc::new(NoAnswer))
+            .with_timeout(Duration::from_millis(10));
+        assert!(tokio::time::timeout(
+            Duration::from_millis(100),
+            execute_with_timeout(&tool, params.clone(), &ctx)
+        )
+        .await
+        .is_err());
+
+        let ctx =
+            ToolContext::default().with_approval(Arc::new(crate::agent::approval::AutoApprove));
+        execute_with_timeout(&tool, params, &ctx).await.unwrap();
+        assert!(dir.is_dir());
+    }
+
+    #[test]
+    fn test_every_tool_has_its_category() {
+        use crate::agent::skills::{Skill, SkillRegistry, SkillTool};
+        use hashline::HashlineConfig;
+        use ToolCategory::*;
+
+        let workspace = std::env::temp_dir();
+        let mut tools: Vec<Arc<dyn Tool>> = vec![
+            Arc::new(builtins::FileReadTool::new(HashlineConfig::default())),
+            Arc::new(builtins::FileListTool),
+            Arc::new(builtins::GrepTool),
+            Arc::new(builtins::GlobTool),
+            Arc::new(builtins::ThinkTool),
+            Arc::new(builtins::TodoWriteTool),
+            Arc::new(builtins::FileWriteTool),
+            Arc::new(builtins::CommandTool),
+            Arc::new(filesystem::FileInfoTool),
+            Arc::new(filesystem::FileSearchContentTool),
+            Arc::new(filesystem::DirectoryTreeTool::new(&workspace)),
+            Arc::new(filesystem::DirectoryListTool::new(&workspace)),
+            Arc::new(filesystem::FileEditTool::new(HashlineConfig::default())),
+            Arc::new(filesystem::FileCreateTool),
+            Arc::new(filesystem::FileAppendTool),
+            Arc::new(filesystem::FileDeleteTool),
+            Arc::new(filesystem::FileMoveTool),
+            Arc::new(filesystem::FileCopyTool),
+            Arc::new(filesystem::FilePermissionsTool),
+            Arc::new(filesystem::DirectoryCreateTool),
+            Arc::new(shell::BashTool),
+            Arc::new(shell::BashBackgroundTool),
+            Arc::new(shell::ShellExecTool::new(&workspace)),
+            Arc::new(web::WebFetchTool),
+            Arc::new(web::WebDownloadTool),
+            Arc::new(git::GitStatusTool::new(&workspace)),
+            Arc::new(git::GitDiffTool::new(&workspace)),
+            Arc::new(git::GitLogTool),
+            Arc::new(git::GitCommitTool),
+            Arc::new(git::GitBranchTool),
+            Arc::new(git::GitStashTool),
+            Arc::new(mcp_management::McpAddServerTool),
+            Arc::new(mcp_management::McpListServersTool),
+            Arc::new(mcp_management::McpRemoveServerTool),
+            Arc::new(dev::DiffTool),
+            Arc::new(dev::FindReplaceTool),
+            Arc::new(dev::PatchTool),
+            Arc::new(dev::CountLinesTool),
+            Arc::new(system::ProcessListTool),
+            Arc::new(system::EnvironmentTool),
+            Arc::new(system::SystemInfoTool),
+            Arc::new(system::WhichTool),
+            Arc::new(system::TreeTool),
+            Arc::new(pdf::PdfReadTool),
+            Arc::new(pdf::PdfCreateTool),
+            Arc::new(pdf::PdfAddPageTool),
+            Arc::new(pdf::PdfMergeTool),
+            Arc::new(openrouter::OpenRouterConsultTool),
+            Arc::new(skill_create::SkillCreateTool::new(
+                Arc::new(SkillRegistry::new()),
+                Arc::new(ToolRegistry::new()),
+            )),
+            Arc::new(skill_invoke::SkillInvokeTool),
+            Arc::new(skill_list::SkillListTool),
+            Arc::new(SkillTool::new(Skill {
+                name: "deploy".to_string(),
+                description: String::new(),
+                content: String::new(),
+                disable_auto_invoke: false,
+                allowed_tools: Vec::new(),
+                path: workspace.clone(),
+            })),
+        ];
+        tools.extend(exa::create_exa_tools(exa::ExaSearchConfig::default()));
+
+        let expected = [
+            ("file_read", ReadOnly),
+            ("file_list", ReadOnly),
+            ("grep", ReadOnly),
+            ("glob", ReadOnly),
+            ("think", ReadOnly),
+            ("todo_write", ReadOnly),
+            ("file_write", Mutating),
+            ("command", Shell),
+            ("file_info", ReadOnly),
+            ("file_search", ReadOnly),
+            ("directory_tree", ReadOnly),
+            ("directory_list", ReadOnly),
+            ("file_edit", Mutating),
+            ("file_create", Mutating),
+            ("file_append", Mutating),
+            ("file_delete", Mutating),
+            ("file_move", Mutating),
+            ("file_copy", Mutating),
+            ("file_chmod", Mutating),
+            ("directory_create", Mutating),
+            ("bash", Shell),
+            ("bash_background", Shell),
+            ("shell_exec", Shell),
+            ("web_fetch", Network),
+            ("web_download", Network),
+            ("git_status", ReadOnly),
+            ("git_diff", ReadOnly),
+            ("git_log", ReadOnly),
+            ("git_commit", Mutating),
+            ("git_branch", Mutating),
+            ("git_stash", Mutating),
+            ("mcp_add_server", Mutating),
+            ("mcp_list_servers", ReadOnly),
+            ("mcp_remove_server", Mutating),
+            ("diff", ReadOnly),
+            ("find_replace", Mutating),
+            ("patch", Mutating),
+            ("wc", ReadOnly),
+            ("process_list", ReadOnly),
+            ("environment", ReadOnly),
+            ("system_info", ReadOnly),
+            ("which", ReadOnly),
+            ("tree", ReadOnly),
+            ("pdf_read", ReadOnly),
+            ("pdf_create", Mutating),
+            ("pdf_add_page", Mutating),
+            ("pdf_merge", Mutating),
+            ("ai_consult", Network),
+            ("skill_create", Mutating),
+            ("skill_invoke", ReadOnly),
+            ("skill_list", ReadOnly),
+            ("deploy", Shell),
+            ("web_search", Network),
+            ("code_search", Network),
+            ("company_research", Network),
+            ("deep_research_start", Network),
+            ("deep_research_check", Network),
+            ("web_crawl", Network),
+        ];
+        assert_eq!(tools.len(), expected.len());
+        for (tool, (name, category)) in tools.iter().zip(expected) {
+            assert_eq!(tool.name(), name);
+            assert_eq!(tool.category(), category, "{}", name);
+        }
+
+        // Writing, running and downloading all wait for approval
+        let approved: Vec<&str> = tools
+            .iter()
+            .filter(|tool| tool.requires_approval())
+            .map(|tool| tool.name())
+            .collect();
+        for name in ["bash", "command", "git_commit", "patch", "web_download", "web_fetch"] {
+            assert!(approved.contains(&name), "{}", name);
+        }
+        assert!(!approved.contains(&"web_search"));
+        assert!(!approved.contains(&"git_status"));
+    }
+}
@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::path::PathBuf;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 // ============================================================================
 // DiffTool - Compare two files or strings
@@ -134,7 +134,11 @@ impl Tool for FindReplaceTool {
     }
 
     fn description(&self) -> &str {
-        "Find and replace text across multiple files in a directory. Supports file pattern filtering. REQUIRES APPROVAL."
+        "Find and replace text across multiple files in a directory. Supports file pattern filtering."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -317,7 +321,11 @@ impl Tool for PatchTool {
     }
 
     fn description(&self) -> &str {
-        "Apply a unified diff patch to a file. The patch should be in unified diff format. REQUIRES APPROVAL."
+        "Apply a unified diff patch to a file. The patch should be in unified diff format."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -11,7 +11,7 @@ use serde_json::Value;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 /// Exa search configuration
 #[derive(Clone, Debug)]
@@ -182,6 +182,10 @@ impl Tool for ExaSearchTool {
         "Search the web for any topic. Returns summarized content from top search results. Use for finding current information, news, facts, or answers to general questions."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -269,6 +273,10 @@ impl Tool for ExaCodeSearchTool {
         "Find code examples, documentation, and programming solutions. Searches GitHub, Stack Overflow, and official docs. Best for: API usage, library examples, code snippets, debugging help."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -355,6 +363,10 @@ impl Tool for ExaCompanyResearchTool {
         "Research any company to get business information, news, and insights. Returns: company products/services, recent news, industry position."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -435,6 +447,10 @@ impl Tool for ExaDeepResearchStartTool {
         "Start an AI-powered deep research task. The AI will search the web, read many sources, and think deeply about your question. Returns a task_id to check results later with deep_research_check."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -521,6 +537,10 @@ impl Tool for ExaDeepResearchCheckTool {
         "Check the status and get results from a deep research task started with deep_research_start."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -603,6 +623,10 @@ impl Tool for ExaCrawlTool {
         "Get the full content of a specific webpage from a known URL. Use when you have an exact URL and need its content."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
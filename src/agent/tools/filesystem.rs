@@ -4,17 +4,52 @@
 //! Claude Code and OpenCode's tool sets.
 
 use async_trait::async_trait;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::git::run_git;
+use crate::agent::tools::hashline::HashlineConfig;
+use crate::agent::tools::messages::tr;
+use crate::agent::tools::templates::{find_template, template_names};
+use crate::agent::tools::text::{
+    decode_text, encoding_param, looks_binary, read_prefix, read_text, read_text_file,
+};
+use crate::agent::tools::{
+    resolve_in_workspace, Tool, ToolCategory, ToolContext, ToolError, ToolProgress, ToolResult,
+};
 
 // ============================================================================
 // FileEditTool - String replacement editing (like Claude Code's StrReplace)
 // Supports Hashline format: line_number|hash|content
 // ============================================================================
 
-pub struct FileEditTool;
+#[derive(Default)]
+pub struct FileEditTool {
+    hashline: HashlineConfig,
+    /// Whether edits write a `.localm.bak` copy when `backup` isn't given
+    backup_by_default: bool,
+}
+
+impl FileEditTool {
+    /// Use the same `hashline` config as `FileReadTool` so hashes verify
+    pub fn new(hashline: HashlineConfig) -> Self {
+        Self {
+            hashline,
+            backup_by_default: false,
+        }
+    }
+
+    /// Default for the `backup` parameter
+    pub fn with_backup_by_default(mut self, enabled: bool) -> Self {
+        self.backup_by_default = enabled;
+        self
+    }
+}
 
 #[async_trait]
 impl Tool for FileEditTool {
@@ -23,7 +58,11 @@ impl Tool for FileEditTool {
     }
 
     fn description(&self) -> &str {
-        "Edit a file by replacing an exact string with a new string. Supports two modes:\n1. str_replace: Provide old_string (exact match) + new_string\n2. Hashline: Provide line_number + hash + new_string (hash from file_read output)\n\nThe hash format improves edit success rates by 10-68% for various models.\nREQUIRES APPROVAL."
+        "Edit a file by replacing an exact string with a new string. Supports two modes:\n1. str_replace: Provide old_string (exact match) + new_string\n2. Hashline: Provide line_number + hash + new_string (hash from file_read output)\n   Set mode to insert_after / insert_before to add lines around the verified line, or delete to remove it.\n   For a block, add end_line + hashes (one per line, in order) to replace or delete lines line_number..=end_line at once.\n\nThe hash format improves edit success rates by 10-68% for various models.\nThe result includes a unified diff of the change."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -40,23 +79,56 @@ impl Tool for FileEditTool {
                 },
                 "new_string": {
                     "type": "string",
-                    "description": "Replacement string"
+                    "description": "Replacement string (the lines to insert for insert_after/insert_before; not needed for delete)"
                 },
                 "replace_all": {
                     "type": "boolean",
                     "description": "Replace ALL occurrences (default: false, replaces first unique match)",
                     "default": false
                 },
+                "fuzzy": {
+                    "type": "boolean",
+                    "description": "If old_string is not found exactly, retry ignoring differences in spaces/tabs and trailing whitespace (default: false)",
+                    "default": false
+                },
                 "line_number": {
                     "type": "number",
                     "description": "Line number to edit (for Hashline mode). Use instead of old_string."
                 },
                 "hash": {
                     "type": "string",
-                    "description": "2-char hash of the line content (from file_read output). Required for Hashline mode."
+                    "description": "Hash of the line content, exactly as shown between the pipes in file_read output. Required for Hashline mode."
+                },
+                "end_line": {
+                    "type": "number",
+                    "description": "Last line (inclusive) of a Hashline range edit. Requires hashes."
+                },
+                "hashes": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Hashes of every line from line_number to end_line, in order (Hashline range edits)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["replace", "insert_after", "insert_before", "delete"],
+                    "description": "Hashline mode only: what to do at the verified line (default: replace)",
+                    "default": "replace"
+                },
+                "backup": {
+                    "type": "boolean",
+                    "description": "Save the original file to <path>.localm.bak before editing (older backups are rotated to .bak.1, .bak.2, ...)"
+                },
+                "encoding": {
+                    "type": "string",
+                    "description": "Text encoding of the file (e.g. windows-1252, utf-16le). Detected automatically when omitted; the file is written back in the same encoding."
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check the edit (hashes, uniqueness) and report the result without writing the file (default: false)",
+                    "default": false
                 }
             },
-            "required": ["path", "new_string"]
+            "required": ["path"]
         })
     }
 
@@ -64,56 +136,151 @@ impl Tool for FileEditTool {
         let path = params["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
-        let new_string = params["new_string"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("new_string is required".into()))?;
         
         // Hashline mode: line_number + hash provided
-        let hashline_mode = params.get("line_number").is_some() && params.get("hash").is_some();
+        let hashline_mode = params.get("line_number").is_some()
+            && (params.get("hash").is_some() || params.get("hashes").is_some());
+        let operation = HashlineOperation::parse(params["mode"].as_str())?;
+        if !hashline_mode && operation != HashlineOperation::Replace {
+            return Err(ToolError::InvalidParameters(format!(
+                "mode '{}' requires line_number + hash",
+                operation.as_str()
+            )));
+        }
+        let new_string = match params["new_string"].as_str() {
+            Some(s) => s,
+            None if operation == HashlineOperation::Delete => "",
+            None => {
+                return Err(ToolError::InvalidParameters(
+                    "new_string is required".into(),
+                ))
+            }
+        };
+        let dry_run = params["dry_run"].as_bool().unwrap_or(false);
         
-        let content = tokio::fs::read_to_string(path)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire le fichier: {}", e)))?;
+        let file = read_text(Path::new(path), encoding_param(&params)?).await?;
+        let content = file.text.as_str();
+
+        // Lines sharing the supplied hash, when it is ambiguous (Hashline mode)
+        let mut collision_lines: Vec<usize> = Vec::new();
+        // Whether old_string only matched with whitespace normalized (str_replace)
+        let mut fuzzy_used = false;
+        // Edits are written back with the file's own line ending
+        let ending = dominant_line_ending(content);
 
         let new_content = if hashline_mode {
-            // Hashline mode: edit by line number + hash
+            // Hashline mode: edit by line number + hash (or a verified range)
             let line_number = params["line_number"]
                 .as_u64()
                 .ok_or_else(|| ToolError::InvalidParameters("line_number must be a number".into()))? as usize;
-            let hash = params["hash"]
-                .as_str()
-                .ok_or_else(|| ToolError::InvalidParameters("hash must be a string".into()))?;
-            
+            let end_line = match params.get("end_line") {
+                Some(v) => v.as_u64().ok_or_else(|| {
+                    ToolError::InvalidParameters("end_line must be a number".into())
+                })? as usize,
+                None => line_number,
+            };
+            if line_number == 0 || end_line < line_number {
+                return Err(ToolError::InvalidParameters(format!(
+                    "invalid line range {}-{}",
+                    line_number, end_line
+                )));
+            }
+            if end_line > line_number
+                && matches!(
+                    operation,
+                    HashlineOperation::InsertAfter | HashlineOperation::InsertBefore
+                )
+            {
+                return Err(ToolError::InvalidParameters(format!(
+                    "mode '{}' takes a single line_number, not a range",
+                    operation.as_str()
+                )));
+            }
+            let expected = expected_hashes(&params, end_line - line_number + 1)?;
+
             let lines: Vec<&str> = content.lines().collect();
-            let line_idx = line_number.saturating_sub(1);
+            let line_idx = line_number - 1;
+            let end_idx = end_line - 1;
             
-            if line_idx >= lines.len() {
+            if end_idx >= lines.len() {
                 return Err(ToolError::ExecutionFailed(format!(
-                    "Line {} does not exist (file has {} lines)", line_number, lines.len()
+                    "Line {} does not exist (file has {} lines)", end_line, lines.len()
                 )));
             }
             
-            let target_line = lines[line_idx];
-            
-            // Compute hash of current line content (without the hash prefix)
-            let current_hash = compute_line_hash(target_line);
-            if current_hash != hash {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Hash mismatch! Expected '{}' but found '{}'. The line content has changed since file_read.",
-                    hash, current_hash
-                )));
+            if let [hash] = expected.as_slice() {
+                let target_line = lines[line_idx];
+                let same_hash = self.hashline.matching_lines(&lines, hash);
+
+                // Compute hash of current line content (without the hash prefix)
+                if !self.hashline.verify(target_line, hash) {
+                    let current_hash = self.hashline.hash(target_line);
+                    let moved = if same_hash.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " Lines with hash '{}' (collision_lines): {:?}.",
+                            hash, same_hash
+                        )
+                    };
+                    return Err(ToolError::HashMismatch(format!(
+                        "expected '{}' but found '{}'. The line content has changed since file_read.{}",
+                        hash, current_hash, moved
+                    )));
+                }
+                if same_hash.len() > 1 {
+                    collision_lines = same_hash;
+                }
+            } else {
+                // Every line of the range must still be what the model read
+                for (offset, hash) in expected.iter().enumerate() {
+                    let line = lines[line_idx + offset];
+                    if !self.hashline.verify(line, hash) {
+                        return Err(ToolError::HashMismatch(format!(
+                            "at line {}, expected '{}' but found '{}'. Re-read lines {}-{} with file_read before editing.",
+                            line_number + offset,
+                            hash,
+                            self.hashline.hash(line),
+                            line_number,
+                            end_line
+                        )));
+                    }
+                }
             }
             
-            // Replace the line
+            // The anchor lines are verified; only now change the structure
+            let replacement = new_string.replace("\r\n", "\n");
             let mut new_lines: Vec<&str> = lines.clone();
-            new_lines[line_idx] = new_string;
-            new_lines.join("\n")
+            let inserted = replacement
+                .strip_suffix('\n')
+                .unwrap_or(&replacement)
+                .split('\n');
+            match operation {
+                HashlineOperation::Replace => {
+                    new_lines.splice(line_idx..=end_idx, replacement.split('\n'));
+                }
+                HashlineOperation::InsertBefore => {
+                    new_lines.splice(line_idx..line_idx, inserted);
+                }
+                HashlineOperation::InsertAfter => {
+                    new_lines.splice(line_idx + 1..line_idx + 1, inserted);
+                }
+                HashlineOperation::Delete => {
+                    new_lines.drain(line_idx..=end_idx);
+                }
+            }
+            let mut joined = new_lines.join(ending);
+            if content.ends_with('\n') && !new_lines.is_empty() {
+                joined.push_str(ending);
+            }
+            joined
         } else {
             // Classic str_replace mode
             let old_string = params["old_string"]
                 .as_str()
                 .ok_or_else(|| ToolError::InvalidParameters("old_string is required (or use hashline mode with line_number + hash)".into()))?;
             let replace_all = params["replace_all"].as_bool().unwrap_or(false);
+            let fuzzy = params["fuzzy"].as_bool().unwrap_or(false);
 
             if old_string == new_string {
                 return Err(ToolError::InvalidParameters(
@@ -121,56 +288,286 @@ impl Tool for FileEditTool {
                 ));
             }
 
-            let count = content.matches(old_string).count();
+            // A CRLF file quoted with LF line breaks: match (and replace) it
+            // with CRLF so the untouched lines keep their endings
+            let quoted_with_lf =
+                ending == "\r\n" && old_string.contains('\n') && !content.contains(old_string);
+            let crlf_quoted;
+            let (old_string, new_string) = if quoted_with_lf {
+                crlf_quoted = (
+                    with_line_ending(old_string, ending),
+                    with_line_ending(new_string, ending),
+                );
+                (crlf_quoted.0.as_str(), crlf_quoted.1.as_str())
+            } else {
+                (old_string, new_string)
+            };
+
+            let mut count = content.matches(old_string).count();
+            let fuzzy_spans = if count == 0 && fuzzy {
+                whitespace_insensitive_matches(content, old_string)
+            } else {
+                Vec::new()
+            };
+            fuzzy_used = !fuzzy_spans.is_empty();
+            if fuzzy_used {
+                count = fuzzy_spans.len();
+            }
             if count == 0 {
-                return Err(ToolError::ExecutionFailed(
-                    "old_string introuvable dans le fichier. Vérifiez l'indentation et les espaces.".into(),
-                ));
+                return Err(ToolError::NotFound(tr("edit.not_found", &[])));
             }
             if count > 1 && !replace_all {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "old_string trouvé {} fois. Ajoutez plus de contexte pour le rendre unique, ou utilisez replace_all=true.",
-                    count
-                )));
+                return Err(ToolError::NotUnique {
+                    count,
+                    message: tr("edit.ambiguous", &[&count]),
+                });
             }
 
-            if replace_all {
+            if fuzzy_used {
+                // Splice from the end so earlier spans stay valid
+                let mut replaced = content.to_string();
+                for (start, end) in fuzzy_spans.into_iter().rev() {
+                    replaced.replace_range(start..end, new_string);
+                }
+                replaced
+            } else if replace_all {
                 content.replace(old_string, new_string)
             } else {
                 content.replacen(old_string, new_string, 1)
             }
         };
 
-        tokio::fs::write(path, &new_content)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible d'écrire le fichier: {}", e)))?;
+        let backup = params["backup"].as_bool().unwrap_or(self.backup_by_default);
+        let backup_path = if backup && !dry_run {
+            let original = file.encode(content)?;
+            let backup = write_backup(Path::new(path), &original)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("edit.backup_failed", &[&e])))?;
+            Some(backup.display().to_string())
+        } else {
+            None
+        };
+
+        let encoded = file.encode(&new_content)?;
+        if !dry_run {
+            atomic_write(Path::new(path), &encoded)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("file.write_failed", &[&e])))?;
+        }
 
-        let count = new_content.matches(new_string).count();
+        let mut data = serde_json::json!({
+            "path": path,
+            "replacements": 1,
+            "mode": if hashline_mode { "hashline" } else { "str_replace" },
+            "total_lines": new_content.lines().count(),
+            "encoding": file.encoding.name()
+        });
+        let (diff, diff_truncated) = unified_diff(path, content, &new_content);
+        data["diff"] = serde_json::json!(diff);
+        if diff_truncated {
+            data["diff_truncated"] = serde_json::json!(true);
+        }
+        let mut message = if hashline_mode {
+            data["operation"] = serde_json::json!(operation.as_str());
+            tr("edit.done_hashline", &[&path, &operation.as_str()])
+        } else {
+            tr("edit.done_str_replace", &[&path])
+        };
+        if fuzzy_used {
+            data["fuzzy_match"] = serde_json::json!(true);
+            message.push_str(&tr("edit.fuzzy_note", &[]));
+        }
+        if !collision_lines.is_empty() {
+            data["collision_lines"] = serde_json::json!(collision_lines);
+            message.push_str(&tr(
+                "edit.hash_collision",
+                &[&format!("{:?}", collision_lines)],
+            ));
+        }
+        if let Some(backup) = backup_path {
+            message.push_str(&tr("edit.backup_note", &[&backup]));
+            data["backup_path"] = serde_json::json!(backup);
+        }
+        if dry_run {
+            return Ok(dry_run_result(data, message));
+        }
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({
-                "path": path,
-                "replacements": 1,
-                "mode": if hashline_mode { "hashline" } else { "str_replace" },
-                "total_lines": new_content.lines().count()
-            }),
-            message: format!(
-                "Fichier édité: {} (1 remplacement, mode: {})",
-                path,
-                if hashline_mode { "hashline" } else { "str_replace" }
-            ),
+            data,
+            message,
         })
     }
 }
 
-/// Compute hash for a line (must match the one in tools.rs)
-fn compute_line_hash(line: &str) -> String {
-    let mut hash: u32 = 2166136261u32;
-    for byte in line.bytes() {
-        hash = hash.wrapping_mul(16777619u32);
-        hash ^= byte as u32;
+/// How many rotated backups (`.bak.1` ..) are kept besides the latest `.bak`
+const MAX_ROTATED_BACKUPS: usize = 5;
+
+/// Save `content` to `<path>.localm.bak`, first shifting any existing backup
+/// to `.bak.1` (and `.bak.1` to `.bak.2`, ...) so earlier backups survive.
+/// Returns the path of the new backup.
+async fn write_backup(path: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    let backup_name = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".localm.bak");
+        if n > 0 {
+            name.push(format!(".{}", n));
+        }
+        PathBuf::from(name)
+    };
+
+    let latest = backup_name(0);
+    if tokio::fs::try_exists(&latest).await? {
+        for n in (0..MAX_ROTATED_BACKUPS).rev() {
+            let from = backup_name(n);
+            if tokio::fs::try_exists(&from).await? {
+                tokio::fs::rename(&from, backup_name(n + 1)).await?;
+            }
+        }
+    }
+    tokio::fs::write(&latest, content).await?;
+    Ok(latest)
+}
+
+/// The line ending used by most lines of `content`: `"\r\n"` or `"\n"`
+fn dominant_line_ending(content: &str) -> &'static str {
+    let crlf = content.matches("\r\n").count();
+    let lf = content.matches('\n').count() - crlf;
+    if crlf > lf {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Byte spans of the non-overlapping occurrences of `needle` in `haystack`
+/// when runs of spaces and tabs compare equal whatever their length and
+/// trailing whitespace on each line is ignored.
+fn whitespace_insensitive_matches(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let (needle, _) = normalize_whitespace(needle);
+    if needle.trim().is_empty() {
+        return Vec::new();
+    }
+    let (normalized, spans) = normalize_whitespace(haystack);
+    normalized
+        .match_indices(&needle)
+        .map(|(i, m)| (spans[i].0, spans[i + m.len() - 1].1))
+        .collect()
+}
+
+/// `text` with each run of spaces, tabs and carriage returns collapsed to a
+/// single space and dropped at the end of a line, along with the byte span
+/// in `text` behind each byte of the result.
+fn normalize_whitespace(text: &str) -> (String, Vec<(usize, usize)>) {
+    let mut normalized = String::with_capacity(text.len());
+    let mut spans = Vec::with_capacity(text.len());
+    let mut blank_run: Option<(usize, usize)> = None;
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        if matches!(c, ' ' | '\t' | '\r') {
+            blank_run = Some((blank_run.map_or(i, |(start, _)| start), end));
+            continue;
+        }
+        if let Some(run) = blank_run.take() {
+            if c != '\n' {
+                normalized.push(' ');
+                spans.push(run);
+            }
+        }
+        normalized.push(c);
+        spans.extend(std::iter::repeat_n((i, end), c.len_utf8()));
+    }
+    (normalized, spans)
+}
+
+/// `text` with every line break turned into `ending`
+fn with_line_ending(text: &str, ending: &str) -> String {
+    text.replace("\r\n", "\n").replace('\n', ending)
+}
+
+/// Hunks beyond this many are left out of `file_edit`'s `diff`
+const MAX_DIFF_HUNKS: usize = 20;
+
+/// Unified diff (3 lines of context) between `old` and `new`, as shown in the
+/// approval dialog and the transcript. Returns whether hunks were left out.
+fn unified_diff(path: &str, old: &str, new: &str) -> (String, bool) {
+    let diff = similar::TextDiff::from_lines(old, new);
+    let mut unified = diff.unified_diff();
+    unified.context_radius(3).missing_newline_hint(false);
+
+    let mut out = format!("--- {}\n+++ {}\n", path, path);
+    let mut hunks = unified.iter_hunks();
+    for hunk in hunks.by_ref().take(MAX_DIFF_HUNKS) {
+        out.push_str(&hunk.to_string());
+    }
+    let remaining = hunks.count();
+    if remaining > 0 {
+        out.push_str(&tr("edit.more_hunks", &[&remaining]));
+    }
+    (out, remaining > 0)
+}
+
+/// The hashes a Hashline edit must verify, one per line of the target range.
+/// `hashes` lists them in order; a lone `hash` covers a single-line edit.
+fn expected_hashes(params: &Value, span: usize) -> Result<Vec<&str>, ToolError> {
+    let hashes: Vec<&str> = match params.get("hashes") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|h| {
+                h.as_str().ok_or_else(|| {
+                    ToolError::InvalidParameters("hashes must be an array of strings".into())
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => {
+            return Err(ToolError::InvalidParameters(
+                "hashes must be an array of strings".into(),
+            ))
+        }
+        None => vec![params["hash"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("hash must be a string".into()))?],
+    };
+    if hashes.len() != span {
+        return Err(ToolError::InvalidParameters(format!(
+            "expected {} hash(es), one per line of the range, got {}",
+            span,
+            hashes.len()
+        )));
+    }
+    Ok(hashes)
+}
+
+/// What a Hashline edit does at its verified line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashlineOperation {
+    Replace,
+    InsertAfter,
+    InsertBefore,
+    Delete,
+}
+
+impl HashlineOperation {
+    fn parse(mode: Option<&str>) -> Result<Self, ToolError> {
+        match mode {
+            None | Some("replace") => Ok(Self::Replace),
+            Some("insert_after") => Ok(Self::InsertAfter),
+            Some("insert_before") => Ok(Self::InsertBefore),
+            Some("delete") => Ok(Self::Delete),
+            Some(other) => Err(ToolError::InvalidParameters(format!(
+                "unknown mode '{}' (expected replace, insert_after, insert_before or delete)",
+                other
+            ))),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Replace => "replace",
+            Self::InsertAfter => "insert_after",
+            Self::InsertBefore => "insert_before",
+            Self::Delete => "delete",
+        }
     }
-    format!("{:02x}", hash & 0xFFF)
 }
 
 // ============================================================================
@@ -186,7 +583,11 @@ impl Tool for FileCreateTool {
     }
 
     fn description(&self) -> &str {
-        "Create a new file with content. Fails if the file already exists. Creates parent directories automatically. REQUIRES APPROVAL."
+        "Create a new file with content, or from a built-in template (rust-lib, rust-bin, gitignore, readme) filled in with variables. Fails if the file already exists. Creates parent directories automatically."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -199,15 +600,29 @@ impl Tool for FileCreateTool {
                 },
                 "content": {
                     "type": "string",
-                    "description": "Content to write to the new file"
+                    "description": "Content to write to the new file (omit when using template)"
+                },
+                "template": {
+                    "type": "string",
+                    "enum": template_names(),
+                    "description": "Built-in template to render instead of content"
+                },
+                "variables": {
+                    "type": "object",
+                    "description": "Values for the template's {{placeholders}} (e.g. {\"name\": \"my-app\"})"
                 },
                 "overwrite": {
                     "type": "boolean",
                     "description": "If true, overwrite existing file (default: false)",
                     "default": false
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check the path and report what would be created without writing anything (default: false)",
+                    "default": false
                 }
             },
-            "required": ["path", "content"]
+            "required": ["path"]
         })
     }
 
@@ -215,19 +630,67 @@ impl Tool for FileCreateTool {
         let path = params["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
-        let content = params["content"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
+        let template = match params["template"].as_str() {
+            Some(name) => Some(find_template(name).ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "unknown template '{}' (available: {})",
+                    name,
+                    template_names().join(", ")
+                ))
+            })?),
+            None => None,
+        };
+        let rendered;
+        let content = match (template, params["content"].as_str()) {
+            (Some(_), Some(_)) => {
+                return Err(ToolError::InvalidParameters(
+                    "give either content or template, not both".into(),
+                ))
+            }
+            (Some(template), None) => {
+                let variables = match &params["variables"] {
+                    Value::Object(map) => map.clone(),
+                    Value::Null => serde_json::Map::new(),
+                    _ => {
+                        return Err(ToolError::InvalidParameters(
+                            "variables must be an object".into(),
+                        ))
+                    }
+                };
+                rendered = template.render(&variables)?;
+                rendered.as_str()
+            }
+            (None, Some(content)) => content,
+            (None, None) => {
+                return Err(ToolError::InvalidParameters(
+                    "content is required (or a template)".into(),
+                ))
+            }
+        };
         let overwrite = params["overwrite"].as_bool().unwrap_or(false);
+        let dry_run = params["dry_run"].as_bool().unwrap_or(false);
 
         let path_buf = PathBuf::from(path);
 
         // Check if file already exists
         if path_buf.exists() && !overwrite {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le fichier '{}' existe déjà. Utilisez overwrite=true pour écraser, ou file_edit pour modifier.",
-                path
-            )));
+            return Err(ToolError::AlreadyExists(tr("create.exists", &[&path])));
+        }
+
+        let lines = content.lines().count();
+        let bytes = content.len();
+        let mut data = serde_json::json!({
+            "path": path,
+            "bytes": bytes,
+            "lines": lines,
+            "created": true
+        });
+        if let Some(template) = template {
+            data["template"] = serde_json::json!(template.name);
+        }
+        let message = tr("create.done", &[&path, &lines, &bytes]);
+        if dry_run {
+            return Ok(dry_run_result(data, message));
         }
 
         // Create parent directories
@@ -235,44 +698,40 @@ impl Tool for FileCreateTool {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier parent: {}", e)))?;
+                    .map_err(|e| ToolError::io(&e, tr("dir.parent_create_failed", &[&e])))?;
             }
         }
 
-        tokio::fs::write(&path_buf, content)
+        atomic_write(&path_buf, content)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le fichier: {}", e)))?;
-
-        let lines = content.lines().count();
-        let bytes = content.len();
+            .map_err(|e| ToolError::io(&e, tr("file.create_failed", &[&e])))?;
 
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({
-                "path": path,
-                "bytes": bytes,
-                "lines": lines,
-                "created": true
-            }),
-            message: format!("Fichier créé: {} ({} lignes, {} octets)", path, lines, bytes),
+            data,
+            message,
         })
     }
 }
 
 // ============================================================================
-// FileDeleteTool - Delete files and directories
+// FileAppendTool - Append to the end of a file
 // ============================================================================
 
-pub struct FileDeleteTool;
+pub struct FileAppendTool;
 
 #[async_trait]
-impl Tool for FileDeleteTool {
+impl Tool for FileAppendTool {
     fn name(&self) -> &str {
-        "file_delete"
+        "file_append"
     }
 
     fn description(&self) -> &str {
-        "Delete a file or empty directory. For safety, cannot delete non-empty directories unless recursive=true. REQUIRES APPROVAL."
+        "Append content to the end of a file without rewriting it. Ideal for logs, TODO lists and accumulating output. Creates the file and its parent directories if they don't exist (unless create=false)."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -281,101 +740,514 @@ impl Tool for FileDeleteTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Path to the file or directory to delete"
+                    "description": "Path to the file to append to"
                 },
-                "recursive": {
+                "content": {
+                    "type": "string",
+                    "description": "Content to append"
+                },
+                "ensure_newline": {
                     "type": "boolean",
-                    "description": "If true, delete directory and all contents recursively (DANGEROUS)",
-                    "default": false
+                    "description": "Insert a newline first if the file doesn't already end with one (default: true)",
+                    "default": true
+                },
+                "create": {
+                    "type": "boolean",
+                    "description": "Create the file if it doesn't exist (default: true)",
+                    "default": true
                 }
             },
-            "required": ["path"]
+            "required": ["path", "content"]
         })
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        use tokio::io::AsyncWriteExt;
+
         let path = params["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
-        let recursive = params["recursive"].as_bool().unwrap_or(false);
+        let content = params["content"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("content is required".into()))?;
+        let ensure_newline = params["ensure_newline"].as_bool().unwrap_or(true);
+        let create = params["create"].as_bool().unwrap_or(true);
 
         let path_buf = PathBuf::from(path);
-
-        if !path_buf.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Le chemin '{}' n'existe pas",
-                path
-            )));
+        let created = !path_buf.exists();
+        if !create && created {
+            return Err(ToolError::NotFound(tr("append.missing", &[&path])));
+        }
+        if created {
+            if let Some(parent) = path_buf.parent() {
+                if !parent.as_os_str().is_empty() && !parent.exists() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| ToolError::io(&e, tr("dir.parent_create_failed", &[&e])))?;
+                }
+            }
         }
 
-        if path_buf.is_file() {
-            tokio::fs::remove_file(&path_buf)
+        let needs_newline = ensure_newline
+            && !ends_with_newline(&path_buf)
                 .await
-                .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de supprimer: {}", e)))?;
+                .map_err(|e| ToolError::io(&e, tr("file.read_failed", &[&e])))?;
 
-            Ok(ToolResult {
-                success: true,
-                data: serde_json::json!({ "path": path, "type": "file" }),
-                message: format!("Fichier supprimé: {}", path),
-            })
-        } else if path_buf.is_dir() {
-            if recursive {
-                tokio::fs::remove_dir_all(&path_buf)
-                    .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de supprimer le dossier: {}", e)))?;
-            } else {
-                tokio::fs::remove_dir(&path_buf)
-                    .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Dossier non vide. Utilisez recursive=true: {}", e)))?;
-            }
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .create(create)
+            .open(&path_buf)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("file.open_failed", &[&e])))?;
 
-            Ok(ToolResult {
-                success: true,
-                data: serde_json::json!({ "path": path, "type": "directory", "recursive": recursive }),
-                message: format!("Dossier supprimé: {}", path),
-            })
-        } else {
-            Err(ToolError::ExecutionFailed(format!(
-                "Type de chemin non supporté: {}",
-                path
-            )))
+        let mut appended = String::with_capacity(content.len() + 1);
+        if needs_newline {
+            appended.push('\n');
+        }
+        appended.push_str(content);
+        file.write_all(appended.as_bytes())
+            .await
+            .map_err(|e| ToolError::io(&e, tr("file.write_failed", &[&e])))?;
+        file.flush()
+            .await
+            .map_err(|e| ToolError::io(&e, tr("file.write_failed", &[&e])))?;
+        drop(file);
+
+        let (bytes, lines) = count_bytes_and_lines(&path_buf)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("file.read_failed", &[&e])))?;
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "appended_bytes": appended.len(),
+                "bytes": bytes,
+                "lines": lines,
+                "created": created
+            }),
+            message: tr("append.done", &[&appended.len(), &path, &lines, &bytes]),
+        })
+    }
+}
+
+/// Whether `path` is empty, missing, or ends with `\n` - i.e. appending
+/// starts on a fresh line.
+async fn ends_with_newline(path: &Path) -> std::io::Result<bool> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = match tokio::fs::File::open(path).await {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    };
+    if file.metadata().await?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(std::io::SeekFrom::End(-1)).await?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last).await?;
+    Ok(last[0] == b'\n')
+}
+
+/// Size and line count of a file, read in chunks rather than all at once.
+/// A final line without a trailing newline still counts.
+async fn count_bytes_and_lines(path: &Path) -> std::io::Result<(u64, usize)> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut bytes = 0u64;
+    let mut lines = 0;
+    let mut last = b'\n';
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
         }
+        bytes += n as u64;
+        lines += buf[..n].iter().filter(|&&b| b == b'\n').count();
+        last = buf[n - 1];
     }
+    if last != b'\n' {
+        lines += 1;
+    }
+    Ok((bytes, lines))
 }
 
 // ============================================================================
-// FileMoveTool - Move/rename files and directories
+// FileDeleteTool - Delete files and directories
 // ============================================================================
 
-pub struct FileMoveTool;
+pub struct FileDeleteTool;
 
 #[async_trait]
-impl Tool for FileMoveTool {
+impl Tool for FileDeleteTool {
     fn name(&self) -> &str {
-        "file_move"
+        "file_delete"
     }
 
     fn description(&self) -> &str {
-        "Move or rename a file or directory. Creates parent directories for destination automatically. REQUIRES APPROVAL."
+        "Delete a file or directory. By default nothing is destroyed: the target is moved to the project trash (.localm/trash/<timestamp>/, keeping its relative path) and the trash location is returned so it can be restored. trash_location=\"system\" uses the OS trash / recycle bin instead. Set trash=false (or permanent=true) only when permanent deletion is really intended. For safety, non-empty directories require recursive=true either way. A symlink is deleted as a link; its target is left untouched."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
             "properties": {
-                "source": {
+                "path": {
                     "type": "string",
-                    "description": "Source path (file or directory)"
+                    "description": "Path to the file or directory to delete"
                 },
-                "destination": {
+                "recursive": {
+                    "type": "boolean",
+                    "description": "If true, delete directory and all contents recursively (DANGEROUS)",
+                    "default": false
+                },
+                "trash": {
+                    "type": "boolean",
+                    "description": "Move to a trash instead of deleting (default: true); false deletes permanently",
+                    "default": true
+                },
+                "trash_location": {
                     "type": "string",
-                    "description": "Destination path"
-                }
-            },
-            "required": ["source", "destination"]
-        })
-    }
-
+                    "enum": ["project", "system"],
+                    "description": "Where trashed items go: the project's .localm/trash folder (default) or the OS trash",
+                    "default": "project"
+                },
+                "permanent": {
+                    "type": "boolean",
+                    "description": "Delete permanently, same as trash=false (default: false)",
+                    "default": false
+                },
+                "to_trash": {
+                    "type": "boolean",
+                    "description": "Deprecated: to_trash=false is the same as permanent=true",
+                    "default": true
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check the path and report what would be deleted without deleting anything (default: false)",
+                    "default": false
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, &ToolContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let recursive = params["recursive"].as_bool().unwrap_or(false);
+        let permanent = params["permanent"].as_bool().unwrap_or(false)
+            || !params["trash"].as_bool().unwrap_or(true)
+            || !params["to_trash"].as_bool().unwrap_or(true);
+        let dry_run = params["dry_run"].as_bool().unwrap_or(false);
+        let trash_kind = match params["trash_location"].as_str() {
+            None | Some("project") => "project",
+            Some("system") => "system",
+            Some(other) => {
+                return Err(ToolError::InvalidParameters(format!(
+                    "unknown trash_location '{}' (expected project or system)",
+                    other
+                )))
+            }
+        };
+
+        let path_buf = PathBuf::from(path);
+
+        // Not followed: a symlink is deleted itself, never what it points to
+        let Ok(metadata) = tokio::fs::symlink_metadata(&path_buf).await else {
+            return Err(ToolError::NotFound(tr("path.missing", &[&path])));
+        };
+
+        let kind = if metadata.is_symlink() {
+            "symlink"
+        } else if metadata.is_file() {
+            "file"
+        } else if metadata.is_dir() {
+            "directory"
+        } else {
+            return Err(ToolError::ExecutionFailed(tr(
+                "path.unsupported_type",
+                &[&path],
+            )));
+        };
+
+        // The recursive guard applies to trashing as well
+        if kind == "directory" && !recursive {
+            let mut entries = tokio::fs::read_dir(&path_buf)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("dir.read_failed", &[&e])))?;
+            if let Ok(Some(_)) = entries.next_entry().await {
+                return Err(ToolError::ExecutionFailed(tr("delete.not_empty", &[])));
+            }
+        }
+
+        let mut trash_path = None;
+        if dry_run {
+            // Validated above; the trash location is only known once moved
+        } else if permanent {
+            remove_permanently(&path_buf, kind, recursive, &ctx.cancel).await?;
+        } else if trash_kind == "system" {
+            let target = path_buf.clone();
+            tokio::task::spawn_blocking(move || trash::delete(&target))
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(tr("delete.trash_failed", &[&e])))?
+                .map_err(|e| ToolError::ExecutionFailed(tr("delete.trash_failed", &[&e])))?;
+        } else {
+            let moved = move_to_project_trash(&path_buf)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("delete.trash_failed", &[&e])))?;
+            trash_path = Some(moved.display().to_string());
+        }
+
+        let is_file = kind != "directory";
+        let message = if permanent {
+            let key = if is_file {
+                "delete.file_permanent"
+            } else {
+                "delete.dir_permanent"
+            };
+            tr(key, &[&path])
+        } else if trash_kind == "system" {
+            let key = if is_file {
+                "delete.file_system_trash"
+            } else {
+                "delete.dir_system_trash"
+            };
+            tr(key, &[&path])
+        } else {
+            let key = if is_file {
+                "delete.file_trashed"
+            } else {
+                "delete.dir_trashed"
+            };
+            let moved = trash_path.as_deref().unwrap_or(".localm/trash");
+            tr(key, &[&path, &moved])
+        };
+
+        let data = serde_json::json!({
+            "path": path,
+            "type": kind,
+            "recursive": recursive,
+            "trashed": !permanent,
+            "trash_location": if permanent { None } else { Some(trash_kind) },
+            "trash_path": trash_path
+        });
+        if dry_run {
+            return Ok(dry_run_result(data, message));
+        }
+        Ok(ToolResult {
+            success: true,
+            data,
+            message,
+        })
+    }
+}
+
+/// Unlink `path` for good (`permanent=true`).
+async fn remove_permanently(
+    path: &Path,
+    kind: &str,
+    recursive: bool,
+    cancel: &CancellationToken,
+) -> Result<(), ToolError> {
+    if kind != "directory" {
+        tokio::fs::remove_file(path)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("delete.failed", &[&e])))
+    } else if recursive {
+        remove_tree(path, cancel).await.map_err(|e| {
+            if cancel.is_cancelled() {
+                ToolError::Cancelled
+            } else {
+                ToolError::io(&e, tr("delete.dir_failed", &[&e]))
+            }
+        })
+    } else {
+        tokio::fs::remove_dir(path)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("delete.not_empty_detail", &[&e])))
+    }
+}
+
+/// `remove_dir_all` that stops before the next entry once `cancel` is
+/// triggered, leaving whatever was not reached yet in place. Symlinks are
+/// removed, not followed, `dir` included.
+fn remove_tree<'a>(
+    dir: &'a Path,
+    cancel: &'a CancellationToken,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = std::io::Result<()>> + Send + 'a>> {
+    Box::pin(async move {
+        if tokio::fs::symlink_metadata(dir).await?.is_symlink() {
+            return tokio::fs::remove_file(dir).await;
+        }
+        let mut entries = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if cancel.is_cancelled() {
+                return Err(std::io::ErrorKind::Interrupted.into());
+            }
+            if entry.file_type().await?.is_dir() {
+                remove_tree(&entry.path(), cancel).await?;
+            } else {
+                tokio::fs::remove_file(entry.path()).await?;
+            }
+        }
+        tokio::fs::remove_dir(dir).await
+    })
+}
+
+/// Whether `a` and `b` are the same file once symlinks are followed
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        matches!(
+            (std::fs::canonicalize(a), std::fs::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+    }
+}
+
+/// `path` made absolute with its directories resolved but not its last
+/// component, so a symlink stays the link itself. Directories that don't
+/// exist yet are appended as given.
+fn resolve_parent(path: &Path) -> std::io::Result<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return std::fs::canonicalize(path);
+    };
+    let mut existing = parent;
+    let mut missing = Vec::new();
+    let base = loop {
+        let dir = if existing.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            existing
+        };
+        match std::fs::canonicalize(dir) {
+            Ok(base) => break base,
+            Err(e) => {
+                let (Some(up), Some(part)) = (existing.parent(), existing.file_name()) else {
+                    return Err(e);
+                };
+                missing.push(part);
+                existing = up;
+            }
+        }
+    };
+    Ok(missing
+        .iter()
+        .rev()
+        .fold(base, |dir, part| dir.join(part))
+        .join(name))
+}
+
+/// Move `path` into `<project>/.localm/trash/<timestamp>/`, keeping its path
+/// relative to the project root so it can be put back where it was.
+/// The project root is the closest ancestor holding `.git` or `.localm`,
+/// else the working directory if `path` is inside it, else `path`'s parent.
+/// Only the parent is resolved, so a symlink is trashed as a link.
+async fn move_to_project_trash(path: &Path) -> std::io::Result<PathBuf> {
+    let abs = resolve_parent(path)?;
+    let parent = abs.parent().unwrap_or(&abs).to_path_buf();
+    let root = parent
+        .ancestors()
+        .find(|dir| dir.join(".git").exists() || dir.join(".localm").exists())
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|cwd| std::fs::canonicalize(cwd).ok())
+                .filter(|cwd| abs.starts_with(cwd) && abs != *cwd)
+        })
+        .unwrap_or(parent);
+
+    let relative = abs.strip_prefix(&root).unwrap_or(&abs);
+    let relative = relative
+        .strip_prefix(std::path::Component::RootDir)
+        .unwrap_or(relative);
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let dest = root.join(".localm").join("trash").join(stamp).join(relative);
+
+    if let Some(dir) = dest.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    move_path(&abs, &dest, |from, to| async move {
+        tokio::fs::rename(from, to).await
+    })
+    .await?;
+    Ok(dest)
+}
+
+// ============================================================================
+// FileMoveTool - Move/rename files and directories
+// ============================================================================
+
+pub struct FileMoveTool;
+
+#[async_trait]
+impl Tool for FileMoveTool {
+    fn name(&self) -> &str {
+        "file_move"
+    }
+
+    fn description(&self) -> &str {
+        "Move or rename a file or directory. Creates parent directories for destination automatically. Fails if the destination exists unless overwrite=true, which moves the replaced destination to the project trash (.localm/trash). Works across filesystems (falls back to copy + delete). A symlink is moved as a link; its target is left untouched."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "string",
+                    "description": "Source path (file or directory)"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "Destination path"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace the destination if it already exists, moving it to the project trash (default: false)",
+                    "default": false
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check source and destination and report the move without performing it (default: false)",
+                    "default": false
+                }
+            },
+            "required": ["source", "destination"]
+        })
+    }
+
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
         let source = params["source"]
             .as_str()
@@ -383,48 +1255,149 @@ impl Tool for FileMoveTool {
         let destination = params["destination"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("destination is required".into()))?;
+        let overwrite = params["overwrite"].as_bool().unwrap_or(false);
 
         let src = PathBuf::from(source);
         let dst = PathBuf::from(destination);
 
-        if !src.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Source '{}' n'existe pas",
-                source
+        // symlink_metadata: a dangling link is still something to move or replace
+        if tokio::fs::symlink_metadata(&src).await.is_err() {
+            return Err(ToolError::NotFound(tr("source.missing", &[&source])));
+        }
+
+        // The same path, a directory moved into itself, or a destination
+        // holding the source: replacing the destination would destroy it
+        let src_abs =
+            resolve_parent(&src).map_err(|e| ToolError::io(&e, tr("move.failed", &[&e])))?;
+        let dst_abs =
+            resolve_parent(&dst).map_err(|e| ToolError::io(&e, tr("move.failed", &[&e])))?;
+        if dst_abs.starts_with(&src_abs) || src_abs.starts_with(&dst_abs) {
+            return Err(ToolError::InvalidParameters(tr(
+                "move.onto_itself",
+                &[&source],
             )));
         }
 
-        if dst.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Destination '{}' existe déjà",
-                destination
+        let overwritten = tokio::fs::symlink_metadata(&dst).await.is_ok();
+        if overwritten && !overwrite {
+            return Err(ToolError::AlreadyExists(tr(
+                "move.destination_exists",
+                &[&destination],
             )));
         }
 
+        if params["dry_run"].as_bool().unwrap_or(false) {
+            return Ok(dry_run_result(
+                serde_json::json!({
+                    "source": source,
+                    "destination": destination,
+                    "overwritten": overwritten
+                }),
+                tr("move.done", &[&source, &destination]),
+            ));
+        }
+
         // Create parent directories
         if let Some(parent) = dst.parent() {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::io(&e, tr("dir.create_failed", &[&e])))?;
             }
         }
 
-        tokio::fs::rename(&src, &dst)
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de déplacer: {}", e)))?;
+        // The replaced destination goes to the trash like a deleted file
+        // (rename() would not replace a non-empty directory anyway)
+        let mut trash_path = None;
+        if overwritten {
+            let moved = move_to_project_trash(&dst)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("move.replace_failed", &[&e])))?;
+            trash_path = Some(moved.display().to_string());
+        }
+
+        let strategy = move_path(&src, &dst, |from, to| async move {
+            tokio::fs::rename(from, to).await
+        })
+        .await
+        .map_err(|e| ToolError::io(&e, tr("move.failed", &[&e])))?;
 
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
                 "source": source,
-                "destination": destination
+                "destination": destination,
+                "strategy": strategy.as_str(),
+                "overwritten": overwritten,
+                "trash_path": trash_path
             }),
-            message: format!("Déplacé: {} -> {}", source, destination),
+            message: tr("move.done", &[&source, &destination]),
         })
     }
 }
 
+/// How `file_move` got the source to its destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MoveStrategy {
+    Rename,
+    /// Cross-device move: copied, then the source was removed
+    CopyDelete,
+}
+
+impl MoveStrategy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rename => "rename",
+            Self::CopyDelete => "copy_delete",
+        }
+    }
+}
+
+/// Move `src` to `dst` with `rename`, falling back to copy + delete when the
+/// rename fails because the two paths are on different filesystems (EXDEV).
+/// `rename` is a parameter so the fallback can be exercised in tests.
+async fn move_path<F, Fut>(src: &Path, dst: &Path, rename: F) -> std::io::Result<MoveStrategy>
+where
+    F: FnOnce(PathBuf, PathBuf) -> Fut,
+    Fut: std::future::Future<Output = std::io::Result<()>>,
+{
+    match rename(src.to_path_buf(), dst.to_path_buf()).await {
+        Ok(()) => return Ok(MoveStrategy::Rename),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {}
+        Err(e) => return Err(e),
+    }
+
+    let file_type = tokio::fs::symlink_metadata(src).await?.file_type();
+    if file_type.is_symlink() {
+        copy_symlink(src, dst, true).await?;
+        tokio::fs::remove_file(src).await?;
+    } else if file_type.is_dir() {
+        let options = CopyOptions {
+            overwrite: true,
+            follow_symlinks: false,
+            preserve: true,
+            recursive: true,
+            skip_build_dirs: false,
+            cancel: CancellationToken::new(),
+        };
+        let src_abs = tokio::fs::canonicalize(src).await?;
+        copy_tree(
+            src,
+            dst,
+            &options,
+            &mut vec![src_abs],
+            &mut CopyStats::default(),
+        )
+        .await?;
+        tokio::fs::remove_dir_all(src).await?;
+    } else {
+        tokio::fs::copy(src, dst).await?;
+        preserve_metadata(src, dst)?;
+        tokio::fs::remove_file(src).await?;
+    }
+    Ok(MoveStrategy::CopyDelete)
+}
+
 // ============================================================================
 // FileInfoTool - Get file metadata
 // ============================================================================
@@ -438,7 +1411,7 @@ impl Tool for FileInfoTool {
     }
 
     fn description(&self) -> &str {
-        "Get detailed information about a file or directory (size, permissions, timestamps, type)."
+        "Get detailed information about a file or directory (size, permissions, timestamps, type, MIME type, language). Optionally computes a checksum (sha256, md5 or blake3) of a file. Symlinks are followed unless follow_symlinks=false; link_target always shows where a link points."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -448,6 +1421,20 @@ impl Tool for FileInfoTool {
                 "path": {
                     "type": "string",
                     "description": "Path to the file or directory"
+                },
+                "checksum": {
+                    "type": "string",
+                    "enum": ["sha256", "md5", "blake3"],
+                    "description": "Also compute a content hash of the file with this algorithm (off by default, reads the whole file)"
+                },
+                "follow_symlinks": {
+                    "type": "boolean",
+                    "description": "Describe what a symlink points to (default: true); false describes the link itself",
+                    "default": true
+                },
+                "git": {
+                    "type": "boolean",
+                    "description": "Also report the git status of the path (clean, modified, staged, untracked, ignored) and the current branch (default: false)"
                 }
             },
             "required": ["path"]
@@ -458,11 +1445,53 @@ impl Tool for FileInfoTool {
         let path = params["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let checksum_algorithm = match params.get("checksum") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(ChecksumAlgorithm::parse(value)?),
+        };
+
+        let follow_symlinks = params["follow_symlinks"].as_bool().unwrap_or(true);
 
         let path_buf = PathBuf::from(path);
-        let metadata = tokio::fs::metadata(&path_buf)
+        let link_metadata = tokio::fs::symlink_metadata(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire les métadonnées: {}", e)))?;
+            .map_err(|e| ToolError::io(&e, tr("metadata.read_failed", &[&e])))?;
+        let is_symlink = link_metadata.is_symlink();
+        let metadata = if is_symlink && follow_symlinks {
+            tokio::fs::metadata(&path_buf)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("metadata.read_failed", &[&e])))?
+        } else {
+            link_metadata
+        };
+        let (link_target, link_resolved) = if is_symlink {
+            (
+                tokio::fs::read_link(&path_buf)
+                    .await
+                    .ok()
+                    .map(|t| t.display().to_string()),
+                tokio::fs::canonicalize(&path_buf)
+                    .await
+                    .ok()
+                    .map(|t| t.display().to_string()),
+            )
+        } else {
+            (None, None)
+        };
+
+        let checksum = match checksum_algorithm {
+            Some(_) if !metadata.is_file() => {
+                return Err(ToolError::InvalidParameters(
+                    "checksum is only available for files".into(),
+                ))
+            }
+            Some(algorithm) => Some(
+                file_checksum(&path_buf, algorithm)
+                    .await
+                    .map_err(|e| ToolError::io(&e, tr("file.read_failed", &[&e])))?,
+            ),
+            None => None,
+        };
 
         let file_type = if metadata.is_file() {
             "file"
@@ -476,18 +1505,18 @@ impl Tool for FileInfoTool {
 
         let size = metadata.len();
         let readonly = metadata.permissions().readonly();
+        let mode = unix_mode(&metadata).map(|m| format!("{:04o}", m));
 
-        let modified = metadata
-            .modified()
-            .ok()
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        let created = metadata
-            .created()
-            .ok()
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
+        let modified_time = metadata.modified().ok();
+        let created_time = metadata.created().ok();
+        let epoch_secs = |t: std::time::SystemTime| {
+            t.duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs())
+        };
+        let modified = modified_time.and_then(epoch_secs);
+        let created = created_time.and_then(epoch_secs);
+        let modified_relative = modified_time.map(|t| format_relative(t, chrono::Local::now()));
 
         // Get extension and line count for files
         let extension = path_buf
@@ -496,9 +1525,29 @@ impl Tool for FileInfoTool {
             .unwrap_or("")
             .to_string();
 
-        let line_count = if metadata.is_file() && size < 10_000_000 {
-            // Only count lines for files < 10MB
-            tokio::fs::read_to_string(&path_buf)
+        // Sniff the first bytes once: they decide both the MIME type of
+        // extensionless files and whether lines are worth counting
+        let prefix = if metadata.is_file() {
+            match tokio::fs::File::open(&path_buf).await {
+                Ok(mut file) => read_prefix(&mut file).await.ok(),
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+        let is_binary = prefix.as_deref().is_some_and(looks_binary);
+        let mime_type = prefix
+            .as_deref()
+            .map(|prefix| sniff_mime_type(&path_buf, prefix, is_binary));
+        let language = if metadata.is_file() {
+            detect_language(&path_buf)
+        } else {
+            None
+        };
+
+        let line_count = if metadata.is_file() && size < 10_000_000 && !is_binary {
+            // Only count lines for text files < 10MB
+            read_text_file(&path_buf)
                 .await
                 .ok()
                 .map(|c| c.lines().count())
@@ -508,98 +1557,512 @@ impl Tool for FileInfoTool {
 
         let size_human = format_size(size);
 
+        let mut data = serde_json::json!({
+            "path": path,
+            "type": file_type,
+            "is_symlink": is_symlink,
+            "link_target": link_target,
+            "link_resolved": link_resolved,
+            "size": size,
+            "size_human": size_human,
+            "readonly": readonly,
+            "mode": mode,
+            "extension": extension,
+            "mime_type": mime_type,
+            "language": language,
+            "binary": is_binary,
+            "modified_timestamp": modified,
+            "created_timestamp": created,
+            "modified_iso": modified_time.map(format_iso),
+            "created_iso": created_time.map(format_iso),
+            "modified_relative": modified_relative,
+            "checksum": checksum,
+            "checksum_algorithm": checksum_algorithm.map(ChecksumAlgorithm::name),
+            "line_count": line_count
+        });
+
+        let mut git_note = String::new();
+        if params["git"].as_bool().unwrap_or(false) {
+            let git = git_path_status(&path_buf, metadata.is_dir()).await;
+            if let Some((status, _)) = &git {
+                git_note = tr("info.git", &[status]);
+            }
+            data["git_status"] = serde_json::json!(git.as_ref().map(|(status, _)| status));
+            data["git_branch"] = serde_json::json!(git.and_then(|(_, branch)| branch));
+        }
+
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({
-                "path": path,
-                "type": file_type,
-                "size": size,
-                "size_human": size_human,
-                "readonly": readonly,
-                "extension": extension,
-                "modified_timestamp": modified,
-                "created_timestamp": created,
-                "line_count": line_count
-            }),
+            data,
             message: format!(
-                "{}: {} ({}, {}{})",
+                "{}: {} ({}, {}{}{})",
                 path,
                 file_type,
                 size_human,
-                if readonly { "lecture seule" } else { "lecture/écriture" },
+                tr(
+                    if readonly {
+                        "perm.readonly"
+                    } else {
+                        "perm.readwrite"
+                    },
+                    &[]
+                ),
                 line_count
-                    .map(|c| format!(", {} lignes", c))
-                    .unwrap_or_default()
+                    .map(|c| tr("info.lines", &[&c]))
+                    .unwrap_or_default(),
+                git_note
             ),
         })
     }
 }
 
-// ============================================================================
-// DirectoryCreateTool - mkdir -p
-// ============================================================================
-
-pub struct DirectoryCreateTool;
+/// Git status of `path` (`clean`, `modified`, `staged`, `untracked` or
+/// `ignored`) and the current branch (`None` when detached), or `None` when
+/// the path is not in a repository or git is unavailable. For a directory,
+/// the most significant status of its entries wins.
+async fn git_path_status(path: &Path, is_dir: bool) -> Option<(&'static str, Option<String>)> {
+    let dir = if is_dir { path } else { path.parent()? };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let dir_str = dir.to_str()?;
+    // Resolve the parent only, so a symlink reports the status of the link
+    let target = match (is_dir, path.file_name()) {
+        (false, Some(name)) => std::fs::canonicalize(dir).ok()?.join(name),
+        _ => std::fs::canonicalize(path).ok()?,
+    };
+    let target = target.to_str()?;
 
-#[async_trait]
-impl Tool for DirectoryCreateTool {
-    fn name(&self) -> &str {
-        "directory_create"
+    let (_, _, in_repo) = run_git(&["rev-parse", "--is-inside-work-tree"], Some(dir_str))
+        .await
+        .ok()?;
+    if !in_repo {
+        return None;
     }
 
-    fn description(&self) -> &str {
-        "Create a directory and all parent directories if they don't exist (like mkdir -p). REQUIRES APPROVAL."
+    let (porcelain, _, ok) = run_git(
+        &["status", "--porcelain=v1", "--ignored", "--", target],
+        Some(dir_str),
+    )
+    .await
+    .ok()?;
+    if !ok {
+        return None;
     }
+    let (branch, _, _) = run_git(&["branch", "--show-current"], Some(dir_str))
+        .await
+        .ok()?;
 
-    fn parameters_schema(&self) -> Value {
-        serde_json::json!({
-            "type": "object",
-            "properties": {
-                "path": {
-                    "type": "string",
-                    "description": "Path of the directory to create"
-                }
-            },
-            "required": ["path"]
-        })
+    let mut status = "clean";
+    let rank = |s: &str| match s {
+        "modified" => 4,
+        "staged" => 3,
+        "untracked" => 2,
+        "ignored" => 1,
+        _ => 0,
+    };
+    for line in porcelain.lines() {
+        let code = line.get(..2).unwrap_or("");
+        let line_status = match code {
+            "??" => "untracked",
+            "!!" => "ignored",
+            _ if code.as_bytes().get(1).is_some_and(|&y| y != b' ') => "modified",
+            _ => "staged",
+        };
+        if rank(line_status) > rank(status) {
+            status = line_status;
+        }
     }
 
-    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
-        let path = params["path"]
-            .as_str()
-            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+    let branch = branch.trim();
+    Some((status, (!branch.is_empty()).then(|| branch.to_string())))
+}
 
-        let path_buf = PathBuf::from(path);
+/// MIME type of a file: from its extension when it has a known one, else
+/// from the magic bytes at the start of its content
+fn sniff_mime_type(path: &Path, prefix: &[u8], is_binary: bool) -> String {
+    if let Some(mime) = mime_guess::from_path(path).first() {
+        return mime.essence_str().to_string();
+    }
+    if let Some(kind) = infer::get(prefix) {
+        return kind.mime_type().to_string();
+    }
+    if is_binary {
+        "application/octet-stream".to_string()
+    } else {
+        "text/plain".to_string()
+    }
+}
 
-        if path_buf.exists() {
-            if path_buf.is_dir() {
-                return Ok(ToolResult {
-                    success: true,
-                    data: serde_json::json!({ "path": path, "already_existed": true }),
-                    message: format!("Le dossier existe déjà: {}", path),
-                });
-            } else {
-                return Err(ToolError::ExecutionFailed(format!(
-                    "Un fichier existe déjà à ce chemin: {}",
-                    path
-                )));
-            }
+/// Hash algorithms offered by the `checksum` parameter of `file_info`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChecksumAlgorithm {
+    Sha256,
+    Md5,
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    fn parse(value: &Value) -> Result<Self, ToolError> {
+        match value
+            .as_str()
+            .map(|s| s.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("sha256") | Some("sha-256") => Ok(Self::Sha256),
+            Some("md5") => Ok(Self::Md5),
+            Some("blake3") => Ok(Self::Blake3),
+            _ => Err(ToolError::InvalidParameters(format!(
+                "checksum must be \"sha256\", \"md5\" or \"blake3\", got {}",
+                value
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Md5 => "md5",
+            Self::Blake3 => "blake3",
+        }
+    }
+}
+
+/// Hex digest of the file at `path`, read in chunks so large files are
+/// never held in memory
+async fn file_checksum(path: &Path, algorithm: ChecksumAlgorithm) -> std::io::Result<String> {
+    use md5::Digest as _;
+    use tokio::io::AsyncReadExt;
+
+    enum Hasher {
+        Sha256(sha2::Sha256),
+        Md5(md5::Md5),
+        Blake3(Box<blake3::Hasher>),
+    }
+
+    let mut hasher = match algorithm {
+        ChecksumAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+        ChecksumAlgorithm::Md5 => Hasher::Md5(md5::Md5::new()),
+        ChecksumAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+    };
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        match &mut hasher {
+            Hasher::Sha256(h) => h.update(&buf[..n]),
+            Hasher::Md5(h) => h.update(&buf[..n]),
+            Hasher::Blake3(h) => {
+                h.update(&buf[..n]);
+            }
+        }
+    }
+
+    Ok(match hasher {
+        Hasher::Sha256(h) => format!("{:x}", h.finalize()),
+        Hasher::Md5(h) => format!("{:x}", h.finalize()),
+        Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+    })
+}
+
+/// Best-effort programming or markup language of a file, from its name
+fn detect_language(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?;
+    let by_name = match file_name {
+        "Makefile" | "makefile" | "GNUmakefile" => Some("Makefile"),
+        "Dockerfile" | "Containerfile" => Some("Dockerfile"),
+        "CMakeLists.txt" => Some("CMake"),
+        "Cargo.lock" => Some("TOML"),
+        "Gemfile" | "Rakefile" => Some("Ruby"),
+        _ => None,
+    };
+    if by_name.is_some() {
+        return by_name;
+    }
+
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let language = match extension.as_str() {
+        "rs" => "Rust",
+        "py" | "pyi" | "pyw" => "Python",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "jsx" => "JavaScript (JSX)",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" => "TypeScript (TSX)",
+        "go" => "Go",
+        "c" => "C",
+        "h" => "C Header",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "cs" => "C#",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "lua" => "Lua",
+        "zig" => "Zig",
+        "dart" => "Dart",
+        "scala" => "Scala",
+        "hs" => "Haskell",
+        "ex" | "exs" => "Elixir",
+        "erl" => "Erlang",
+        "ml" | "mli" => "OCaml",
+        "r" => "R",
+        "jl" => "Julia",
+        "sh" | "bash" | "zsh" => "Shell",
+        "fish" => "Fish",
+        "ps1" | "psm1" => "PowerShell",
+        "bat" | "cmd" => "Batch",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "Sass",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "md" | "markdown" => "Markdown",
+        "rst" => "reStructuredText",
+        "tex" => "LaTeX",
+        "json" => "JSON",
+        "jsonc" | "json5" => "JSON with Comments",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "xml" => "XML",
+        "svg" => "SVG",
+        "ini" | "cfg" => "INI",
+        "proto" => "Protocol Buffers",
+        "graphql" | "gql" => "GraphQL",
+        "tf" => "Terraform",
+        "nix" => "Nix",
+        "vim" => "Vim Script",
+        "txt" => "Plain Text",
+        _ => return None,
+    };
+    Some(language)
+}
+
+// ============================================================================
+// FilePermissionsTool - Read and set permission bits (chmod)
+// ============================================================================
+
+pub struct FilePermissionsTool;
+
+#[async_trait]
+impl Tool for FilePermissionsTool {
+    fn name(&self) -> &str {
+        "file_chmod"
+    }
+
+    fn description(&self) -> &str {
+        "Read or change the permissions of a file or directory. On Unix, mode sets the octal permission bits (e.g. \"755\" to make a script executable). On Windows only the read-only flag exists: use readonly, or a mode whose owner write bit decides it. Without mode or readonly, the current permissions are returned."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the file or directory"
+                },
+                "mode": {
+                    "type": "string",
+                    "description": "Octal mode such as \"755\", \"644\" or \"0600\""
+                },
+                "readonly": {
+                    "type": "boolean",
+                    "description": "Set (true) or clear (false) the read-only flag instead of giving a mode"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+        let mode = params["mode"].as_str().map(parse_mode).transpose()?;
+        let readonly = params["readonly"].as_bool();
+        if mode.is_some() && readonly.is_some() {
+            return Err(ToolError::InvalidParameters(
+                "give either mode or readonly, not both".into(),
+            ));
+        }
+
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("metadata.read_failed", &[&e])))?;
+        let mode_before = unix_mode(&metadata);
+        let readonly_before = metadata.permissions().readonly();
+
+        let mut permissions = metadata.permissions();
+        if let Some(mode) = mode {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                permissions.set_mode(mode);
+            }
+            #[cfg(not(unix))]
+            {
+                // No mode bits to set: the owner write bit decides read-only
+                permissions.set_readonly(mode & 0o200 == 0);
+            }
+        } else if let Some(readonly) = readonly {
+            permissions.set_readonly(readonly);
+        }
+        let note = (mode.is_some() && !cfg!(unix)).then(|| tr("chmod.modes_unsupported", &[]));
+        if mode.is_some() || readonly.is_some() {
+            tokio::fs::set_permissions(path, permissions)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("chmod.failed", &[&e])))?;
+        }
+
+        let after = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| ToolError::io(&e, tr("metadata.read_failed", &[&e])))?;
+        let mode_after = unix_mode(&after);
+        let readonly_after = after.permissions().readonly();
+        let show = |mode: Option<u32>, readonly: bool| match mode {
+            Some(mode) => format!("{:04o}", mode),
+            None if readonly => tr("perm.readonly", &[]),
+            None => tr("perm.readwrite", &[]),
+        };
+
+        let mut message = if mode.is_none() && readonly.is_none() {
+            tr("chmod.current", &[&path, &show(mode_after, readonly_after)])
+        } else {
+            tr(
+                "chmod.changed",
+                &[
+                    &path,
+                    &show(mode_before, readonly_before),
+                    &show(mode_after, readonly_after),
+                ],
+            )
+        };
+        if let Some(note) = note {
+            message.push_str(&format!(" ({})", note));
+        }
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "mode_before": mode_before.map(|m| format!("{:04o}", m)),
+                "mode_after": mode_after.map(|m| format!("{:04o}", m)),
+                "readonly_before": readonly_before,
+                "readonly_after": readonly_after,
+                "modes_supported": cfg!(unix)
+            }),
+            message,
+        })
+    }
+}
+
+/// Parse an octal mode string (`"755"`, `"0644"`, `"0o600"`)
+fn parse_mode(mode: &str) -> Result<u32, ToolError> {
+    let digits = mode.trim().trim_start_matches("0o");
+    let invalid = || {
+        ToolError::InvalidParameters(format!(
+            "invalid mode '{}' (expected octal digits such as \"755\")",
+            mode
+        ))
+    };
+    if digits.is_empty() || digits.len() > 4 {
+        return Err(invalid());
+    }
+    u32::from_str_radix(digits, 8).map_err(|_| invalid())
+}
+
+/// Permission bits (`st_mode & 0o7777`) on Unix, `None` elsewhere
+fn unix_mode(metadata: &std::fs::Metadata) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Some(metadata.permissions().mode() & 0o7777)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+// ============================================================================
+// DirectoryCreateTool - mkdir -p
+// ============================================================================
+
+pub struct DirectoryCreateTool;
+
+#[async_trait]
+impl Tool for DirectoryCreateTool {
+    fn name(&self) -> &str {
+        "directory_create"
+    }
+
+    fn description(&self) -> &str {
+        "Create a directory and all parent directories if they don't exist (like mkdir -p)."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path of the directory to create"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidParameters("path is required".into()))?;
+
+        let path_buf = PathBuf::from(path);
+
+        if path_buf.exists() {
+            if path_buf.is_dir() {
+                return Ok(ToolResult {
+                    success: true,
+                    data: serde_json::json!({ "path": path, "already_existed": true }),
+                    message: tr("mkdir.exists", &[&path]),
+                });
+            } else {
+                return Err(ToolError::AlreadyExists(tr("mkdir.file_exists", &[&path])));
+            }
         }
 
         tokio::fs::create_dir_all(&path_buf)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+            .map_err(|e| ToolError::io(&e, tr("dir.create_failed", &[&e])))?;
 
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({ "path": path, "created": true }),
-            message: format!("Dossier créé: {}", path),
+            message: tr("mkdir.done", &[&path]),
         })
     }
 }
 
 // ============================================================================
-// FileCopyTool - Copy files
+// FileCopyTool - Copy files and directory trees
 // ============================================================================
 
 pub struct FileCopyTool;
@@ -611,7 +2074,11 @@ impl Tool for FileCopyTool {
     }
 
     fn description(&self) -> &str {
-        "Copy a file to a new location. Creates parent directories automatically. REQUIRES APPROVAL."
+        "Copy a file or a whole directory tree to a new location. Creates parent directories automatically. Keeps the source's permissions (including the executable bit) and modification time unless preserve=false.\nFails if the destination exists unless overwrite=true (for directories, existing files inside are then replaced and the trees merged).\nDirectories are copied recursively, skipping build/VCS folders (.git, node_modules, target, __pycache__) unless skip_build_dirs=false; recursive=false copies only the top-level files.\nSymlinks (the source itself or inside a copied directory) are recreated as symlinks pointing at the same target by default; set follow_symlinks=true to copy what they point to instead."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -620,11 +2087,41 @@ impl Tool for FileCopyTool {
             "properties": {
                 "source": {
                     "type": "string",
-                    "description": "Source file path"
+                    "description": "Source file or directory path"
                 },
                 "destination": {
                     "type": "string",
-                    "description": "Destination file path"
+                    "description": "Destination path"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace existing destination files (default: false)",
+                    "default": false
+                },
+                "follow_symlinks": {
+                    "type": "boolean",
+                    "description": "Copy the targets of symlinks, including a symlinked source, instead of recreating the links (default: false)",
+                    "default": false
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "For directories: copy subdirectories too (default: true). false copies only the top-level files.",
+                    "default": true
+                },
+                "skip_build_dirs": {
+                    "type": "boolean",
+                    "description": "Skip .git, node_modules, target and __pycache__ directories (default: true)",
+                    "default": true
+                },
+                "preserve": {
+                    "type": "boolean",
+                    "description": "Copy permissions and modification time from the source (default: true)",
+                    "default": true
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check source and destination and report the copy without performing it (default: false)",
+                    "default": false
                 }
             },
             "required": ["source", "destination"]
@@ -632,6 +2129,15 @@ impl Tool for FileCopyTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, &ToolContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
         let source = params["source"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("source is required".into()))?;
@@ -640,38 +2146,309 @@ impl Tool for FileCopyTool {
             .ok_or_else(|| ToolError::InvalidParameters("destination is required".into()))?;
 
         let src = PathBuf::from(source);
-        if !src.exists() {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Source '{}' n'existe pas",
-                source
-            )));
+        let src_is_symlink = match tokio::fs::symlink_metadata(&src).await {
+            Ok(meta) => meta.is_symlink(),
+            Err(_) => false,
+        };
+        if !src.exists() && !src_is_symlink {
+            return Err(ToolError::NotFound(tr("source.missing", &[&source])));
         }
 
+        let dry_run = params["dry_run"].as_bool().unwrap_or(false);
         let dst = PathBuf::from(destination);
-        if let Some(parent) = dst.parent() {
+
+        let options = CopyOptions {
+            overwrite: params["overwrite"].as_bool().unwrap_or(false),
+            follow_symlinks: params["follow_symlinks"].as_bool().unwrap_or(false),
+            preserve: params["preserve"].as_bool().unwrap_or(true),
+            recursive: params["recursive"].as_bool().unwrap_or(true),
+            skip_build_dirs: params["skip_build_dirs"].as_bool().unwrap_or(true),
+            cancel: ctx.cancel.clone(),
+        };
+
+        // Everything is checked before the first directory is created, so a
+        // refused call leaves nothing behind
+        let dst_exists = tokio::fs::symlink_metadata(&dst).await.is_ok();
+        if dst_exists && !options.overwrite {
+            return Err(ToolError::AlreadyExists(tr(
+                "copy.destination_exists",
+                &[&destination],
+            )));
+        }
+        let src_abs = resolve_parent(&src).unwrap_or_else(|_| src.clone());
+        let dst_abs = resolve_parent(&dst).unwrap_or_else(|_| dst.clone());
+        if src_abs == dst_abs || (dst_exists && is_same_file(&src, &dst)) {
+            return Err(ToolError::InvalidParameters(tr(
+                "copy.onto_itself",
+                &[&source],
+            )));
+        }
+        let copies_tree = src.is_dir() && (!src_is_symlink || options.follow_symlinks);
+        if copies_tree {
+            let src_dir = std::fs::canonicalize(&src).unwrap_or_else(|_| src.clone());
+            if dst_abs.starts_with(&src_dir) {
+                return Err(ToolError::InvalidParameters(tr("copy.into_itself", &[])));
+            }
+        }
+
+        if let Some(parent) = dst.parent().filter(|_| !dry_run) {
             if !parent.exists() {
                 tokio::fs::create_dir_all(parent)
                     .await
-                    .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le dossier: {}", e)))?;
+                    .map_err(|e| ToolError::io(&e, tr("dir.create_failed", &[&e])))?;
+            }
+        }
+
+        if src_is_symlink && !options.follow_symlinks {
+            let target = tokio::fs::read_link(&src)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("copy.failed", &[&e])))?;
+            let data = serde_json::json!({
+                "source": source,
+                "destination": destination,
+                "type": "symlink",
+                "link_target": target.display().to_string()
+            });
+            let message = tr(
+                "copy.symlink_done",
+                &[&source, &destination, &target.display()],
+            );
+            if dry_run {
+                return Ok(dry_run_result(data, message));
             }
+            copy_symlink(&src, &dst, options.overwrite)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("copy.failed", &[&e])))?;
+            return Ok(ToolResult {
+                success: true,
+                data,
+                message,
+            });
+        }
+
+        if copies_tree {
+            let root = std::fs::canonicalize(&src).unwrap_or_else(|_| src.clone());
+            if dry_run {
+                return Ok(dry_run_result(
+                    serde_json::json!({
+                        "source": source,
+                        "destination": destination,
+                        "type": "directory",
+                        "preserved": options.preserve
+                    }),
+                    tr("copy.dir_preview", &[&source, &destination]),
+                ));
+            }
+
+            let mut stats = CopyStats::default();
+            copy_tree(&src, &dst, &options, &mut vec![root], &mut stats)
+                .await
+                .map_err(|e| {
+                    if options.cancel.is_cancelled() {
+                        ToolError::Cancelled
+                    } else {
+                        ToolError::io(&e, tr("copy.failed", &[&e]))
+                    }
+                })?;
+
+            return Ok(ToolResult {
+                success: true,
+                data: serde_json::json!({
+                    "source": source,
+                    "destination": destination,
+                    "files": stats.files,
+                    "directories": stats.directories,
+                    "symlinks": stats.symlinks,
+                    "skipped_directories": stats.skipped_directories,
+                    "bytes": stats.bytes,
+                    "preserved": options.preserve
+                }),
+                message: tr(
+                    "copy.dir_done",
+                    &[
+                        &source,
+                        &destination,
+                        &stats.files,
+                        &format_size(stats.bytes),
+                    ],
+                ),
+            });
         }
 
+        let preserve = options.preserve;
+        if dry_run {
+            let bytes = tokio::fs::metadata(&src)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            return Ok(dry_run_result(
+                serde_json::json!({
+                    "source": source,
+                    "destination": destination,
+                    "type": "file",
+                    "bytes": bytes,
+                    "preserved": preserve
+                }),
+                tr("copy.done", &[&source, &destination, &bytes]),
+            ));
+        }
+        // Unlinked first: copying onto a symlink would write through it
+        if dst_exists && !dst.is_dir() {
+            tokio::fs::remove_file(&dst)
+                .await
+                .map_err(|e| ToolError::io(&e, tr("copy.failed", &[&e])))?;
+        }
         let bytes = tokio::fs::copy(&src, &dst)
             .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de copier: {}", e)))?;
+            .map_err(|e| ToolError::io(&e, tr("copy.failed", &[&e])))?;
+
+        let mut data = serde_json::json!({
+            "source": source,
+            "destination": destination,
+            "bytes": bytes,
+            "preserved": preserve
+        });
+        if preserve {
+            let mode = preserve_metadata(&src, &dst)
+                .map_err(|e| ToolError::io(&e, tr("copy.preserve_failed", &[&e])))?;
+            if let Some(mode) = mode {
+                data["mode"] = serde_json::json!(format!("{:04o}", mode));
+            }
+        }
 
         Ok(ToolResult {
             success: true,
-            data: serde_json::json!({
-                "source": source,
-                "destination": destination,
-                "bytes": bytes
-            }),
-            message: format!("Copié: {} -> {} ({} octets)", source, destination, bytes),
+            data,
+            message: tr("copy.done", &[&source, &destination, &bytes]),
         })
     }
 }
 
+/// How `copy_tree` treats existing files, symlinks and metadata
+struct CopyOptions {
+    overwrite: bool,
+    follow_symlinks: bool,
+    preserve: bool,
+    /// Descend into subdirectories (otherwise only top-level files are copied)
+    recursive: bool,
+    /// Leave out `SKIPPED_DIR_NAMES`
+    skip_build_dirs: bool,
+    /// Checked before every entry; the copy stops half done when triggered
+    cancel: CancellationToken,
+}
+
+#[derive(Default)]
+struct CopyStats {
+    files: usize,
+    directories: usize,
+    symlinks: usize,
+    /// Subdirectories left out (build/VCS folders, or all of them when not recursive)
+    skipped_directories: usize,
+    bytes: u64,
+}
+
+/// Recursively copy the directory `src` to `dst`.
+///
+/// Symlinks are recreated as links to the same target unless
+/// `follow_symlinks` is set, in which case their targets are copied;
+/// `ancestors` holds the canonical directories being copied so a link back up
+/// the tree is reported instead of recursing forever.
+fn copy_tree<'a>(
+    src: &'a Path,
+    dst: &'a Path,
+    options: &'a CopyOptions,
+    ancestors: &'a mut Vec<PathBuf>,
+    stats: &'a mut CopyStats,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = std::io::Result<()>> + Send + 'a>> {
+    Box::pin(async move {
+        tokio::fs::create_dir_all(dst).await?;
+        stats.directories += 1;
+
+        let mut entries = tokio::fs::read_dir(src).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if options.cancel.is_cancelled() {
+                return Err(std::io::ErrorKind::Interrupted.into());
+            }
+            let from = entry.path();
+            let to = dst.join(entry.file_name());
+            let mut file_type = entry.file_type().await?;
+
+            if file_type.is_symlink() {
+                if !options.follow_symlinks {
+                    copy_symlink(&from, &to, options.overwrite).await?;
+                    stats.symlinks += 1;
+                    continue;
+                }
+                file_type = tokio::fs::metadata(&from).await?.file_type();
+            }
+
+            if file_type.is_dir() {
+                let is_build_dir = SKIPPED_DIR_NAMES
+                    .iter()
+                    .any(|name| entry.file_name() == *name);
+                if !options.recursive || (options.skip_build_dirs && is_build_dir) {
+                    stats.skipped_directories += 1;
+                    continue;
+                }
+                let canonical = tokio::fs::canonicalize(&from).await?;
+                if ancestors.contains(&canonical) {
+                    return Err(std::io::Error::other(tr(
+                        "copy.symlink_loop",
+                        &[&from.display()],
+                    )));
+                }
+                ancestors.push(canonical);
+                copy_tree(&from, &to, options, ancestors, stats).await?;
+                ancestors.pop();
+            } else {
+                if !options.overwrite && tokio::fs::symlink_metadata(&to).await.is_ok() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        tr("path.exists", &[&to.display()]),
+                    ));
+                }
+                stats.bytes += tokio::fs::copy(&from, &to).await?;
+                stats.files += 1;
+                if options.preserve {
+                    preserve_metadata(&from, &to)?;
+                }
+            }
+        }
+
+        if options.preserve {
+            preserve_metadata(src, dst)?;
+        }
+        Ok(())
+    })
+}
+
+/// Recreate the symlink `from` at `to`, pointing at the same target.
+async fn copy_symlink(from: &Path, to: &Path, overwrite: bool) -> std::io::Result<()> {
+    let target = tokio::fs::read_link(from).await?;
+    if tokio::fs::symlink_metadata(to).await.is_ok() {
+        if !overwrite {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                tr("path.exists", &[&to.display()]),
+            ));
+        }
+        tokio::fs::remove_file(to).await?;
+    }
+
+    #[cfg(unix)]
+    {
+        tokio::fs::symlink(&target, to).await
+    }
+    #[cfg(windows)]
+    {
+        if tokio::fs::metadata(from).await.map(|m| m.is_dir()).unwrap_or(false) {
+            tokio::fs::symlink_dir(&target, to).await
+        } else {
+            tokio::fs::symlink_file(&target, to).await
+        }
+    }
+}
+
 // ============================================================================
 // FileSearchContentTool - Search file content with context
 // ============================================================================
@@ -685,7 +2462,7 @@ impl Tool for FileSearchContentTool {
     }
 
     fn description(&self) -> &str {
-        "Search for text content across files in a directory. Returns matching files with line numbers and context. More user-friendly than grep for simple text searches."
+        "Search for text content across files in a directory. Returns matching files with line numbers and context. More user-friendly than grep for simple text searches.\nPaths ignored by .gitignore files are skipped (which also keeps searches fast on large repos); set include_ignored=true to search them too.\nBinary files and files larger than max_file_size are skipped; the result reports how many."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -703,17 +2480,37 @@ impl Tool for FileSearchContentTool {
                 },
                 "file_pattern": {
                     "type": "string",
-                    "description": "File extension filter (e.g., 'rs', 'py', 'js')"
+                    "description": "Comma-separated extensions and/or globs on the file name (e.g., 'rs', 'rs,toml', '*.test.js')"
                 },
                 "case_sensitive": {
                     "type": "boolean",
                     "description": "Case sensitive search (default: false)",
                     "default": false
                 },
+                "whole_word": {
+                    "type": "boolean",
+                    "description": "Only match the query as a whole word, e.g. 'count' does not match 'accountant' (default: false)",
+                    "default": false
+                },
                 "max_results": {
                     "type": "integer",
                     "description": "Maximum results to return",
                     "default": 30
+                },
+                "include_ignored": {
+                    "type": "boolean",
+                    "description": "Also search paths ignored by .gitignore (default: false)",
+                    "default": false
+                },
+                "max_file_size": {
+                    "type": "integer",
+                    "description": "Skip files larger than this many bytes (default: 5 MB); they are listed in skipped_large_files",
+                    "default": SEARCH_DEFAULT_MAX_FILE_SIZE
+                },
+                "exclude": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Globs of paths to leave out, relative to path (e.g. ['tests/fixtures', '*.generated.rs']); patterns without '/' match any file or directory name. Applied on top of the built-in skip list"
                 }
             },
             "required": ["query", "path"]
@@ -721,137 +2518,3461 @@ impl Tool for FileSearchContentTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, &ToolContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        self.search(params, ctx, None).await
+    }
+
+    /// Sends `{"matches": [...]}` each time more matches are settled, in the
+    /// order of the final result
+    async fn execute_streaming(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+        progress: ToolProgress,
+    ) -> Result<ToolResult, ToolError> {
+        self.search(params, ctx, Some(progress)).await
+    }
+}
+
+impl FileSearchContentTool {
+    async fn search(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+        progress: Option<ToolProgress>,
+    ) -> Result<ToolResult, ToolError> {
         let query = params["query"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("query is required".into()))?;
         let path = params["path"].as_str().unwrap_or(".");
-        let file_pattern = params["file_pattern"].as_str();
+        let file_pattern = params["file_pattern"]
+            .as_str()
+            .map(FilePatterns::parse)
+            .transpose()?
+            .flatten();
+        let exclude = ExcludePatterns::from_params(&params)?;
         let case_sensitive = params["case_sensitive"].as_bool().unwrap_or(false);
+        let whole_word = params["whole_word"].as_bool().unwrap_or(false);
         let max_results = params["max_results"].as_u64().unwrap_or(30) as usize;
+        let include_ignored = params["include_ignored"].as_bool().unwrap_or(false);
+        let max_file_size = params["max_file_size"]
+            .as_u64()
+            .unwrap_or(SEARCH_DEFAULT_MAX_FILE_SIZE);
 
-        let search_query = if case_sensitive {
-            query.to_string()
+        let path_buf = PathBuf::from(path);
+
+        // .gitignore rules are matched against absolute paths, so resolve the
+        // root once and pick up any .gitignore files from enclosing directories.
+        let abs_root = std::fs::canonicalize(&path_buf).unwrap_or_else(|_| path_buf.clone());
+        let gitignores = if include_ignored {
+            None
         } else {
-            query.to_lowercase()
+            let workspace = std::env::current_dir().unwrap_or_else(|_| abs_root.clone());
+            Some(ancestor_gitignores(&abs_root, &workspace))
         };
 
-        let path_buf = PathBuf::from(path);
-        let mut results = Vec::new();
-
-        search_content_recursive(
-            &path_buf,
-            &search_query,
-            case_sensitive,
-            file_pattern,
-            &mut results,
+        let outcome = search_content(
+            SearchScope {
+                root: path_buf,
+                abs_root,
+                file_pattern,
+                exclude,
+                gitignores,
+                max_file_size,
+                cancel: ctx.cancel.clone(),
+                progress,
+            },
+            Arc::new(ContentMatcher::new(query, case_sensitive, whole_word)),
             max_results,
+            SEARCH_MAX_OPEN_FILES,
         )
         .await?;
 
-        let total = results.len();
+        let total = outcome.matches.len();
+        let mut message = tr("search.results", &[&total, &query]);
+        if outcome.skipped_files > 0 {
+            message.push_str(&tr("search.skipped", &[&outcome.skipped_files]));
+        }
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
-                "matches": results,
+                "matches": outcome.matches,
                 "total": total,
-                "query": query
+                "query": query,
+                "skipped_files": outcome.skipped_files,
+                "skipped_large_files": outcome.skipped_large_files,
+                "metrics": {
+                    "files_scanned": outcome.files_scanned,
+                    "bytes_read": outcome.bytes_read
+                }
             }),
-            message: format!("{} résultat(s) pour \"{}\"", total, query),
+            message,
         })
     }
 }
 
-fn search_content_recursive<'a>(
-    path: &'a PathBuf,
-    query: &'a str,
+/// Maximum number of files `file_search` reads at the same time.
+const SEARCH_MAX_OPEN_FILES: usize = 32;
+
+/// How many discovered files the directory walk may queue ahead of the scanners.
+const SEARCH_WALK_BUFFER: usize = 256;
+
+/// Files above this size are skipped by `file_search` unless overridden.
+const SEARCH_DEFAULT_MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Where `file_search` looks.
+struct SearchScope {
+    root: PathBuf,
+    abs_root: PathBuf,
+    file_pattern: Option<FilePatterns>,
+    /// User excludes, applied on top of `SKIPPED_DIR_NAMES` and .gitignore.
+    exclude: ExcludePatterns,
+    /// .gitignore matchers in scope, outermost first; `None` disables
+    /// .gitignore handling entirely (`include_ignored=true`).
+    gitignores: Option<Vec<Gitignore>>,
+    /// Files larger than this many bytes are skipped without being read.
+    max_file_size: u64,
+    /// Checked at every file and directory; the search stops with
+    /// `ToolError::Cancelled` once it is triggered.
+    cancel: CancellationToken,
+    /// Receives matches as they are settled, see `OrderedBuckets::take_settled`
+    progress: Option<ToolProgress>,
+}
+
+/// What `file_search` found.
+struct SearchOutcome {
+    matches: Vec<Value>,
+    /// Binary, oversized or unreadable files that were not searched.
+    skipped_files: usize,
+    /// `{file, size}` of the files skipped for exceeding `max_file_size`.
+    skipped_large_files: Vec<Value>,
+    /// Files read and searched, and their total size
+    files_scanned: usize,
+    bytes_read: u64,
+}
+
+/// What `file_search` looks for in each line.
+struct ContentMatcher {
+    query: String,
     case_sensitive: bool,
-    file_pattern: Option<&'a str>,
-    results: &'a mut Vec<Value>,
+    /// Only accept occurrences not glued to other word characters
+    whole_word: bool,
+}
+
+impl ContentMatcher {
+    fn new(query: &str, case_sensitive: bool, whole_word: bool) -> Self {
+        let query = if case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+        Self {
+            query,
+            case_sensitive,
+            whole_word,
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        if self.case_sensitive {
+            self.find_in(line)
+        } else {
+            self.find_in(&line.to_lowercase())
+        }
+    }
+
+    fn find_in(&self, line: &str) -> bool {
+        if !self.whole_word {
+            return line.contains(&self.query);
+        }
+        if self.query.is_empty() {
+            return false;
+        }
+
+        // A boundary is only required where the query itself starts or ends
+        // with a word character, so "foo(" still matches in "foo(x)"
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let needs_start = self.query.chars().next().is_some_and(is_word);
+        let needs_end = self.query.chars().next_back().is_some_and(is_word);
+
+        let mut from = 0;
+        while let Some(offset) = line[from..].find(&self.query) {
+            let start = from + offset;
+            let end = start + self.query.len();
+            let start_ok = !needs_start || !line[..start].chars().next_back().is_some_and(is_word);
+            let end_ok = !needs_end || !line[end..].chars().next().is_some_and(is_word);
+            if start_ok && end_ok {
+                return true;
+            }
+            // Retry one character further, occurrences may overlap
+            from = start + line[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        false
+    }
+}
+
+/// Search every file in `scope` for `matcher`, returning at most `max_results`
+/// matches.
+///
+/// The directory walk runs on its own task and streams files, in sorted
+/// directory order, to a pool that scans up to `max_open_files` of them
+/// concurrently. Each file's matches land in a bucket keyed by its position in
+/// the walk, and buckets are flattened in that order, so the output is the
+/// same as a sequential scan regardless of which reads finish first. Once the
+/// completed prefix of buckets holds `max_results` matches, the walk and any
+/// pending scans are stopped. The same happens when `scope.cancel` is
+/// triggered, except that the search then fails with `ToolError::Cancelled`.
+/// Matches go to `scope.progress` as soon as their bucket joins the
+/// completed prefix, so they arrive in their final order.
+async fn search_content(
+    scope: SearchScope,
+    matcher: Arc<ContentMatcher>,
     max_results: usize,
-) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), ToolError>> + Send + 'a>> {
-    Box::pin(async move {
-        if results.len() >= max_results {
-            return Ok(());
+    max_open_files: usize,
+) -> Result<SearchOutcome, ToolError> {
+    if max_results == 0 {
+        return Ok(SearchOutcome {
+            matches: Vec::new(),
+            skipped_files: 0,
+            skipped_large_files: Vec::new(),
+            files_scanned: 0,
+            bytes_read: 0,
+        });
+    }
+
+    let max_file_size = scope.max_file_size;
+    let cancel = scope.cancel.clone();
+    let progress = scope.progress.clone();
+    let report = |buckets: &mut OrderedBuckets| {
+        if let Some(progress) = &progress {
+            let fresh = buckets.take_settled(max_results);
+            if !fresh.is_empty() {
+                // The receiver may be gone; the search goes on regardless
+                let _ = progress.send(serde_json::json!({ "matches": fresh }));
+            }
         }
+    };
 
-        if path.is_file() {
-            // Check file pattern
-            if let Some(pattern) = file_pattern {
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ext != pattern {
-                    return Ok(());
-                }
+    let (tx, mut rx) = tokio::sync::mpsc::channel(SEARCH_WALK_BUFFER);
+    let walker = tokio::spawn(async move {
+        collect_search_files(
+            &scope.root,
+            &scope.abs_root,
+            Path::new(""),
+            &scope,
+            scope.gitignores.as_deref(),
+            &tx,
+        )
+        .await;
+    });
+
+    let semaphore = Arc::new(Semaphore::new(max_open_files.max(1)));
+    let mut scans = JoinSet::new();
+    let mut buckets = OrderedBuckets::default();
+    let mut next_index = 0;
+
+    while let Some(file) = rx.recv().await {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+        while let Some(Ok((index, scan))) = scans.try_join_next() {
+            buckets.insert(index, scan);
+        }
+        report(&mut buckets);
+        if buckets.settled_count() >= max_results {
+            break;
+        }
+
+        let matcher = matcher.clone();
+        let index = next_index;
+        next_index += 1;
+        scans.spawn(async move {
+            let _permit = permit;
+            (
+                index,
+                scan_file(&file, &matcher, max_results, max_file_size).await,
+            )
+        });
+    }
+
+    // Dropping the receiver makes the walker's next send fail, ending the walk
+    drop(rx);
+
+    if cancel.is_cancelled() {
+        scans.abort_all();
+        let _ = walker.await;
+        return Err(ToolError::Cancelled);
+    }
+
+    while let Some(joined) = scans.join_next().await {
+        if let Ok((index, scan)) = joined {
+            buckets.insert(index, scan);
+            report(&mut buckets);
+            // Anything still running comes after the settled prefix
+            if buckets.settled_count() >= max_results {
+                scans.abort_all();
             }
+        }
+    }
+    let _ = walker.await;
 
-            if let Ok(content) = tokio::fs::read_to_string(path).await {
-                for (i, line) in content.lines().enumerate() {
-                    if results.len() >= max_results {
-                        break;
-                    }
-                    let matches = if case_sensitive {
-                        line.contains(query)
-                    } else {
-                        line.to_lowercase().contains(query)
-                    };
-                    if matches {
-                        results.push(serde_json::json!({
-                            "file": path.display().to_string(),
-                            "line_number": i + 1,
-                            "content": line.trim()
-                        }));
-                    }
+    Ok(buckets.into_outcome(max_results))
+}
+
+/// The `file_pattern` filter of `file_search`, compiled once before the walk.
+struct FilePatterns(Vec<glob::Pattern>);
+
+impl FilePatterns {
+    /// Parse a comma-separated list of bare extensions (`rs`, `.toml`) and
+    /// globs on the file name (`*.test.js`). Returns `None` for an empty list.
+    fn parse(spec: &str) -> Result<Option<Self>, ToolError> {
+        let patterns = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let glob = if p.contains(['*', '?', '[']) {
+                    p.to_string()
+                } else {
+                    format!("*.{}", p.trim_start_matches('.'))
+                };
+                glob::Pattern::new(&glob).map_err(|e| {
+                    ToolError::InvalidParameters(format!("invalid file_pattern '{}': {}", p, e))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!patterns.is_empty()).then_some(Self(patterns)))
+    }
+
+    /// Whether the file name of `path` matches any of the patterns
+    fn matches(&self, path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        self.0.iter().any(|p| p.matches(name))
+    }
+}
+
+/// The `exclude` globs of `file_search`, compiled once before the walk.
+#[derive(Default)]
+struct ExcludePatterns(Vec<glob::Pattern>);
+
+impl ExcludePatterns {
+    fn from_params(params: &Value) -> Result<Self, ToolError> {
+        let patterns = match params.get("exclude") {
+            None | Some(Value::Null) => return Ok(Self::default()),
+            Some(Value::Array(items)) => items,
+            Some(_) => {
+                return Err(ToolError::InvalidParameters(
+                    "exclude must be an array of glob patterns".into(),
+                ))
+            }
+        };
+        patterns
+            .iter()
+            .map(|item| {
+                let pattern = item.as_str().ok_or_else(|| {
+                    ToolError::InvalidParameters("exclude must be an array of glob patterns".into())
+                })?;
+                let trimmed = pattern
+                    .trim()
+                    .trim_start_matches("./")
+                    .trim_end_matches('/');
+                glob::Pattern::new(trimmed).map_err(|e| {
+                    ToolError::InvalidParameters(format!("invalid exclude '{}': {}", pattern, e))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+
+    /// Whether the entry at `rel_path` (relative to the search root) is
+    /// excluded. Patterns without a `/` are also tried on its bare name.
+    fn matches(&self, rel_path: &Path) -> bool {
+        let rel = rel_path.to_string_lossy().replace('\\', "/");
+        let name = rel.rsplit('/').next().unwrap_or(&rel);
+        self.0
+            .iter()
+            .any(|p| p.matches(&rel) || (!p.as_str().contains('/') && p.matches(name)))
+    }
+}
+
+/// Result of scanning a single file.
+#[derive(Clone)]
+enum FileScan {
+    /// Matching lines, and the size of the file that was read
+    Matches { matches: Vec<Value>, bytes: u64 },
+    /// Larger than `max_file_size`, never opened for reading.
+    TooLarge {
+        file: String,
+        size: u64,
+    },
+    /// Binary or not readable as text.
+    Skipped,
+}
+
+/// Per-file scan results indexed by walk order.
+#[derive(Default)]
+struct OrderedBuckets {
+    buckets: Vec<Option<FileScan>>,
+    /// Length of the prefix of `buckets` that is fully filled in.
+    settled: usize,
+    /// Number of matches in that prefix.
+    settled_matches: usize,
+    /// Buckets of the prefix already handed out by `take_settled`, and
+    /// their matches
+    taken: usize,
+    taken_matches: usize,
+}
+
+impl OrderedBuckets {
+    fn insert(&mut self, index: usize, scan: FileScan) {
+        if self.buckets.len() <= index {
+            self.buckets.resize(index + 1, None);
+        }
+        self.buckets[index] = Some(scan);
+        while let Some(Some(scan)) = self.buckets.get(self.settled) {
+            if let FileScan::Matches { matches, .. } = scan {
+                self.settled_matches += matches.len();
+            }
+            self.settled += 1;
+        }
+    }
+
+    fn settled_count(&self) -> usize {
+        self.settled_matches
+    }
+
+    /// Matches settled since the last call, in walk order, up to
+    /// `max_results` over all calls
+    fn take_settled(&mut self, max_results: usize) -> Vec<Value> {
+        let mut fresh = Vec::new();
+        while self.taken < self.settled && self.taken_matches < max_results {
+            if let Some(FileScan::Matches { matches, .. }) = &self.buckets[self.taken] {
+                let room = max_results - self.taken_matches;
+                fresh.extend(matches.iter().take(room).cloned());
+                self.taken_matches += matches.len().min(room);
+            }
+            self.taken += 1;
+        }
+        fresh
+    }
+
+    /// Flatten buckets in walk order up to `max_results` matches. Skipped
+    /// files are only counted up to the point where the cap is reached, so
+    /// the count does not depend on which scans happened to finish.
+    fn into_outcome(self, max_results: usize) -> SearchOutcome {
+        let mut matches = Vec::new();
+        let mut skipped_files = 0;
+        let mut skipped_large_files = Vec::new();
+        let mut files_scanned = 0;
+        let mut bytes_read = 0;
+        for scan in self.buckets.into_iter().flatten() {
+            if matches.len() >= max_results {
+                break;
+            }
+            match scan {
+                FileScan::Matches {
+                    matches: found,
+                    bytes,
+                } => {
+                    files_scanned += 1;
+                    bytes_read += bytes;
+                    matches.extend(found);
+                }
+                FileScan::TooLarge { file, size } => {
+                    skipped_files += 1;
+                    skipped_large_files.push(serde_json::json!({ "file": file, "size": size }));
                 }
+                FileScan::Skipped => skipped_files += 1,
             }
-        } else if path.is_dir() {
-            let mut entries = match tokio::fs::read_dir(path).await {
-                Ok(e) => e,
-                Err(_) => return Ok(()),
-            };
+        }
+        matches.truncate(max_results);
+        SearchOutcome {
+            matches,
+            skipped_files,
+            skipped_large_files,
+            files_scanned,
+            bytes_read,
+        }
+    }
+}
+
+/// Scan one file, returning at most `limit` matching lines.
+///
+/// Only the first few KB are read up front: files over `max_file_size` are
+/// rejected from their metadata, and files with a NUL byte in the sniffed
+/// prefix are treated as binary, so neither is ever loaded whole.
+async fn scan_file(
+    path: &Path,
+    matcher: &ContentMatcher,
+    limit: usize,
+    max_file_size: u64,
+) -> FileScan {
+    use tokio::io::AsyncReadExt;
+
+    let Ok(mut file) = tokio::fs::File::open(path).await else {
+        return FileScan::Skipped;
+    };
+    match file.metadata().await {
+        Ok(meta) if meta.len() <= max_file_size => {}
+        Ok(meta) => {
+            return FileScan::TooLarge {
+                file: path.display().to_string(),
+                size: meta.len(),
+            }
+        }
+        Err(_) => return FileScan::Skipped,
+    }
+
+    let mut bytes = match read_prefix(&mut file).await {
+        Ok(prefix) if !looks_binary(&prefix) => prefix,
+        _ => return FileScan::Skipped,
+    };
+    if file.read_to_end(&mut bytes).await.is_err() {
+        return FileScan::Skipped;
+    }
+    let size = bytes.len() as u64;
+    let Ok(decoded) = decode_text(bytes, None) else {
+        return FileScan::Skipped;
+    };
+
+    let matches = decoded
+        .text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| matcher.is_match(line))
+        .take(limit)
+        .map(|(i, line)| {
+            serde_json::json!({
+                "file": path.display().to_string(),
+                "line_number": i + 1,
+                "content": line.trim()
+            })
+        })
+        .collect();
+    FileScan::Matches {
+        matches,
+        bytes: size,
+    }
+}
 
-            while let Ok(Some(entry)) = entries.next_entry().await {
-                if results.len() >= max_results {
-                    break;
+/// Walk `path` and send every file worth searching to `tx`, visiting directory
+/// entries in name order. Returns `false` once the receiver is gone or
+/// `scope.cancel` is triggered, which is checked before every file and
+/// directory. `gitignores` are the matchers in scope at `path`.
+///
+/// Ignored directories are pruned before descending, so huge build or vendor
+/// trees are never walked. The built-in skip list always applies on top of
+/// .gitignore, and is the only filter for trees without a .gitignore; the
+/// user's `exclude` globs are checked in addition to both.
+fn collect_search_files<'a>(
+    path: &'a Path,
+    abs_path: &'a Path,
+    rel_path: &'a Path,
+    scope: &'a SearchScope,
+    gitignores: Option<&'a [Gitignore]>,
+    tx: &'a tokio::sync::mpsc::Sender<PathBuf>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = bool> + Send + 'a>> {
+    Box::pin(async move {
+        if scope.cancel.is_cancelled() {
+            return false;
+        }
+        if path.is_file() {
+            if let Some(patterns) = &scope.file_pattern {
+                if !patterns.matches(path) {
+                    return true;
                 }
-                let name = entry.file_name().to_string_lossy().to_string();
-                if name.starts_with('.')
-                    || name == "node_modules"
-                    || name == "target"
-                    || name == "__pycache__"
-                    || name == ".git"
-                {
+            }
+            return tx.send(path.to_path_buf()).await.is_ok();
+        }
+        if !path.is_dir() {
+            return true;
+        }
+
+        let mut read_dir = match tokio::fs::read_dir(path).await {
+            Ok(e) => e,
+            Err(_) => return true,
+        };
+        let mut entries = Vec::new();
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            entries.push(entry);
+        }
+        entries.sort_by_key(|e| e.file_name());
+
+        // A .gitignore in this directory scopes over everything below it
+        let scoped: Option<Vec<Gitignore>> = gitignores.map(|outer| {
+            let mut scoped = outer.to_vec();
+            if let Some(local) = load_gitignore(abs_path) {
+                scoped.push(local);
+            }
+            scoped
+        });
+
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || SKIPPED_DIR_NAMES.contains(&name.as_str()) {
+                continue;
+            }
+            let entry_rel = rel_path.join(entry.file_name());
+            if scope.exclude.matches(&entry_rel) {
+                continue;
+            }
+            let entry_abs = abs_path.join(entry.file_name());
+            if let Some(matchers) = scoped.as_deref() {
+                let is_dir = entry
+                    .file_type()
+                    .await
+                    .map(|ft| ft.is_dir())
+                    .unwrap_or(false);
+                if is_gitignored(matchers, &entry_abs, is_dir) {
                     continue;
                 }
-                search_content_recursive(
-                    &entry.path(),
-                    query,
-                    case_sensitive,
-                    file_pattern,
-                    results,
-                    max_results,
-                )
-                .await?;
+            }
+            if !collect_search_files(
+                &entry.path(),
+                &entry_abs,
+                &entry_rel,
+                scope,
+                scoped.as_deref(),
+                tx,
+            )
+            .await
+            {
+                return false;
             }
         }
-        Ok(())
+        true
     })
 }
 
+/// Build a matcher from `dir/.gitignore`, if present and parseable.
+fn load_gitignore(dir: &Path) -> Option<Gitignore> {
+    let file = dir.join(".gitignore");
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&file) {
+        tracing::debug!("Ignoring malformed .gitignore {}: {}", file.display(), e);
+    }
+    builder.build().ok()
+}
+
+/// Collect .gitignore matchers from the directories enclosing `root`, up to
+/// and including the repository root, or the workspace root outside a
+/// repository (a .gitignore in $HOME is not the project's). Returned
+/// outermost first. The root's own .gitignore is loaded during traversal,
+/// and a single-file search is never filtered, so both are skipped here.
+fn ancestor_gitignores(root: &Path, workspace: &Path) -> Vec<Gitignore> {
+    let mut found = Vec::new();
+    if !root.is_dir() || root.join(".git").exists() {
+        return found;
+    }
+    let in_repo = root
+        .ancestors()
+        .skip(1)
+        .any(|dir| dir.join(".git").exists());
+    let workspace = std::fs::canonicalize(workspace).unwrap_or_else(|_| workspace.to_path_buf());
+    for dir in root.ancestors().skip(1) {
+        if !in_repo && !dir.starts_with(&workspace) {
+            break;
+        }
+        if let Some(gi) = load_gitignore(dir) {
+            found.push(gi);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    found.reverse();
+    found
+}
+
+/// Whether the innermost matching .gitignore rule ignores `path`.
+/// Deeper files take precedence, and a `!pattern` whitelist wins over
+/// ignores from enclosing directories.
+fn is_gitignored(matchers: &[Gitignore], path: &Path, is_dir: bool) -> bool {
+    for gi in matchers.iter().rev() {
+        let m = gi.matched(path, is_dir);
+        if m.is_ignore() {
+            return true;
+        }
+        if m.is_whitelist() {
+            return false;
+        }
+    }
+    false
+}
+
 // ============================================================================
-// Helpers
+// DirectoryTreeTool - Indented overview of a directory
 // ============================================================================
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
+pub struct DirectoryTreeTool {
+    /// Paths are resolved against it and may not leave it
+    workspace: PathBuf,
+}
 
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
+impl DirectoryTreeTool {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DirectoryTreeTool {
+    fn name(&self) -> &str {
+        "directory_tree"
+    }
+
+    fn description(&self) -> &str {
+        "Show the structure of a workspace directory as an indented tree with file sizes, down to max_depth. Skips hidden entries, build/VCS directories (.git, node_modules, target...) and, by default, paths ignored by .gitignore. Cheap orientation before reading files."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to show, relative to the workspace",
+                    "default": "."
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "How many levels below path to show (default: 3)",
+                    "default": 3
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "description": "Stop after this many files and directories (default: 200)",
+                    "default": TREE_DEFAULT_MAX_ENTRIES
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Leave out paths ignored by .gitignore (default: true)",
+                    "default": true
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, &ToolContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let path = params["path"].as_str().unwrap_or(".").to_string();
+        let max_depth = params["max_depth"].as_u64().unwrap_or(3) as usize;
+        let max_entries = params["max_entries"]
+            .as_u64()
+            .map_or(TREE_DEFAULT_MAX_ENTRIES, |n| n as usize);
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+
+        let root = resolve_in_workspace(&self.workspace, Some(&path))?;
+        if !root.is_dir() {
+            return Err(ToolError::InvalidParameters(tr("tree.not_dir", &[&path])));
+        }
+
+        let walk = DirWalk {
+            max_depth,
+            max_entries,
+            include_hidden: false,
+            respect_gitignore,
+            cancel: ctx.cancel.clone(),
+            ..DirWalk::default()
+        };
+        let (walk, entries) = walk.run(root, self.workspace.clone()).await?;
+        ctx.check_cancelled()?;
+
+        let mut nodes = Vec::new();
+        flatten_nodes(entries, 1, &mut nodes);
+        let tree = format!("{}/\n{}", path.trim_end_matches('/'), walk.lines);
+        let mut message = tr("tree.done", &[&path, &walk.directories, &walk.files]);
+        if walk.truncated {
+            message.push_str(&tr("tree.truncated", &[&max_entries]));
+        }
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "tree": tree,
+                "nodes": nodes,
+                "files": walk.files,
+                "directories": walk.directories,
+                "truncated": walk.truncated
+            }),
+            message,
+        })
+    }
+}
+
+/// Entries `directory_tree` lists unless told otherwise.
+const TREE_DEFAULT_MAX_ENTRIES: usize = 200;
+
+/// `entries` (from `DirWalk::visit`, at `depth`) in walk order, each with its
+/// `depth` instead of its `children`
+fn flatten_nodes(entries: Vec<Value>, depth: usize, out: &mut Vec<Value>) {
+    for mut node in entries {
+        let children = node
+            .as_object_mut()
+            .and_then(|fields| fields.remove("children"));
+        node["depth"] = serde_json::json!(depth);
+        out.push(node);
+        if let Some(Value::Array(children)) = children {
+            flatten_nodes(children, depth + 1, out);
+        }
+    }
+}
+
+// ============================================================================
+// DirectoryListTool - Directory entries with size and modification time
+// ============================================================================
+
+/// Levels `directory_list` descends when `recursive` is set without `max_depth`
+const LIST_DEFAULT_MAX_DEPTH: usize = 3;
+/// Deepest `max_depth` a call may ask for
+const LIST_MAX_DEPTH: usize = 10;
+/// Entries `directory_list` returns unless told otherwise
+const LIST_DEFAULT_MAX_ENTRIES: usize = 500;
+
+pub struct DirectoryListTool {
+    /// Paths are resolved against it and may not leave it
+    workspace: PathBuf,
+}
+
+impl DirectoryListTool {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DirectoryListTool {
+    fn name(&self) -> &str {
+        "directory_list"
+    }
+
+    fn description(&self) -> &str {
+        "List the entries of a workspace directory with their type, size and modification time. Set recursive to descend into subdirectories (down to max_depth); the result is a nested tree plus a compact text rendering. Skips hidden entries, build/VCS directories (.git, node_modules, target...) and paths ignored by .gitignore unless told otherwise."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to list, relative to the workspace",
+                    "default": "."
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "Also list subdirectories (default: false)",
+                    "default": false
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "With recursive, how many levels below path to list (default: 3)",
+                    "minimum": 1,
+                    "maximum": LIST_MAX_DEPTH
+                },
+                "max_entries": {
+                    "type": "integer",
+                    "description": "Stop after this many entries (default: 500)",
+                    "minimum": 1,
+                    "default": LIST_DEFAULT_MAX_ENTRIES
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Also list entries starting with a dot (default: false)",
+                    "default": false
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Leave out paths ignored by .gitignore (default: true)",
+                    "default": true
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
+        self.execute_with_context(params, &ToolContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let path = params["path"].as_str().unwrap_or(".").to_string();
+        let max_depth = if params["recursive"].as_bool().unwrap_or(false) {
+            params["max_depth"]
+                .as_u64()
+                .map_or(LIST_DEFAULT_MAX_DEPTH, |n| n as usize)
+                .clamp(1, LIST_MAX_DEPTH)
+        } else {
+            1
+        };
+        let max_entries = params["max_entries"]
+            .as_u64()
+            .map_or(LIST_DEFAULT_MAX_ENTRIES, |n| n as usize);
+        let include_hidden = params["include_hidden"].as_bool().unwrap_or(false);
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+
+        let root = resolve_in_workspace(&self.workspace, Some(&path))?;
+        if !root.is_dir() {
+            return Err(ToolError::InvalidParameters(tr("tree.not_dir", &[&path])));
+        }
+
+        let walk = DirWalk {
+            max_depth,
+            max_entries,
+            include_hidden,
+            respect_gitignore,
+            cancel: ctx.cancel.clone(),
+            ..DirWalk::default()
+        };
+        let (walk, entries) = walk.run(root, self.workspace.clone()).await?;
+        ctx.check_cancelled()?;
+
+        let mut message = tr("list.done", &[&path, &walk.directories, &walk.files]);
+        if walk.truncated {
+            message.push_str(&tr("tree.truncated", &[&max_entries]));
+        }
+        message.push_str(&format!(
+            "\n{}/\n{}",
+            path.trim_end_matches('/'),
+            walk.lines
+        ));
+
+        Ok(ToolResult {
+            success: true,
+            data: serde_json::json!({
+                "path": path,
+                "entries": entries,
+                "files": walk.files,
+                "directories": walk.directories,
+                "truncated": walk.truncated
+            }),
+            message: message.trim_end().to_string(),
+        })
+    }
+}
+
+/// Walk behind `directory_tree` and `directory_list`: the entries of a
+/// workspace directory, their counts and their text rendering
+#[derive(Default)]
+struct DirWalk {
+    max_depth: usize,
+    max_entries: usize,
+    include_hidden: bool,
+    respect_gitignore: bool,
+    cancel: CancellationToken,
+    lines: String,
+    listed: usize,
+    files: usize,
+    directories: usize,
+    /// Entries were left out because `max_entries` was reached
+    truncated: bool,
+}
+
+impl DirWalk {
+    /// Walk `root` (inside `workspace`) on a blocking thread; returns the
+    /// walk and the entries of `root`
+    async fn run(
+        mut self,
+        root: PathBuf,
+        workspace: PathBuf,
+    ) -> Result<(Self, Vec<Value>), ToolError> {
+        tokio::task::spawn_blocking(move || {
+            let gitignores = self
+                .respect_gitignore
+                .then(|| ancestor_gitignores(&root, &workspace));
+            let entries = self.visit(&root, Path::new(""), "", 0, gitignores.as_deref());
+            (self, entries)
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(tr("dir.read_failed", &[&e])))
+    }
+
+    /// Entries of `dir` (at `depth` below the root, `rel` from it) in name
+    /// order, directories carrying their own `children` until `max_depth`.
+    /// Symlinks are listed but not followed.
+    fn visit(
+        &mut self,
+        dir: &Path,
+        rel: &Path,
+        prefix: &str,
+        depth: usize,
+        gitignores: Option<&[Gitignore]>,
+    ) -> Vec<Value> {
+        if depth >= self.max_depth || self.cancel.is_cancelled() {
+            return Vec::new();
+        }
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        // A .gitignore in this directory scopes over everything below it
+        let scoped: Option<Vec<Gitignore>> = gitignores.map(|outer| {
+            let mut scoped = outer.to_vec();
+            if let Some(local) = load_gitignore(dir) {
+                scoped.push(local);
+            }
+            scoped
+        });
+
+        let mut entries: Vec<(String, std::fs::DirEntry, Option<std::fs::FileType>)> = read_dir
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if (name.starts_with('.') && !self.include_hidden)
+                    || SKIPPED_DIR_NAMES.contains(&name.as_str())
+                {
+                    return None;
+                }
+                let file_type = entry.file_type().ok();
+                if let Some(matchers) = scoped.as_deref() {
+                    let is_dir = file_type.is_some_and(|t| t.is_dir());
+                    if is_gitignored(matchers, &dir.join(&name), is_dir) {
+                        return None;
+                    }
+                }
+                Some((name, entry, file_type))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let count = entries.len();
+        let mut nodes = Vec::with_capacity(count);
+        for (i, (name, entry, file_type)) in entries.into_iter().enumerate() {
+            if self.listed >= self.max_entries {
+                self.truncated = true;
+                break;
+            }
+            self.listed += 1;
+            let last = i + 1 == count;
+            let connector = if last { "└── " } else { "├── " };
+            let entry_rel = rel.join(&name);
+            // Symlinks describe themselves, not their target
+            let metadata = std::fs::symlink_metadata(entry.path()).ok();
+            let modified = metadata
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .map(format_iso);
+            let mut node = serde_json::json!({
+                "name": name,
+                "path": entry_rel.to_string_lossy().replace('\\', "/"),
+                "modified": modified,
+            });
+
+            if file_type.is_some_and(|t| t.is_symlink()) {
+                let target = std::fs::read_link(entry.path())
+                    .map(|t| t.display().to_string())
+                    .unwrap_or_default();
+                self.lines
+                    .push_str(&format!("{}{}{} -> {}\n", prefix, connector, name, target));
+                node["type"] = serde_json::json!("symlink");
+                node["link_target"] = serde_json::json!(target);
+                self.files += 1;
+            } else if file_type.is_some_and(|t| t.is_dir()) {
+                self.lines
+                    .push_str(&format!("{}{}{}/\n", prefix, connector, name));
+                node["type"] = serde_json::json!("directory");
+                self.directories += 1;
+                if depth + 1 < self.max_depth {
+                    let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                    node["children"] = Value::Array(self.visit(
+                        &entry.path(),
+                        &entry_rel,
+                        &child_prefix,
+                        depth + 1,
+                        scoped.as_deref(),
+                    ));
+                }
+            } else {
+                let size = metadata.as_ref().map_or(0, |m| m.len());
+                self.lines.push_str(&format!(
+                    "{}{}{} ({})\n",
+                    prefix,
+                    connector,
+                    name,
+                    format_size(size)
+                ));
+                node["type"] = serde_json::json!("file");
+                node["size"] = serde_json::json!(size);
+                self.files += 1;
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Build output and VCS directories that tree-walking tools leave out by default
+const SKIPPED_DIR_NAMES: &[&str] = &[".git", "node_modules", "target", "__pycache__"];
+
+/// Result of a `dry_run` call: what the tool would have done, flagged with
+/// `"dry_run": true`, after every check passed but before touching the disk.
+fn dry_run_result(mut data: Value, message: String) -> ToolResult {
+    data["dry_run"] = serde_json::json!(true);
+    ToolResult {
+        success: true,
+        data,
+        message: tr("dry_run", &[&message]),
+    }
+}
+
+/// Replace `path` with `contents` without ever leaving it half-written.
+///
+/// The data goes to a sibling temp file (`.name.tmp-<rand>`) that is then
+/// renamed over `path`, which is atomic on the same filesystem. If the rename
+/// crosses devices anyway (bind mounts, network shares), the temp file is
+/// copied into place instead. An existing file's owner and permissions carry
+/// over, and the temp file is removed if anything fails or the write is
+/// dropped half way (tool timeout, stop button); the target is then left as
+/// it was. A symlink at `path` is written through: its target gets the new
+/// contents and the link stays.
+async fn atomic_write(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = &write_target(path).await?;
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(
+        ".tmp-{}",
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    ));
+    let tmp = dir.join(tmp_name);
+
+    let _cleanup = TempFileGuard(&tmp);
+    write_and_swap(&tmp, path, contents.as_ref()).await
+}
+
+/// Most symlinks followed in a row before `write_target` gives up on a loop
+const MAX_LINK_HOPS: usize = 40;
+
+/// The file a write to `path` lands in: `path` itself, or what the symlink
+/// at `path` points to, through any chain of links. The target does not
+/// have to exist yet.
+async fn write_target(path: &Path) -> std::io::Result<PathBuf> {
+    let mut target = path.to_path_buf();
+    for _ in 0..MAX_LINK_HOPS {
+        match tokio::fs::symlink_metadata(&target).await {
+            Ok(meta) if meta.is_symlink() => {
+                let link = tokio::fs::read_link(&target).await?;
+                target = match target.parent() {
+                    Some(parent) => parent.join(link),
+                    None => link,
+                };
+            }
+            _ => return Ok(target),
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "too many levels of symbolic links",
+    ))
+}
+
+/// Deletes an `atomic_write` temp file when dropped, so it is cleaned up on
+/// errors and when the write future is cancelled. After a successful rename
+/// there is nothing left to delete.
+struct TempFileGuard<'a>(&'a Path);
+
+impl Drop for TempFileGuard<'_> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.0);
+    }
+}
+
+async fn write_and_swap(tmp: &Path, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(tmp)
+        .await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    if let Ok(meta) = tokio::fs::metadata(path).await {
+        preserve_owner(&meta, tmp);
+        tokio::fs::set_permissions(tmp, meta.permissions()).await?;
+    }
+
+    match tokio::fs::rename(tmp, path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            tokio::fs::copy(tmp, path).await?;
+            let _ = tokio::fs::remove_file(tmp).await;
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Give `dst` the owner and group in `meta` on Unix. Best effort: only root
+/// can give a file away, and a file made by its owner needs nothing.
+fn preserve_owner(meta: &std::fs::Metadata, dst: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let _ = std::os::unix::fs::chown(dst, Some(meta.uid()), Some(meta.gid()));
+    }
+    #[cfg(not(unix))]
+    let _ = (meta, dst);
+}
+
+/// Give `dst` the modification/access times, owner and permissions of `src`.
+/// Returns the Unix mode bits that were applied, or `None` on other platforms.
+fn preserve_metadata(src: &Path, dst: &Path) -> std::io::Result<Option<u32>> {
+    let meta = std::fs::metadata(src)?;
+    // Times first: a read-only mode could otherwise block the update
+    filetime::set_file_times(
+        dst,
+        filetime::FileTime::from_last_access_time(&meta),
+        filetime::FileTime::from_last_modification_time(&meta),
+    )?;
+    // The owner before the mode: a chown clears setuid and setgid bits
+    preserve_owner(&meta, dst);
+    std::fs::set_permissions(dst, meta.permissions())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Ok(Some(meta.permissions().mode() & 0o7777))
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(None)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// RFC 3339 timestamp in local time, e.g. `2024-05-01T14:03:22+02:00`
+fn format_iso(time: std::time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// How long before `now` `time` was, e.g. "3 hours ago" or "in 2 minutes"
+fn format_relative(time: std::time::SystemTime, now: chrono::DateTime<chrono::Local>) -> String {
+    let delta = now.signed_duration_since(chrono::DateTime::<chrono::Local>::from(time));
+    let secs = delta.num_seconds();
+    let abs = secs.unsigned_abs();
+    if abs < 60 {
+        return "just now".to_string();
+    }
+
+    let (count, unit) = if abs < 3600 {
+        (abs / 60, "minute")
+    } else if abs < 86_400 {
+        (abs / 3600, "hour")
+    } else if abs < 30 * 86_400 {
+        (abs / 86_400, "day")
+    } else if abs < 365 * 86_400 {
+        (abs / (30 * 86_400), "month")
+    } else {
+        (abs / (365 * 86_400), "year")
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if secs < 0 {
+        format!("in {} {}{}", count, unit, plural)
+    } else {
+        format!("{} {}{} ago", count, unit, plural)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Lay out `dirs` x `files_per_dir` small files, each with one match.
+    fn generate_tree(root: &Path, dirs: usize, files_per_dir: usize) {
+        for d in 0..dirs {
+            let dir = root.join(format!("dir_{:03}", d));
+            std::fs::create_dir_all(&dir).unwrap();
+            for f in 0..files_per_dir {
+                let mut body = String::new();
+                for l in 0..40 {
+                    if l == 20 {
+                        body.push_str("let needle = compute();\n");
+                    } else {
+                        body.push_str(&format!("// filler line {} of file {}\n", l, f));
+                    }
+                }
+                std::fs::write(dir.join(format!("file_{:03}.rs", f)), body).unwrap();
+            }
+        }
+    }
+
+    fn scope(root: &Path) -> SearchScope {
+        SearchScope {
+            root: root.to_path_buf(),
+            abs_root: root.to_path_buf(),
+            file_pattern: None,
+            exclude: ExcludePatterns::default(),
+            gitignores: None,
+            max_file_size: SEARCH_DEFAULT_MAX_FILE_SIZE,
+            cancel: CancellationToken::new(),
+            progress: None,
+        }
+    }
+
+    fn matcher() -> Arc<ContentMatcher> {
+        Arc::new(ContentMatcher::new("NEEDLE", false, false))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    #[ignore = "timing-dependent; run with --ignored on an idle machine"]
+    async fn test_parallel_search_is_faster_than_sequential() {
+        let tmp = tempfile::tempdir().unwrap();
+        generate_tree(tmp.path(), 50, 60);
+
+        let started = Instant::now();
+        let sequential = search_content(scope(tmp.path()), matcher(), usize::MAX, 1)
+            .await
+            .unwrap()
+            .matches;
+        let sequential_time = started.elapsed();
+
+        let started = Instant::now();
+        let parallel = search_content(
+            scope(tmp.path()),
+            matcher(),
+            usize::MAX,
+            SEARCH_MAX_OPEN_FILES,
+        )
+        .await
+        .unwrap()
+        .matches;
+        let parallel_time = started.elapsed();
+
+        assert_eq!(parallel.len(), 3000);
+        assert_eq!(parallel, sequential);
+        assert!(
+            parallel_time < sequential_time,
+            "parallel {:?}, sequential {:?}",
+            parallel_time,
+            sequential_time
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_parallel_search_order_is_stable_and_capped() {
+        let tmp = tempfile::tempdir().unwrap();
+        generate_tree(tmp.path(), 5, 20);
+
+        let first = search_content(scope(tmp.path()), matcher(), 25, SEARCH_MAX_OPEN_FILES)
+            .await
+            .unwrap()
+            .matches;
+        let second = search_content(scope(tmp.path()), matcher(), 25, SEARCH_MAX_OPEN_FILES)
+            .await
+            .unwrap()
+            .matches;
+        assert_eq!(first.len(), 25);
+        assert_eq!(first, second);
+        let sequential = search_content(scope(tmp.path()), matcher(), 25, 1)
+            .await
+            .unwrap()
+            .matches;
+        assert_eq!(first, sequential);
+
+        // Walk order: dir_000/file_000 .. dir_000/file_019, then dir_001/file_000 ...
+        let files: Vec<&str> = first.iter().map(|m| m["file"].as_str().unwrap()).collect();
+        assert!(files[0].ends_with("dir_000/file_000.rs"));
+        assert!(files[19].ends_with("dir_000/file_019.rs"));
+        assert!(files[24].ends_with("dir_001/file_004.rs"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_search_streams_matches_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        generate_tree(tmp.path(), 5, 20);
+
+        let (progress, mut updates) = tokio::sync::mpsc::unbounded_channel();
+        let ctx = ToolContext::default().with_progress(progress);
+        let params = serde_json::json!({
+            "path": tmp.path().to_string_lossy(),
+            "query": "needle",
+            "max_results": 50
+        });
+        let result = crate::agent::tools::execute_with_timeout(&FileSearchContentTool, params, &ctx)
+            .await
+            .unwrap();
+
+        let mut streamed = Vec::new();
+        let mut batches = 0;
+        while let Ok(update) = updates.try_recv() {
+            streamed.extend(update["matches"].as_array().unwrap().iter().cloned());
+            batches += 1;
+        }
+        assert!(batches >= 1);
+        assert_eq!(streamed.len(), 50);
+        assert_eq!(&streamed, result.data["matches"].as_array().unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_cancelled_search_stops_early() {
+        let tmp = tempfile::tempdir().unwrap();
+        generate_tree(tmp.path(), 60, 60);
+        let params = serde_json::json!({
+            "query": "needle",
+            "path": tmp.path(),
+            "max_results": 100000
+        });
+
+        let started = Instant::now();
+        let full = FileSearchContentTool.execute(params.clone()).await.unwrap();
+        let full_time = started.elapsed();
+        assert_eq!(full.data["total"], 3600);
+
+        let ctx = ToolContext::default();
+        let cancel = ctx.cancel.clone();
+        let stopper = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            cancel.cancel();
+        });
+        let started = Instant::now();
+        let err = FileSearchContentTool
+            .execute_with_context(params, &ctx)
+            .await
+            .unwrap_err();
+        let cancelled_time = started.elapsed();
+        stopper.await.unwrap();
+
+        assert!(matches!(err, ToolError::Cancelled), "{}", err);
+        assert!(
+            cancelled_time < full_time / 2,
+            "cancelled after {:?}, full search took {:?}",
+            cancelled_time,
+            full_time
+        );
+
+        // Copies stop at the next entry as well
+        let ctx = ToolContext::default();
+        ctx.cancel.cancel();
+        let err = FileCopyTool
+            .execute_with_context(
+                serde_json::json!({
+                    "source": tmp.path().join("dir_000"),
+                    "destination": tmp.path().join("copy")
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Cancelled), "{}", err);
+        assert!(!tmp.path().join("copy/file_000.rs").exists());
+    }
+
+    #[test]
+    fn test_whole_word_matching() {
+        let whole = ContentMatcher::new("count", false, true);
+        assert!(!whole.is_match("let accountant = 1;"));
+        assert!(!whole.is_match("counter += 1"));
+        assert!(!whole.is_match("my_count"));
+        assert!(whole.is_match("count"));
+        assert!(whole.is_match("let n = items.count();"));
+        assert!(whole.is_match("// Count the items"));
+        // A later occurrence can match after a rejected one
+        assert!(whole.is_match("accountant count"));
+
+        let sensitive = ContentMatcher::new("Count", true, true);
+        assert!(sensitive.is_match("Count the items"));
+        assert!(!sensitive.is_match("count the items"));
+        assert!(!sensitive.is_match("Counts"));
+
+        // Non-word edges need no boundary
+        assert!(ContentMatcher::new("foo(", false, true).is_match("x = foo(1)"));
+        assert!(!ContentMatcher::new("foo(", false, true).is_match("x = barfoo(1)"));
+        assert!(ContentMatcher::new("été", false, true).is_match("un été chaud"));
+        assert!(!ContentMatcher::new("été", false, true).is_match("étés"));
+
+        assert!(ContentMatcher::new("count", false, false).is_match("accountant"));
+    }
+
+    #[tokio::test]
+    async fn test_file_search_whole_word() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("a.rs"),
+            "let accountant = 1;\nlet count = 2;\nrecount();\n",
+        )
+        .unwrap();
+
+        let result = FileSearchContentTool
+            .execute(serde_json::json!({
+                "query": "count",
+                "path": tmp.path(),
+                "whole_word": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["total"], 1);
+        assert_eq!(result.data["matches"][0]["line_number"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_file_pattern_list_and_globs() {
+        let tmp = tempfile::tempdir().unwrap();
+        for name in [
+            "main.rs",
+            "Cargo.toml",
+            "app.js",
+            "app.test.js",
+            "notes.md",
+            "rs",
+        ] {
+            std::fs::write(tmp.path().join(name), "needle\n").unwrap();
+        }
+        let found = |spec: &str| {
+            let mut search_scope = scope(tmp.path());
+            search_scope.file_pattern = FilePatterns::parse(spec).unwrap();
+            async move {
+                let outcome = search_content(search_scope, matcher(), 100, SEARCH_MAX_OPEN_FILES)
+                    .await
+                    .unwrap();
+                let mut names: Vec<String> = outcome
+                    .matches
+                    .iter()
+                    .map(|m| {
+                        Path::new(m["file"].as_str().unwrap())
+                            .file_name()
+                            .unwrap()
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .collect();
+                names.sort();
+                names
+            }
+        };
+
+        // The single-extension form still works
+        assert_eq!(found("rs").await, ["main.rs"]);
+        assert_eq!(found("rs, .toml").await, ["Cargo.toml", "main.rs"]);
+        assert_eq!(found("*.test.js").await, ["app.test.js"]);
+        assert_eq!(found("js,md").await, ["app.js", "app.test.js", "notes.md"]);
+        assert!(FilePatterns::parse(" , ").unwrap().is_none());
+        assert!(FilePatterns::parse("[rs").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_exclude_globs() {
+        let tmp = tempfile::tempdir().unwrap();
+        for dir in ["src", "tests/fixtures", "tests/unit", "node_modules"] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        for file in [
+            "src/lib.rs",
+            "src/schema.generated.rs",
+            "tests/fixtures/sample.rs",
+            "tests/unit/lib_test.rs",
+            "node_modules/dep.js",
+        ] {
+            std::fs::write(tmp.path().join(file), "needle\n").unwrap();
+        }
+
+        let mut search_scope = scope(tmp.path());
+        search_scope.exclude = ExcludePatterns::from_params(&serde_json::json!({
+            "exclude": ["tests/fixtures/", "*.generated.rs"]
+        }))
+        .unwrap();
+        let outcome = search_content(search_scope, matcher(), 100, SEARCH_MAX_OPEN_FILES)
+            .await
+            .unwrap();
+
+        let mut files: Vec<String> = outcome
+            .matches
+            .iter()
+            .map(|m| {
+                Path::new(m["file"].as_str().unwrap())
+                    .strip_prefix(tmp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        // node_modules stays skipped: excludes add to the built-in list
+        assert_eq!(files, ["src/lib.rs", "tests/unit/lib_test.rs"]);
+
+        assert!(ExcludePatterns::from_params(&serde_json::json!({ "exclude": "tests" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_binary_and_large_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "needle here\n").unwrap();
+        std::fs::write(tmp.path().join("b.pack"), b"needle\0\x01\x02needle").unwrap();
+        std::fs::write(tmp.path().join("c.log"), "needle\n".repeat(1000)).unwrap();
+
+        let mut search_scope = scope(tmp.path());
+        search_scope.max_file_size = 1024;
+        let outcome = search_content(search_scope, matcher(), 100, SEARCH_MAX_OPEN_FILES)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.matches.len(), 1);
+        assert!(outcome.matches[0]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("a.txt"));
+        assert_eq!(outcome.skipped_files, 2);
+        assert_eq!(outcome.skipped_large_files.len(), 1);
+        assert!(outcome.skipped_large_files[0]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("c.log"));
+        assert_eq!(outcome.skipped_large_files[0]["size"], 7000);
+    }
+
+    #[tokio::test]
+    async fn test_read_and_edit_refuse_binary_files() {
+        use crate::agent::tools::builtins::FileReadTool;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("logo.png");
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x10";
+        std::fs::write(&file, png).unwrap();
+        let path = file.to_str().unwrap();
+
+        let read = FileReadTool::default()
+            .execute(serde_json::json!({ "path": path }))
+            .await
+            .unwrap_err();
+        assert!(
+            read.to_string().contains("file appears to be binary"),
+            "{}",
+            read
+        );
+
+        let edit = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "PNG",
+                "new_string": "GIF"
+            }))
+            .await
+            .unwrap_err();
+        assert!(
+            edit.to_string().contains("file appears to be binary"),
+            "{}",
+            edit
+        );
+        assert_eq!(std::fs::read(&file).unwrap(), png);
+    }
+
+    #[tokio::test]
+    async fn test_edit_round_trips_windows_1252() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("config.ini");
+        // "[Général]\nnom=Café\n" in Windows-1252
+        std::fs::write(&file, b"[G\xe9n\xe9ral]\nnom=Caf\xe9\n").unwrap();
+        let path = file.to_str().unwrap();
+
+        let result = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "nom=Café",
+                "new_string": "nom=Crème brûlée"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["encoding"], "windows-1252");
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            b"[G\xe9n\xe9ral]\nnom=Cr\xe8me br\xfbl\xe9e\n"
+        );
+
+        // A character Windows-1252 can't hold is refused, not mangled
+        let err = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "Crème",
+                "new_string": "\u{1f36e}"
+            }))
+            .await;
+        assert!(err.is_err());
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            b"[G\xe9n\xe9ral]\nnom=Cr\xe8me br\xfbl\xe9e\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bom_utf8_edit_keeps_bom() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("bom.cs");
+        std::fs::write(&file, b"\xef\xbb\xbfusing System;\nclass A {}").unwrap();
+        let path = file.to_str().unwrap();
+
+        // The BOM is not part of line 1, so its hash is the plain text's
+        let hash = HashlineConfig::default().hash("using System;");
+        let result = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 1,
+                "hash": hash,
+                "new_string": "using System.IO;"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["encoding"], "UTF-8");
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            b"\xef\xbb\xbfusing System.IO;\nclass A {}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latin1_file_is_read_and_searched() {
+        use crate::agent::tools::builtins::FileReadTool;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("legacy.conf");
+        // "# réglages\nnom=Müller\n" in Latin-1
+        std::fs::write(&file, b"# r\xe9glages\nnom=M\xfcller\n").unwrap();
+
+        let read = FileReadTool::default()
+            .execute(serde_json::json!({ "path": file.to_str().unwrap() }))
+            .await
+            .unwrap();
+        assert_eq!(read.data["encoding"], "windows-1252");
+        assert!(read.data["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("| nom=Müller"));
+
+        let outcome = search_content(
+            scope(tmp.path()),
+            Arc::new(ContentMatcher::new("Müller", true, false)),
+            10,
+            SEARCH_MAX_OPEN_FILES,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.matches.len(), 1);
+        assert_eq!(outcome.skipped_files, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failures_carry_error_codes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("dup.txt");
+        std::fs::write(&file, "same\nsame\n").unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+
+        let err = edit
+            .execute(serde_json::json!({ "path": path, "old_string": "same", "new_string": "x" }))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, ToolError::NotUnique { count: 2, .. }),
+            "{}",
+            err
+        );
+        assert_eq!(err.to_json()["code"], "not_unique");
+        assert_eq!(err.to_json()["count"], 2);
+        assert!(!err.is_retryable());
+
+        let err = edit
+            .execute(serde_json::json!({ "path": path, "line_number": 1, "hash": "zzzz", "new_string": "x" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "hash_mismatch");
+
+        let err = FileCreateTool
+            .execute(serde_json::json!({ "path": path, "content": "" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "already_exists");
+
+        let missing = tmp.path().join("missing.txt");
+        let err = edit
+            .execute(serde_json::json!({ "path": missing.to_str().unwrap(), "old_string": "a", "new_string": "b" }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "not_found", "{}", err);
+
+        let busy = std::io::Error::from(std::io::ErrorKind::TimedOut);
+        let err = ToolError::io(&busy, "slow disk".into());
+        assert_eq!(err.to_json()["kind"], busy.kind().to_string());
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_edit_preserves_crlf_and_trailing_newline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("win.txt");
+        std::fs::write(&file, "one\r\ntwo\r\nthree\r\n").unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+
+        let hash = HashlineConfig::default().hash("two");
+        edit.execute(serde_json::json!({
+            "path": path,
+            "line_number": 2,
+            "hash": hash,
+            "new_string": "2a\n2b"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            b"one\r\n2a\r\n2b\r\nthree\r\n"
+        );
+
+        // LF-quoted old_string still matches, and only that region changes
+        edit.execute(serde_json::json!({
+            "path": path,
+            "old_string": "2a\n2b",
+            "new_string": "two\nand a half"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read(&file).unwrap(),
+            b"one\r\ntwo\r\nand a half\r\nthree\r\n"
+        );
+
+        // No trailing newline stays that way
+        std::fs::write(&file, "a\nb").unwrap();
+        edit.execute(serde_json::json!({
+            "path": path,
+            "line_number": 1,
+            "hash": HashlineConfig::default().hash("a"),
+            "new_string": "A"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(&file).unwrap(), b"A\nb");
+    }
+
+    /// Lines that are easy to hash inconsistently: empty, whitespace-only,
+    /// trailing spaces, tabs, non-ASCII, and content containing the `|` separator.
+    const TRICKY_LINES: &[&str] = &[
+        "",
+        "    ",
+        "fn main() {   ",
+        "\tlet s = \"a|b|c\";",
+        "  // caf\u{e9} \u{1f980}",
+        "|",
+        "}",
+    ];
+
+    /// Parse `file_read` output into (line number, hash) pairs.
+    fn read_hashes(output: &str) -> Vec<(u64, String)> {
+        output
+            .lines()
+            .map(|l| {
+                let mut parts = l.splitn(3, '|');
+                let number = parts.next().unwrap().trim().parse().unwrap();
+                let hash = parts.next().unwrap().to_string();
+                (number, hash)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_file_read_hashes_verify_in_file_edit() {
+        use crate::agent::tools::builtins::FileReadTool;
+        use crate::agent::tools::hashline::HashAlgorithm;
+
+        let configs = [
+            HashlineConfig::default(),
+            HashlineConfig::new(HashAlgorithm::Fnv1, 12),
+            HashlineConfig::new(HashAlgorithm::Fnv1, 5),
+            HashlineConfig::new(HashAlgorithm::Fnv1a, 32),
+        ];
+        for config in configs {
+            let tmp = tempfile::tempdir().unwrap();
+            let file = tmp.path().join("tricky.rs");
+            std::fs::write(&file, TRICKY_LINES.join("\n")).unwrap();
+            let path = file.to_str().unwrap();
+
+            let read = FileReadTool::new(config)
+                .execute(serde_json::json!({ "path": path }))
+                .await
+                .unwrap();
+            let hashes = read_hashes(read.data["content"].as_str().unwrap());
+            assert_eq!(hashes.len(), TRICKY_LINES.len());
+
+            let edit = FileEditTool::new(config);
+            for ((number, hash), line) in hashes.iter().zip(TRICKY_LINES) {
+                assert_eq!(hash.len(), config.hex_width());
+                // Rewriting a line with itself must always pass verification
+                let result = edit
+                    .execute(serde_json::json!({
+                        "path": path,
+                        "line_number": number,
+                        "hash": hash,
+                        "new_string": line
+                    }))
+                    .await;
+                assert!(result.is_ok(), "{:?} line {}: {:?}", config, number, result);
+            }
+
+            // Once a line changes, its old hash must be rejected
+            let (number, hash) = &hashes[2];
+            edit.execute(serde_json::json!({
+                "path": path,
+                "line_number": number,
+                "hash": hash,
+                "new_string": "fn main() -> Result<(), Error> {"
+            }))
+            .await
+            .unwrap();
+            let stale = edit
+                .execute(serde_json::json!({
+                    "path": path,
+                    "line_number": number,
+                    "hash": hash,
+                    "new_string": "fn main() {"
+                }))
+                .await;
+            assert!(matches!(stale, Err(ToolError::HashMismatch(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hashline_edit_reports_collision_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("dupes.rs");
+        std::fs::write(&file, "}\nfn a() {\n}\nfn b() {\n}").unwrap();
+        let path = file.to_str().unwrap();
+
+        let edit = FileEditTool::default();
+        let brace = HashlineConfig::default().hash("}");
+
+        let result = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 3,
+                "hash": brace,
+                "new_string": "} // end a"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["collision_lines"], serde_json::json!([1, 3, 5]));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "}\nfn a() {\n} // end a\nfn b() {\n}"
+        );
+
+        // A unique hash carries no collision report
+        let result = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "hash": HashlineConfig::default().hash("fn a() {"),
+                "new_string": "fn a() -> u8 {"
+            }))
+            .await
+            .unwrap();
+        assert!(result.data.get("collision_lines").is_none());
+
+        // A stale line number points the caller at the lines that do match
+        let err = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 4,
+                "hash": brace,
+                "new_string": "}"
+            }))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("collision_lines): [1, 5]"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hashline_insert_and_delete() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("ops.rs");
+        std::fs::write(&file, "fn a() {\n    1\n}").unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+        let hash = |line: &str| HashlineConfig::default().hash(line);
+
+        edit.execute(serde_json::json!({
+            "path": path,
+            "line_number": 2,
+            "hash": hash("    1"),
+            "mode": "insert_after",
+            "new_string": "    2\n    3\n"
+        }))
+        .await
+        .unwrap();
+        edit.execute(serde_json::json!({
+            "path": path,
+            "line_number": 1,
+            "hash": hash("fn a() {"),
+            "mode": "insert_before",
+            "new_string": "/// Doc"
+        }))
+        .await
+        .unwrap();
+        let result = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 3,
+                "hash": hash("    1"),
+                "mode": "delete"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["operation"], "delete");
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "/// Doc\nfn a() {\n    2\n    3\n}"
+        );
+
+        // The anchor is still verified before any structural change
+        let stale = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 3,
+                "hash": hash("    1"),
+                "mode": "insert_after",
+                "new_string": "    4"
+            }))
+            .await;
+        assert!(matches!(stale, Err(ToolError::HashMismatch(_))));
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "/// Doc\nfn a() {\n    2\n    3\n}"
+        );
+
+        let bad_mode = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 1,
+                "hash": hash("/// Doc"),
+                "mode": "append"
+            }))
+            .await;
+        assert!(matches!(bad_mode, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_hashline_range_edit_verifies_every_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("range.rs");
+        let original = "use std::io;\nfn old() {\n    todo!()\n}\nfn keep() {}";
+        std::fs::write(&file, original).unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+        let config = HashlineConfig::default();
+        let hashes: Vec<String> = ["fn old() {", "    todo!()", "}"]
+            .iter()
+            .map(|l| config.hash(l))
+            .collect();
+
+        // One stale hash in the middle aborts the edit and names the line
+        let mut stale = hashes.clone();
+        stale[1] = config.hash("    unimplemented!()");
+        let err = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "end_line": 4,
+                "hashes": stale,
+                "new_string": "fn new() {}"
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("at line 3"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), original);
+
+        // The number of hashes must match the range
+        let short = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "end_line": 4,
+                "hashes": &hashes[..2],
+                "new_string": "fn new() {}"
+            }))
+            .await;
+        assert!(matches!(short, Err(ToolError::InvalidParameters(_))));
+
+        edit.execute(serde_json::json!({
+            "path": path,
+            "line_number": 2,
+            "end_line": 4,
+            "hashes": hashes,
+            "new_string": "fn new() -> u8 {\n    1\n}"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "use std::io;\nfn new() -> u8 {\n    1\n}\nfn keep() {}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_edit_backup_rotates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("notes.txt");
+        std::fs::write(&file, "v1").unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+
+        for (old, new) in [("v1", "v2"), ("v2", "v3"), ("v3", "v4")] {
+            let result = edit
+                .execute(serde_json::json!({
+                    "path": path,
+                    "old_string": old,
+                    "new_string": new,
+                    "backup": true
+                }))
+                .await
+                .unwrap();
+            assert_eq!(
+                result.data["backup_path"],
+                format!("{}.localm.bak", path).as_str()
+            );
+        }
+
+        let backup = |suffix: &str| {
+            std::fs::read_to_string(format!("{}.localm.bak{}", path, suffix)).unwrap()
+        };
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v4");
+        assert_eq!(backup(""), "v3");
+        assert_eq!(backup(".1"), "v2");
+        assert_eq!(backup(".2"), "v1");
+
+        // No backup unless asked for (or enabled by default)
+        let result = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "v4",
+                "new_string": "v5"
+            }))
+            .await
+            .unwrap();
+        assert!(result.data.get("backup_path").is_none());
+        assert_eq!(backup(""), "v3");
+    }
+
+    #[tokio::test]
+    async fn test_12_bit_hash_collision_is_flagged() {
+        use crate::agent::tools::hashline::HashAlgorithm;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("collide.rs");
+        // Distinct lines that share a 12-bit hash
+        std::fs::write(&file, "let x = 19;\nlet x = 484;").unwrap();
+        let path = file.to_str().unwrap();
+
+        let narrow = HashlineConfig::new(HashAlgorithm::Fnv1, 12);
+        let result = FileEditTool::new(narrow)
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "hash": narrow.hash("let x = 19;"),
+                "new_string": "let x = 485;"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["collision_lines"], serde_json::json!([1, 2]));
+
+        // The default width tells them apart, so the stale hash is rejected
+        std::fs::write(&file, "let x = 19;\nlet x = 484;").unwrap();
+        let default = HashlineConfig::default();
+        let stale = FileEditTool::new(default)
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "hash": default.hash("let x = 19;"),
+                "new_string": "let x = 485;"
+            }))
+            .await;
+        assert!(matches!(stale, Err(ToolError::HashMismatch(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_validates_without_touching_disk() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("a.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+        let path = file.to_str().unwrap();
+        let other = tmp.path().join("b.txt");
+        let other = other.to_str().unwrap();
+
+        let edit = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "two",
+                "new_string": "deux",
+                "backup": true,
+                "dry_run": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(edit.data["dry_run"], true);
+        assert!(edit.data.get("backup_path").is_none());
+
+        // Validation still runs: a stale hash is refused
+        let stale = FileEditTool::default()
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 1,
+                "hash": "0000",
+                "new_string": "un",
+                "dry_run": true
+            }))
+            .await;
+        assert!(stale.is_err());
+
+        let calls = [
+            FileCreateTool
+                .execute(serde_json::json!({ "path": other, "content": "x", "dry_run": true }))
+                .await,
+            FileCopyTool
+                .execute(
+                    serde_json::json!({ "source": path, "destination": other, "dry_run": true }),
+                )
+                .await,
+            FileMoveTool
+                .execute(
+                    serde_json::json!({ "source": path, "destination": other, "dry_run": true }),
+                )
+                .await,
+            FileDeleteTool
+                .execute(serde_json::json!({ "path": path, "dry_run": true }))
+                .await,
+        ];
+        for result in calls {
+            assert_eq!(result.unwrap().data["dry_run"], true);
+        }
+
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\ntwo\n");
+        let entries: Vec<_> = std::fs::read_dir(tmp.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template() {
+        let tmp = tempfile::tempdir().unwrap();
+        let main = tmp.path().join("app/src/main.rs");
+        let path = main.to_str().unwrap();
+
+        let result = FileCreateTool
+            .execute(serde_json::json!({
+                "path": path,
+                "template": "rust-bin",
+                "variables": { "name": "app" }
+            }))
+            .await
+            .unwrap();
+        let written = std::fs::read_to_string(&main).unwrap();
+        assert!(
+            written.contains("println!(\"Hello from app!\");"),
+            "{}",
+            written
+        );
+        assert!(written.starts_with("//! Command-line entry point.\n"));
+        assert_eq!(result.data["template"], "rust-bin");
+        assert_eq!(result.data["bytes"], written.len());
+        assert_eq!(result.data["lines"], written.lines().count());
+
+        let other = tmp.path().join("README.md");
+        let other = other.to_str().unwrap();
+        for params in [
+            serde_json::json!({ "path": other, "template": "readme" }),
+            serde_json::json!({ "path": other, "template": "nope" }),
+            serde_json::json!({ "path": other, "template": "gitignore", "content": "x" }),
+        ] {
+            let err = FileCreateTool.execute(params).await.unwrap_err();
+            assert!(matches!(err, ToolError::InvalidParameters(_)), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edit_fuzzy_whitespace_fallback() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        let original = "fn main() {\n\tlet x = 1;  \n\tprintln!(\"{}\", x);\n}\n";
+        std::fs::write(&file, original).unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+        // The model quoted the tab-indented body with four spaces
+        let params = serde_json::json!({
+            "path": path,
+            "old_string": "    let x = 1;\n    println!(\"{}\",  x);",
+            "new_string": "\tlet x = 2;\n\tprintln!(\"{}\", x);"
+        });
+
+        // Strict callers keep exact semantics
+        let err = edit.execute(params.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("old_string not found"), "{}", err);
+
+        let mut fuzzy = params;
+        fuzzy["fuzzy"] = serde_json::json!(true);
+        let result = edit.execute(fuzzy).await.unwrap();
+        assert_eq!(result.data["fuzzy_match"], true);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "fn main() {\n\tlet x = 2;\n\tprintln!(\"{}\", x);\n}\n"
+        );
+
+        // Several whitespace-insensitive matches are still ambiguous
+        std::fs::write(&file, "  a = 1\n\ta = 1\n").unwrap();
+        let err = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "   a = 1",
+                "new_string": "   a = 2",
+                "fuzzy": true
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("found 2 times"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_edit_returns_unified_diff() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}\nfn b() {}\nfn c() {}").unwrap();
+        let path = file.to_str().unwrap();
+        let edit = FileEditTool::default();
+
+        let replaced = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "fn b() {}",
+                "new_string": "fn b() -> u8 { 1 }"
+            }))
+            .await
+            .unwrap();
+        let diff = replaced.data["diff"].as_str().unwrap();
+        assert!(diff.starts_with(&format!("--- {}\n+++ {}\n@@ ", path, path)));
+        assert!(
+            diff.contains("\n-fn b() {}\n+fn b() -> u8 { 1 }\n"),
+            "{}",
+            diff
+        );
+
+        let hash = HashlineConfig::default().hash("fn b() -> u8 { 1 }");
+        let deleted = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "line_number": 2,
+                "hash": hash,
+                "mode": "delete"
+            }))
+            .await
+            .unwrap();
+        let diff = deleted.data["diff"].as_str().unwrap();
+        assert!(diff.contains("\n-fn b() -> u8 { 1 }\n"), "{}", diff);
+        assert!(
+            !diff.lines().skip(2).any(|l| l.starts_with('+')),
+            "{}",
+            diff
+        );
+
+        // Far-apart changes make one hunk each; only the first are kept
+        let lines: Vec<String> = (0..500).map(|i| format!("x{}", i % 10)).collect();
+        std::fs::write(&file, lines.join("\n")).unwrap();
+        let many = edit
+            .execute(serde_json::json!({
+                "path": path,
+                "old_string": "x5",
+                "new_string": "y5",
+                "replace_all": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(many.data["diff_truncated"], true);
+        let diff = many.data["diff"].as_str().unwrap();
+        assert_eq!(diff.matches("\n@@ ").count() + 1, MAX_DIFF_HUNKS + 1);
+        assert!(diff.ends_with("more hunk(s) not shown\n"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_policy_in_info_copy_and_move() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("config.toml");
+        std::fs::write(&target, "key = 1\n").unwrap();
+        let link = tmp.path().join("link.toml");
+        std::os::unix::fs::symlink("config.toml", &link).unwrap();
+
+        let followed = FileInfoTool
+            .execute(serde_json::json!({ "path": link }))
+            .await
+            .unwrap();
+        assert_eq!(followed.data["type"], "file");
+        assert_eq!(followed.data["is_symlink"], true);
+        assert_eq!(followed.data["link_target"], "config.toml");
+        assert_eq!(followed.data["line_count"], 1);
+
+        let own = FileInfoTool
+            .execute(serde_json::json!({ "path": link, "follow_symlinks": false }))
+            .await
+            .unwrap();
+        assert_eq!(own.data["type"], "symlink");
+        assert_eq!(
+            own.data["link_resolved"],
+            std::fs::canonicalize(&target)
+                .unwrap()
+                .display()
+                .to_string()
+        );
+
+        // Copy recreates the link by default, or copies the target on request
+        let copied_link = tmp.path().join("copy-link.toml");
+        let result = FileCopyTool
+            .execute(serde_json::json!({ "source": link, "destination": copied_link }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["type"], "symlink");
+        assert_eq!(
+            std::fs::read_link(&copied_link).unwrap(),
+            PathBuf::from("config.toml")
+        );
+        let copied_file = tmp.path().join("copy.toml");
+        FileCopyTool
+            .execute(serde_json::json!({
+                "source": link,
+                "destination": copied_file,
+                "follow_symlinks": true
+            }))
+            .await
+            .unwrap();
+        assert!(!std::fs::symlink_metadata(&copied_file)
+            .unwrap()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&copied_file).unwrap(), "key = 1\n");
+
+        // A dangling link can still be moved, as a link
+        let dangling = tmp.path().join("dangling");
+        std::os::unix::fs::symlink("missing.txt", &dangling).unwrap();
+        let moved = tmp.path().join("moved");
+        FileMoveTool
+            .execute(serde_json::json!({ "source": dangling, "destination": moved }))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_link(&moved).unwrap(),
+            PathBuf::from("missing.txt")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_info_reports_git_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let out = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .unwrap();
+            assert!(
+                out.status.success(),
+                "{}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        };
+        git(&["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(repo.join("tracked.txt"), "one\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        std::fs::write(repo.join("new.txt"), "new\n").unwrap();
+        std::fs::write(repo.join("debug.log"), "log\n").unwrap();
+
+        let status = |name: &str| {
+            let path = repo.join(name);
+            async move {
+                FileInfoTool
+                    .execute(serde_json::json!({ "path": path, "git": true }))
+                    .await
+                    .unwrap()
+                    .data
+            }
+        };
+
+        let clean = status("tracked.txt").await;
+        assert_eq!(clean["git_status"], "clean");
+        assert_eq!(clean["git_branch"], "main");
+        assert_eq!(status("new.txt").await["git_status"], "untracked");
+        assert_eq!(status("debug.log").await["git_status"], "ignored");
+
+        std::fs::write(repo.join("tracked.txt"), "two\n").unwrap();
+        assert_eq!(status("tracked.txt").await["git_status"], "modified");
+        git(&["add", "tracked.txt"]);
+        assert_eq!(status("tracked.txt").await["git_status"], "staged");
+
+        // Outside a repository the fields are null; without git=true they're absent
+        let outside = tmp.path().join("loose.txt");
+        std::fs::write(&outside, "x").unwrap();
+        let data = FileInfoTool
+            .execute(serde_json::json!({ "path": outside, "git": true }))
+            .await
+            .unwrap()
+            .data;
+        assert!(data["git_status"].is_null());
+        assert!(data["git_branch"].is_null());
+        let data = FileInfoTool
+            .execute(serde_json::json!({ "path": outside }))
+            .await
+            .unwrap()
+            .data;
+        assert!(data.get("git_status").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_directory_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src/nested/deep")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(root.join("debug.log"), "noise").unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "x".repeat(2048)).unwrap();
+        std::fs::write(root.join("src/nested/deep/too_deep.rs"), "").unwrap();
+        let path = root.to_str().unwrap();
+
+        let result = DirectoryTreeTool::new(root)
+            .execute(serde_json::json!({ "path": path, "max_depth": 2 }))
+            .await
+            .unwrap();
+        let tree = result.data["tree"].as_str().unwrap();
+        let expected = format!(
+            "{}/\n├── Cargo.toml (10 B)\n└── src/\n    ├── lib.rs (2.0 KB)\n    └── nested/\n",
+            path
+        );
+        assert_eq!(tree, expected);
+        assert_eq!(result.data["files"], 2);
+        assert_eq!(result.data["directories"], 2);
+        assert_eq!(result.data["truncated"], false);
+        let nodes = result.data["nodes"].as_array().unwrap();
+        assert_eq!(nodes[2]["path"], "src/lib.rs");
+        assert_eq!(nodes[2]["type"], "file");
+        assert_eq!(nodes[2]["size"], 2048);
+        assert_eq!(nodes[3]["type"], "directory");
+
+        // .gitignore can be bypassed; the skip list still applies
+        let all = DirectoryTreeTool::new(root)
+            .execute(
+                serde_json::json!({ "path": path, "max_depth": 1, "respect_gitignore": false }),
+            )
+            .await
+            .unwrap();
+        let names: Vec<&str> = all.data["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Cargo.toml", "debug.log", "src"]);
+
+        let capped = DirectoryTreeTool::new(root)
+            .execute(serde_json::json!({ "path": path, "max_entries": 2 }))
+            .await
+            .unwrap();
+        assert_eq!(capped.data["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(capped.data["truncated"], true);
+
+        // Confined to the workspace, like directory_list
+        let outside = DirectoryTreeTool::new(root.join("src"))
+            .execute(serde_json::json!({ "path": ".." }))
+            .await;
+        assert!(matches!(outside, Err(ToolError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_directory_list() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src/nested/deep")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(root.join(".env"), "KEY=1").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "x".repeat(2048)).unwrap();
+        std::fs::write(root.join("src/nested/deep/too_deep.rs"), "").unwrap();
+        let tool = DirectoryListTool::new(root);
+
+        // One level by default, with sizes and times
+        let flat = tool.execute(serde_json::json!({})).await.unwrap();
+        let entries = flat.data["entries"].as_array().unwrap();
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Cargo.toml", "src"]);
+        assert_eq!(entries[0]["type"], "file");
+        assert_eq!(entries[0]["size"], 10);
+        assert!(entries[0]["modified"].as_str().is_some());
+        assert_eq!(entries[1]["type"], "directory");
+        assert!(entries[1].get("children").is_none());
+        assert!(flat.message.ends_with("\n./\n├── Cargo.toml (10 B)\n└── src/"));
+
+        let tree = tool
+            .execute(
+                serde_json::json!({ "recursive": true, "max_depth": 2, "include_hidden": true }),
+            )
+            .await
+            .unwrap();
+        let entries = tree.data["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["name"], ".env");
+        let src = &entries[2]["children"];
+        assert_eq!(src[0]["path"], "src/lib.rs");
+        assert_eq!(src[1]["name"], "nested");
+        assert!(src[1].get("children").is_none());
+        assert_eq!(tree.data["files"], 3);
+        assert_eq!(tree.data["directories"], 2);
+
+        let capped = tool
+            .execute(serde_json::json!({ "recursive": true, "max_entries": 2 }))
+            .await
+            .unwrap();
+        assert_eq!(capped.data["truncated"], true);
+
+        // The workspace can't be left
+        let outside = tool.execute(serde_json::json!({ "path": ".." })).await;
+        assert!(matches!(outside, Err(ToolError::PermissionDenied(_))));
+        let missing = tool.execute(serde_json::json!({ "path": "nope" })).await;
+        assert!(matches!(missing, Err(ToolError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_nested_gitignores_stop_at_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        // A .gitignore above the workspace, as in $HOME, is not the project's
+        std::fs::write(tmp.path().join(".gitignore"), "*.md\n").unwrap();
+        let workspace = tmp.path().join("proj");
+        std::fs::create_dir_all(workspace.join("sub/deep")).unwrap();
+        std::fs::write(workspace.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(workspace.join("sub/.gitignore"), "!keep.log\n*.tmp\n").unwrap();
+        for file in [
+            "sub/a.log",
+            "sub/keep.log",
+            "sub/notes.md",
+            "sub/deep/x.tmp",
+            "sub/deep/y.rs",
+        ] {
+            std::fs::write(workspace.join(file), "").unwrap();
+        }
+
+        let sub = std::fs::canonicalize(workspace.join("sub")).unwrap();
+        let matchers = ancestor_gitignores(&sub, &workspace);
+        assert_eq!(matchers.len(), 1);
+        assert!(is_gitignored(&matchers, &sub.join("a.log"), false));
+        assert!(!is_gitignored(&matchers, &sub.join("notes.md"), false));
+
+        let listed = DirectoryListTool::new(&workspace)
+            .execute(serde_json::json!({ "path": "sub", "recursive": true }))
+            .await
+            .unwrap();
+        let entries = listed.data["entries"].as_array().unwrap();
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        // The deeper .gitignore whitelists keep.log and adds *.tmp
+        assert_eq!(names, ["deep", "keep.log", "notes.md"]);
+        let deep: Vec<&str> = entries[0]["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(deep, ["y.rs"]);
+    }
+
+    #[test]
+    fn test_mutating_tools_require_approval() {
+        let mutating: Vec<Box<dyn Tool>> = vec![
+            Box::new(FileEditTool::default()),
+            Box::new(FileCreateTool),
+            Box::new(FileAppendTool),
+            Box::new(FileDeleteTool),
+            Box::new(FileMoveTool),
+            Box::new(FilePermissionsTool),
+            Box::new(DirectoryCreateTool),
+            Box::new(FileCopyTool),
+        ];
+        for tool in &mutating {
+            assert_eq!(tool.category(), ToolCategory::Mutating, "{}", tool.name());
+            assert!(tool.requires_approval(), "{}", tool.name());
+            assert!(!tool.description().contains("APPROVAL"), "{}", tool.name());
+        }
+
+        let read_only: Vec<Box<dyn Tool>> =
+            vec![Box::new(FileInfoTool), Box::new(FileSearchContentTool)];
+        for tool in &read_only {
+            assert_eq!(tool.category(), ToolCategory::ReadOnly, "{}", tool.name());
+            assert!(!tool.requires_approval(), "{}", tool.name());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_info_checksum() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("data.bin");
+        // Larger than one read chunk, so the digest covers several updates
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&file, &content).unwrap();
+        let path = file.to_str().unwrap();
+
+        let plain = FileInfoTool
+            .execute(serde_json::json!({ "path": path }))
+            .await
+            .unwrap();
+        assert!(plain.data["checksum"].is_null());
+
+        let small = tmp.path().join("abc.txt");
+        std::fs::write(&small, "abc").unwrap();
+        let digest = |algorithm: &str| {
+            let path = small.to_str().unwrap().to_string();
+            let algorithm = algorithm.to_string();
+            async move {
+                FileInfoTool
+                    .execute(serde_json::json!({ "path": path, "checksum": algorithm }))
+                    .await
+                    .unwrap()
+                    .data["checksum"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            }
+        };
+        assert_eq!(
+            digest("sha256").await,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(digest("md5").await, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(digest("blake3").await.len(), 64);
+
+        let streamed = file_checksum(&file, ChecksumAlgorithm::Blake3)
+            .await
+            .unwrap();
+        assert_eq!(streamed, blake3::hash(&content).to_hex().to_string());
+
+        for bad in [serde_json::json!("crc32"), serde_json::json!(1)] {
+            assert!(FileInfoTool
+                .execute(serde_json::json!({ "path": path, "checksum": bad }))
+                .await
+                .is_err());
+        }
+        assert!(FileInfoTool
+            .execute(serde_json::json!({ "path": tmp.path(), "checksum": "md5" }))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = chrono::Local::now();
+        let ago =
+            |secs: u64| std::time::SystemTime::from(now) - std::time::Duration::from_secs(secs);
+        let relative = |time| format_relative(time, now);
+        assert_eq!(relative(ago(5)), "just now");
+        assert_eq!(relative(ago(60)), "1 minute ago");
+        assert_eq!(relative(ago(3 * 3600 + 59)), "3 hours ago");
+        assert_eq!(relative(ago(2 * 86_400)), "2 days ago");
+        assert_eq!(relative(ago(400 * 86_400)), "1 year ago");
+        let soon = std::time::SystemTime::from(now) + std::time::Duration::from_secs(120);
+        assert_eq!(relative(soon), "in 2 minutes");
+    }
+
+    #[tokio::test]
+    async fn test_file_info_reports_mime_type_and_language() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("main.rs");
+        let image = tmp.path().join("logo");
+        let notes = tmp.path().join("NOTES");
+        std::fs::write(&source, "fn main() {}\n").unwrap();
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0").unwrap();
+        std::fs::write(&notes, "plain notes\n").unwrap();
+
+        let info = |path: &Path| FileInfoTool.execute(serde_json::json!({ "path": path }));
+
+        let result = info(&source).await.unwrap();
+        assert_eq!(result.data["mime_type"], "text/x-rust");
+        assert_eq!(result.data["language"], "Rust");
+        assert_eq!(result.data["line_count"], 1);
+
+        // Extensionless files are sniffed; binary content gets no line count
+        let result = info(&image).await.unwrap();
+        assert_eq!(result.data["mime_type"], "image/png");
+        assert_eq!(result.data["binary"], true);
+        assert!(result.data["language"].is_null());
+        assert!(result.data["line_count"].is_null());
+
+        let result = info(&notes).await.unwrap();
+        assert_eq!(result.data["mime_type"], "text/plain");
+        assert_eq!(result.data["line_count"], 1);
+
+        assert_eq!(result.data["modified_relative"], "just now");
+        let iso = result.data["modified_iso"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(iso).is_ok(), "{}", iso);
+
+        assert_eq!(detect_language(Path::new("Dockerfile")), Some("Dockerfile"));
+        assert_eq!(detect_language(Path::new("README.MD")), Some("Markdown"));
+        let dir = info(tmp.path()).await.unwrap();
+        assert!(dir.data["mime_type"].is_null());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_chmod_sets_and_reports_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("run.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let path = script.to_str().unwrap();
+
+        let result = FilePermissionsTool
+            .execute(serde_json::json!({ "path": path, "mode": "755" }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["mode_before"], "0644");
+        assert_eq!(result.data["mode_after"], "0755");
+        assert_eq!(
+            std::fs::metadata(&script).unwrap().permissions().mode() & 0o7777,
+            0o755
+        );
+
+        let query = FilePermissionsTool
+            .execute(serde_json::json!({ "path": path }))
+            .await
+            .unwrap();
+        assert_eq!(query.data["mode_after"], "0755");
+
+        for bad in ["999", "rwx", "77777", ""] {
+            assert!(FilePermissionsTool
+                .execute(serde_json::json!({ "path": path, "mode": bad }))
+                .await
+                .is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_atomic_write_replaces_file_and_cleans_up() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("script.sh");
+        std::fs::write(&file, "old").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        atomic_write(&file, "new").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "new");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+
+        // Failures leave neither the target nor a temp file behind
+        let missing = tmp.path().join("no_such_dir").join("f.txt");
+        assert!(atomic_write(&missing, "x").await.is_err());
+        let names: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("script.sh")]);
+
+        // A write abandoned half way (tool timeout) never leaves a partial file
+        let big = "x".repeat(8 << 20);
+        let _ = tokio::time::timeout(std::time::Duration::ZERO, atomic_write(&file, &big)).await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(
+            content == "new" || content == big,
+            "{} bytes",
+            content.len()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_edit_through_a_symlink_updates_the_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("shared")).unwrap();
+        let target = tmp.path().join("shared/config.toml");
+        std::fs::write(&target, "key = 1\n").unwrap();
+        let link = tmp.path().join("config.toml");
+        std::os::unix::fs::symlink("shared/config.toml", &link).unwrap();
+
+        FileEditTool::new(HashlineConfig::default())
+            .execute(serde_json::json!({
+                "path": link.to_str().unwrap(),
+                "old_string": "key = 1",
+                "new_string": "key = 2"
+            }))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "key = 2\n");
+        assert!(std::fs::symlink_metadata(&link).unwrap().is_symlink());
+
+        // A dangling link gets its target created, the temp file beside it
+        let dangling = tmp.path().join("new.txt");
+        std::os::unix::fs::symlink("shared/new.txt", &dangling).unwrap();
+        atomic_write(&dangling, "fresh").await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("shared/new.txt")).unwrap(),
+            "fresh"
+        );
+        assert!(std::fs::symlink_metadata(&dangling).unwrap().is_symlink());
+        assert_eq!(
+            std::fs::read_dir(tmp.path().join("shared"))
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_append_ensures_newline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("log.txt");
+        let path = file.to_str().unwrap();
+
+        // Missing file is created; no leading newline needed
+        let result = FileAppendTool
+            .execute(serde_json::json!({ "path": path, "content": "first" }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["created"], true);
+        let result = FileAppendTool
+            .execute(serde_json::json!({ "path": path, "content": "second\n" }))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "first\nsecond\n");
+        assert_eq!(result.data["bytes"], 13);
+        assert_eq!(result.data["lines"], 2);
+        assert_eq!(result.data["created"], false);
+
+        // Parent directories are created like file_create does
+        let nested = tmp.path().join("logs/2026/run.log");
+        FileAppendTool
+            .execute(serde_json::json!({ "path": nested.to_str().unwrap(), "content": "x" }))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&nested).unwrap(), "x");
+
+        let result = FileAppendTool
+            .execute(serde_json::json!({
+                "path": path,
+                "content": "raw",
+                "ensure_newline": false
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["lines"], 3);
+
+        let missing = tmp.path().join("missing.txt");
+        let err = FileAppendTool
+            .execute(serde_json::json!({
+                "path": missing.to_str().unwrap(),
+                "content": "x",
+                "create": false
+            }))
+            .await;
+        assert!(matches!(err, Err(ToolError::NotFound(_))));
+        assert!(!missing.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_copy_preserves_mode_and_mtime() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("run.sh");
+        std::fs::write(&src, "#!/bin/sh\necho hi\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_mtime(&src, mtime).unwrap();
+
+        let dst = tmp.path().join("copy").join("run.sh");
+        let result = FileCopyTool
+            .execute(serde_json::json!({
+                "source": src.to_str().unwrap(),
+                "destination": dst.to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+
+        let meta = std::fs::metadata(&dst).unwrap();
+        assert_eq!(filetime::FileTime::from_last_modification_time(&meta), mtime);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(meta.permissions().mode() & 0o777, 0o755);
+            assert_eq!(result.data["mode"], "0755");
+        }
+        assert_eq!(result.data["preserved"], true);
+    }
+
+    #[tokio::test]
+    async fn test_file_copy_refuses_the_same_file_and_leaves_nothing_behind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("a.txt");
+        std::fs::write(&src, "keep me").unwrap();
+
+        let same = tmp.path().join(".").join("a.txt");
+        let err = FileCopyTool
+            .execute(serde_json::json!({
+                "source": src.to_str().unwrap(),
+                "destination": same.to_str().unwrap(),
+                "overwrite": true
+            }))
+            .await;
+        assert!(matches!(err, Err(ToolError::InvalidParameters(_))));
+        assert_eq!(std::fs::read_to_string(&src).unwrap(), "keep me");
+
+        let tree = tmp.path().join("tree");
+        std::fs::create_dir(&tree).unwrap();
+        let nested = tree.join("deep").join("copy");
+        let err = FileCopyTool
+            .execute(serde_json::json!({
+                "source": tree.to_str().unwrap(),
+                "destination": nested.to_str().unwrap()
+            }))
+            .await;
+        assert!(matches!(err, Err(ToolError::InvalidParameters(_))));
+        assert!(!tree.join("deep").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_copy_replaces_a_symlinked_destination() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("new.txt");
+        std::fs::write(&src, "new").unwrap();
+        let target = tmp.path().join("target.txt");
+        std::fs::write(&target, "untouched").unwrap();
+        let link = tmp.path().join("link.txt");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        FileCopyTool
+            .execute(serde_json::json!({
+                "source": src.to_str().unwrap(),
+                "destination": link.to_str().unwrap(),
+                "overwrite": true
+            }))
+            .await
+            .unwrap();
+
+        assert!(!std::fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(std::fs::read_to_string(&link).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "untouched");
+    }
+
+    #[tokio::test]
+    async fn test_file_copy_directory_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src");
+        std::fs::create_dir_all(src.join("nested/deeper")).unwrap();
+        std::fs::write(src.join("a.txt"), "aaaa").unwrap();
+        std::fs::write(src.join("nested/b.txt"), "bb").unwrap();
+        std::fs::write(src.join("nested/deeper/c.txt"), "c").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("a.txt", src.join("link.txt")).unwrap();
+
+        let dst = tmp.path().join("dst");
+        let params = serde_json::json!({
+            "source": src.to_str().unwrap(),
+            "destination": dst.to_str().unwrap()
+        });
+        let result = FileCopyTool.execute(params.clone()).await.unwrap();
+        assert_eq!(result.data["files"], 3);
+        assert_eq!(result.data["bytes"], 7);
+        assert_eq!(
+            std::fs::read_to_string(dst.join("nested/deeper/c.txt")).unwrap(),
+            "c"
+        );
+        #[cfg(unix)]
+        {
+            assert_eq!(result.data["symlinks"], 1);
+            assert_eq!(
+                std::fs::read_link(dst.join("link.txt")).unwrap(),
+                PathBuf::from("a.txt")
+            );
+        }
+
+        // Existing destination needs overwrite=true
+        assert!(FileCopyTool.execute(params.clone()).await.is_err());
+        std::fs::write(src.join("a.txt"), "new").unwrap();
+        let mut params = params;
+        params["overwrite"] = serde_json::json!(true);
+        FileCopyTool.execute(params).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dst.join("a.txt")).unwrap(), "new");
+
+        // Copying a directory into itself is refused
+        let into_self = FileCopyTool
+            .execute(serde_json::json!({
+                "source": src.to_str().unwrap(),
+                "destination": src.join("inner").to_str().unwrap()
+            }))
+            .await;
+        assert!(matches!(into_self, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[tokio::test]
+    async fn test_file_copy_skips_build_dirs_and_respects_recursive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("project");
+        for dir in ["src", "target/debug", ".git", "node_modules/pkg"] {
+            std::fs::create_dir_all(src.join(dir)).unwrap();
+        }
+        for file in [
+            "Cargo.toml",
+            "src/main.rs",
+            "target/debug/app",
+            ".git/HEAD",
+            "node_modules/pkg/index.js",
+        ] {
+            std::fs::write(src.join(file), "x").unwrap();
+        }
+        let copy = |dst: &str, extra: Value| {
+            let mut params = serde_json::json!({
+                "source": src.to_str().unwrap(),
+                "destination": tmp.path().join(dst).to_str().unwrap()
+            });
+            for (k, v) in extra.as_object().unwrap() {
+                params[k] = v.clone();
+            }
+            FileCopyTool.execute(params)
+        };
+
+        let result = copy("default", serde_json::json!({})).await.unwrap();
+        assert_eq!(result.data["files"], 2);
+        assert_eq!(result.data["skipped_directories"], 3);
+        assert!(tmp.path().join("default/src/main.rs").exists());
+        assert!(!tmp.path().join("default/target").exists());
+        assert!(!tmp.path().join("default/.git").exists());
+
+        let result = copy("all", serde_json::json!({ "skip_build_dirs": false }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["files"], 5);
+
+        let result = copy("shallow", serde_json::json!({ "recursive": false }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["files"], 1);
+        assert!(tmp.path().join("shallow/Cargo.toml").exists());
+        assert!(!tmp.path().join("shallow/src").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_move_overwrite() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("new.txt");
+        let dst = tmp.path().join("old.txt");
+        std::fs::write(&src, "new").unwrap();
+        std::fs::write(&dst, "old").unwrap();
+        let params = serde_json::json!({
+            "source": src.to_str().unwrap(),
+            "destination": dst.to_str().unwrap()
+        });
+
+        assert!(FileMoveTool.execute(params.clone()).await.is_err());
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "old");
+
+        let mut params = params;
+        params["overwrite"] = serde_json::json!(true);
+        let result = FileMoveTool.execute(params).await.unwrap();
+        assert_eq!(result.data["strategy"], "rename");
+        assert_eq!(result.data["overwritten"], true);
+        assert_eq!(std::fs::read_to_string(&dst).unwrap(), "new");
+        assert!(!src.exists());
+        // The replaced file went to the trash, not away
+        let trashed = PathBuf::from(result.data["trash_path"].as_str().unwrap());
+        assert_eq!(std::fs::read_to_string(trashed).unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn test_file_move_refuses_to_overwrite_the_source() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "keep").unwrap();
+
+        // The source itself, spelled differently
+        let same = FileMoveTool
+            .execute(serde_json::json!({
+                "source": dir.to_str().unwrap(),
+                "destination": tmp.path().join(".").join("dir").to_str().unwrap(),
+                "overwrite": true
+            }))
+            .await;
+        assert!(matches!(same, Err(ToolError::InvalidParameters(_))));
+
+        // A destination that contains the source
+        let parent = FileMoveTool
+            .execute(serde_json::json!({
+                "source": dir.join("a.txt").to_str().unwrap(),
+                "destination": dir.to_str().unwrap(),
+                "overwrite": true
+            }))
+            .await;
+        assert!(matches!(parent, Err(ToolError::InvalidParameters(_))));
+
+        // A directory moved into itself
+        let inside = FileMoveTool
+            .execute(serde_json::json!({
+                "source": dir.to_str().unwrap(),
+                "destination": dir.join("sub/dir").to_str().unwrap()
+            }))
+            .await;
+        assert!(matches!(inside, Err(ToolError::InvalidParameters(_))));
+
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "keep");
+        assert!(!dir.join("sub").exists());
+    }
+
+    #[tokio::test]
+    async fn test_move_falls_back_to_copy_on_cross_device() {
+        let cross_device = |_: PathBuf, _: PathBuf| async {
+            Err(std::io::Error::from(std::io::ErrorKind::CrossesDevices))
+        };
+        let tmp = tempfile::tempdir().unwrap();
+
+        let file = tmp.path().join("a.txt");
+        std::fs::write(&file, "data").unwrap();
+        let moved = tmp.path().join("b.txt");
+        let strategy = move_path(&file, &moved, cross_device).await.unwrap();
+        assert_eq!(strategy, MoveStrategy::CopyDelete);
+        assert!(!file.exists());
+        assert_eq!(std::fs::read_to_string(&moved).unwrap(), "data");
+
+        let dir = tmp.path().join("dir");
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("sub/c.txt"), "c").unwrap();
+        let moved_dir = tmp.path().join("moved");
+        let strategy = move_path(&dir, &moved_dir, cross_device).await.unwrap();
+        assert_eq!(strategy, MoveStrategy::CopyDelete);
+        assert!(!dir.exists());
+        assert_eq!(
+            std::fs::read_to_string(moved_dir.join("sub/c.txt")).unwrap(),
+            "c"
+        );
+
+        // Other rename errors are not retried
+        let denied = |_: PathBuf, _: PathBuf| async {
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        };
+        std::fs::write(&file, "data").unwrap();
+        assert!(move_path(&file, &moved, denied).await.is_err());
+        assert!(file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_delete_guards_and_permanent_removal() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("dir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("f.txt"), "x").unwrap();
+
+        // Non-empty directories need recursive=true, even for the trash
+        let err = FileDeleteTool
+            .execute(serde_json::json!({ "path": dir.to_str().unwrap() }))
+            .await;
+        assert!(matches!(err, Err(ToolError::ExecutionFailed(_))));
+        assert!(dir.join("f.txt").exists());
+
+        let result = FileDeleteTool
+            .execute(serde_json::json!({
+                "path": dir.to_str().unwrap(),
+                "recursive": true,
+                "to_trash": false
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["trashed"], false);
+        assert!(result.message.contains("permanently"));
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_delete_trash_false_deletes_permanently() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("gone.txt");
+        std::fs::write(&file, "bye").unwrap();
+
+        let params = serde_json::json!({
+            "path": file.to_str().unwrap(),
+            "trash": false
+        });
+        crate::agent::tools::schema::validate_params(&FileDeleteTool.parameters_schema(), &params)
+            .unwrap();
+        let result = FileDeleteTool.execute(params).await.unwrap();
+
+        assert_eq!(result.data["trashed"], false);
+        assert!(result.data["trash_location"].is_null());
+        assert!(!file.exists());
+        assert!(!tmp.path().join(".localm").exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_delete_moves_to_project_trash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+        std::fs::create_dir_all(project.join("src/old")).unwrap();
+        std::fs::write(project.join("src/old/mod.rs"), "// old").unwrap();
+
+        let result = FileDeleteTool
+            .execute(serde_json::json!({
+                "path": project.join("src/old").to_str().unwrap(),
+                "recursive": true
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(result.data["trash_location"], "project");
+        let trash_path = PathBuf::from(result.data["trash_path"].as_str().unwrap());
+        let trash_root = std::fs::canonicalize(project.join(".localm/trash")).unwrap();
+        assert!(trash_path.starts_with(&trash_root), "{}", trash_path.display());
+        // <trash>/<timestamp>/src/old keeps the project-relative layout
+        assert!(trash_path.ends_with("src/old"));
+        assert_eq!(
+            std::fs::read_to_string(trash_path.join("mod.rs")).unwrap(),
+            "// old"
+        );
+        assert!(!project.join("src/old").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_file_delete_trashes_a_symlink_not_its_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("proj");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("outside")).unwrap();
+        let precious = tmp.path().join("outside/precious.txt");
+        std::fs::write(&precious, "precious").unwrap();
+        let link = project.join("link.txt");
+        std::os::unix::fs::symlink(&precious, &link).unwrap();
+
+        let result = FileDeleteTool
+            .execute(serde_json::json!({ "path": link.to_str().unwrap() }))
+            .await
+            .unwrap();
+
+        let trash_path = PathBuf::from(result.data["trash_path"].as_str().unwrap());
+        let trash_root = std::fs::canonicalize(project.join(".localm/trash")).unwrap();
+        assert!(
+            trash_path.starts_with(&trash_root),
+            "{}",
+            trash_path.display()
+        );
+        assert!(trash_path.ends_with("link.txt"));
+        assert!(std::fs::symlink_metadata(&trash_path).unwrap().is_symlink());
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert_eq!(std::fs::read_to_string(&precious).unwrap(), "precious");
+        assert!(!tmp.path().join("outside/.localm").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_permanent_delete_removes_a_directory_symlink_not_its_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let outside = tmp.path().join("outside/dir");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("keep.txt"), "keep").unwrap();
+        std::fs::create_dir_all(tmp.path().join("proj")).unwrap();
+        let link = tmp.path().join("proj/link");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+        let result = FileDeleteTool
+            .execute(serde_json::json!({
+                "path": link.to_str().unwrap(),
+                "recursive": true,
+                "permanent": true
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.data["type"], "symlink");
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert_eq!(
+            std::fs::read_to_string(outside.join("keep.txt")).unwrap(),
+            "keep"
+        );
+
+        // The same holds when the link is reached through remove_tree
+        let link = tmp.path().join("proj/again");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+        remove_tree(&link, &CancellationToken::new()).await.unwrap();
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert!(outside.join("keep.txt").exists());
     }
 }
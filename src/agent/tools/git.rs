@@ -1,15 +1,18 @@
 //! Git tools - Status, Diff, Log, Commit, Branch operations
 //!
 //! Provides git operations by shelling out to the git CLI.
+//! `git_status` and `git_diff` are scoped to the workspace and parse git's
+//! output into lists; outside a repository they fail with `NotFound`.
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{resolve_in_workspace, Tool, ToolCategory, ToolError, ToolResult};
 
 /// Helper to run git commands
-async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, String, bool), ToolError> {
+pub async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, String, bool), ToolError> {
     let mut cmd = Command::new("git");
     for arg in args {
         cmd.arg(arg);
@@ -28,18 +31,49 @@ async fn run_git(args: &[&str], working_dir: Option<&str>) -> Result<(String, St
     Ok((stdout, stderr, output.status.success()))
 }
 
+/// Stdout of `git args` run in `dir`, or the error git reported. Outside a
+/// repository, that is `ToolError::NotFound`.
+async fn git_stdout(args: &[&str], dir: &Path) -> Result<String, ToolError> {
+    let (stdout, stderr, ok) = run_git(args, Some(&dir.to_string_lossy())).await?;
+    if ok {
+        return Ok(stdout);
+    }
+    if stderr.to_lowercase().contains("not a git repository") {
+        return Err(ToolError::NotFound(format!(
+            "{} n'est pas dans un dépôt git",
+            dir.display()
+        )));
+    }
+    Err(ToolError::ExecutionFailed(format!(
+        "git {} a échoué: {}",
+        args.first().unwrap_or(&""),
+        stderr.trim()
+    )))
+}
+
 // ============================================================================
 // GitStatusTool
 // ============================================================================
 
-pub struct GitStatusTool;
+pub struct GitStatusTool {
+    /// Repositories are looked up from here; `working_dir` stays inside it
+    workspace: PathBuf,
+}
+
+impl GitStatusTool {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+}
 
 #[async_trait]
 impl Tool for GitStatusTool {
     fn name(&self) -> &str { "git_status" }
 
     fn description(&self) -> &str {
-        "Get the current git status: modified files, staged changes, branch info, untracked files."
+        "Get the git status of the workspace: branch and upstream, staged and unstaged changes, untracked and conflicted files, as lists."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -48,45 +82,152 @@ impl Tool for GitStatusTool {
             "properties": {
                 "working_dir": {
                     "type": "string",
-                    "description": "Repository path (default: current directory)"
+                    "description": "Directory inside the workspace to look from (default: the workspace root)"
                 }
             }
         })
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
-        let wd = params["working_dir"].as_str();
+        let dir = resolve_in_workspace(&self.workspace, params["working_dir"].as_str())?;
 
-        let (status_out, _, _) = run_git(&["status", "--porcelain=v2", "--branch"], wd).await?;
-        let (status_short, _, _) = run_git(&["status", "--short"], wd).await?;
-        let (branch, _, _) = run_git(&["branch", "--show-current"], wd).await?;
+        let porcelain = git_stdout(&["status", "--porcelain=v2", "--branch", "-z"], &dir).await?;
+        let status = parse_porcelain_v2(&porcelain);
+        let changed = status.staged.len() + status.unstaged.len() + status.untracked.len();
 
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
-                "branch": branch.trim(),
-                "status_porcelain": status_out,
-                "status_short": status_short,
+                "branch": status.branch,
+                "upstream": status.upstream,
+                "ahead": status.ahead,
+                "behind": status.behind,
+                "staged": status.staged,
+                "unstaged": status.unstaged,
+                "untracked": status.untracked,
+                "conflicted": status.conflicted,
+                "clean": changed == 0 && status.conflicted.is_empty(),
             }),
-            message: format!("Branche: {} | {} fichier(s) modifié(s)", 
-                branch.trim(), 
-                status_short.lines().count()),
+            message: format!(
+                "Branche: {} | {} indexé(s), {} modifié(s), {} non suivi(s)",
+                status.branch.as_deref().unwrap_or("(détachée)"),
+                status.staged.len(),
+                status.unstaged.len(),
+                status.untracked.len()
+            ),
         })
     }
 }
 
+/// `git status --porcelain=v2 --branch -z`, parsed
+#[derive(Debug, Default, PartialEq)]
+struct GitStatus {
+    /// `None` on a detached HEAD
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u64,
+    behind: u64,
+    /// `{path, status}` (and `from` for renames) for the index
+    staged: Vec<Value>,
+    /// `{path, status}` for the working tree
+    unstaged: Vec<Value>,
+    untracked: Vec<String>,
+    conflicted: Vec<String>,
+}
+
+/// Word for a porcelain status letter
+fn change_kind(code: char) -> &'static str {
+    match code {
+        'M' => "modified",
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'T' => "type_changed",
+        _ => "changed",
+    }
+}
+
+fn parse_porcelain_v2(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|r| !r.is_empty());
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let fields: Vec<&str> = record.splitn(11, ' ').collect();
+        match fields[0] {
+            "?" => status.untracked.push(record[2..].to_string()),
+            "u" if fields.len() == 11 => status.conflicted.push(fields[10].to_string()),
+            // Ordinary (1) and renamed or copied (2) entries; a rename is
+            // followed by its original path
+            kind @ ("1" | "2") => {
+                let path_field = if kind == "1" { 8 } else { 9 };
+                let Some(path) = record.splitn(path_field + 1, ' ').nth(path_field) else {
+                    continue;
+                };
+                let from = if kind == "2" { records.next() } else { None };
+                let mut codes = fields[1].chars();
+                let (index, worktree) = (codes.next().unwrap_or('.'), codes.next().unwrap_or('.'));
+                if index != '.' {
+                    let mut entry =
+                        serde_json::json!({ "path": path, "status": change_kind(index) });
+                    if let Some(from) = from {
+                        entry["from"] = serde_json::json!(from);
+                    }
+                    status.staged.push(entry);
+                }
+                if worktree != '.' {
+                    status
+                        .unstaged
+                        .push(serde_json::json!({ "path": path, "status": change_kind(worktree) }));
+                }
+            }
+            _ => {}
+        }
+    }
+    status
+}
+
 // ============================================================================
 // GitDiffTool
 // ============================================================================
 
-pub struct GitDiffTool;
+pub struct GitDiffTool {
+    /// Repositories are looked up from here; `working_dir` stays inside it
+    workspace: PathBuf,
+}
+
+impl GitDiffTool {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+}
 
 #[async_trait]
 impl Tool for GitDiffTool {
     fn name(&self) -> &str { "git_diff" }
 
     fn description(&self) -> &str {
-        "Show git diff - unstaged changes, staged changes, or diff between commits/branches."
+        "Show the git diff of the workspace - unstaged changes, staged changes, or between commits/branches - as the unified diff plus the list of files with their added and deleted line counts."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -112,7 +253,7 @@ impl Tool for GitDiffTool {
                 },
                 "working_dir": {
                     "type": "string",
-                    "description": "Repository path"
+                    "description": "Directory inside the workspace to run from (default: the workspace root)"
                 }
             }
         })
@@ -123,9 +264,9 @@ impl Tool for GitDiffTool {
         let file = params["file"].as_str();
         let ref1 = params["ref1"].as_str();
         let ref2 = params["ref2"].as_str();
-        let wd = params["working_dir"].as_str();
+        let dir = resolve_in_workspace(&self.workspace, params["working_dir"].as_str())?;
 
-        let mut args: Vec<&str> = vec!["diff", "--stat"];
+        let mut args: Vec<&str> = vec!["diff", "--numstat"];
         if staged {
             args.push("--cached");
         }
@@ -140,11 +281,12 @@ impl Tool for GitDiffTool {
             args.push(f);
         }
 
-        let (stat_out, _, _) = run_git(&args, wd).await?;
+        let numstat = git_stdout(&args, &dir).await?;
+        let files = parse_numstat(&numstat);
 
-        // Also get the actual diff (without --stat)
-        args.retain(|a| *a != "--stat");
-        let (diff_out, _, _) = run_git(&args, wd).await?;
+        // Also get the actual diff (without --numstat)
+        args.retain(|a| *a != "--numstat");
+        let diff_out = git_stdout(&args, &dir).await?;
 
         // Truncate if too long (safe char-boundary slicing)
         let diff_display = if diff_out.len() > 50000 {
@@ -154,17 +296,49 @@ impl Tool for GitDiffTool {
             diff_out
         };
 
+        let (added, deleted) = files.iter().fold((0, 0), |(a, d), f| {
+            (
+                a + f["added"].as_u64().unwrap_or(0),
+                d + f["deleted"].as_u64().unwrap_or(0),
+            )
+        });
         Ok(ToolResult {
             success: true,
             data: serde_json::json!({
-                "stat": stat_out,
+                "files": files,
+                "added": added,
+                "deleted": deleted,
                 "diff": diff_display,
             }),
-            message: format!("Diff: {} fichier(s) modifié(s)", stat_out.lines().count().saturating_sub(1)),
+            message: format!(
+                "Diff: {} fichier(s) modifié(s), +{} -{}",
+                files.len(),
+                added,
+                deleted
+            ),
         })
     }
 }
 
+/// `git diff --numstat` as `{path, added, deleted, binary}`; binary files
+/// have no line counts
+fn parse_numstat(output: &str) -> Vec<Value> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (added, deleted, path) = (fields.next()?, fields.next()?, fields.next()?);
+            let binary = added == "-";
+            Some(serde_json::json!({
+                "path": path,
+                "added": added.parse::<u64>().unwrap_or(0),
+                "deleted": deleted.parse::<u64>().unwrap_or(0),
+                "binary": binary,
+            }))
+        })
+        .collect()
+}
+
 // ============================================================================
 // GitLogTool
 // ============================================================================
@@ -280,7 +454,11 @@ impl Tool for GitCommitTool {
     fn name(&self) -> &str { "git_commit" }
 
     fn description(&self) -> &str {
-        "Stage files and create a git commit. Can stage specific files or all changes. REQUIRES APPROVAL."
+        "Stage files and create a git commit. Can stage specific files or all changes."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
     }
 
     fn parameters_schema(&self) -> Value {
@@ -366,6 +544,10 @@ impl Tool for GitBranchTool {
         "List, create, switch, or delete git branches."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -457,6 +639,10 @@ impl Tool for GitStashTool {
         "Stash or restore uncommitted changes. Actions: save, pop, list, drop."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -512,3 +698,71 @@ impl Tool for GitStashTool {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain_v2() {
+        let output = [
+            "# branch.oid 1234567890abcdef",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 M. N... 100644 100644 100644 aaa bbb src/lib.rs",
+            "1 .M N... 100644 100644 100644 aaa aaa src/with space.rs",
+            "2 R. N... 100644 100644 100644 aaa bbb R100 src/new.rs",
+            "src/old.rs",
+            "u UU N... 100644 100644 100644 100644 aaa bbb ccc Cargo.lock",
+            "? notes.txt",
+            "",
+        ]
+        .join("\0");
+        let status = parse_porcelain_v2(&output);
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(
+            status.staged,
+            [
+                serde_json::json!({ "path": "src/lib.rs", "status": "modified" }),
+                serde_json::json!({ "path": "src/new.rs", "status": "renamed", "from": "src/old.rs" }),
+            ]
+        );
+        assert_eq!(
+            status.unstaged,
+            [serde_json::json!({ "path": "src/with space.rs", "status": "modified" })]
+        );
+        assert_eq!(status.untracked, ["notes.txt"]);
+        assert_eq!(status.conflicted, ["Cargo.lock"]);
+
+        let detached = parse_porcelain_v2("# branch.head (detached)\0");
+        assert_eq!(detached.branch, None);
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let files = parse_numstat("3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n");
+        assert_eq!(files[0]["path"], "src/lib.rs");
+        assert_eq!(files[0]["added"], 3);
+        assert_eq!(files[0]["deleted"], 1);
+        assert_eq!(files[1]["binary"], true);
+    }
+
+    #[tokio::test]
+    async fn test_git_status_outside_a_repository() {
+        let tmp = tempfile::tempdir().unwrap();
+        let error = GitStatusTool::new(tmp.path())
+            .execute(serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ToolError::NotFound(_)), "{:?}", error);
+
+        let outside = GitDiffTool::new(tmp.path())
+            .execute(serde_json::json!({ "working_dir": ".." }))
+            .await;
+        assert!(matches!(outside, Err(ToolError::PermissionDenied(_))));
+    }
+}
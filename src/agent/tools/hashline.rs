@@ -0,0 +1,187 @@
+//! Hashline - short per-line content hashes
+//!
+//! `file_read` tags every line with a hash of its content and `file_edit`
+//! checks that hash before touching the line, so a stale line number cannot
+//! silently edit the wrong content. Both tools must hash identically, so they
+//! share one [`HashlineConfig`], built from `AgentConfig` at registration.
+//!
+//! A hash only tells lines apart up to its width: with the original 12 bits
+//! (4096 values) a file of a few hundred lines very likely has two lines with
+//! the same hash, and a stale edit could verify against the wrong one. The
+//! default is therefore 16 bits, and `file_edit` reports `collision_lines`
+//! whenever the hash it was given is shared by several lines.
+//! See https://github.com/0xZKnw/oh-my-pi
+
+use serde::{Deserialize, Serialize};
+
+/// Narrowest supported hash, in bits
+pub const MIN_HASH_BITS: u32 = 4;
+/// Widest supported hash, in bits
+pub const MAX_HASH_BITS: u32 = 32;
+
+/// Hash function used for line tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    /// FNV-1 (multiply, then xor) - the original Hashline hash
+    #[default]
+    Fnv1,
+    /// FNV-1a (xor, then multiply) - mixes the last bytes of a line better
+    Fnv1a,
+}
+
+/// Hash settings shared by `file_read` and `file_edit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashlineConfig {
+    pub algorithm: HashAlgorithm,
+    /// Hash width in bits, clamped to `MIN_HASH_BITS..=MAX_HASH_BITS`
+    pub bits: u32,
+}
+
+impl Default for HashlineConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: HashAlgorithm::Fnv1,
+            bits: 16,
+        }
+    }
+}
+
+impl HashlineConfig {
+    pub fn new(algorithm: HashAlgorithm, bits: u32) -> Self {
+        Self { algorithm, bits }
+    }
+
+    fn effective_bits(&self) -> u32 {
+        self.bits.clamp(MIN_HASH_BITS, MAX_HASH_BITS)
+    }
+
+    /// Number of hex characters in every hash (one per 4 bits, rounded up)
+    pub fn hex_width(&self) -> usize {
+        self.effective_bits().div_ceil(4) as usize
+    }
+
+    /// Hash a line (without its line terminator).
+    /// Always exactly `hex_width()` lowercase hex characters.
+    pub fn hash(&self, line: &str) -> String {
+        let raw = match self.algorithm {
+            HashAlgorithm::Fnv1 => fnv1(line.as_bytes()),
+            HashAlgorithm::Fnv1a => fnv1a(line.as_bytes()),
+        };
+        let bits = self.effective_bits();
+        let masked = if bits >= 32 {
+            raw
+        } else {
+            raw & ((1u32 << bits) - 1)
+        };
+        format!("{:0width$x}", masked, width = self.hex_width())
+    }
+
+    /// Whether `expected` (as copied from `file_read` output) matches `line`
+    pub fn verify(&self, line: &str, expected: &str) -> bool {
+        self.hash(line).eq_ignore_ascii_case(expected.trim())
+    }
+
+    /// 1-indexed numbers of every line in `lines` whose hash is `expected`.
+    /// More than one entry means the hash alone does not identify a line.
+    pub fn matching_lines(&self, lines: &[&str], expected: &str) -> Vec<usize> {
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| self.verify(line, expected))
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    /// Render a line the way `file_read` shows it: `number|hash| content`
+    pub fn format_line(&self, line_number: usize, line: &str) -> String {
+        format!("{:>4}|{}| {}", line_number, self.hash(line), line)
+    }
+}
+
+const FNV_OFFSET: u32 = 2166136261;
+const FNV_PRIME: u32 = 16777619;
+
+fn fnv1(bytes: &[u8]) -> u32 {
+    let mut hash = FNV_OFFSET;
+    for &byte in bytes {
+        hash = hash.wrapping_mul(FNV_PRIME);
+        hash ^= byte as u32;
+    }
+    hash
+}
+
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash = FNV_OFFSET;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_fixed_width() {
+        for bits in MIN_HASH_BITS..=MAX_HASH_BITS {
+            let config = HashlineConfig::new(HashAlgorithm::Fnv1, bits);
+            for line in ["", "a", "fn main() {", "    }", "\t\u{e9}t\u{e9}"] {
+                let hash = config.hash(line);
+                assert_eq!(
+                    hash.len(),
+                    config.hex_width(),
+                    "bits={} line={:?}",
+                    bits,
+                    line
+                );
+                assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_old_12_bit_hash_is_reproducible() {
+        // The former `format!("{:02x}", hash & 0xFFF)` rendered 2 or 3 chars;
+        // the 12-bit setting now always renders 3, with the same value.
+        let config = HashlineConfig::new(HashAlgorithm::Fnv1, 12);
+        let line = "let x = 1;";
+        let legacy = format!("{:02x}", fnv1(line.as_bytes()) & 0xFFF);
+        assert_eq!(config.hash(line), format!("{:0>3}", legacy));
+    }
+
+    #[test]
+    fn test_12_bit_collision_is_resolved_by_default_width() {
+        // These two lines share a hash under the old 12-bit mask (0x73c)
+        let a = "let x = 19;";
+        let b = "let x = 484;";
+        let narrow = HashlineConfig::new(HashAlgorithm::Fnv1, 12);
+        assert_eq!(narrow.hash(a), "73c");
+        assert_eq!(narrow.hash(a), narrow.hash(b));
+        assert_eq!(
+            narrow.matching_lines(&[a, "}", b], &narrow.hash(a)),
+            vec![1, 3]
+        );
+
+        let default = HashlineConfig::default();
+        assert_ne!(default.hash(a), default.hash(b));
+        assert_eq!(
+            default.matching_lines(&[a, "}", b], &default.hash(a)),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_algorithms_differ_and_verify_is_case_insensitive() {
+        let fnv1 = HashlineConfig::new(HashAlgorithm::Fnv1, 32);
+        let fnv1a = HashlineConfig::new(HashAlgorithm::Fnv1a, 32);
+        assert_ne!(fnv1.hash("hello"), fnv1a.hash("hello"));
+
+        let hash = fnv1.hash("hello");
+        assert!(fnv1.verify("hello", &hash.to_uppercase()));
+        assert!(fnv1.verify("hello", &format!(" {} ", hash)));
+        assert!(!fnv1.verify("hello!", &hash));
+    }
+}
@@ -13,7 +13,7 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 // ============================================================================
 // MCP Server Configuration
@@ -540,6 +540,10 @@ impl Tool for DynamicMcpTool {
         &self.tool_description
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         if self.input_schema.is_null() || self.input_schema == Value::Object(Default::default()) {
             serde_json::json!({
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde_json::Value;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 use crate::agent::tools::mcp_client::{McpServerConfig, McpTransport};
 use crate::agent::mcp_config;
 
@@ -18,6 +18,10 @@ impl Tool for McpAddServerTool {
         "Add a new MCP server configuration to the global mcp.json file."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -173,6 +177,10 @@ impl Tool for McpRemoveServerTool {
         "Remove an MCP server from the local configuration."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
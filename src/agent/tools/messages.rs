@@ -0,0 +1,325 @@
+//! Message catalog for the file tools
+//!
+//! `ToolError` texts and `ToolResult::message` are shown in the chat and read
+//! back by the model, so they follow the user's language (`settings.language`)
+//! instead of being hardcoded in French. Messages are looked up by key and
+//! rendered with [`tr`]; English is used until [`set_locale`] is called.
+//! The structured `data` of a result never goes through the catalog.
+
+use std::fmt::{Display, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language of tool messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    /// Locale for a `settings.language` code; unknown codes fall back to English
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "fr" => Self::Fr,
+            _ => Self::En,
+        }
+    }
+}
+
+static ACTIVE_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the language of tool messages (called when the language setting changes)
+pub fn set_locale(locale: Locale) {
+    ACTIVE_LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The language tool messages are currently rendered in
+pub fn locale() -> Locale {
+    match ACTIVE_LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Fr,
+        _ => Locale::En,
+    }
+}
+
+/// Render the message `key` in the active locale, replacing each `{}` of the
+/// template with the next argument
+pub fn tr(key: &str, args: &[&dyn Display]) -> String {
+    tr_in(locale(), key, args)
+}
+
+/// [`tr`] for an explicit locale. An unknown key renders as the key itself.
+pub fn tr_in(locale: Locale, key: &str, args: &[&dyn Display]) -> String {
+    let template = CATALOG
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, en, fr)| match locale {
+            Locale::En => *en,
+            Locale::Fr => *fr,
+        })
+        .unwrap_or(key);
+
+    let mut out = String::with_capacity(template.len() + 32);
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => {
+                let _ = write!(out, "{}", arg);
+            }
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// (key, English, French)
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Shared
+    ("file.read_failed", "Could not read the file: {}", "Impossible de lire le fichier: {}"),
+    ("file.open_failed", "Could not open the file: {}", "Impossible d'ouvrir le fichier: {}"),
+    ("file.write_failed", "Could not write the file: {}", "Impossible d'écrire le fichier: {}"),
+    ("file.create_failed", "Could not create the file: {}", "Impossible de créer le fichier: {}"),
+    ("file.binary", "file appears to be binary: {}", "le fichier semble binaire: {}"),
+    ("dir.read_failed", "Could not read the directory: {}", "Impossible de lire le dossier: {}"),
+    ("dir.create_failed", "Could not create the directory: {}", "Impossible de créer le dossier: {}"),
+    (
+        "dir.parent_create_failed",
+        "Could not create the parent directory: {}",
+        "Impossible de créer le dossier parent: {}",
+    ),
+    ("metadata.read_failed", "Could not read metadata: {}", "Impossible de lire les métadonnées: {}"),
+    ("path.missing", "The path '{}' does not exist", "Le chemin '{}' n'existe pas"),
+    ("path.exists", "{} already exists", "{} existe déjà"),
+    ("path.unsupported_type", "Unsupported path type: {}", "Type de chemin non supporté: {}"),
+    ("source.missing", "Source '{}' does not exist", "Source '{}' n'existe pas"),
+    ("perm.readonly", "read-only", "lecture seule"),
+    ("perm.readwrite", "read/write", "lecture/écriture"),
+    ("dry_run", "[dry_run] {} (nothing was changed)", "[dry_run] {} (aucune modification effectuée)"),
+    // Text decoding
+    (
+        "text.invalid",
+        "{} is not valid {} text (set the encoding parameter)",
+        "{} n'est pas un texte {} valide (précisez le paramètre encoding)",
+    ),
+    (
+        "text.unmappable",
+        "the text contains characters that cannot be represented in {}",
+        "le texte contient des caractères non représentables en {}",
+    ),
+    // file_edit
+    (
+        "edit.not_found",
+        "old_string not found in the file. Check indentation and whitespace.",
+        "old_string introuvable dans le fichier. Vérifiez l'indentation et les espaces.",
+    ),
+    (
+        "edit.ambiguous",
+        "old_string found {} times. Add more context to make it unique, or use replace_all=true.",
+        "old_string trouvé {} fois. Ajoutez plus de contexte pour le rendre unique, ou utilisez replace_all=true.",
+    ),
+    ("edit.backup_failed", "Could not create the backup: {}", "Impossible de créer la sauvegarde: {}"),
+    ("edit.done_hashline", "File edited: {} (mode: hashline, {})", "Fichier édité: {} (mode: hashline, {})"),
+    (
+        "edit.done_str_replace",
+        "File edited: {} (1 replacement, mode: str_replace)",
+        "Fichier édité: {} (1 remplacement, mode: str_replace)",
+    ),
+    (
+        "edit.hash_collision",
+        ". Warning: the hash is shared by lines {}, check that the right line was changed",
+        ". Attention: le hash est partagé par les lignes {}, vérifiez que la bonne ligne a été modifiée",
+    ),
+    (
+        "edit.fuzzy_note",
+        " (matched ignoring whitespace differences)",
+        " (correspondance trouvée en ignorant les différences d'espacement)",
+    ),
+    ("edit.backup_note", " (backup: {})", " (sauvegarde: {})"),
+    ("edit.more_hunks", "... {} more hunk(s) not shown\n", "... {} hunk(s) de plus non affiché(s)\n"),
+    // file_create
+    (
+        "create.exists",
+        "The file '{}' already exists. Use overwrite=true to replace it, or file_edit to modify it.",
+        "Le fichier '{}' existe déjà. Utilisez overwrite=true pour écraser, ou file_edit pour modifier.",
+    ),
+    ("create.done", "File created: {} ({} lines, {} bytes)", "Fichier créé: {} ({} lignes, {} octets)"),
+    // file_append
+    ("append.missing", "'{}' does not exist (create=false)", "'{}' n'existe pas (create=false)"),
+    (
+        "append.done",
+        "{} bytes appended to {} ({} lines, {} bytes in total)",
+        "{} octets ajoutés à {} ({} lignes, {} octets au total)",
+    ),
+    // file_delete
+    ("delete.not_empty", "Directory not empty. Use recursive=true", "Dossier non vide. Utilisez recursive=true"),
+    (
+        "delete.not_empty_detail",
+        "Directory not empty. Use recursive=true: {}",
+        "Dossier non vide. Utilisez recursive=true: {}",
+    ),
+    ("delete.failed", "Could not delete: {}", "Impossible de supprimer: {}"),
+    ("delete.dir_failed", "Could not delete the directory: {}", "Impossible de supprimer le dossier: {}"),
+    ("delete.trash_failed", "Could not move to the trash: {}", "Impossible de mettre à la corbeille: {}"),
+    ("delete.file_permanent", "File permanently deleted: {}", "Fichier supprimé définitivement: {}"),
+    ("delete.dir_permanent", "Directory permanently deleted: {}", "Dossier supprimé définitivement: {}"),
+    ("delete.file_trashed", "File moved to the trash: {} -> {}", "Fichier mis à la corbeille: {} -> {}"),
+    ("delete.dir_trashed", "Directory moved to the trash: {} -> {}", "Dossier mis à la corbeille: {} -> {}"),
+    (
+        "delete.file_system_trash",
+        "File moved to the system trash: {}",
+        "Fichier mis à la corbeille système: {}",
+    ),
+    (
+        "delete.dir_system_trash",
+        "Directory moved to the system trash: {}",
+        "Dossier mis à la corbeille système: {}",
+    ),
+    // file_move
+    (
+        "move.destination_exists",
+        "Destination '{}' already exists. Use overwrite=true to replace it.",
+        "Destination '{}' existe déjà. Utilisez overwrite=true pour la remplacer.",
+    ),
+    (
+        "move.replace_failed",
+        "Could not replace the destination: {}",
+        "Impossible de remplacer la destination: {}",
+    ),
+    (
+        "move.onto_itself",
+        "Cannot move '{}' onto itself or into or over a directory containing it",
+        "Impossible de déplacer '{}' sur lui-même, dans lui-même ou sur un dossier qui le contient",
+    ),
+    ("move.failed", "Could not move: {}", "Impossible de déplacer: {}"),
+    ("move.done", "Moved: {} -> {}", "Déplacé: {} -> {}"),
+    // file_info
+    ("info.lines", ", {} lines", ", {} lignes"),
+    ("info.git", ", git: {}", ", git: {}"),
+    // file_chmod
+    (
+        "chmod.failed",
+        "Could not change permissions: {}",
+        "Impossible de modifier les permissions: {}",
+    ),
+    (
+        "chmod.modes_unsupported",
+        "full modes are not supported on this platform, only the read-only flag was changed",
+        "les modes complets ne sont pas supportés sur cette plateforme, seul l'attribut lecture seule a été modifié",
+    ),
+    ("chmod.current", "Permissions of {}: {}", "Permissions de {}: {}"),
+    ("chmod.changed", "Permissions of {}: {} -> {}", "Permissions de {}: {} -> {}"),
+    // directory_create
+    ("mkdir.exists", "Directory already exists: {}", "Le dossier existe déjà: {}"),
+    ("mkdir.file_exists", "A file already exists at this path: {}", "Un fichier existe déjà à ce chemin: {}"),
+    ("mkdir.done", "Directory created: {}", "Dossier créé: {}"),
+    // file_copy
+    (
+        "copy.destination_exists",
+        "The destination '{}' already exists. Use overwrite=true to replace it.",
+        "La destination '{}' existe déjà. Utilisez overwrite=true pour écraser.",
+    ),
+    (
+        "copy.into_itself",
+        "Cannot copy a directory into itself",
+        "Impossible de copier un dossier dans lui-même",
+    ),
+    (
+        "copy.onto_itself",
+        "Cannot copy '{}' onto itself",
+        "Impossible de copier '{}' sur lui-même",
+    ),
+    ("copy.failed", "Could not copy: {}", "Impossible de copier: {}"),
+    (
+        "copy.preserve_failed",
+        "Copied, but could not keep permissions/timestamps: {}",
+        "Copié, mais impossible de conserver les permissions/dates: {}",
+    ),
+    ("copy.symlink_loop", "symlink loop: {}", "boucle de liens symboliques: {}"),
+    ("copy.dir_preview", "Directory copied: {} -> {}", "Dossier copié: {} -> {}"),
+    (
+        "copy.dir_done",
+        "Directory copied: {} -> {} ({} file(s), {})",
+        "Dossier copié: {} -> {} ({} fichier(s), {})",
+    ),
+    ("copy.done", "Copied: {} -> {} ({} bytes)", "Copié: {} -> {} ({} octets)"),
+    (
+        "copy.symlink_done",
+        "Symlink copied: {} -> {} (points to {})",
+        "Lien symbolique copié: {} -> {} (pointe vers {})",
+    ),
+    // directory_tree
+    ("tree.not_dir", "{} is not a directory", "{} n'est pas un dossier"),
+    (
+        "tree.done",
+        "Tree of {}: {} directories, {} files",
+        "Arborescence de {}: {} dossier(s), {} fichier(s)",
+    ),
+    (
+        "tree.truncated",
+        " (stopped after {} entries: raise max_entries or lower max_depth)",
+        " (arrêté après {} entrées: augmentez max_entries ou réduisez max_depth)",
+    ),
+    // Workspace-bound tools (directory_list, shell_exec, git)
+    (
+        "workspace.outside",
+        "{} is outside the workspace {}",
+        "{} est en dehors de l'espace de travail {}",
+    ),
+    // directory_list
+    (
+        "list.done",
+        "Contents of {}: {} directories, {} files",
+        "Contenu de {}: {} dossier(s), {} fichier(s)",
+    ),
+    // file_search
+    ("search.results", "{} result(s) for \"{}\"", "{} résultat(s) pour \"{}\""),
+    (
+        "search.skipped",
+        " ({} binary or oversized file(s) skipped)",
+        " ({} fichier(s) binaire(s) ou trop volumineux ignoré(s))",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_is_consistent() {
+        for (i, (key, en, fr)) in CATALOG.iter().enumerate() {
+            assert!(
+                CATALOG[i + 1..].iter().all(|(other, _, _)| other != key),
+                "duplicate key {}",
+                key
+            );
+            assert_eq!(
+                en.matches("{}").count(),
+                fr.matches("{}").count(),
+                "placeholders differ for {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_tr_renders_in_locale() {
+        assert_eq!(
+            tr_in(Locale::En, "move.done", &[&"a.txt", &"b.txt"]),
+            "Moved: a.txt -> b.txt"
+        );
+        assert_eq!(
+            tr_in(Locale::Fr, "move.done", &[&"a.txt", &"b.txt"]),
+            "Déplacé: a.txt -> b.txt"
+        );
+        assert_eq!(tr_in(Locale::En, "info.lines", &[&3]), ", 3 lines");
+        // Missing arguments leave the placeholder, unknown keys render as is
+        assert_eq!(tr_in(Locale::En, "move.done", &[&"a"]), "Moved: a -> {}");
+        assert_eq!(tr_in(Locale::Fr, "no.such.key", &[]), "no.such.key");
+        assert_eq!(Locale::from_code("fr"), Locale::Fr);
+        assert_eq!(Locale::from_code("de"), Locale::En);
+    }
+}
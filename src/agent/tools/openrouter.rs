@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 // ============================================================================
 // OpenRouter Configuration
@@ -84,6 +84,10 @@ impl Tool for OpenRouterConsultTool {
         "Consult a more powerful external AI model (via OpenRouter) for complex reasoning, explanations, or problems you struggle with. Use this when you need help understanding something complex or want a second opinion. The response will be optimized for your understanding."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::path::PathBuf;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 // ============================================================================
 // PdfReadTool - Extract text from PDF
@@ -150,6 +150,10 @@ impl Tool for PdfCreateTool {
         "Créer un nouveau fichier PDF avec du contenu texte."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -276,6 +280,10 @@ impl Tool for PdfAddPageTool {
         "Ajouter une page avec du texte à un PDF existant."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
@@ -409,6 +417,10 @@ impl Tool for PdfMergeTool {
         "Fusionner plusieurs fichiers PDF en un seul."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
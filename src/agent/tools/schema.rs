@@ -0,0 +1,196 @@
+//! Parameter validation against a tool's `parameters_schema()`
+//!
+//! Tool calls are checked here before they run, so a model that forgets a
+//! field or sends the wrong type gets one `ToolError::InvalidParameters`
+//! listing every problem, in the same words for every tool.
+//!
+//! Only the JSON Schema keywords the tools use are understood: `type`,
+//! `required`, `properties`, `items`, `enum`, `minimum` and `maximum`.
+//! Anything else (MCP servers may send richer schemas) is ignored rather
+//! than rejected, and a `null` optional field counts as absent, since models
+//! often send those for "not set".
+
+use serde_json::Value;
+
+use super::ToolError;
+
+/// Check `params` against `schema`, listing every problem in one error
+pub fn validate_params(schema: &Value, params: &Value) -> Result<(), ToolError> {
+    let mut problems = Vec::new();
+    // A call without arguments may come through as null
+    let empty = Value::Object(Default::default());
+    let params = if params.is_null() { &empty } else { params };
+    check(schema, params, "", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ToolError::InvalidParameters(problems.join("; ")))
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let at = if path.is_empty() { "parameters" } else { path };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            problems.push(format!(
+                "{} must be {}, got {}",
+                at,
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            // Nested checks would only repeat the same mistake
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let options: Vec<String> = options.iter().map(Value::to_string).collect();
+            problems.push(format!("{} must be one of {}", at, options.join(", ")));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                problems.push(format!("{} must be at least {}", at, min));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                problems.push(format!("{} must be at most {}", at, max));
+            }
+        }
+    }
+
+    if let Some(fields) = value.as_object() {
+        let present = |name: &str| fields.get(name).is_some_and(|v| !v.is_null());
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !present(name) {
+                    problems.push(format!("{} is required", join(path, name)));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                if present(name) {
+                    check(property, &fields[name], &join(path, name), problems);
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in values.iter().enumerate() {
+            check(items, item, &format!("{}[{}]", at, i), problems);
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        // Not 3.0: tools read integers with `as_u64`/`as_i64`, which would
+        // silently drop it for the default
+        "integer" => value.is_u64() || value.is_i64(),
+        // Unknown types are not ours to reject
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_every_problem_at_once() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "max_results": { "type": "integer", "minimum": 1 },
+                "mode": { "type": "string", "enum": ["fast", "full"] },
+                "todos": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "content": { "type": "string" } },
+                        "required": ["content"]
+                    }
+                }
+            },
+            "required": ["path", "todos"]
+        });
+
+        assert!(validate_params(&schema, &json!({ "path": "a", "todos": [] })).is_ok());
+        // Optional fields sent as null are fine, as are unknown ones
+        assert!(validate_params(
+            &schema,
+            &json!({ "path": "a", "todos": [], "mode": null, "extra": 1, "max_results": 3 })
+        )
+        .is_ok());
+        let err = validate_params(
+            &schema,
+            &json!({ "path": "a", "todos": [], "max_results": 3.0 }),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("max_results must be integer, got number"));
+
+        let err = validate_params(
+            &schema,
+            &json!({ "max_results": "ten", "mode": "slow", "todos": [{ "content": 1 }, {}] }),
+        )
+        .unwrap_err();
+        let ToolError::InvalidParameters(message) = err else {
+            panic!("{}", err);
+        };
+        assert_eq!(
+            message,
+            "path is required; max_results must be integer, got string; \
+             mode must be one of \"fast\", \"full\"; todos[0].content must be string, got number; \
+             todos[1].content is required"
+        );
+
+        let err = validate_params(&schema, &json!("a")).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("parameters must be object, got string"));
+    }
+}
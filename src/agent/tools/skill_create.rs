@@ -3,7 +3,7 @@ use serde_json::Value;
 use std::path::PathBuf;
 use std::sync::Arc;
 use regex::Regex;
-use crate::agent::tools::{Tool, ToolResult, ToolError, ToolRegistry};
+use crate::agent::tools::{Tool, ToolCategory, ToolResult, ToolError, ToolRegistry};
 use crate::agent::skills::SkillRegistry;
 use crate::storage::get_data_dir;
 
@@ -85,6 +85,10 @@ impl Tool for SkillCreateTool {
         "Create a new skill for the AI. Generates a SKILL.md file with the provided content and metadata."
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Mutating
+    }
+
     fn parameters_schema(&self) -> Value {
         serde_json::json!({
             "type": "object",
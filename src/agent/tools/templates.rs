@@ -0,0 +1,181 @@
+//! Built-in file templates for `file_create`
+//!
+//! Scaffolding a project means writing the same boilerplate again and again.
+//! `file_create` takes a `template` name and a `variables` object instead of
+//! `content`; placeholders written `{{name}}` are replaced with the matching
+//! variable, or the template's default when the caller leaves it out.
+
+use serde_json::{Map, Value};
+
+use crate::agent::tools::ToolError;
+
+/// A named file skeleton
+pub struct FileTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Variables used by `content`, with their default (`None`: required)
+    pub variables: &'static [(&'static str, Option<&'static str>)],
+    pub content: &'static str,
+}
+
+/// Templates `file_create` can render
+pub const TEMPLATES: &[FileTemplate] = &[
+    FileTemplate {
+        name: "rust-lib",
+        description: "src/lib.rs of a Rust library crate, with a test module",
+        variables: &[("description", Some("Library crate."))],
+        content: r#"//! {{description}}
+
+/// Adds two numbers.
+pub fn add(left: u64, right: u64) -> u64 {
+    left + right
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(add(2, 2), 4);
+    }
+}
+"#,
+    },
+    FileTemplate {
+        name: "rust-bin",
+        description: "src/main.rs of a Rust binary crate",
+        variables: &[
+            ("name", None),
+            ("description", Some("Command-line entry point.")),
+        ],
+        content: r#"//! {{description}}
+
+fn main() {
+    println!("Hello from {{name}}!");
+}
+"#,
+    },
+    FileTemplate {
+        name: "gitignore",
+        description: ".gitignore for a Rust project (build output, editor and OS files, secrets)",
+        variables: &[],
+        content: r#"# Build output
+/target
+**/*.rs.bk
+
+# Editors and OS
+.idea/
+.vscode/
+*.swp
+.DS_Store
+Thumbs.db
+
+# Local configuration
+.env
+*.log
+"#,
+    },
+    FileTemplate {
+        name: "readme",
+        description: "README.md with description, usage and license sections",
+        variables: &[
+            ("name", None),
+            ("description", Some("")),
+            ("license", Some("MIT")),
+        ],
+        content: r#"# {{name}}
+
+{{description}}
+
+## Usage
+
+```sh
+cargo run
+```
+
+## License
+
+{{license}}
+"#,
+    },
+];
+
+/// The template called `name`
+pub fn find_template(name: &str) -> Option<&'static FileTemplate> {
+    TEMPLATES.iter().find(|t| t.name == name)
+}
+
+/// Names of all templates, for error messages and the tool schema
+pub fn template_names() -> Vec<&'static str> {
+    TEMPLATES.iter().map(|t| t.name).collect()
+}
+
+impl FileTemplate {
+    /// Replace every `{{name}}` placeholder with its variable. Strings are
+    /// inserted as is, other JSON values in their JSON form. Fails listing
+    /// the required variables that were not given.
+    pub fn render(&self, variables: &Map<String, Value>) -> Result<String, ToolError> {
+        let mut out = String::with_capacity(self.content.len());
+        let mut missing: Vec<&str> = Vec::new();
+        let mut rest = self.content;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let key = rest[start + 2..start + 2 + len].trim();
+            out.push_str(&rest[..start]);
+            match variables.get(key) {
+                Some(Value::String(value)) => out.push_str(value),
+                Some(value) => out.push_str(&value.to_string()),
+                None => match self.variables.iter().find(|(name, _)| *name == key) {
+                    Some((_, Some(default))) => out.push_str(default),
+                    _ => {
+                        if !missing.contains(&key) {
+                            missing.push(key);
+                        }
+                    }
+                },
+            }
+            rest = &rest[start + 2 + len + 2..];
+        }
+        out.push_str(rest);
+
+        if !missing.is_empty() {
+            return Err(ToolError::InvalidParameters(format!(
+                "template '{}' needs variables: {}",
+                self.name,
+                missing.join(", ")
+            )));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_and_defaults() {
+        let readme = find_template("readme").unwrap();
+        let vars = serde_json::json!({ "name": "clawrs", "description": "A local agent." });
+        let rendered = readme.render(vars.as_object().unwrap()).unwrap();
+        assert!(rendered.starts_with("# clawrs\n\nA local agent.\n"));
+        assert!(rendered.ends_with("## License\n\nMIT\n"));
+
+        let err = readme.render(&Map::new()).unwrap_err();
+        assert!(err.to_string().contains("needs variables: name"), "{}", err);
+
+        // Every placeholder of every template is declared
+        for template in TEMPLATES {
+            let defaults: Map<String, Value> = template
+                .variables
+                .iter()
+                .map(|(name, _)| (name.to_string(), Value::String("x".into())))
+                .collect();
+            let rendered = template.render(&defaults).unwrap();
+            assert!(!rendered.contains("{{"), "{}", template.name);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Text file helpers shared by the file tools
+//!
+//! Decides whether a file is text before a tool treats it as such, so pointing
+//! `file_read` or `file_edit` at a PNG or a compiled binary fails with a clear
+//! message instead of a UTF-8 decoding error, and `file_search` can skip it.
+//!
+//! Text that is not UTF-8 (Latin-1 / Windows-1252 configs, Shift_JIS, ...) is
+//! decoded with the encoding `chardetng` guesses, and [`TextFile::encode`]
+//! turns the edited text back into that same encoding, so editing a legacy
+//! file does not silently convert it. Tools take an optional `encoding`
+//! parameter for when the guess is wrong.
+//!
+//! A byte order mark settles the question (UTF-8, UTF-16LE, UTF-16BE): it is
+//! stripped from [`TextFile::text`], so hashes and matches on the first line
+//! don't see it, and put back by [`TextFile::encode`].
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde_json::Value;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+use crate::agent::tools::messages::tr;
+use crate::agent::tools::ToolError;
+
+/// How many leading bytes are inspected to decide whether a file is binary
+pub const SNIFF_LEN: usize = 8 * 1024;
+
+/// Whether the first bytes of a file look like binary data: a NUL byte, or
+/// more than one control character (other than tab, newlines, form feed and
+/// escape) in every 32 bytes. Bytes that are not UTF-8 are not enough on
+/// their own: they may be text in a legacy encoding. A leading BOM marks the
+/// file as text (UTF-16 is full of NUL bytes).
+pub fn looks_binary(prefix: &[u8]) -> bool {
+    if Encoding::for_bom(prefix).is_some() {
+        return false;
+    }
+    if prefix.contains(&0) {
+        return true;
+    }
+    let controls = prefix
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    controls * 32 > prefix.len()
+}
+
+/// Read up to `SNIFF_LEN` bytes from the start of `file`
+pub async fn read_prefix(file: &mut tokio::fs::File) -> std::io::Result<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut prefix).await?;
+    Ok(prefix)
+}
+
+/// The optional `encoding` tool parameter (a WHATWG label such as
+/// `"windows-1252"`, `"latin1"` or `"utf-16le"`)
+pub fn encoding_param(params: &Value) -> Result<Option<&'static Encoding>, ToolError> {
+    match params.get("encoding") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(label)) => Encoding::for_label(label.trim().as_bytes())
+            .map(Some)
+            .ok_or_else(|| ToolError::InvalidParameters(format!("unknown encoding '{}'", label))),
+        Some(_) => Err(ToolError::InvalidParameters(
+            "encoding must be a string".into(),
+        )),
+    }
+}
+
+/// A decoded text file and the encoding to write it back in
+#[derive(Debug, Clone)]
+pub struct TextFile {
+    /// Decoded content, without the BOM
+    pub text: String,
+    pub encoding: &'static Encoding,
+    /// Whether the file started with a byte order mark
+    pub bom: bool,
+}
+
+impl TextFile {
+    /// Encode `text` in this file's encoding. Fails when `text` contains
+    /// characters the encoding cannot represent, rather than writing `&#...;`
+    /// replacements into the file.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, ToolError> {
+        let mut out = Vec::with_capacity(text.len() + 3);
+        if self.encoding == UTF_16LE {
+            if self.bom {
+                out.extend_from_slice(&[0xFF, 0xFE]);
+            }
+            out.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            return Ok(out);
+        }
+        if self.encoding == UTF_16BE {
+            if self.bom {
+                out.extend_from_slice(&[0xFE, 0xFF]);
+            }
+            out.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            return Ok(out);
+        }
+        if self.bom && self.encoding == UTF_8 {
+            out.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+        }
+        let (bytes, _, unmappable) = self.encoding.encode(text);
+        if unmappable {
+            return Err(ToolError::ExecutionFailed(tr(
+                "text.unmappable",
+                &[&self.encoding.name()],
+            )));
+        }
+        out.extend_from_slice(&bytes);
+        Ok(out)
+    }
+}
+
+/// Read `path` as text, refusing binary files after looking at the first
+/// `SNIFF_LEN` bytes rather than loading the whole file.
+pub async fn read_text_file(path: &Path) -> Result<String, ToolError> {
+    read_text(path, None).await.map(|file| file.text)
+}
+
+/// Read and decode `path` (see [`decode_text`]).
+/// Binary files are refused unless an encoding is forced.
+pub async fn read_text(
+    path: &Path,
+    encoding: Option<&'static Encoding>,
+) -> Result<TextFile, ToolError> {
+    let read_error = |e: std::io::Error| ToolError::io(&e, tr("file.read_failed", &[&e]));
+
+    let mut file = tokio::fs::File::open(path).await.map_err(read_error)?;
+    let mut bytes = read_prefix(&mut file).await.map_err(read_error)?;
+    if encoding.is_none() && looks_binary(&bytes) {
+        return Err(ToolError::ExecutionFailed(tr(
+            "file.binary",
+            &[&path.display()],
+        )));
+    }
+    file.read_to_end(&mut bytes).await.map_err(read_error)?;
+
+    decode_text(bytes, encoding).map_err(|encoding| {
+        ToolError::ExecutionFailed(tr("text.invalid", &[&path.display(), &encoding.name()]))
+    })
+}
+
+/// Decode file contents: as `encoding` when given, else as the encoding its
+/// BOM declares, else as UTF-8 when the bytes are valid UTF-8, else with the
+/// encoding `chardetng` detects. Fails with the encoding that was tried when
+/// the bytes are not valid in it.
+pub fn decode_text(
+    bytes: Vec<u8>,
+    encoding: Option<&'static Encoding>,
+) -> Result<TextFile, &'static Encoding> {
+    let (encoding, bom_len) = match (encoding, Encoding::for_bom(&bytes)) {
+        (Some(forced), Some((declared, len))) if forced == declared => (forced, len),
+        (Some(forced), _) => (forced, 0),
+        (None, Some((declared, len))) => (declared, len),
+        (None, None) => match String::from_utf8(bytes) {
+            Ok(text) => {
+                return Ok(TextFile {
+                    text,
+                    encoding: UTF_8,
+                    bom: false,
+                })
+            }
+            Err(e) => {
+                let bytes = e.into_bytes();
+                let detected = detect_encoding(&bytes);
+                return decode_text(bytes, Some(detected));
+            }
+        },
+    };
+
+    let (text, malformed) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    if malformed {
+        return Err(encoding);
+    }
+    Ok(TextFile {
+        text: text.into_owned(),
+        encoding,
+        bom: bom_len > 0,
+    })
+}
+
+/// Best guess at the legacy encoding of `bytes`
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_binary() {
+        assert!(!looks_binary(b""));
+        assert!(!looks_binary("fn main() {}\n// café\n".as_bytes()));
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(looks_binary(b"\x01\x02\x03\x04 not really text"));
+        // Latin-1 "é" is not UTF-8, but it is still text
+        assert!(!looks_binary(b"caf\xe9 latin-1"));
+        // "é" is 0xC3 0xA9; a sample ending after 0xC3 is still text
+        assert!(!looks_binary(b"caf\xc3"));
+    }
+
+    #[tokio::test]
+    async fn test_read_text_file_refuses_binary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let text = tmp.path().join("a.txt");
+        let image = tmp.path().join("a.png");
+        std::fs::write(&text, "hello\n").unwrap();
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        assert_eq!(read_text_file(&text).await.unwrap(), "hello\n");
+        let err = read_text_file(&image).await.unwrap_err();
+        assert!(
+            err.to_string().contains("file appears to be binary"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_text_detects_and_forces_encoding() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("legacy.txt");
+        // "Fenêtre déjà ouverte — « OK »" in Windows-1252
+        let bytes = b"Fen\xeatre d\xe9j\xe0 ouverte \x97 \xab OK \xbb\n";
+        std::fs::write(&file, bytes).unwrap();
+
+        let detected = read_text(&file, None).await.unwrap();
+        assert_eq!(detected.encoding, encoding_rs::WINDOWS_1252);
+        assert_eq!(detected.text, "Fenêtre déjà ouverte — « OK »\n");
+        assert_eq!(detected.encode(&detected.text).unwrap(), bytes);
+        assert!(detected.encode("emoji \u{1f980}").is_err());
+
+        let utf16 = tmp.path().join("utf16.txt");
+        std::fs::write(&utf16, b"h\0i\0\n\0").unwrap();
+        let forced = encoding_param(&serde_json::json!({ "encoding": "utf-16le" }))
+            .unwrap()
+            .unwrap();
+        let decoded = read_text(&utf16, Some(forced)).await.unwrap();
+        assert_eq!(decoded.text, "hi\n");
+        assert_eq!(decoded.encode("ho\n").unwrap(), b"h\0o\0\n\0");
+
+        assert!(encoding_param(&serde_json::json!({ "encoding": "klingon" })).is_err());
+    }
+
+    #[test]
+    fn test_bom_is_stripped_and_restored() {
+        let utf8 = b"\xef\xbb\xbfkey = \"\xc3\xa9t\xc3\xa9\"\n".to_vec();
+        let decoded = decode_text(utf8.clone(), None).unwrap();
+        assert_eq!(decoded.text, "key = \"été\"\n");
+        assert!(decoded.bom);
+        assert_eq!(decoded.encode(&decoded.text).unwrap(), utf8);
+
+        // UTF-16 is recognised from its BOM despite the NUL bytes
+        let utf16 = b"\xff\xfeh\0i\0\n\0".to_vec();
+        assert!(!looks_binary(&utf16));
+        let decoded = decode_text(utf16, None).unwrap();
+        assert_eq!(decoded.encoding, UTF_16LE);
+        assert_eq!(decoded.text, "hi\n");
+        assert_eq!(decoded.encode("ho\n").unwrap(), b"\xff\xfeh\0o\0\n\0");
+
+        let plain = decode_text(b"no bom".to_vec(), None).unwrap();
+        assert!(!plain.bom);
+        assert_eq!(plain.encode("no bom").unwrap(), b"no bom");
+    }
+}
@@ -4,13 +4,22 @@
 
 use async_trait::async_trait;
 use serde_json::Value;
+use std::time::Duration;
 
-use crate::agent::tools::{Tool, ToolError, ToolResult};
+use crate::agent::tools::{Tool, ToolCategory, ToolError, ToolResult};
 
 // ============================================================================
 // WebFetchTool - Fetch URL content
 // ============================================================================
 
+/// Body read when the call doesn't set `max_bytes`
+const DEFAULT_FETCH_BYTES: u64 = 1024 * 1024;
+/// Largest body a call may ask for
+const MAX_FETCH_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 30;
+const MAX_FETCH_TIMEOUT_SECS: u64 = 120;
+const MAX_REDIRECTS: usize = 10;
+
 pub struct WebFetchTool;
 
 #[async_trait]
@@ -20,7 +29,7 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch the content of a URL and return it as text. Supports HTML pages (converted to readable text), JSON APIs, and raw text. Use for reading documentation, API responses, or web pages."
+        "Fetch a URL with an HTTP GET, following redirects, and return its status, content type and body. Use for reading documentation, API responses or web pages; set as_text to get the readable text of an HTML page instead of its markup."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -29,134 +38,193 @@ impl Tool for WebFetchTool {
             "properties": {
                 "url": {
                     "type": "string",
-                    "description": "The URL to fetch"
+                    "description": "The http(s) URL to fetch"
                 },
-                "method": {
-                    "type": "string",
-                    "enum": ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD"],
-                    "description": "HTTP method (default: GET)",
-                    "default": "GET"
+                "as_text": {
+                    "type": "boolean",
+                    "description": "For HTML, return the readable text without tags, scripts and styles (default: false)"
                 },
                 "headers": {
                     "type": "object",
-                    "description": "Optional HTTP headers as key-value pairs"
+                    "description": "Optional HTTP headers as key-value pairs (e.g. Accept)"
                 },
-                "body": {
-                    "type": "string",
-                    "description": "Request body (for POST/PUT/PATCH)"
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Stop reading the body after this many bytes (default: 1048576)",
+                    "minimum": 1,
+                    "maximum": MAX_FETCH_BYTES
                 },
-                "max_length": {
+                "timeout_secs": {
                     "type": "integer",
-                    "description": "Maximum response length in characters (default: 50000)",
-                    "default": 50000
+                    "description": "Timeout in seconds (default: 30)",
+                    "minimum": 1,
+                    "maximum": MAX_FETCH_TIMEOUT_SECS
                 }
             },
             "required": ["url"]
         })
     }
 
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
+    /// The page and the URL are the user's to see before they leave the
+    /// machine: a fetch can carry data out in its query string
+    fn requires_approval(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, params: Value) -> Result<ToolResult, ToolError> {
         let url = params["url"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidParameters("url is required".into()))?;
-        let method = params["method"].as_str().unwrap_or("GET");
-        let headers = params["headers"].as_object();
-        let body = params["body"].as_str();
-        let max_length = params["max_length"].as_u64().unwrap_or(50000) as usize;
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| ToolError::InvalidParameters(format!("URL invalide {}: {}", url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(ToolError::InvalidParameters(format!(
+                "Seules les URL http(s) sont acceptées: {}",
+                url
+            )));
+        }
+        let as_text = params["as_text"].as_bool().unwrap_or(false);
+        let max_bytes = params["max_bytes"]
+            .as_u64()
+            .unwrap_or(DEFAULT_FETCH_BYTES)
+            .clamp(1, MAX_FETCH_BYTES) as usize;
+        let timeout_secs = params["timeout_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_FETCH_TIMEOUT_SECS)
+            .clamp(1, MAX_FETCH_TIMEOUT_SECS);
 
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(Duration::from_secs(timeout_secs))
+            .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
             .user_agent("clawRS/0.2.0")
             .build()
             .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de créer le client HTTP: {}", e)))?;
 
-        let mut request = match method.to_uppercase().as_str() {
-            "GET" => client.get(url),
-            "POST" => client.post(url),
-            "PUT" => client.put(url),
-            "DELETE" => client.delete(url),
-            "PATCH" => client.patch(url),
-            "HEAD" => client.head(url),
-            _ => return Err(ToolError::InvalidParameters(format!("Méthode HTTP inconnue: {}", method))),
-        };
-
-        // Add headers
-        if let Some(hdrs) = headers {
-            for (key, value) in hdrs {
-                if let Some(val) = value.as_str() {
-                    request = request.header(key.as_str(), val);
+        let mut request = client.get(parsed);
+        if let Some(headers) = params["headers"].as_object() {
+            for (key, value) in headers {
+                if let Some(value) = value.as_str() {
+                    request = request.header(key.as_str(), value);
                 }
             }
         }
 
-        // Add body
-        if let Some(b) = body {
-            request = request.body(b.to_string());
-        }
-
-        let response = request
-            .send()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e)))?;
+        let http_error = |e: reqwest::Error| {
+            if e.is_timeout() {
+                ToolError::Timeout {
+                    elapsed_ms: timeout_secs * 1000,
+                }
+            } else if e.is_redirect() {
+                ToolError::ExecutionFailed(format!(
+                    "Plus de {} redirections pour {}",
+                    MAX_REDIRECTS, url
+                ))
+            } else {
+                ToolError::ExecutionFailed(format!("Erreur HTTP: {}", e))
+            }
+        };
 
+        let mut response = request.send().await.map_err(http_error)?;
         let status = response.status().as_u16();
+        let final_url = response.url().to_string();
         let content_type = response
             .headers()
-            .get("content-type")
+            .get(reqwest::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("")
             .to_string();
 
-        let response_headers: serde_json::Map<String, Value> = response
-            .headers()
-            .iter()
-            .filter_map(|(k, v)| {
-                v.to_str()
-                    .ok()
-                    .map(|val| (k.to_string(), Value::String(val.to_string())))
-            })
-            .collect();
-
-        let text = response
-            .text()
-            .await
-            .map_err(|e| ToolError::ExecutionFailed(format!("Impossible de lire la réponse: {}", e)))?;
+        // Read up to the cap, without buffering the rest of a huge body
+        let mut body = Vec::new();
+        let mut body_truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(http_error)? {
+            let room = max_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                body_truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
 
-        // Process content based on type
-        let processed = if content_type.contains("text/html") {
-            html_to_text(&text)
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        let content = if is_textual(&mime) {
+            let text = decode_body(&body, &content_type);
+            if as_text && mime == "text/html" {
+                Value::String(html_to_text(&text))
+            } else {
+                Value::String(text)
+            }
         } else {
-            text
+            // Binary bodies (images, archives...) are only described
+            Value::Null
         };
 
-        // Truncate if needed (safe char-boundary slicing)
-        let display = if processed.len() > max_length {
-            let safe = crate::truncate_str(&processed, max_length);
-            format!(
-                "{}...\n\n[Truncated: {} chars out of {}]",
-                safe,
-                safe.len(),
-                processed.len()
-            )
-        } else {
-            processed
-        };
+        let mut message = format!(
+            "HTTP GET {} ({}, {} octets)",
+            status,
+            if mime.is_empty() { "?" } else { &mime },
+            body.len()
+        );
+        if body_truncated {
+            message.push_str(&format!(", coupé à {} octets", max_bytes));
+        }
+        if content.is_null() {
+            message.push_str(", contenu binaire non affiché");
+        }
 
         Ok(ToolResult {
             success: status < 400,
             data: serde_json::json!({
                 "url": url,
+                "final_url": final_url,
                 "status": status,
                 "content_type": content_type,
-                "content": display,
-                "headers": response_headers,
-                "content_length": display.len()
+                "content": content,
+                "bytes": body.len(),
+                "body_truncated": body_truncated,
             }),
-            message: format!("HTTP {} {} ({}, {} chars)", method, status, content_type, display.len()),
+            message,
         })
     }
 }
 
+/// Whether a body of this MIME type is text worth showing the model
+fn is_textual(mime: &str) -> bool {
+    mime.is_empty()
+        || mime.starts_with("text/")
+        || [
+            "json",
+            "xml",
+            "javascript",
+            "ecmascript",
+            "yaml",
+            "toml",
+            "csv",
+        ]
+        .iter()
+        .any(|kind| mime.contains(kind))
+}
+
+/// `body` decoded with the charset of `content_type`, UTF-8 by default
+fn decode_body(body: &[u8], content_type: &str) -> String {
+    let encoding = content_type
+        .split(';')
+        .filter_map(|param| param.trim().strip_prefix("charset="))
+        .find_map(|label| encoding_rs::Encoding::for_label(label.trim_matches('"').as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(body).0.into_owned()
+}
+
 // ============================================================================
 // WebDownloadTool - Download files from URL
 // ============================================================================
@@ -170,7 +238,16 @@ impl Tool for WebDownloadTool {
     }
 
     fn description(&self) -> &str {
-        "Download a file from a URL and save it to disk. REQUIRES APPROVAL."
+        "Download a file from a URL and save it to disk."
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Network
+    }
+
+    /// Writes what it fetched to disk, wherever the path points
+    fn requires_approval(&self) -> bool {
+        true
     }
 
     fn parameters_schema(&self) -> Value {
@@ -252,59 +329,78 @@ impl Tool for WebDownloadTool {
 // Helpers
 // ============================================================================
 
-/// Simple HTML to text conversion (strips tags)
+/// Tags that start a new line of text
+const BLOCK_TAGS: &[&str] = &[
+    "<br",
+    "<p",
+    "<div",
+    "<h1",
+    "<h2",
+    "<h3",
+    "<h4",
+    "<h5",
+    "<h6",
+    "<li",
+    "<tr",
+    "<table",
+    "<section",
+    "<article",
+    "<header",
+    "<footer",
+    "<blockquote",
+];
+
+/// HTML entities decoded in text
+const ENTITIES: &[(&str, char)] = &[
+    ("&amp;", '&'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&nbsp;", ' '),
+    ("&quot;", '"'),
+    ("&#39;", '\''),
+    ("&apos;", '\''),
+];
+
+/// Simple HTML to text conversion (strips tags, scripts, styles and comments)
 fn html_to_text(html: &str) -> String {
+    // ASCII lowercasing keeps byte offsets, so `lower[i..]` lines up with
+    // `html[i..]` whatever the page's other characters
+    let lower = html.to_ascii_lowercase();
     let mut text = String::new();
     let mut in_tag = false;
-    let mut in_script = false;
-    let mut in_style = false;
+    // End of the script, style or comment being skipped
+    let mut skip_until: Option<&str> = None;
     let mut last_was_space = false;
+    let mut resume = 0;
 
-    let lower = html.to_lowercase();
-    let chars: Vec<char> = html.chars().collect();
-    let lower_chars: Vec<char> = lower.chars().collect();
-
-    let mut i = 0;
-    while i < chars.len() {
-        if in_script {
-            if i + 8 < lower_chars.len() && lower[i..].starts_with("</script") {
-                in_script = false;
-            }
-            i += 1;
+    for (i, c) in html.char_indices() {
+        if i < resume {
             continue;
         }
-        if in_style {
-            if i + 7 < lower_chars.len() && lower[i..].starts_with("</style") {
-                in_style = false;
+        if let Some(end) = skip_until {
+            if lower[i..].starts_with(end) {
+                skip_until = None;
+                // The rest of the closing tag is skipped as a tag
+                in_tag = end != "-->";
+                resume = i + end.len();
             }
-            i += 1;
             continue;
         }
 
-        match chars[i] {
+        match c {
             '<' => {
-                // Check for script/style tags
-                if i + 7 < lower_chars.len() && lower[i..].starts_with("<script") {
-                    in_script = true;
-                } else if i + 6 < lower_chars.len() && lower[i..].starts_with("<style") {
-                    in_style = true;
-                }
-                // Block elements add newlines
-                if i + 3 < chars.len() {
-                    let tag_start = &lower[i..];
-                    if tag_start.starts_with("<br")
-                        || tag_start.starts_with("<p")
-                        || tag_start.starts_with("<div")
-                        || tag_start.starts_with("<h1")
-                        || tag_start.starts_with("<h2")
-                        || tag_start.starts_with("<h3")
-                        || tag_start.starts_with("<li")
-                        || tag_start.starts_with("<tr")
-                    {
-                        if !text.ends_with('\n') {
-                            text.push('\n');
-                        }
-                    }
+                let tag = &lower[i..];
+                if tag.starts_with("<!--") {
+                    skip_until = Some("-->");
+                    continue;
+                } else if tag.starts_with("<script") {
+                    skip_until = Some("</script");
+                } else if tag.starts_with("<style") {
+                    skip_until = Some("</style");
+                } else if BLOCK_TAGS.iter().any(|block| tag.starts_with(block))
+                    && !text.ends_with('\n')
+                {
+                    text.push('\n');
                 }
                 in_tag = true;
             }
@@ -312,25 +408,17 @@ fn html_to_text(html: &str) -> String {
                 in_tag = false;
             }
             '&' if !in_tag => {
-                // Handle common HTML entities
-                if i + 4 < chars.len() && html[i..].starts_with("&amp;") {
-                    text.push('&');
-                    i += 4;
-                } else if i + 3 < chars.len() && html[i..].starts_with("&lt;") {
-                    text.push('<');
-                    i += 3;
-                } else if i + 3 < chars.len() && html[i..].starts_with("&gt;") {
-                    text.push('>');
-                    i += 3;
-                } else if i + 5 < chars.len() && html[i..].starts_with("&nbsp;") {
-                    text.push(' ');
-                    i += 5;
-                } else if i + 5 < chars.len() && html[i..].starts_with("&quot;") {
-                    text.push('"');
-                    i += 5;
-                } else {
-                    text.push('&');
+                match ENTITIES
+                    .iter()
+                    .find(|(entity, _)| html[i..].starts_with(entity))
+                {
+                    Some((entity, decoded)) => {
+                        text.push(*decoded);
+                        resume = i + entity.len();
+                    }
+                    None => text.push('&'),
                 }
+                last_was_space = false;
             }
             c if !in_tag => {
                 if c.is_whitespace() {
@@ -345,7 +433,6 @@ fn html_to_text(html: &str) -> String {
             }
             _ => {}
         }
-        i += 1;
     }
 
     // Clean up: remove excessive newlines
@@ -367,3 +454,89 @@ fn html_to_text(html: &str) -> String {
 
     result.trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_html_to_text() {
+        let html =
+            "<html><head><style>p { color: red; }</style><script>if (a < b) {}</script></head>\
+            <body><!-- <p>hidden</p> --><h1>Café &amp; crème</h1><p>Prix&nbsp;: 5 €</p>\
+            <ul><li>un</li><li>deux</li></ul></body></html>";
+        assert_eq!(html_to_text(html), "Café & crème\nPrix : 5 €\nun\ndeux");
+    }
+
+    #[test]
+    fn test_body_decoding() {
+        assert!(is_textual("application/json"));
+        assert!(is_textual("text/html"));
+        assert!(!is_textual("image/png"));
+        assert_eq!(
+            decode_body(b"caf\xe9", "text/plain; charset=ISO-8859-1"),
+            "café"
+        );
+        assert_eq!(decode_body("café".as_bytes(), "text/plain"), "café");
+    }
+
+    /// Serves one canned response per connection: `/old` redirects to
+    /// `/page`, anything else gets `body`
+    async fn serve(body: &'static [u8], content_type: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let n = socket.read(&mut request).await.unwrap_or(0);
+                let response = if request[..n].starts_with(b"GET /old ") {
+                    b"HTTP/1.1 302 Found\r\nLocation: /page\r\nContent-Length: 0\r\n\r\n".to_vec()
+                } else {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                        content_type,
+                        body.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(body);
+                    response
+                };
+                let _ = socket.write_all(&response).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_web_fetch_follows_redirects_and_caps_the_body() {
+        let base = serve(
+            b"<p>Bient\xf4t</p><script>track()</script>",
+            "text/html; charset=iso-8859-1",
+        )
+        .await;
+
+        let result = WebFetchTool
+            .execute(serde_json::json!({ "url": format!("{}/old", base), "as_text": true }))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.data["status"], 200);
+        assert_eq!(result.data["final_url"], format!("{}/page", base));
+        assert_eq!(result.data["content"], "Bientôt");
+        assert_eq!(result.data["body_truncated"], false);
+
+        let raw = WebFetchTool
+            .execute(serde_json::json!({ "url": format!("{}/page", base), "max_bytes": 9 }))
+            .await
+            .unwrap();
+        assert_eq!(raw.data["content"], "<p>Bientô");
+        assert_eq!(raw.data["body_truncated"], true);
+
+        let refused = WebFetchTool
+            .execute(serde_json::json!({ "url": "file:///etc/passwd" }))
+            .await;
+        assert!(matches!(refused, Err(ToolError::InvalidParameters(_))));
+        assert!(WebFetchTool.requires_approval());
+    }
+}
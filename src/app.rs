@@ -2,16 +2,20 @@
 //!
 //! This module contains the main App component that serves as the root of the UI tree.
 
-use crate::inference::LlamaEngine;
-use crate::storage::conversations::Conversation;
-use crate::storage::settings::{AppSettings, load_settings};
+use crate::inference::{LlamaEngine, LoadedModelInfo, ReasoningTag};
+use crate::storage::conversations::{save_conversation, Conversation};
+use crate::storage::settings::{AppSettings, load_settings, save_settings};
+use crate::storage::StorageError;
+use crate::system::gpu::vram_used_mb;
 use crate::ui::Layout;
-use crate::agent::{Agent, AgentConfig};
+use crate::agent::{set_locale, Agent, AgentConfig, Locale};
 use dioxus::prelude::*;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use crate::ui::chat::message::Message;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use crate::ui::chat::message::{regenerate_cut, Message, MessageRole};
 
 /// Represents the current state of the model
 #[derive(Clone, PartialEq, Debug)]
@@ -31,19 +35,37 @@ pub struct AppState {
     pub conversations: Signal<Vec<Conversation>>,
     pub settings: Signal<AppSettings>,
     pub model_state: Signal<ModelState>,
+    /// Reasoning markers declared by the loaded model's chat template
+    pub reasoning_tags: Signal<Vec<ReasoningTag>>,
     pub stop_signal: Arc<AtomicBool>,
+    /// Cancels the tool call in flight when generation is stopped; replaced
+    /// by a fresh token at the start of each run
+    pub tool_cancel: Arc<std::sync::Mutex<CancellationToken>>,
     /// Global generation flag - generation continues even when navigating away
     pub is_generating: Signal<bool>,
     /// Active messages buffer - persists across navigation
     pub active_messages: Signal<Vec<Message>>,
+    /// Text to load into the chat input (edit and resend); the input takes
+    /// it and clears it
+    pub input_draft: Signal<String>,
+    /// Message to bring into view once its conversation is shown (search
+    /// results); the chat scrolls to it and clears it
+    pub scroll_to_message: Signal<Option<Uuid>>,
+    /// VRAM in use (MB) right before the loaded model was loaded, when the
+    /// GPU reports live usage; the difference is what the model takes
+    pub vram_baseline_mb: Signal<Option<u64>>,
+    /// Architecture of the loaded model, for memory estimates in the settings
+    pub loaded_model: Signal<Option<LoadedModelInfo>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         tracing::info!("AppState initialized");
         let settings = load_settings();
+        set_locale(Locale::from_code(&settings.language));
         let mut agent_config = AgentConfig::default();
         agent_config.disabled_mcp_servers = settings.disabled_mcp_servers.clone();
+        agent_config.disabled_tools = settings.disabled_tools.clone();
         
         Self {
             agent: Arc::new(Agent::new(agent_config)),
@@ -52,11 +74,149 @@ impl AppState {
             conversations: Signal::new(Vec::new()),
             settings: Signal::new(settings),
             model_state: Signal::new(ModelState::NotLoaded),
+            reasoning_tags: Signal::new(Vec::new()),
             stop_signal: Arc::new(AtomicBool::new(false)),
+            tool_cancel: Arc::new(std::sync::Mutex::new(CancellationToken::new())),
             is_generating: Signal::new(false),
             active_messages: Signal::new(Vec::new()),
+            input_draft: Signal::new(String::new()),
+            scroll_to_message: Signal::new(None),
+            vram_baseline_mb: Signal::new(None),
+            loaded_model: Signal::new(None),
         }
     }
+
+    /// Switch to `settings` wholesale (reset or import) and save them. The
+    /// language and tool access apply at once, the rest as it is next read.
+    pub fn replace_settings(&mut self, settings: AppSettings) -> Result<(), StorageError> {
+        set_locale(Locale::from_code(&settings.language));
+        self.agent
+            .tool_registry
+            .set_disabled(&settings.disabled_tools);
+        save_settings(&settings)?;
+        self.settings.set(settings);
+        Ok(())
+    }
+
+    /// Load the model at `path` in place of the current one. The current
+    /// model is unloaded first so its VRAM is free for the new one, and
+    /// `path` is saved as the last model once it loads.
+    pub async fn switch_model(mut self, path: String) {
+        self.model_state.set(ModelState::Loading);
+        self.loaded_model.set(None);
+        let (gpu_layers, cpu_threads) = {
+            let settings = self.settings.read();
+            (settings.gpu_layers, settings.cpu_threads)
+        };
+
+        let result = {
+            let mut engine = self.engine.lock().await;
+            engine.unload_model_async().await;
+            let baseline = tokio::task::spawn_blocking(vram_used_mb)
+                .await
+                .ok()
+                .flatten();
+            self.vram_baseline_mb.set(baseline);
+            if !engine.is_initialized() {
+                if let Err(e) = engine.init() {
+                    return self.model_state.set(ModelState::Error(e.to_string()));
+                }
+            }
+            engine
+                .load_model_async(&path, gpu_layers, cpu_threads)
+                .await
+        };
+        match result {
+            Ok(info) => {
+                tracing::info!("Switched to model {}", path);
+                self.loaded_model.set(Some(info.clone()));
+                self.reasoning_tags.set(info.reasoning_tags);
+                self.model_state.set(ModelState::Loaded(path.clone()));
+                let mut settings = self.settings.write();
+                settings.last_model_path = Some(path);
+                if let Err(error) = save_settings(&settings) {
+                    tracing::error!("Failed to save settings: {}", error);
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to load model {}: {}", path, e);
+                self.vram_baseline_mb.set(None);
+                self.model_state.set(ModelState::Error(e.to_string()));
+            }
+        }
+    }
+
+    /// Tokens `messages` take in a prompt, by the loaded model's tokenizer
+    /// and chat template; estimated at ~4 characters per token without a
+    /// model
+    pub async fn count_tokens(&self, messages: &[crate::types::message::Message]) -> usize {
+        let counted = self
+            .engine
+            .lock()
+            .await
+            .count_tokens(messages.to_vec())
+            .await;
+        counted.unwrap_or_else(|_| messages.iter().map(|m| m.content.len() / 4).sum())
+    }
+
+    /// Drop the active message at `index` and everything after it, and save
+    /// the shortened conversation (regenerate, edit and resend)
+    pub fn truncate_messages(&mut self, index: usize) {
+        self.active_messages.write().truncate(index);
+        let stored: Vec<crate::types::message::Message> = self
+            .active_messages
+            .read()
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect();
+        let mut conv_write = self.current_conversation.write();
+        if let Some(ref mut conv) = *conv_write {
+            conv.messages = stored;
+            if let Err(e) = save_conversation(conv) {
+                tracing::error!("Failed to save conversation: {}", e);
+            }
+        }
+    }
+
+    /// Index of the active message `id`
+    fn message_index(&self, id: Uuid) -> Option<usize> {
+        self.active_messages.read().iter().position(|m| m.id == id)
+    }
+
+    /// Put the user message `id` back in the input, dropping it and
+    /// everything after it. `false` when it is no longer in the
+    /// conversation or is not a user message.
+    pub fn edit_message(&mut self, id: Uuid) -> bool {
+        let Some(index) = self.message_index(id) else {
+            return false;
+        };
+        let content = {
+            let messages = self.active_messages.read();
+            let message = &messages[index];
+            if message.role != MessageRole::User {
+                return false;
+            }
+            message.content.clone()
+        };
+        self.truncate_messages(index);
+        self.input_draft.set(content);
+        true
+    }
+
+    /// Drop the assistant message `id` and everything after it, back to the
+    /// user message it answers, so that message can be answered again.
+    /// `false` when there is nothing to regenerate from.
+    pub fn rewind_to_prompt(&mut self, id: Uuid) -> bool {
+        let Some(index) = self.message_index(id) else {
+            return false;
+        };
+        let Some(cut) = regenerate_cut(&self.active_messages.read(), index) else {
+            return false;
+        };
+        self.truncate_messages(cut);
+        true
+    }
 }
 
 #[component]
@@ -32,6 +32,7 @@ use llama_cpp_2::sampling::LlamaSampler;
 use thiserror::Error;
 
 use crate::inference::model::{validate_gguf, ModelError};
+use crate::inference::reasoning::{detect_reasoning_tags, ReasoningTag};
 use crate::inference::streaming::StreamToken;
 use crate::types::message::{Message as ChatMessage, Role as ChatRole};
 
@@ -79,11 +80,21 @@ pub struct GenerationParams {
     pub temperature: f32,
     pub top_k: u32,
     pub top_p: f32,
+    /// Drop tokens less likely than `min_p` times the most likely one (0: off)
+    pub min_p: f32,
+    /// Penalty on tokens seen in the last `REPEAT_PENALTY_LAST_N` (1.0: off)
     pub repeat_penalty: f32,
     pub seed: u32,
     pub max_context_size: u32,
 }
 
+/// Tokens looked back over for the repeat penalty
+const REPEAT_PENALTY_LAST_N: i32 = 64;
+
+/// VRAM kept free for llama.cpp's compute buffers when estimating how many
+/// layers fit
+const VRAM_COMPUTE_RESERVE_BYTES: u64 = 512 * 1024 * 1024;
+
 impl Default for GenerationParams {
     fn default() -> Self {
         Self {
@@ -91,6 +102,7 @@ impl Default for GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.95,
+            min_p: 0.05,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 16384, // 16K context - validated with LM Studio on 8GB VRAM
@@ -105,6 +117,7 @@ impl GenerationParams {
             temperature: 0.0,
             top_k: 1,
             top_p: 1.0,
+            min_p: 0.0,
             repeat_penalty: 1.0,
             seed: 0,
             max_context_size: 4096,
@@ -117,6 +130,7 @@ impl GenerationParams {
             temperature: 0.7,
             top_k: 40,
             top_p: 0.9,
+            min_p: 0.05,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 8192,
@@ -129,6 +143,7 @@ impl GenerationParams {
             temperature: 0.8,
             top_k: 50,
             top_p: 0.95,
+            min_p: 0.05,
             repeat_penalty: 1.1,
             seed: 0,
             max_context_size: 16384,
@@ -145,6 +160,64 @@ pub struct LoadedModelInfo {
     pub context_length: u32,
     pub param_count: u64,
     pub size_bytes: u64,
+    /// Transformer blocks, each with its own KV cache
+    pub n_layer: u32,
+    /// Width of one layer's K (or V) cache per token: the embedding width
+    /// scaled by `n_head_kv / n_head` for grouped-query attention
+    pub kv_dim: u32,
+    /// Sampling the model's authors recommend, from its GGUF metadata
+    pub sampling_defaults: SamplingDefaults,
+    /// Reasoning markers declared by the chat template, beyond the defaults
+    pub reasoning_tags: Vec<ReasoningTag>,
+}
+
+/// Recommended sampling from the `general.sampling.*` GGUF keys; `None`
+/// where the file does not set one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamplingDefaults {
+    pub temperature: Option<f32>,
+    pub top_k: Option<u32>,
+    pub top_p: Option<f32>,
+    pub min_p: Option<f32>,
+    pub repeat_penalty: Option<f32>,
+}
+
+impl SamplingDefaults {
+    /// Read the keys with `meta`, which returns a metadata value as a string
+    fn from_metadata(meta: impl Fn(&str) -> Option<String>) -> Self {
+        let float = |key: &str| meta(key).and_then(|v| v.trim().parse::<f32>().ok());
+        Self {
+            temperature: float("general.sampling.temp"),
+            top_k: meta("general.sampling.top_k").and_then(|v| v.trim().parse().ok()),
+            top_p: float("general.sampling.top_p"),
+            min_p: float("general.sampling.min_p"),
+            repeat_penalty: float("general.sampling.penalty_repeat"),
+        }
+    }
+}
+
+impl LoadedModelInfo {
+    /// Memory the KV cache takes for `n_ctx` tokens: K and V for every
+    /// layer, in f16 (llama.cpp's default cache type)
+    pub fn kv_cache_bytes(&self, n_ctx: u32) -> u64 {
+        2 * self.n_layer as u64 * self.kv_dim as u64 * n_ctx as u64 * 2
+    }
+
+    /// Layers that can be offloaded into `vram_bytes` of free VRAM with a
+    /// `n_ctx` context: each takes its share of the weights (embeddings and
+    /// output included, so the count errs low) and of the KV cache, after
+    /// setting aside room for llama.cpp's compute buffers
+    pub fn gpu_layers_that_fit(&self, n_ctx: u32, vram_bytes: u64) -> u32 {
+        if self.n_layer == 0 {
+            return 0;
+        }
+        let per_layer = (self.size_bytes + self.kv_cache_bytes(n_ctx)) / self.n_layer as u64;
+        let usable = vram_bytes.saturating_sub(VRAM_COMPUTE_RESERVE_BYTES);
+        match usable.checked_div(per_layer) {
+            Some(layers) => layers.min(self.n_layer as u64) as u32,
+            None => self.n_layer,
+        }
+    }
 }
 
 /// Commands sent to the worker thread
@@ -153,15 +226,23 @@ enum WorkerCommand {
     LoadModel {
         path: PathBuf,
         gpu_layers: u32,
+        cpu_threads: u32,
         response_tx: Sender<Result<LoadedModelInfo, EngineError>>,
     },
-    UnloadModel,
+    UnloadModel {
+        /// Told once the model and context are dropped
+        done_tx: Option<Sender<()>>,
+    },
     Generate {
         messages: Vec<ChatMessage>,
         params: GenerationParams,
         token_tx: Sender<StreamToken>,
         stop_signal: Arc<AtomicBool>,
     },
+    CountTokens {
+        messages: Vec<ChatMessage>,
+        response_tx: Sender<Result<usize, EngineError>>,
+    },
     Shutdown,
 }
 
@@ -212,6 +293,7 @@ impl LlamaEngine {
         &mut self,
         path: P,
         gpu_layers: u32,
+        cpu_threads: u32,
     ) -> Result<LoadedModelInfo, EngineError> {
         let command_tx = self
             .command_tx
@@ -228,6 +310,7 @@ impl LlamaEngine {
             .send(WorkerCommand::LoadModel {
                 path,
                 gpu_layers,
+                cpu_threads,
                 response_tx,
             })
             .map_err(|e| EngineError::WorkerError(e.to_string()))?;
@@ -251,6 +334,7 @@ impl LlamaEngine {
         &mut self,
         path: P,
         gpu_layers: u32,
+        cpu_threads: u32,
     ) -> Result<LoadedModelInfo, EngineError> {
         let command_tx = self
             .command_tx
@@ -266,6 +350,7 @@ impl LlamaEngine {
             .send(WorkerCommand::LoadModel {
                 path: path.to_path_buf(),
                 gpu_layers,
+                cpu_threads,
                 response_tx,
             })
             .map_err(|e| EngineError::WorkerError(e.to_string()))?;
@@ -282,13 +367,33 @@ impl LlamaEngine {
 
     pub fn unload_model(&mut self) {
         if let Some(tx) = &self.command_tx {
-            let _ = tx.send(WorkerCommand::UnloadModel);
+            let _ = tx.send(WorkerCommand::UnloadModel { done_tx: None });
         }
         self.model_info = None;
         self.model_loaded = false;
         tracing::info!("Model unload requested");
     }
 
+    /// Unload the model and wait until the worker has dropped it, so its
+    /// memory is free when this returns
+    pub async fn unload_model_async(&mut self) {
+        self.model_info = None;
+        self.model_loaded = false;
+        let Some(tx) = &self.command_tx else {
+            return;
+        };
+        let (done_tx, done_rx) = mpsc::channel();
+        if tx
+            .send(WorkerCommand::UnloadModel {
+                done_tx: Some(done_tx),
+            })
+            .is_ok()
+        {
+            let _ = tokio::task::spawn_blocking(move || done_rx.recv()).await;
+        }
+        tracing::info!("Model unloaded");
+    }
+
     pub fn model_info(&self) -> Option<&LoadedModelInfo> {
         self.model_info.as_ref()
     }
@@ -301,6 +406,35 @@ impl LlamaEngine {
         self.initialized
     }
 
+    /// Tokens `messages` take once laid out by the model's chat template,
+    /// as the prompt of a generation would. Waits for a running generation
+    /// to finish first.
+    pub async fn count_tokens(&self, messages: Vec<ChatMessage>) -> Result<usize, EngineError> {
+        let command_tx = self
+            .command_tx
+            .as_ref()
+            .ok_or(EngineError::BackendNotInitialized)?
+            .clone();
+
+        if !self.model_loaded {
+            return Err(EngineError::NoModelLoaded);
+        }
+
+        let (response_tx, response_rx) = mpsc::channel();
+
+        command_tx
+            .send(WorkerCommand::CountTokens {
+                messages,
+                response_tx,
+            })
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || response_rx.recv())
+            .await
+            .map_err(|e| EngineError::WorkerError(format!("Task join error: {}", e)))?
+            .map_err(|e| EngineError::WorkerError(e.to_string()))?
+    }
+
     pub fn generate_stream(
         &self,
         prompt: &str,
@@ -372,7 +506,7 @@ struct WorkerState {
     ctx_n_ctx: u32,
     /// Current batch size (needed to verify reuse compatibility)
     ctx_n_batch: u32,
-    /// Optimal thread count (cached)
+    /// Generation threads, set when a model is loaded
     n_threads: i32,
 }
 
@@ -384,7 +518,7 @@ impl WorkerState {
             ctx: None,
             ctx_n_ctx: 0,
             ctx_n_batch: 0,
-            n_threads: get_optimal_threads(),
+            n_threads: crate::system::resources::physical_cores() as i32,
         }
     }
 }
@@ -414,6 +548,7 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
             Ok(WorkerCommand::LoadModel {
                 path,
                 gpu_layers,
+                cpu_threads,
                 response_tx,
             }) => {
                 // Drop existing context FIRST (before model)
@@ -421,6 +556,8 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
+                state.n_threads = cpu_threads.max(1) as i32;
+                tracing::info!("Generation threads: {}", state.n_threads);
                 
                 match load_model_internal(&state.backend, &path, gpu_layers) {
                     Ok((info, loaded_model)) => {
@@ -432,13 +569,16 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                     }
                 }
             }
-            Ok(WorkerCommand::UnloadModel) => {
+            Ok(WorkerCommand::UnloadModel { done_tx }) => {
                 // Drop context FIRST, then model
                 state.ctx = None;
                 state.ctx_n_ctx = 0;
                 state.ctx_n_batch = 0;
                 state.model = None;
                 tracing::info!("Model and context unloaded");
+                if let Some(done_tx) = done_tx {
+                    let _ = done_tx.send(());
+                }
             }
             Ok(WorkerCommand::Generate {
                 messages,
@@ -455,6 +595,16 @@ fn worker_thread_main(command_rx: Receiver<WorkerCommand>) {
                     let _ = token_tx.send(StreamToken::Error(e));
                 }
             }
+            Ok(WorkerCommand::CountTokens {
+                messages,
+                response_tx,
+            }) => {
+                let count = match state.model.as_ref() {
+                    Some(model) => count_prompt_tokens(model, &messages),
+                    None => Err(EngineError::NoModelLoaded),
+                };
+                let _ = response_tx.send(count);
+            }
             Ok(WorkerCommand::Shutdown) => {
                 // Clean shutdown: drop context first, then model
                 state.ctx = None;
@@ -509,6 +659,19 @@ fn load_model_internal(
         context_length: model.n_ctx_train(),
         param_count: model.n_params() as u64,
         size_bytes: model.size() as u64,
+        n_layer: model.n_layer(),
+        kv_dim: match model.n_head() {
+            0 => model.n_embd().max(0) as u32,
+            n_head => {
+                (model.n_embd().max(0) as u64 * model.n_head_kv() as u64 / n_head as u64) as u32
+            }
+        },
+        sampling_defaults: SamplingDefaults::from_metadata(|key| model.meta_val_str(key).ok()),
+        reasoning_tags: model
+            .chat_template(None)
+            .ok()
+            .and_then(|template| template.to_str().ok().map(detect_reasoning_tags))
+            .unwrap_or_default(),
     };
 
     tracing::info!(
@@ -670,6 +833,16 @@ fn run_generation_persistent(
     run_inference(ctx, model, tokens, clamped, actual_n_ctx, n_batch, tx, stop_signal)
 }
 
+/// Length of the prompt `messages` make, built as for a generation
+fn count_prompt_tokens(model: &LlamaModel, messages: &[ChatMessage]) -> Result<usize, EngineError> {
+    let prompt = build_chat_prompt_from_messages(model, messages)
+        .unwrap_or_else(|_| build_fallback_prompt(messages));
+    model
+        .str_to_token(&prompt, AddBos::Always)
+        .map(|tokens| tokens.len())
+        .map_err(|e| EngineError::Tokenization(e.to_string()))
+}
+
 /// Pick a good context size (round up for reusability)
 fn pick_context_size(needed: u32, max: u32) -> u32 {
     // Round up to standard sizes for better context reuse
@@ -682,20 +855,6 @@ fn pick_context_size(needed: u32, max: u32) -> u32 {
     std::cmp::min(needed, max)
 }
 
-/// Get optimal number of threads
-fn get_optimal_threads() -> i32 {
-    let logical = std::thread::available_parallelism()
-        .map(|p| p.get() as i32)
-        .unwrap_or(4);
-    
-    // Use physical cores (logical / 2 on HT systems)
-    // But at least 2, and cap at 16
-    let physical = std::cmp::max(2, logical / 2);
-    let result = std::cmp::min(physical, 16);
-    tracing::info!("Thread config: {} logical -> {} threads", logical, result);
-    result
-}
-
 /// Calculate optimal batch size
 fn calculate_optimal_batch(n_ctx: u32, prompt_len: u32) -> u32 {
     let base = if prompt_len < 512 {
@@ -732,6 +891,7 @@ fn build_chat_prompt_from_messages(
             ChatRole::System => "system",
             ChatRole::User => "user",
             ChatRole::Assistant => "assistant",
+            ChatRole::Tool => "tool",
         };
         let chat_msg = LlamaChatMessage::new(role.to_string(), msg.content.clone())
             .map_err(|e| format!("Chat message error: {e}"))?;
@@ -750,6 +910,7 @@ fn build_fallback_prompt(messages: &[ChatMessage]) -> String {
             ChatRole::System => "System",
             ChatRole::User => "User",
             ChatRole::Assistant => "Assistant",
+            ChatRole::Tool => "Tool",
         };
         out.push_str(role);
         out.push_str(": ");
@@ -796,6 +957,8 @@ fn run_inference(
     let prompt_start = std::time::Instant::now();
     for (chunk_index, chunk) in prompt_tokens.chunks(batch_size).enumerate() {
         if stop_signal.load(Ordering::Relaxed) {
+            // Stopped before the first token: the next run clears the KV cache
+            let _ = tx.send(StreamToken::Done);
             return Ok(());
         }
         
@@ -822,12 +985,19 @@ fn run_inference(
     // Sampler
     let seed = if params.seed == 0 { rand_seed() } else { params.seed };
 
+    // Temperature 0 picks the most likely token, still after the repeat
+    // penalty so that setting keeps working
     let mut sampler = if params.temperature < 0.01 {
-        LlamaSampler::greedy()
+        LlamaSampler::chain_simple([
+            LlamaSampler::penalties(REPEAT_PENALTY_LAST_N, params.repeat_penalty, 0.0, 0.0),
+            LlamaSampler::greedy(),
+        ])
     } else {
         LlamaSampler::chain_simple([
+            LlamaSampler::penalties(REPEAT_PENALTY_LAST_N, params.repeat_penalty, 0.0, 0.0),
             LlamaSampler::top_k(params.top_k as i32),
             LlamaSampler::top_p(params.top_p, 1),
+            LlamaSampler::min_p(params.min_p, 1),
             LlamaSampler::temp(params.temperature),
             LlamaSampler::dist(seed),
         ])
@@ -987,6 +1157,69 @@ mod tests {
         assert_eq!(pick_context_size(10000, 32768), 16384);
     }
 
+    #[test]
+    fn test_kv_cache_estimate() {
+        // Llama 3 8B: 32 layers, 4096 wide, 8 of 32 heads for K/V
+        let info = LoadedModelInfo {
+            path: String::new(),
+            vocab_size: 128256,
+            embedding_dim: 4096,
+            context_length: 8192,
+            param_count: 8_000_000_000,
+            size_bytes: 0,
+            n_layer: 32,
+            kv_dim: 1024,
+            sampling_defaults: SamplingDefaults::default(),
+            reasoning_tags: Vec::new(),
+        };
+        // 128 KiB per token, so 8K tokens take 1 GiB
+        assert_eq!(info.kv_cache_bytes(1), 128 * 1024);
+        assert_eq!(info.kv_cache_bytes(8192), 1 << 30);
+    }
+
+    #[test]
+    fn test_gpu_layers_that_fit() {
+        const GIB: u64 = 1 << 30;
+        // 8 GiB of weights over 32 layers and a 1 GiB cache at 8K: 288 MiB a layer
+        let info = LoadedModelInfo {
+            path: String::new(),
+            vocab_size: 128256,
+            embedding_dim: 4096,
+            context_length: 8192,
+            param_count: 8_000_000_000,
+            size_bytes: 8 * GIB,
+            n_layer: 32,
+            kv_dim: 1024,
+            sampling_defaults: SamplingDefaults::default(),
+            reasoning_tags: Vec::new(),
+        };
+        assert_eq!(info.gpu_layers_that_fit(8192, 24 * GIB), 32);
+        // 4.5 GiB less the 0.5 GiB reserve is 4 GiB, 14 layers of 288 MiB
+        assert_eq!(info.gpu_layers_that_fit(8192, 9 * GIB / 2), 14);
+        // A shorter context leaves room for more
+        assert!(info.gpu_layers_that_fit(2048, 9 * GIB / 2) > 14);
+        assert_eq!(info.gpu_layers_that_fit(8192, GIB / 4), 0);
+        assert_eq!(info.gpu_layers_that_fit(8192, 0), 0);
+    }
+
+    #[test]
+    fn test_sampling_defaults_from_metadata() {
+        let defaults = SamplingDefaults::from_metadata(|key| match key {
+            "general.sampling.temp" => Some("0.6".into()),
+            "general.sampling.top_k" => Some("20".into()),
+            "general.sampling.top_p" => Some("not a number".into()),
+            _ => None,
+        });
+        assert_eq!(
+            defaults,
+            SamplingDefaults {
+                temperature: Some(0.6),
+                top_k: Some(20),
+                ..SamplingDefaults::default()
+            }
+        );
+    }
+
     #[test]
     fn test_unload_without_model() {
         let mut engine = LlamaEngine::new();
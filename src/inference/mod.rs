@@ -4,9 +4,11 @@
 
 pub mod engine;
 pub mod model;
+pub mod reasoning;
 pub mod streaming;
 
 // Re-export main types for convenience
-pub use engine::{EngineError, GenerationParams, LlamaEngine, LoadedModelInfo};
+pub use engine::{EngineError, GenerationParams, LlamaEngine, LoadedModelInfo, SamplingDefaults};
 pub use model::{validate_gguf, GgufMetadata, ModelError, GGUF_MAGIC};
+pub use reasoning::{default_reasoning_tags, ReasoningTag};
 pub use streaming::StreamToken;
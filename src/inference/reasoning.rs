@@ -0,0 +1,192 @@
+//! Reasoning tag pairs
+//!
+//! Local models wrap their chain of thought in different markers: Qwen and
+//! DeepSeek use `<think>`, others `<thinking>`, `<reasoning>` or `<|thinking|>`.
+//! The chat UI folds every pair it knows into a collapsible block. The pairs a
+//! model's chat template mentions are read when the model is loaded, so a
+//! model with its own marker is recognised as well.
+
+use serde::{Deserialize, Serialize};
+
+/// Opening and closing markers around a model's reasoning
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningTag {
+    pub open: String,
+    pub close: String,
+}
+
+impl ReasoningTag {
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+        }
+    }
+}
+
+/// Pairs recognised whatever the loaded model
+const DEFAULT_TAGS: &[(&str, &str)] = &[
+    ("<think>", "</think>"),
+    ("<thinking>", "</thinking>"),
+    ("<reasoning>", "</reasoning>"),
+    ("<|thinking|>", "<|/thinking|>"),
+];
+
+/// Words that mark a template tag as a reasoning marker
+const REASONING_WORDS: &[&str] = &["think", "reason", "thought"];
+
+/// The pairs recognised for every model
+pub fn default_reasoning_tags() -> Vec<ReasoningTag> {
+    DEFAULT_TAGS
+        .iter()
+        .map(|(open, close)| ReasoningTag::new(*open, *close))
+        .collect()
+}
+
+/// Reasoning tag pairs declared by a chat template: every `<name>` /
+/// `</name>` or `<|name|>` / `<|/name|>` pair it contains whose name mentions
+/// thinking or reasoning. Pairs already in the defaults are left out.
+pub fn detect_reasoning_tags(template: &str) -> Vec<ReasoningTag> {
+    let defaults = default_reasoning_tags();
+    let mut found: Vec<ReasoningTag> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..=end];
+        rest = &rest[1..];
+
+        let (name, close) = match tag.strip_prefix("<|").and_then(|t| t.strip_suffix("|>")) {
+            Some(name) => (name, format!("<|/{}|>", name)),
+            None => {
+                let name = &tag[1..tag.len() - 1];
+                (name, format!("</{}>", name))
+            }
+        };
+        let is_name = !name.is_empty()
+            && name.len() <= 32
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let lower = name.to_ascii_lowercase();
+        if !is_name || !REASONING_WORDS.iter().any(|w| lower.contains(w)) {
+            continue;
+        }
+        if !template.contains(&close) {
+            continue;
+        }
+
+        let pair = ReasoningTag::new(tag, close);
+        if !defaults.contains(&pair) && !found.contains(&pair) {
+            found.push(pair);
+        }
+    }
+    found
+}
+
+/// `text` without its reasoning: every block delimited by one of `tags`
+/// (nested blocks included), reasoning before a stray closing marker (the
+/// template opened the block) and an unterminated block at the end.
+pub fn strip_reasoning(text: &str, tags: &[ReasoningTag]) -> String {
+    let mut rest = text;
+
+    // Reasoning opened by the chat template: only the closing marker shows
+    let first_open = tags
+        .iter()
+        .filter_map(|tag| rest.find(&tag.open))
+        .min()
+        .unwrap_or(rest.len());
+    if let Some((pos, close)) = tags
+        .iter()
+        .filter_map(|tag| rest.find(&tag.close).map(|pos| (pos, tag.close.as_str())))
+        .filter(|(pos, _)| *pos < first_open)
+        .min_by_key(|(pos, _)| *pos)
+    {
+        rest = &rest[pos + close.len()..];
+    }
+
+    let mut kept = String::with_capacity(rest.len());
+    while let Some((start, tag)) = tags
+        .iter()
+        .filter_map(|tag| rest.find(&tag.open).map(|pos| (pos, tag)))
+        .min_by_key(|(pos, _)| *pos)
+    {
+        kept.push_str(&rest[..start]);
+        let body = &rest[start + tag.open.len()..];
+        match matching_close(body, &tag.open, &tag.close) {
+            Some(end) => rest = &body[end + tag.close.len()..],
+            None => return kept,
+        }
+    }
+    kept.push_str(rest);
+    kept
+}
+
+/// Position of the closing tag that ends a block whose body is `body`,
+/// skipping over blocks of the same kind nested inside it.
+pub fn matching_close(body: &str, open_tag: &str, close_tag: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = 0;
+    loop {
+        let next_open = body[pos..].find(open_tag).map(|i| pos + i);
+        let next_close = body[pos..].find(close_tag).map(|i| pos + i)?;
+        match next_open {
+            Some(open) if open < next_close => {
+                depth += 1;
+                pos = open + open_tag.len();
+            }
+            _ if depth == 0 => return Some(next_close),
+            _ => {
+                depth -= 1;
+                pos = next_close + close_tag.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_reasoning_tags() {
+        let qwen =
+            "{%- if message.role == 'assistant' %}<think>\n{{ reasoning }}\n</think>{% endif %}";
+        assert!(detect_reasoning_tags(qwen).is_empty());
+
+        let custom = "<|im_start|>assistant\n<|reasoning_start|>{{ r }}<|/reasoning_start|>\
+                      <inner_thoughts>{{ t }}</inner_thoughts><b>x</b>";
+        assert_eq!(
+            detect_reasoning_tags(custom),
+            vec![
+                ReasoningTag::new("<|reasoning_start|>", "<|/reasoning_start|>"),
+                ReasoningTag::new("<inner_thoughts>", "</inner_thoughts>"),
+            ]
+        );
+
+        // An opening marker without its closing counterpart is not a pair
+        assert!(detect_reasoning_tags("<thought_begin> only").is_empty());
+    }
+
+    #[test]
+    fn test_strip_reasoning() {
+        let tags = default_reasoning_tags();
+        for tag in &tags {
+            let text = format!("{}plan{}Paris trip", tag.open, tag.close);
+            assert_eq!(strip_reasoning(&text, &tags), "Paris trip");
+        }
+        assert_eq!(
+            strip_reasoning(
+                "A<think>x<think>y</think>z</think>B<reasoning>w</reasoning>C",
+                &tags
+            ),
+            "ABC"
+        );
+        // Opened by the template, and cut off by the token limit
+        assert_eq!(strip_reasoning("weighing</think>Answer", &tags), "Answer");
+        assert_eq!(strip_reasoning("Answer<thinking>and then", &tags), "Answer");
+        assert_eq!(strip_reasoning("no reasoning", &tags), "no reasoning");
+    }
+}
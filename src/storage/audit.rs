@@ -0,0 +1,212 @@
+//! Tool audit log
+//!
+//! Every tool call the agent runs is appended as one JSON line to
+//! `tool_audit.jsonl` in the data directory, for review after the fact. Long
+//! strings (file contents, command output) are cut down before they are
+//! written, and the file is rotated to `tool_audit.1.jsonl` once it passes
+//! `MAX_LOG_BYTES`, so the log stays small.
+
+use crate::storage::{get_data_dir, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Log file name in the data directory
+pub const AUDIT_FILE_NAME: &str = "tool_audit.jsonl";
+
+/// Longest string kept in a logged parameter or message, in characters
+const MAX_FIELD_CHARS: usize = 500;
+
+/// Size past which the log is moved aside and a new one started
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// One tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub tool: String,
+    pub params: Value,
+    pub success: bool,
+    pub duration_ms: u64,
+    /// The result's message, or the error the call failed with
+    pub message: String,
+}
+
+impl AuditEntry {
+    /// The entry with every string longer than `MAX_FIELD_CHARS` cut down
+    fn redacted(&self) -> Self {
+        let mut entry = self.clone();
+        truncate_strings(&mut entry.params);
+        if let Some(short) = truncate_field(&entry.message) {
+            entry.message = short;
+        }
+        entry
+    }
+}
+
+/// `text` cut to `MAX_FIELD_CHARS` with a note of what was left out, or
+/// `None` when it is short enough
+fn truncate_field(text: &str) -> Option<String> {
+    let total = text.chars().count();
+    if total <= MAX_FIELD_CHARS {
+        return None;
+    }
+    let kept: String = text.chars().take(MAX_FIELD_CHARS).collect();
+    Some(format!(
+        "{}… [{} more characters]",
+        kept,
+        total - MAX_FIELD_CHARS
+    ))
+}
+
+fn truncate_strings(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            if let Some(short) = truncate_field(text) {
+                *text = short;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(truncate_strings),
+        Value::Object(fields) => fields.values_mut().for_each(truncate_strings),
+        _ => {}
+    }
+}
+
+/// Append-only JSONL log of tool calls
+pub struct ToolAuditLog {
+    path: PathBuf,
+    /// Keeps concurrent calls from interleaving their lines or rotating twice
+    write_lock: Mutex<()>,
+}
+
+impl ToolAuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// The log in the app data directory
+    pub fn in_data_dir() -> Result<Self, StorageError> {
+        Ok(Self::new(get_data_dir()?.join(AUDIT_FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Where the previous log goes when the current one is rotated
+    fn rotated_path(&self) -> PathBuf {
+        self.path.with_extension("1.jsonl")
+    }
+
+    /// Add `entry`, its long strings cut down
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), StorageError> {
+        let line = serde_json::to_string(&entry.redacted())?;
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        if fs::metadata(&self.path).is_ok_and(|meta| meta.len() > MAX_LOG_BYTES) {
+            fs::rename(&self.path, self.rotated_path())?;
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Up to `limit` entries, newest first. Lines that do not parse (a write
+    /// cut short) are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<AuditEntry>, StorageError> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect())
+    }
+
+    /// Delete the log and the rotated one
+    pub fn clear(&self) -> Result<(), StorageError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        for path in [self.path.clone(), self.rotated_path()] {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != ErrorKind::NotFound {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tool: &str, params: Value) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            tool: tool.to_string(),
+            params,
+            success: true,
+            duration_ms: 12,
+            message: "ok".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_audit_log_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ToolAuditLog::new(dir.path().join("logs").join(AUDIT_FILE_NAME));
+        assert!(log.recent(10).unwrap().is_empty());
+
+        log.append(&entry("file_read", serde_json::json!({ "path": "a.txt" })))
+            .unwrap();
+        log.append(&entry("shell_exec", serde_json::json!({ "command": "ls" })))
+            .unwrap();
+
+        let recent = log.recent(10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].tool, "shell_exec");
+        assert_eq!(recent[1].params["path"], "a.txt");
+        assert_eq!(log.recent(1).unwrap().len(), 1);
+
+        log.clear().unwrap();
+        assert!(log.recent(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_truncates_long_strings() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ToolAuditLog::new(dir.path().join(AUDIT_FILE_NAME));
+        let content = "é".repeat(MAX_FIELD_CHARS + 100);
+        let mut call = entry(
+            "file_write",
+            serde_json::json!({ "path": "big.txt", "edits": [{ "content": content }] }),
+        );
+        call.message = content.clone();
+        log.append(&call).unwrap();
+
+        let logged = &log.recent(1).unwrap()[0];
+        let kept = logged.params["edits"][0]["content"].as_str().unwrap();
+        assert!(kept.starts_with(&"é".repeat(MAX_FIELD_CHARS)));
+        assert!(kept.ends_with("… [100 more characters]"));
+        assert_eq!(logged.message, kept);
+        assert_eq!(logged.params["path"], "big.txt");
+    }
+}
@@ -1,6 +1,12 @@
 //! Conversation storage
 //!
 //! Manages saving and loading of chat conversations.
+//!
+//! Each file records the format `version` it was written with. Older files
+//! are upgraded as they load: fields added since then have serde defaults
+//! (message ids, the model), and changed shapes are read in both forms
+//! (message timestamps, once Unix seconds). Files from a newer version are
+//! left alone rather than rewritten without the fields this one ignores.
 
 use crate::storage::{get_data_dir, StorageError};
 use crate::types::message::Message;
@@ -10,9 +16,18 @@ use std::fs;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+/// Format written by this version
+///
+/// - 0: unversioned files
+/// - 1: adds `version` and `model`
+pub const CONVERSATION_VERSION: u32 = 1;
+
 /// A chat conversation
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Conversation {
+    /// Format the conversation was saved with
+    #[serde(default)]
+    pub version: u32,
     /// Unique identifier for the conversation
     pub id: String,
     /// Human-readable title (auto-generated from first message)
@@ -23,6 +38,9 @@ pub struct Conversation {
     pub created_at: DateTime<Utc>,
     /// When the conversation was last updated
     pub updated_at: DateTime<Utc>,
+    /// Name of the model that last answered
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 impl Conversation {
@@ -39,11 +57,13 @@ impl Conversation {
         };
 
         Self {
+            version: CONVERSATION_VERSION,
             id,
             title,
             messages,
             created_at: now,
             updated_at: now,
+            model: None,
         }
     }
 
@@ -81,8 +101,27 @@ fn get_conversation_path(id: &str) -> Result<PathBuf, StorageError> {
     Ok(get_conversations_dir()?.join(format!("{}.json", id)))
 }
 
+/// Read a saved conversation, upgrading older formats
+fn parse_conversation(json: &str) -> Result<Conversation, StorageError> {
+    let mut conversation: Conversation = serde_json::from_str(json)?;
+    if conversation.version > CONVERSATION_VERSION {
+        return Err(StorageError::UnsupportedVersion {
+            id: conversation.id,
+            version: conversation.version,
+        });
+    }
+    conversation.version = CONVERSATION_VERSION;
+    Ok(conversation)
+}
+
 /// Save a conversation to disk
 pub fn save_conversation(conversation: &Conversation) -> Result<(), StorageError> {
+    if conversation.version > CONVERSATION_VERSION {
+        return Err(StorageError::UnsupportedVersion {
+            id: conversation.id.clone(),
+            version: conversation.version,
+        });
+    }
     let dir = get_conversations_dir()?;
     std::fs::create_dir_all(&dir)?;
     let path = get_conversation_path(&conversation.id)?;
@@ -101,11 +140,19 @@ pub fn load_conversation(id: &str) -> Result<Conversation, StorageError> {
     }
 
     let json = fs::read_to_string(&path)?;
-    let conversation: Conversation = serde_json::from_str(&json)?;
+    let conversation = parse_conversation(&json)?;
     tracing::debug!("Loaded conversation: {}", id);
     Ok(conversation)
 }
 
+/// Give a saved conversation a new title, returning it as saved
+pub fn rename_conversation(id: &str, title: &str) -> Result<Conversation, StorageError> {
+    let mut conversation = load_conversation(id)?;
+    conversation.title = title.trim().to_string();
+    save_conversation(&conversation)?;
+    Ok(conversation)
+}
+
 /// List all conversations
 ///
 /// Returns a list of conversations sorted by updated_at (most recent first)
@@ -124,7 +171,7 @@ pub fn list_conversations() -> Result<Vec<Conversation>, StorageError> {
 
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
             match fs::read_to_string(&path) {
-                Ok(json) => match serde_json::from_str::<Conversation>(&json) {
+                Ok(json) => match parse_conversation(&json) {
                     Ok(conv) => conversations.push(conv),
                     Err(e) => {
                         tracing::warn!("Failed to parse conversation file {:?}: {}", path, e);
@@ -214,4 +261,26 @@ mod tests {
         assert_eq!(conv.title, deserialized.title);
         assert_eq!(conv.messages.len(), deserialized.messages.len());
     }
+
+    #[test]
+    fn test_conversation_versions() {
+        // Unversioned, with Unix-second timestamps and no message ids
+        let legacy = r#"{
+            "id": "old",
+            "title": "Old chat",
+            "messages": [{"role": "User", "content": "Hi", "timestamp": 1700000000}],
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }"#;
+        let conv = parse_conversation(legacy).unwrap();
+        assert_eq!(conv.version, CONVERSATION_VERSION);
+        assert_eq!(conv.model, None);
+        assert_eq!(conv.messages[0].timestamp.timestamp(), 1_700_000_000);
+
+        let newer = legacy.replacen('{', r#"{"version": 99,"#, 1);
+        assert!(matches!(
+            parse_conversation(&newer),
+            Err(StorageError::UnsupportedVersion { version: 99, .. })
+        ));
+    }
 }
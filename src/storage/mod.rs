@@ -5,9 +5,11 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
+pub mod audit;
 pub mod conversations;
 pub mod huggingface;
 pub mod models;
+pub mod search;
 pub mod settings;
 
 /// Storage-related errors
@@ -21,6 +23,8 @@ pub enum StorageError {
     JsonError(#[from] serde_json::Error),
     #[error("Conversation not found: {0}")]
     ConversationNotFound(String),
+    #[error("Conversation {id} was saved by a newer version (format {version})")]
+    UnsupportedVersion { id: String, version: u32 },
 }
 
 /// Get the application data directory
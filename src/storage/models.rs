@@ -53,6 +53,30 @@ impl ModelInfo {
             format!("{:.2} GB", bytes / (1024.0 * 1024.0 * 1024.0))
         }
     }
+
+    /// Quantization named in the filename, e.g. "Q4_K_M" for
+    /// `Llama-3-8B-Instruct-Q4_K_M.gguf`
+    pub fn quantization(&self) -> Option<String> {
+        let stem = self
+            .filename
+            .strip_suffix(".gguf")
+            .unwrap_or(&self.filename);
+        stem.split(['-', '.'])
+            .map(|part| part.to_ascii_uppercase())
+            .rev()
+            .find(|part| is_quantization(part))
+    }
+}
+
+/// Whether `part` of a filename is a llama.cpp quantization type: Q8_0,
+/// Q4_K_M, IQ3_XS, F16, BF16...
+fn is_quantization(part: &str) -> bool {
+    if matches!(part, "F16" | "F32" | "BF16") {
+        return true;
+    }
+    let rest = part.strip_prefix('I').unwrap_or(part);
+    rest.strip_prefix('Q')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
 }
 
 /// Scan a directory for GGUF model files
@@ -151,6 +175,34 @@ mod tests {
         assert!(large_model.size_string().contains("GB"));
     }
 
+    #[test]
+    fn test_quantization() {
+        let quantization = |filename: &str| {
+            ModelInfo {
+                path: PathBuf::from(filename),
+                filename: filename.to_string(),
+                size_bytes: 0,
+                last_modified: SystemTime::now(),
+            }
+            .quantization()
+        };
+
+        assert_eq!(
+            quantization("Meta-Llama-3-8B-Instruct-Q4_K_M.gguf").as_deref(),
+            Some("Q4_K_M")
+        );
+        assert_eq!(
+            quantization("mistral-7b-instruct-v0.2.q8_0.gguf").as_deref(),
+            Some("Q8_0")
+        );
+        assert_eq!(
+            quantization("Qwen2.5-7B-IQ3_XS.gguf").as_deref(),
+            Some("IQ3_XS")
+        );
+        assert_eq!(quantization("gemma-2b-bf16.gguf").as_deref(), Some("BF16"));
+        assert_eq!(quantization("Qwen2.5-7B-Instruct.gguf"), None);
+    }
+
     #[test]
     fn test_scan_models_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,212 @@
+//! Full-text search over saved conversations
+//!
+//! The index keeps a lowercased copy of every user message and answer (the
+//! answers without their reasoning; tool records and system scaffolding are
+//! left out), so a query is a case-insensitive substring scan over memory
+//! rather than a pass over the files.
+
+use crate::storage::conversations::Conversation;
+use crate::types::message::{clean_thinking_tags, Role};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Characters of context kept before the match in a snippet
+const SNIPPET_BEFORE: usize = 40;
+/// Characters kept from the match on
+const SNIPPET_AFTER: usize = 80;
+
+/// A conversation matching a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub conversation_id: String,
+    pub title: String,
+    /// The message with the most matches, to open the conversation at
+    pub message_id: Uuid,
+    /// Text around the first match in that message
+    pub snippet: String,
+    /// Matches across the whole conversation
+    pub matches: usize,
+}
+
+#[derive(PartialEq)]
+struct IndexedMessage {
+    id: Uuid,
+    text: String,
+    lowercase: String,
+}
+
+#[derive(PartialEq)]
+struct IndexedConversation {
+    id: String,
+    title: String,
+    updated_at: DateTime<Utc>,
+    messages: Vec<IndexedMessage>,
+}
+
+/// Searchable copy of the saved conversations
+#[derive(PartialEq)]
+pub struct ConversationIndex {
+    conversations: Vec<IndexedConversation>,
+}
+
+impl ConversationIndex {
+    pub fn build(conversations: &[Conversation]) -> Self {
+        let conversations = conversations
+            .iter()
+            .map(|conversation| IndexedConversation {
+                id: conversation.id.clone(),
+                title: conversation.title.clone(),
+                updated_at: conversation.updated_at,
+                messages: conversation
+                    .messages
+                    .iter()
+                    .filter_map(|message| {
+                        let text = match message.role {
+                            Role::User => message.content.clone(),
+                            Role::Assistant => clean_thinking_tags(&message.content),
+                            Role::System | Role::Tool => return None,
+                        };
+                        Some(IndexedMessage {
+                            id: message.id,
+                            lowercase: text.to_lowercase(),
+                            text,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { conversations }
+    }
+
+    /// Conversations containing `query`, most matches first, then the most
+    /// recently active
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<(SearchHit, DateTime<Utc>)> = self
+            .conversations
+            .iter()
+            .filter_map(|conversation| {
+                let mut total = 0;
+                let mut best: Option<(&IndexedMessage, usize)> = None;
+                for message in &conversation.messages {
+                    let count = message.lowercase.matches(&needle).count();
+                    total += count;
+                    if count > best.map_or(0, |(_, most)| most) {
+                        best = Some((message, count));
+                    }
+                }
+                let (message, _) = best?;
+                let hit = SearchHit {
+                    conversation_id: conversation.id.clone(),
+                    title: conversation.title.clone(),
+                    message_id: message.id,
+                    snippet: snippet(message, &needle),
+                    matches: total,
+                };
+                Some((hit, conversation.updated_at))
+            })
+            .collect();
+
+        hits.sort_by(|(a, a_updated), (b, b_updated)| {
+            b.matches
+                .cmp(&a.matches)
+                .then_with(|| b_updated.cmp(a_updated))
+        });
+        hits.into_iter().map(|(hit, _)| hit).collect()
+    }
+}
+
+/// One line of text around the first match of `needle`
+fn snippet(message: &IndexedMessage, needle: &str) -> String {
+    // Lowercasing can change the length of a few characters; the match is
+    // then located by character rather than by byte
+    let start = message
+        .lowercase
+        .find(needle)
+        .map(|pos| message.lowercase[..pos].chars().count())
+        .unwrap_or(0);
+    let chars: Vec<char> = message.text.chars().collect();
+    let from = start.saturating_sub(SNIPPET_BEFORE).min(chars.len());
+    let to = (start + SNIPPET_AFTER).min(chars.len());
+    let text: String = chars[from..to].iter().collect();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    format!(
+        "{}{}{}",
+        if from > 0 { "…" } else { "" },
+        text,
+        if to < chars.len() { "…" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::message::Message;
+
+    fn conversation(title: &str, messages: Vec<Message>) -> Conversation {
+        let mut conversation = Conversation::new(None);
+        conversation.title = title.to_string();
+        conversation.messages = messages;
+        conversation
+    }
+
+    #[test]
+    fn test_search_ranks_and_snippets() {
+        let answer = Message::new(
+            Role::Assistant,
+            "<think>Borrow checker again</think>Use a Borrow here, the borrow ends at the call.",
+        );
+        let rust = conversation(
+            "Rust lifetimes",
+            vec![
+                Message::new(Role::User, "Why does the borrow fail?"),
+                answer.clone(),
+            ],
+        );
+        let cooking = conversation(
+            "Bread",
+            vec![
+                Message::new(Role::User, "Can I borrow your oven?"),
+                Message::new(Role::System, "borrow borrow borrow"),
+                Message::new(Role::Tool, r#"{"tool":"borrow"}"#),
+            ],
+        );
+        let index = ConversationIndex::build(&[cooking.clone(), rust.clone()]);
+
+        let hits = index.search("  BORROW ");
+        assert_eq!(hits.len(), 2);
+        // Reasoning, system and tool messages don't count
+        assert_eq!(hits[0].conversation_id, rust.id);
+        assert_eq!(hits[0].matches, 3);
+        assert_eq!(hits[0].message_id, answer.id);
+        assert_eq!(
+            hits[0].snippet,
+            "Use a Borrow here, the borrow ends at the call."
+        );
+        assert_eq!(hits[1].conversation_id, cooking.id);
+        assert_eq!(hits[1].matches, 1);
+
+        assert!(index.search("oven mitt").is_empty());
+        assert!(index.search("   ").is_empty());
+    }
+
+    #[test]
+    fn test_snippet_is_cut_around_the_match() {
+        let long = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let index =
+            ConversationIndex::build(&[conversation("Long", vec![Message::new(Role::User, long)])]);
+        let snippet = &index.search("needle")[0].snippet;
+        assert!(
+            snippet.starts_with('…') && snippet.ends_with('…'),
+            "{}",
+            snippet
+        );
+        assert!(snippet.contains("needle"));
+        assert!(snippet.chars().count() <= SNIPPET_BEFORE + SNIPPET_AFTER + 2);
+    }
+}
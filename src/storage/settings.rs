@@ -5,7 +5,7 @@
 use crate::storage::{get_data_dir, StorageError};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Application settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,20 +16,40 @@ pub struct AppSettings {
     pub top_p: f32,
     /// Top-k sampling parameter
     pub top_k: u32,
+    /// Min-p sampling threshold (0.0 - 1.0, 0 = off)
+    #[serde(default = "default_min_p")]
+    pub min_p: f32,
+    /// Penalty on recently repeated tokens (1.0 - 2.0, 1 = off)
+    #[serde(default = "default_repeat_penalty")]
+    pub repeat_penalty: f32,
     /// Maximum number of tokens to generate
     pub max_tokens: u32,
     /// Context window size
     pub context_size: u32,
     /// System prompt prepended to conversations
     pub system_prompt: String,
+    /// The user edited the system prompt or picked a template. Otherwise it
+    /// follows the built-in default (and the language) on every load
+    #[serde(default)]
+    pub system_prompt_custom: bool,
     /// Number of GPU layers to offload (0 = CPU only)
     pub gpu_layers: u32,
+    /// Threads llama.cpp uses for generation (1 - logical CPUs), defaults to
+    /// the physical core count
+    #[serde(default = "default_cpu_threads")]
+    pub cpu_threads: u32,
     /// Directory where model files (.gguf) are stored
     pub models_directory: PathBuf,
     /// UI theme: "dark" or "light"
     pub theme: String,
     /// Font size: "small", "medium", or "large"
     pub font_size: String,
+    /// Chat typeface: "system", "sans", "serif", or "mono"
+    #[serde(default = "default_font_family")]
+    pub font_family: String,
+    /// Accent color as "#RRGGBB"
+    #[serde(default = "default_accent_color")]
+    pub accent_color: String,
     /// Exa MCP server URL
     #[serde(default)]
     pub exa_mcp_url: String,
@@ -51,9 +71,71 @@ pub struct AppSettings {
     /// List of disabled MCP server IDs
     #[serde(default)]
     pub disabled_mcp_servers: Vec<String>,
+    /// Tools the agent may not use at all, by name
+    #[serde(default)]
+    pub disabled_tools: Vec<String>,
     /// OpenRouter model to use for ai_consult tool (default: openrouter/pony-alpha)
     #[serde(default = "default_openrouter_model")]
     pub openrouter_model: String,
+    /// Render `$...$` / `$$...$$` LaTeX in messages as math (off: show the source)
+    #[serde(default = "default_render_math")]
+    pub render_math: bool,
+    /// Show the send time under each message
+    #[serde(default = "default_show_timestamps")]
+    pub show_timestamps: bool,
+    /// Open thinking blocks when they appear instead of collapsing them
+    #[serde(default)]
+    pub expand_thinking: bool,
+    /// Show the messages the app adds for the model (reflection prompts,
+    /// summaries) in the transcript
+    #[serde(default)]
+    pub show_system_messages: bool,
+    /// Seconds a tool call may run before it is abandoned (1 - 3600), unless
+    /// the tool sets its own limit
+    #[serde(default = "default_tool_timeout_secs")]
+    pub tool_timeout_secs: u64,
+    /// Largest tool result handed to the model, in KB of JSON (4 - 1024);
+    /// bigger ones are cut down, keeping their head and tail
+    #[serde(default = "default_tool_output_max_kb")]
+    pub tool_output_max_kb: u32,
+}
+
+/// GPU layers setting meaning "offload every layer"; no model the app runs
+/// has more
+pub const MAX_GPU_LAYERS: u32 = 99;
+
+/// Smallest context window accepted in the settings, in tokens
+pub const MIN_CONTEXT_SIZE: u32 = 512;
+/// Largest context window accepted in the settings, in tokens
+pub const MAX_CONTEXT_SIZE: u32 = 131072;
+
+/// The built-in accent, rouge vin mat
+pub const DEFAULT_ACCENT_COLOR: &str = "#8B2635";
+
+fn default_accent_color() -> String {
+    DEFAULT_ACCENT_COLOR.to_string()
+}
+
+/// Chat typefaces offered in the appearance settings
+pub const FONT_FAMILIES: [&str; 4] = ["system", "sans", "serif", "mono"];
+
+fn default_font_family() -> String {
+    "system".to_string()
+}
+
+/// `#RRGGBB` (or `#RGB`, with or without the `#`) as uppercase `#RRGGBB`,
+/// `None` if it is not a hex color
+pub fn normalize_hex_color(input: &str) -> Option<String> {
+    let hex = input.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = match hex.len() {
+        6 => hex.to_string(),
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        _ => return None,
+    };
+    Some(format!("#{}", hex.to_ascii_uppercase()))
 }
 
 fn default_auto_load() -> bool {
@@ -68,11 +150,48 @@ fn default_openrouter_model() -> String {
     "openrouter/pony-alpha".to_string()
 }
 
+fn default_render_math() -> bool {
+    true
+}
+
+fn default_show_timestamps() -> bool {
+    true
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    120
+}
+
+fn default_tool_output_max_kb() -> u32 {
+    64
+}
+
+pub fn default_min_p() -> f32 {
+    0.05
+}
+
+pub fn default_repeat_penalty() -> f32 {
+    1.1
+}
+
+fn default_cpu_threads() -> u32 {
+    crate::system::resources::physical_cores() as u32
+}
+
 /// Default system prompt from code. Used on every app load so the prompt always matches the code.
 pub fn default_system_prompt() -> String {
     default_system_prompt_for_lang("fr")
 }
 
+/// The line telling the model which language to answer in
+pub fn response_language_instruction(lang: &str) -> &'static str {
+    if lang == "en" {
+        "Always respond in English."
+    } else {
+        "Always respond in French (toujours repondre en francais)."
+    }
+}
+
 /// Build system prompt for a specific language
 pub fn default_system_prompt_for_lang(lang: &str) -> String {
     let os_name = match std::env::consts::OS {
@@ -100,11 +219,7 @@ pub fn default_system_prompt_for_lang(lang: &str) -> String {
         "ls -la"
     };
 
-    let response_lang_instruction = if lang == "en" {
-        "Always respond in English."
-    } else {
-        "Always respond in French (toujours repondre en francais)."
-    };
+    let response_lang_instruction = response_language_instruction(lang);
 
     format!(
         r#"You are clawRS, a helpful AI assistant running locally on the user's machine.
@@ -184,16 +299,22 @@ impl Default for AppSettings {
             temperature: 0.7,
             top_p: 0.9,
             top_k: 40,
+            min_p: default_min_p(),
+            repeat_penalty: default_repeat_penalty(),
             max_tokens: 4096,    // 4K output - OK with 16K context
             context_size: 16384, // 16K context - user confirmed 36 tok/s in LM Studio with 16K on 8GB VRAM
             system_prompt: default_system_prompt(),
+            system_prompt_custom: false,
             gpu_layers: 99, // Offload all layers to GPU by default
+            cpu_threads: default_cpu_threads(),
             models_directory: get_data_dir()
                 .ok()
                 .map(|d| d.join("models"))
                 .unwrap_or_else(|| PathBuf::from("./models")),
             theme: "dark".to_string(),
             font_size: "medium".to_string(),
+            font_family: default_font_family(),
+            accent_color: default_accent_color(),
             exa_mcp_url: "https://mcp.exa.ai/mcp".to_string(),
             last_model_path: None,
             auto_load_model: true,
@@ -201,7 +322,14 @@ impl Default for AppSettings {
             auto_approve_all_tools: false,
             tool_allowlist: Vec::new(),
             disabled_mcp_servers: Vec::new(),
+            disabled_tools: Vec::new(),
             openrouter_model: default_openrouter_model(),
+            render_math: default_render_math(),
+            show_timestamps: default_show_timestamps(),
+            expand_thinking: false,
+            show_system_messages: false,
+            tool_timeout_secs: default_tool_timeout_secs(),
+            tool_output_max_kb: default_tool_output_max_kb(),
         }
     }
 }
@@ -214,21 +342,19 @@ impl AppSettings {
     pub fn validate(&mut self) {
         self.temperature = self.temperature.clamp(0.0, 2.0);
         self.top_p = self.top_p.clamp(0.0, 1.0);
+        self.min_p = self.min_p.clamp(0.0, 1.0);
+        self.repeat_penalty = self.repeat_penalty.clamp(1.0, 2.0);
 
         if self.top_k == 0 {
             self.top_k = 40;
         }
 
         self.max_tokens = self.max_tokens.clamp(1, 65536);
+        self.gpu_layers = self.gpu_layers.min(MAX_GPU_LAYERS);
 
-        // Valid context sizes
-        let valid_context_sizes = [2048, 4096, 8192, 16384, 32768, 65536, 131072];
-        if !valid_context_sizes.contains(&self.context_size) {
-            self.context_size = *valid_context_sizes
-                .iter()
-                .min_by_key(|&&size| (size as i64 - self.context_size as i64).abs())
-                .unwrap_or(&4096);
-        }
+        // Presets or any custom size, in steps of 256 tokens
+        self.context_size =
+            (self.context_size.clamp(MIN_CONTEXT_SIZE, MAX_CONTEXT_SIZE) / 256) * 256;
 
         // === VRAM-aware context cap ===
         // Prevent KV cache from overflowing dedicated VRAM.
@@ -252,10 +378,24 @@ impl AppSettings {
             self.theme = "dark".to_string();
         }
 
+        self.tool_timeout_secs = self.tool_timeout_secs.clamp(1, 3600);
+        self.tool_output_max_kb = self.tool_output_max_kb.clamp(4, 1024);
+
+        // More threads than hardware threads only adds contention
+        let max_threads = crate::system::resources::logical_cores() as u32;
+        self.cpu_threads = self.cpu_threads.clamp(1, max_threads.max(1));
+
         if !["small", "medium", "large"].contains(&self.font_size.as_str()) {
             self.font_size = "medium".to_string();
         }
 
+        if !FONT_FAMILIES.contains(&self.font_family.as_str()) {
+            self.font_family = default_font_family();
+        }
+
+        self.accent_color =
+            normalize_hex_color(&self.accent_color).unwrap_or_else(default_accent_color);
+
         if self.exa_mcp_url.trim().is_empty() {
             self.exa_mcp_url = "https://mcp.exa.ai/mcp".to_string();
         }
@@ -320,19 +460,81 @@ fn load_settings_internal() -> Result<AppSettings, StorageError> {
         return Ok(AppSettings::default());
     }
 
-    let json = fs::read_to_string(&path)?;
-    let mut settings: AppSettings = serde_json::from_str(&json)?;
+    let settings = parse_settings(&fs::read_to_string(&path)?)?;
+
+    tracing::debug!("Loaded settings from disk");
+    Ok(settings)
+}
+
+/// Settings from a JSON document, validated. Missing fields get their defaults.
+fn parse_settings(json: &str) -> Result<AppSettings, StorageError> {
+    let mut settings: AppSettings = serde_json::from_str(json)?;
 
-    // Always use system prompt from code so app reflects current version on reload
-    settings.system_prompt = default_system_prompt_for_lang(&settings.language);
+    // Use the system prompt from code so app reflects current version on reload,
+    // unless the user wrote their own
+    if !settings.system_prompt_custom || settings.system_prompt.trim().is_empty() {
+        settings.system_prompt = default_system_prompt_for_lang(&settings.language);
+        settings.system_prompt_custom = false;
+    }
 
-    // Validate loaded settings
     settings.validate();
-
-    tracing::debug!("Loaded settings from disk");
     Ok(settings)
 }
 
+/// Settings read back by [`import_settings`]
+#[derive(Debug, Clone)]
+pub struct ImportedSettings {
+    pub settings: AppSettings,
+    /// The file's models directory, when it does not exist on this machine.
+    /// The current one is kept instead.
+    pub missing_models_directory: Option<PathBuf>,
+}
+
+/// Write `settings` to `path` as JSON, to move them to another machine
+pub fn export_settings(settings: &AppSettings, path: &Path) -> Result<(), StorageError> {
+    fs::write(path, serde_json::to_string_pretty(settings)?)?;
+    tracing::info!("Exported settings to {}", path.display());
+    Ok(())
+}
+
+/// Read settings exported by [`export_settings`], validated against this
+/// machine. Nothing is saved: the caller applies them.
+///
+/// Tool approval (`auto_approve_all_tools`, `tool_allowlist`) is never
+/// imported: a file from elsewhere must not turn off the confirmation of
+/// shell commands and file changes, so this machine's choice is kept.
+pub fn import_settings(
+    path: &Path,
+    current: &AppSettings,
+) -> Result<ImportedSettings, StorageError> {
+    let mut settings = parse_settings(&fs::read_to_string(path)?)?;
+    settings.auto_approve_all_tools = current.auto_approve_all_tools;
+    settings.tool_allowlist = current.tool_allowlist.clone();
+
+    let missing_models_directory = if settings.models_directory.is_dir() {
+        None
+    } else {
+        Some(std::mem::replace(
+            &mut settings.models_directory,
+            current.models_directory.clone(),
+        ))
+    };
+    // The last model lives in the models directory of the other machine
+    if settings
+        .last_model_path
+        .as_deref()
+        .is_some_and(|model| !Path::new(model).is_file())
+    {
+        settings.last_model_path = current.last_model_path.clone();
+    }
+
+    tracing::info!("Imported settings from {}", path.display());
+    Ok(ImportedSettings {
+        settings,
+        missing_models_directory,
+    })
+}
+
 /// Save settings to disk
 pub fn save_settings(settings: &AppSettings) -> Result<(), StorageError> {
     let path = get_settings_path()?;
@@ -361,6 +563,7 @@ mod tests {
         assert_eq!(settings.top_k, 40);
         assert_eq!(settings.theme, "dark");
         assert_eq!(settings.font_size, "medium");
+        assert_eq!(settings.font_family, "system");
     }
 
     #[test]
@@ -390,6 +593,27 @@ mod tests {
         settings.font_size = "huge".to_string();
         settings.validate();
         assert_eq!(settings.font_size, "medium");
+
+        // Test invalid font family
+        settings.font_family = "comic".to_string();
+        settings.validate();
+        assert_eq!(settings.font_family, "system");
+
+        // Accent colors are normalized, invalid ones reset
+        settings.accent_color = "1a7".to_string();
+        settings.validate();
+        assert_eq!(settings.accent_color, "#11AA77");
+        settings.accent_color = "red".to_string();
+        settings.validate();
+        assert_eq!(settings.accent_color, DEFAULT_ACCENT_COLOR);
+
+        // Custom context sizes are kept, in steps of 256 tokens
+        settings.context_size = 3000;
+        settings.validate();
+        assert_eq!(settings.context_size, 2816);
+        settings.context_size = 10;
+        settings.validate();
+        assert_eq!(settings.context_size, MIN_CONTEXT_SIZE);
     }
 
     #[test]
@@ -417,4 +641,49 @@ mod tests {
         assert_eq!(settings.temperature, loaded.temperature);
         assert_eq!(settings.theme, loaded.theme);
     }
+
+    #[test]
+    fn test_settings_export_import() {
+        let dir = std::env::temp_dir().join(format!("clawrs-settings-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+
+        let exported = AppSettings {
+            temperature: 0.3,
+            gpu_layers: 500,
+            models_directory: dir.join("not-here"),
+            last_model_path: Some(dir.join("not-here/model.gguf").display().to_string()),
+            auto_approve_all_tools: true,
+            tool_allowlist: vec!["shell_exec".to_string()],
+            ..AppSettings::default()
+        };
+        export_settings(&exported, &path).unwrap();
+
+        let current = AppSettings {
+            models_directory: dir.clone(),
+            tool_allowlist: vec!["file_read".to_string()],
+            ..AppSettings::default()
+        };
+        let imported = import_settings(&path, &current).unwrap();
+        assert_eq!(imported.settings.temperature, 0.3);
+        assert_eq!(imported.settings.gpu_layers, MAX_GPU_LAYERS);
+        // Paths from another machine fall back to this one's
+        assert_eq!(
+            imported.missing_models_directory,
+            Some(dir.join("not-here"))
+        );
+        assert_eq!(imported.settings.models_directory, dir);
+        assert_eq!(imported.settings.last_model_path, None);
+        // Tool approval stays as it is on this machine
+        assert!(!imported.settings.auto_approve_all_tools);
+        assert_eq!(imported.settings.tool_allowlist, ["file_read"]);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            import_settings(&path, &current),
+            Err(StorageError::JsonError(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,24 @@
+//! System clipboard
+//!
+//! On Linux (X11) the clipboard content is served by the process that set
+//! it, so the handle is kept for the lifetime of the app instead of being
+//! dropped after each copy.
+
+use std::sync::{Mutex, OnceLock};
+
+use arboard::Clipboard;
+
+fn clipboard() -> &'static Mutex<Option<Clipboard>> {
+    static CLIPBOARD: OnceLock<Mutex<Option<Clipboard>>> = OnceLock::new();
+    CLIPBOARD.get_or_init(|| Mutex::new(None))
+}
+
+/// Put `text` on the system clipboard
+pub fn copy_text(text: &str) -> Result<(), String> {
+    let mut guard = clipboard().lock().map_err(|e| e.to_string())?;
+    if guard.is_none() {
+        *guard = Some(Clipboard::new().map_err(|e| e.to_string())?);
+    }
+    let clipboard = guard.as_mut().expect("clipboard was just opened");
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
@@ -1,17 +1,63 @@
 //! GPU detection and management
 //!
 //! Detects available GPUs and their capabilities for model acceleration.
+//! Each platform tries the source with the most detail first: nvidia-smi
+//! (NVIDIA, live VRAM usage), the amdgpu sysfs counters (AMD on Linux, live
+//! usage), system_profiler / sysctl (Metal on macOS, total only), then the
+//! PCI / WMI device list (name only). With none of them, inference runs on
+//! the CPU and the GPU is reported as not detected.
 
 use std::process::Command;
 
+/// Where the GPU information came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GpuBackend {
+    /// No GPU found: inference runs on the CPU
+    #[default]
+    None,
+    /// nvidia-smi
+    Nvidia,
+    /// /sys/class/drm/card*/device/mem_info_vram_*
+    Amd,
+    /// system_profiler or sysctl on macOS
+    Metal,
+    /// Device name from lspci or WMI, without VRAM usage
+    Generic,
+}
+
+impl GpuBackend {
+    /// Short label for the hardware settings
+    pub fn label(self) -> &'static str {
+        match self {
+            GpuBackend::None => "CPU",
+            GpuBackend::Nvidia => "NVIDIA (nvidia-smi)",
+            GpuBackend::Amd => "AMD (amdgpu)",
+            GpuBackend::Metal => "Apple Metal",
+            GpuBackend::Generic => "PCI",
+        }
+    }
+}
+
 /// GPU information
 #[derive(Debug, Clone, Default)]
 pub struct GpuInfo {
     pub name: String,
     pub vram_total_mb: u64,
     pub vram_used_mb: u64,
+    /// `vram_used_mb` is a live reading (NVIDIA, AMD), not a placeholder
     pub vram_usage_available: bool,
     pub is_available: bool,
+    pub backend: GpuBackend,
+}
+
+impl GpuInfo {
+    /// No GPU could be detected
+    fn not_detected() -> Self {
+        Self {
+            name: "GPU not detected".to_string(),
+            ..Self::default()
+        }
+    }
 }
 
 /// Get total dedicated VRAM in GB (returns 0.0 if detection fails)
@@ -24,6 +70,12 @@ pub fn get_total_vram_gb() -> Option<f64> {
     }
 }
 
+/// VRAM currently in use in MB, where the backend reports it live
+pub fn vram_used_mb() -> Option<u64> {
+    let gpu = detect_gpu();
+    gpu.vram_usage_available.then_some(gpu.vram_used_mb)
+}
+
 /// Detect available GPU (best effort)
 pub fn detect_gpu() -> GpuInfo {
     #[cfg(target_os = "windows")]
@@ -43,13 +95,7 @@ pub fn detect_gpu() -> GpuInfo {
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        GpuInfo {
-            name: "GPU not detected".to_string(),
-            vram_total_mb: 0,
-            vram_used_mb: 0,
-            vram_usage_available: false,
-            is_available: false,
-        }
+        GpuInfo::not_detected()
     }
 }
 
@@ -69,13 +115,7 @@ fn detect_gpu_macos() -> GpuInfo {
         return info;
     }
 
-    GpuInfo {
-        name: "GPU not detected".to_string(),
-        vram_total_mb: 0,
-        vram_used_mb: 0,
-        vram_usage_available: false,
-        is_available: false,
-    }
+    GpuInfo::not_detected()
 }
 
 /// Detect GPU using system_profiler SPDisplaysDataType
@@ -136,6 +176,7 @@ fn detect_gpu_system_profiler() -> Option<GpuInfo> {
         vram_used_mb: 0,
         vram_usage_available: false,
         is_available: true,
+        backend: GpuBackend::Metal,
     })
 }
 
@@ -165,6 +206,7 @@ fn detect_gpu_apple_silicon() -> Option<GpuInfo> {
         vram_used_mb: 0,
         vram_usage_available: false,
         is_available: true,
+        backend: GpuBackend::Metal,
     })
 }
 
@@ -192,7 +234,15 @@ fn get_macos_total_ram_mb() -> Option<u64> {
 #[cfg(target_os = "linux")]
 fn detect_gpu_linux() -> GpuInfo {
     // Try nvidia-smi first (NVIDIA GPUs with full VRAM info)
-    if let Some(info) = detect_gpu_nvidia_smi_linux() {
+    if let Some(info) = detect_gpu_nvidia_smi() {
+        return info;
+    }
+
+    // AMD GPUs expose their VRAM counters through the amdgpu driver
+    if let Some(mut info) = detect_gpu_amdgpu_sysfs() {
+        if let Some(pci) = detect_gpu_lspci() {
+            info.name = pci.name;
+        }
         return info;
     }
 
@@ -201,50 +251,49 @@ fn detect_gpu_linux() -> GpuInfo {
         return info;
     }
 
-    GpuInfo {
-        name: "GPU not detected".to_string(),
-        vram_total_mb: 0,
-        vram_used_mb: 0,
-        vram_usage_available: false,
-        is_available: false,
-    }
+    GpuInfo::not_detected()
 }
 
-/// Detect NVIDIA GPU via nvidia-smi (available on all distros with NVIDIA drivers)
+/// Read VRAM total and usage of the first amdgpu card from sysfs
 #[cfg(target_os = "linux")]
-fn detect_gpu_nvidia_smi_linux() -> Option<GpuInfo> {
-    let output = Command::new("nvidia-smi")
-        .args([
-            "--query-gpu=name,memory.total,memory.used",
-            "--format=csv,noheader,nounits",
-        ])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let line = stdout.lines().find(|l| !l.trim().is_empty())?;
-    let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
-    if parts.len() < 3 {
-        return None;
-    }
-
-    let name = parts[0].to_string();
-    let vram_total_mb = parts[1].parse::<u64>().ok()?;
-    let vram_used_mb = parts[2].parse::<u64>().ok()?;
-
-    Some(GpuInfo {
-        name,
-        vram_total_mb,
-        vram_used_mb,
-        vram_usage_available: true,
-        is_available: true,
+fn detect_gpu_amdgpu_sysfs() -> Option<GpuInfo> {
+    let mut cards: Vec<_> = std::fs::read_dir("/sys/class/drm")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("card") && !n.contains('-'))
+        })
+        .collect();
+    cards.sort();
+
+    cards.into_iter().find_map(|card| {
+        let device = card.join("device");
+        let read = |file: &str| std::fs::read_to_string(device.join(file)).ok();
+        let (total, used) =
+            parse_amdgpu_vram(&read("mem_info_vram_total")?, &read("mem_info_vram_used")?)?;
+        Some(GpuInfo {
+            name: "AMD GPU".to_string(),
+            vram_total_mb: total,
+            vram_used_mb: used,
+            vram_usage_available: true,
+            is_available: true,
+            backend: GpuBackend::Amd,
+        })
     })
 }
 
+/// `(total_mb, used_mb)` from the byte counts in amdgpu's
+/// `mem_info_vram_total` and `mem_info_vram_used`
+#[cfg(any(target_os = "linux", test))]
+fn parse_amdgpu_vram(total: &str, used: &str) -> Option<(u64, u64)> {
+    let total = total.trim().parse::<u64>().ok()? / 1024 / 1024;
+    let used = used.trim().parse::<u64>().ok()? / 1024 / 1024;
+    (total > 0).then_some((total, used))
+}
+
 /// Detect GPU via lspci (works on Ubuntu, Fedora, Mint, Arch, etc.)
 #[cfg(target_os = "linux")]
 fn detect_gpu_lspci() -> Option<GpuInfo> {
@@ -276,6 +325,7 @@ fn detect_gpu_lspci() -> Option<GpuInfo> {
                     vram_used_mb: 0,
                     vram_usage_available: false,
                     is_available: true,
+                    backend: GpuBackend::Generic,
                 });
             }
         }
@@ -298,16 +348,15 @@ fn detect_gpu_windows() -> GpuInfo {
         return info;
     }
 
-    GpuInfo {
-        name: "GPU not detected".to_string(),
-        vram_total_mb: 0,
-        vram_used_mb: 0,
-        vram_usage_available: false,
-        is_available: false,
-    }
+    GpuInfo::not_detected()
 }
 
-#[cfg(target_os = "windows")]
+// =============================================================================
+// NVIDIA (Linux and Windows)
+// =============================================================================
+
+/// Detect an NVIDIA GPU via nvidia-smi, installed with the NVIDIA drivers
+#[cfg(any(target_os = "linux", target_os = "windows"))]
 fn detect_gpu_nvidia_smi() -> Option<GpuInfo> {
     let output = Command::new("nvidia-smi")
         .args([
@@ -321,7 +370,13 @@ fn detect_gpu_nvidia_smi() -> Option<GpuInfo> {
         return None;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the first GPU of `nvidia-smi --query-gpu=name,memory.total,memory.used
+/// --format=csv,noheader,nounits`
+#[cfg(any(target_os = "linux", target_os = "windows", test))]
+fn parse_nvidia_smi(stdout: &str) -> Option<GpuInfo> {
     let line = stdout.lines().find(|l| !l.trim().is_empty())?;
     let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
     if parts.len() < 3 {
@@ -338,6 +393,7 @@ fn detect_gpu_nvidia_smi() -> Option<GpuInfo> {
         vram_used_mb,
         vram_usage_available: true,
         is_available: true,
+        backend: GpuBackend::Nvidia,
     })
 }
 
@@ -390,5 +446,28 @@ fn detect_gpu_wmic() -> Option<GpuInfo> {
         vram_used_mb: 0,
         vram_usage_available: false,
         is_available: true,
+        backend: GpuBackend::Generic,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vram_readings() {
+        let gpu = parse_nvidia_smi("NVIDIA GeForce RTX 3060, 12288, 1536\n").unwrap();
+        assert_eq!(gpu.name, "NVIDIA GeForce RTX 3060");
+        assert_eq!((gpu.vram_total_mb, gpu.vram_used_mb), (12288, 1536));
+        assert!(gpu.vram_usage_available);
+        assert_eq!(gpu.backend, GpuBackend::Nvidia);
+        // Driver error text instead of CSV
+        assert!(parse_nvidia_smi("NVIDIA-SMI has failed\n").is_none());
+
+        assert_eq!(
+            parse_amdgpu_vram("17163091968\n", "1073741824\n"),
+            Some((16368, 1024))
+        );
+        assert_eq!(parse_amdgpu_vram("0\n", "0\n"), None);
+    }
+}
@@ -1,6 +1,7 @@
 //! System utilities
 //!
-//! This module provides system-level functionality like GPU detection and resource monitoring.
+//! This module provides system-level functionality like GPU detection, resource monitoring and the clipboard.
 
+pub mod clipboard;
 pub mod gpu;
 pub mod resources;
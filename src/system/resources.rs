@@ -38,6 +38,76 @@ pub fn get_resource_usage() -> ResourceUsage {
     }
 }
 
+// =============================================================================
+// CPU cores
+// =============================================================================
+
+/// Logical CPUs (hardware threads) available to the process
+pub fn logical_cores() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Physical CPU cores, the best default for inference threads since
+/// hyperthreads share the same execution units. Falls back to half the
+/// logical CPUs when the platform query fails.
+pub fn physical_cores() -> usize {
+    #[cfg(target_os = "linux")]
+    let detected = fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|cpuinfo| parse_cpuinfo_cores(&cpuinfo));
+
+    #[cfg(target_os = "macos")]
+    let detected = Command::new("sysctl")
+        .args(["-n", "hw.physicalcpu"])
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8_lossy(&out.stdout).trim().parse().ok());
+
+    #[cfg(target_os = "windows")]
+    let detected = Command::new("wmic")
+        .args(["cpu", "get", "NumberOfCores", "/value"])
+        .output()
+        .ok()
+        .map(|out| {
+            String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("NumberOfCores="))
+                .filter_map(|n| n.trim().parse::<usize>().ok())
+                .sum()
+        });
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    let detected: Option<usize> = None;
+
+    match detected {
+        Some(cores) if cores > 0 => cores,
+        _ => (logical_cores() / 2).max(1),
+    }
+}
+
+/// Count distinct `(physical id, core id)` pairs in /proc/cpuinfo
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpuinfo_cores(cpuinfo: &str) -> Option<usize> {
+    let mut cores = std::collections::HashSet::new();
+    let mut physical_id = "";
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "physical id" => physical_id = value.trim(),
+            "core id" => {
+                cores.insert((physical_id, value.trim()));
+            }
+            _ => {}
+        }
+    }
+    // Some ARM kernels list no core ids at all
+    (!cores.is_empty()).then_some(cores.len())
+}
+
 // =============================================================================
 // macOS resource monitoring
 // =============================================================================
@@ -215,3 +285,26 @@ fn get_resource_usage_windows() -> ResourceUsage {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpuinfo_cores() {
+        // Two sockets, two cores each, hyperthreaded
+        let mut cpuinfo = String::new();
+        for (physical, core) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            for _ in 0..2 {
+                cpuinfo.push_str(&format!(
+                    "processor\t: 0\nphysical id\t: {physical}\ncore id\t\t: {core}\n\n"
+                ));
+            }
+        }
+        assert_eq!(parse_cpuinfo_cores(&cpuinfo), Some(4));
+        assert_eq!(
+            parse_cpuinfo_cores("processor\t: 0\nBogoMIPS\t: 48.00\n"),
+            None
+        );
+        assert!(physical_cores() >= 1);
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Defines chat message structures and roles.
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
 
 /// Role of a message sender
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -13,62 +15,145 @@ pub enum Role {
     Assistant,
     /// System prompt
     System,
+    /// A tool call and its result, recorded for the transcript
+    /// (the content is a serialized [`ToolCallRecord`])
+    Tool,
 }
 
 /// A single chat message
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Message {
+    /// Stable identifier, for editing a message or linking to it. Messages
+    /// saved before this existed get a new one when loaded.
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
     /// The role of the message sender
     pub role: Role,
     /// The content of the message
     pub content: String,
-    /// Timestamp when the message was created
-    pub timestamp: u64,
+    /// When the message was created. Conversations saved before this was a
+    /// date stored Unix seconds, and older ones nothing at all.
+    #[serde(default = "Utc::now", deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
 }
 
 impl Message {
     /// Create a new message
     pub fn new(role: Role, content: impl Into<String>) -> Self {
         Self {
+            id: Uuid::new_v4(),
             role,
             content: content.into(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
+            timestamp: Utc::now(),
         }
     }
 }
 
-/// Clean thinking tags from content for display
-/// This is a safety measure to prevent thinking from appearing to users
-pub fn clean_thinking_tags(content: &str) -> String {
-    let mut result = content.to_string();
-
-    // Remove thinking tags
-    let thinking_patterns = [
-        "<think>",
-        "</thinking>",
-        "<think>",
-        "</think>",
-        "<thinking>",
-        "</thinking>",
-        "<thinking>",
-        "</think>",
-    ];
-
-    for pattern in &thinking_patterns {
-        result = result.replace(pattern, "");
+/// Accept an RFC 3339 date or legacy Unix seconds
+fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Date(DateTime<Utc>),
+        UnixSeconds(i64),
+    }
+
+    match Timestamp::deserialize(deserializer)? {
+        Timestamp::Date(date) => Ok(date),
+        Timestamp::UnixSeconds(secs) => Utc
+            .timestamp_opt(secs, 0)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp {}", secs))),
     }
+}
+
+/// What the agent called and what came back, stored as the content of a
+/// [`Role::Tool`] message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// Name of the tool
+    pub tool: String,
+    /// Parameters the tool was called with
+    pub params: serde_json::Value,
+    /// Whether the call succeeded
+    pub success: bool,
+    /// Tool output (`ToolResult::message`) or error text
+    pub result: String,
+    /// How long the call took
+    pub duration_ms: u64,
+    /// Structured part of the output worth showing as is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ToolOutput>,
+}
+
+/// Longest match list kept in a [`ToolCallRecord`]
+const MAX_RECORDED_MATCHES: usize = 200;
 
-    // Also remove XML-style thinking
-    let xml_patterns = ["<think>", "</reflexion>", "<réflexion>", "</réflexion>"];
+/// Output of a tool call shown in its own form in the transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ToolOutput {
+    /// Unified diff of a file change (`file_edit`)
+    Diff(String),
+    /// Lines found by a search (`file_search`)
+    Matches {
+        matches: Vec<SearchMatch>,
+        /// Matches found, including those not kept
+        total: usize,
+    },
+}
+
+/// One line found by a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub file: String,
+    pub line_number: u64,
+    pub content: String,
+}
+
+impl ToolOutput {
+    /// The diff or the matches in a tool's `ToolResult::data`, if any
+    pub fn from_data(data: &serde_json::Value) -> Option<Self> {
+        if let Some(diff) = data["diff"].as_str() {
+            return Some(ToolOutput::Diff(diff.to_string()));
+        }
+        let found = data["matches"].as_array()?;
+        let matches: Vec<SearchMatch> = found
+            .iter()
+            .take(MAX_RECORDED_MATCHES)
+            .filter_map(|m| serde_json::from_value(m.clone()).ok())
+            .collect();
+        if matches.is_empty() {
+            return None;
+        }
+        Some(ToolOutput::Matches {
+            matches,
+            total: found.len(),
+        })
+    }
+}
 
-    for pattern in &xml_patterns {
-        result = result.replace(pattern, "");
+impl ToolCallRecord {
+    /// Message content for this record
+    pub fn to_content(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
     }
 
-    result.trim().to_string()
+    /// Parse the content of a [`Role::Tool`] message
+    pub fn from_content(content: &str) -> Option<Self> {
+        serde_json::from_str(content).ok()
+    }
+}
+
+/// Content without the model's reasoning, for places that show only the
+/// answer. See [`strip_reasoning`](crate::inference::reasoning::strip_reasoning).
+pub fn clean_thinking_tags(content: &str) -> String {
+    use crate::inference::reasoning::{default_reasoning_tags, strip_reasoning};
+
+    strip_reasoning(content, &default_reasoning_tags())
+        .trim()
+        .to_string()
 }
 
 #[cfg(test)]
@@ -80,7 +165,74 @@ mod tests {
         let msg = Message::new(Role::User, "Hello, world!");
         assert_eq!(msg.role, Role::User);
         assert_eq!(msg.content, "Hello, world!");
-        assert!(msg.timestamp > 0);
+        assert!(msg.timestamp.timestamp() > 0);
+    }
+
+    #[test]
+    fn test_legacy_timestamps_deserialize() {
+        let secs: Message =
+            serde_json::from_str(r#"{"role":"User","content":"hi","timestamp":1700000000}"#)
+                .unwrap();
+        assert_eq!(secs.timestamp.timestamp(), 1_700_000_000);
+
+        let missing: Message =
+            serde_json::from_str(r#"{"role":"Assistant","content":"hello"}"#).unwrap();
+        assert!((Utc::now() - missing.timestamp).num_seconds() < 5);
+        // Legacy messages get an id, kept from then on
+        assert!(!missing.id.is_nil());
+        let saved: Message =
+            serde_json::from_str(&serde_json::to_string(&missing).unwrap()).unwrap();
+        assert_eq!(saved.id, missing.id);
+
+        let round_trip: Message =
+            serde_json::from_str(&serde_json::to_string(&secs).unwrap()).unwrap();
+        assert_eq!(round_trip, secs);
+    }
+
+    #[test]
+    fn test_tool_call_record_round_trip() {
+        let record = ToolCallRecord {
+            tool: "file_read".to_string(),
+            params: serde_json::json!({ "path": "src/main.rs" }),
+            success: true,
+            result: "42 lines".to_string(),
+            duration_ms: 12,
+            output: None,
+        };
+        let msg = Message::new(Role::Tool, record.to_content());
+        let stored: Message = serde_json::from_str(&serde_json::to_string(&msg).unwrap()).unwrap();
+        assert_eq!(stored.role, Role::Tool);
+        assert_eq!(ToolCallRecord::from_content(&stored.content), Some(record));
+        assert_eq!(ToolCallRecord::from_content("plain text"), None);
+    }
+
+    #[test]
+    fn test_tool_output_from_data() {
+        let edit = serde_json::json!({ "path": "a.rs", "diff": "--- a.rs\n+++ a.rs\n" });
+        assert_eq!(
+            ToolOutput::from_data(&edit),
+            Some(ToolOutput::Diff("--- a.rs\n+++ a.rs\n".to_string()))
+        );
+
+        let search = serde_json::json!({
+            "matches": [
+                { "file": "src/lib.rs", "line_number": 3, "content": "pub mod app;" },
+                { "unexpected": true }
+            ],
+            "total": 2
+        });
+        let Some(ToolOutput::Matches { matches, total }) = ToolOutput::from_data(&search) else {
+            panic!("no matches");
+        };
+        assert_eq!(total, 2);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 3);
+
+        assert_eq!(
+            ToolOutput::from_data(&serde_json::json!({ "matches": [] })),
+            None
+        );
+        assert_eq!(ToolOutput::from_data(&serde_json::json!("text")), None);
     }
 
     #[test]
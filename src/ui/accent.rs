@@ -0,0 +1,121 @@
+//! User-chosen accent color
+//!
+//! The stylesheet derives every accent shade from `--accent-rgb` and
+//! `--accent-primary`, so picking a color only overrides a handful of CSS
+//! variables on the theme wrapper. Text drawn on the accent (`--accent-text`)
+//! switches between the light and dark ink, whichever contrasts more.
+
+use crate::storage::settings::{normalize_hex_color, DEFAULT_ACCENT_COLOR};
+
+/// Swatches offered in the appearance settings: color, name (en, fr)
+pub const ACCENT_PRESETS: &[(&str, &str, &str)] = &[
+    (DEFAULT_ACCENT_COLOR, "Wine", "Vin"),
+    ("#B33A0B", "Rust", "Rouille"),
+    ("#B7791F", "Ochre", "Ocre"),
+    ("#3F6212", "Olive", "Olive"),
+    ("#2A6B7C", "Teal", "Canard"),
+    ("#3B5BDB", "Blue", "Bleu"),
+    ("#7048E8", "Violet", "Violet"),
+    ("#5C5650", "Graphite", "Graphite"),
+];
+
+/// Light ink, the theme's `--text-primary` in dark mode
+const LIGHT_TEXT: (u8, u8, u8) = (0xF2, 0xED, 0xE7);
+/// Dark ink, the theme's `--text-inverse` in dark mode
+const DARK_TEXT: (u8, u8, u8) = (0x17, 0x16, 0x14);
+
+/// Inline CSS overriding the accent variables, empty for the built-in accent
+/// (its hand-tuned hover shades stay in the stylesheet)
+pub fn accent_style(accent: &str) -> String {
+    let Some(hex) = normalize_hex_color(accent) else {
+        return String::new();
+    };
+    if hex == DEFAULT_ACCENT_COLOR {
+        return String::new();
+    }
+    let rgb = parse_rgb(&hex);
+    let hover = to_hex(mix(rgb, (255, 255, 255), 0.2));
+    format!(
+        "--accent-rgb: {}, {}, {}; --accent-primary: {hex}; --accent-gradient: {hex}; \
+         --accent-secondary: {hover}; --accent-tertiary: {hover}; --accent-gradient-hover: {hover}; \
+         --accent-text: {};",
+        rgb.0,
+        rgb.1,
+        rgb.2,
+        to_hex(text_on(rgb)),
+    )
+}
+
+/// The ink that reads best on `background`
+pub fn text_on(background: (u8, u8, u8)) -> (u8, u8, u8) {
+    if contrast(background, LIGHT_TEXT) >= contrast(background, DARK_TEXT) {
+        LIGHT_TEXT
+    } else {
+        DARK_TEXT
+    }
+}
+
+/// Channels of a normalized `#RRGGBB` color
+pub fn parse_rgb(hex: &str) -> (u8, u8, u8) {
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    (channel(1), channel(3), channel(5))
+}
+
+fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+fn mix(from: (u8, u8, u8), to: (u8, u8, u8), amount: f32) -> (u8, u8, u8) {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    (
+        channel(from.0, to.0),
+        channel(from.1, to.1),
+        channel(from.2, to.2),
+    )
+}
+
+/// WCAG relative luminance
+fn luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    let linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// WCAG contrast ratio, from 1 to 21
+fn contrast(a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accent_style() {
+        // The built-in accent keeps the stylesheet's shades
+        assert_eq!(accent_style(DEFAULT_ACCENT_COLOR), "");
+        assert_eq!(accent_style("not a color"), "");
+
+        let style = accent_style("#3b5bdb");
+        assert!(style.contains("--accent-rgb: 59, 91, 219;"));
+        assert!(style.contains("--accent-primary: #3B5BDB;"));
+        assert!(style.contains("--accent-text: #F2EDE7;"));
+
+        // Light accents get dark text
+        assert!(accent_style("#FFE066").contains("--accent-text: #171614;"));
+        for (hex, _, _) in ACCENT_PRESETS {
+            assert!(
+                contrast(parse_rgb(hex), text_on(parse_rgb(hex))) >= 4.5,
+                "{}",
+                hex
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Context usage indicator under the chat input
+//!
+//! Counts what the next prompt will hold (system prompt plus conversation)
+//! with the loaded model's tokenizer, against the configured context size.
+//! The count is refreshed when the conversation settles, not on every
+//! streamed token.
+
+use dioxus::prelude::*;
+
+use super::ARCHIVED_THRESHOLD;
+use crate::app::AppState;
+use crate::types::message::{Message as StorageMessage, Role as StorageRole};
+
+/// Room the engine keeps for an answer; a prompt leaving less is truncated
+const MIN_REPLY_TOKENS: usize = 256;
+
+/// How full the context is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContextLevel {
+    Normal,
+    /// Past the point where older messages get compressed
+    High,
+    /// The next answer would not fit
+    Overflow,
+}
+
+pub fn context_level(tokens: usize, context_size: usize) -> ContextLevel {
+    if tokens + MIN_REPLY_TOKENS > context_size {
+        ContextLevel::Overflow
+    } else if tokens as f32 >= context_size as f32 * ARCHIVED_THRESHOLD {
+        ContextLevel::High
+    } else {
+        ContextLevel::Normal
+    }
+}
+
+/// `950`, `3.2k`, `16k`
+fn format_tokens(tokens: usize) -> String {
+    if tokens < 1000 {
+        tokens.to_string()
+    } else if tokens < 10_000 && !tokens.is_multiple_of(1000) {
+        format!("{:.1}k", tokens as f32 / 1000.0)
+    } else {
+        format!("{}k", (tokens as f32 / 1000.0).round())
+    }
+}
+
+#[component]
+pub fn ContextMeter() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let context_size = app_state.settings.read().context_size as usize;
+    let mut tokens = use_signal(|| None::<usize>);
+    // Only the latest count is shown when several are in flight
+    let mut request = use_signal(|| 0u64);
+
+    let app_state_count = app_state.clone();
+    use_effect(move || {
+        let app_state = app_state_count.clone();
+        if *app_state.is_generating.read() {
+            return;
+        }
+        // Recount after a run, on conversation switch, and when the prompt
+        // or the model changes
+        let _ = app_state.model_state.read();
+        let mut history = vec![StorageMessage::new(
+            StorageRole::System,
+            app_state.settings.read().system_prompt.clone(),
+        )];
+        history.extend(
+            app_state
+                .active_messages
+                .read()
+                .iter()
+                .cloned()
+                .map(Into::into),
+        );
+
+        let id = *request.peek() + 1;
+        request.set(id);
+        spawn(async move {
+            let count = app_state.count_tokens(&history).await;
+            if *request.peek() == id {
+                tokens.set(Some(count));
+            }
+        });
+    });
+
+    let Some(tokens) = tokens() else {
+        return rsx! {};
+    };
+    let level = context_level(tokens, context_size);
+    let color = match level {
+        ContextLevel::Normal => "var(--text-tertiary)",
+        ContextLevel::High => "var(--warning)",
+        ContextLevel::Overflow => "var(--error)",
+    };
+    let title = match (level, is_en) {
+        (ContextLevel::Overflow, true) => {
+            "The next answer does not fit: older messages will be compressed or cut"
+        }
+        (ContextLevel::Overflow, false) => {
+            "La prochaine réponse ne tient pas : les anciens messages seront compressés ou coupés"
+        }
+        (_, true) => "Tokens in the next prompt / context size",
+        (_, false) => "Tokens du prochain prompt / taille du contexte",
+    };
+
+    rsx! {
+        span {
+            class: "flex-shrink-0 text-[11px] font-mono",
+            style: "color: {color};",
+            title: "{title}",
+            if level == ContextLevel::Overflow { "⚠ " }
+            "{format_tokens(tokens)} / {format_tokens(context_size)}"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_level() {
+        assert_eq!(context_level(1000, 16384), ContextLevel::Normal);
+        assert_eq!(context_level(14000, 16384), ContextLevel::High);
+        assert_eq!(context_level(16300, 16384), ContextLevel::Overflow);
+        assert_eq!(format_tokens(950), "950");
+        assert_eq!(format_tokens(3240), "3.2k");
+        assert_eq!(format_tokens(16384), "16k");
+        assert_eq!(format_tokens(8000), "8k");
+    }
+}
@@ -0,0 +1,72 @@
+//! Syntax highlighting for fenced code blocks
+//!
+//! The fence's language hint (```` ```rust ````, ```` ```py ````) picks a
+//! syntax from syntect's bundled set, matched by name or file extension.
+//! Colors come from one theme per app theme and are inlined as `style`
+//! attributes, so no stylesheet has to follow the theme switch. All code is
+//! escaped, so the output is safe to inject as HTML.
+
+use std::sync::OnceLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Longer blocks are shown plain: highlighting runs again on every
+/// streamed token and would stall the UI
+const MAX_HIGHLIGHT_BYTES: usize = 64 * 1024;
+
+const DARK_THEME: &str = "base16-eighties.dark";
+const LIGHT_THEME: &str = "InspiredGitHub";
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme(dark: bool) -> &'static Theme {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    let themes = THEMES.get_or_init(ThemeSet::load_defaults);
+    &themes.themes[if dark { DARK_THEME } else { LIGHT_THEME }]
+}
+
+/// `code` as colored HTML spans, or `None` when the language is unknown
+/// (or missing) and the block should be shown as plain text.
+pub fn highlight_code(code: &str, lang: &str, dark: bool) -> Option<String> {
+    let lang = lang.trim();
+    if lang.is_empty() || code.len() > MAX_HIGHLIGHT_BYTES {
+        return None;
+    }
+    let syntaxes = syntaxes();
+    let syntax = syntaxes.find_syntax_by_token(lang)?;
+    let mut highlighter = HighlightLines::new(syntax, theme(dark));
+
+    let mut html = String::with_capacity(code.len() * 2);
+    for line in LinesWithEndings::from(code) {
+        let regions = highlighter.highlight_line(line, syntaxes).ok()?;
+        html.push_str(&styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()?);
+    }
+    Some(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_code() {
+        let html = highlight_code("fn main() { let x = \"<b>\"; }", "rust", true).unwrap();
+        assert!(html.contains("<span style=\"color:"));
+        // Code is escaped, never injected
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(!html.contains("<b>"));
+
+        // Extensions work as hints too
+        assert!(highlight_code("x = 1", "py", false).is_some());
+        // Unknown or missing hints fall back to plain text
+        assert!(highlight_code("x", "", true).is_none());
+        assert!(highlight_code("x", "not-a-language", true).is_none());
+    }
+}
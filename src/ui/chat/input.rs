@@ -3,6 +3,7 @@
 use crate::app::AppState;
 use crate::agent::skills::loader::SkillLoader;
 use crate::agent::skills::Skill;
+use crate::ui::chat::context_meter::ContextMeter;
 use dioxus::prelude::*;
 
 /// Estimate how many rows the textarea needs based on content
@@ -31,6 +32,16 @@ pub fn ChatInput(
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
 
+    // Take text handed over by "Edit" on a message
+    let mut input_draft = app_state.input_draft;
+    use_effect(move || {
+        let draft = input_draft();
+        if !draft.is_empty() {
+            text.set(draft);
+            input_draft.set(String::new());
+        }
+    });
+
     // Load skills on mount
     use_effect(move || {
         spawn(async move {
@@ -149,7 +160,7 @@ pub fn ChatInput(
 
     let mb = if is_multiline { " margin-bottom: 8px;" } else { "" };
     let send_style = if can_send {
-        format!("background: var(--accent-primary); color: #F2EDE7; box-shadow: 0 2px 8px -2px rgba(42,107,124,0.3);{mb}")
+        format!("background: var(--accent-primary); color: var(--accent-text); box-shadow: 0 2px 8px -2px rgba(42,107,124,0.3);{mb}")
     } else {
         format!("background: var(--bg-elevated);{mb}")
     };
@@ -230,7 +241,7 @@ pub fn ChatInput(
 
                     // Textarea — auto-expanding
                     textarea {
-                        class: "flex-1 bg-transparent outline-none text-[var(--text-primary)] resize-none placeholder-[var(--text-tertiary)] text-[15px] custom-scrollbar",
+                        class: "flex-1 bg-transparent outline-none text-[var(--text-primary)] resize-none placeholder-[var(--text-tertiary)] text-chat custom-scrollbar",
                         style: "{textarea_style}",
                         placeholder: "{placeholder}",
                         value: "{text}",
@@ -283,10 +294,13 @@ pub fn ChatInput(
                     }
                 }
 
-                // Hint text
-                p {
-                    class: "text-center text-[11px] text-[var(--text-tertiary)] mt-2 opacity-40",
-                    "{hint}"
+                // Hint text and context usage
+                div { class: "flex items-center justify-between gap-3 mt-2 px-2",
+                    p {
+                        class: "text-[11px] text-[var(--text-tertiary)] opacity-40",
+                        "{hint}"
+                    }
+                    ContextMeter {}
                 }
             }
         }
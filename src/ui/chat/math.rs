@@ -0,0 +1,827 @@
+//! LaTeX to MathML for the math in messages
+//!
+//! Models answering technical questions write `$...$` and `$$...$$` LaTeX.
+//! The webview renders MathML natively, so instead of loading a JavaScript
+//! math library the LaTeX is converted here. The converter covers what models
+//! actually write: fractions, roots, scripts, Greek letters and operators,
+//! `\text`, font commands, accents, `\left`/`\right` and matrix-like
+//! environments. An unknown command is shown as its name rather than dropped.
+//! All text is escaped, so the output is safe to inject as HTML.
+
+/// `latex` as a `<math>` element, displayed as a block or inline.
+pub fn latex_to_mathml(latex: &str, display: bool) -> String {
+    let mut parser = Parser {
+        tokens: tokenize(latex),
+        pos: 0,
+        display,
+    };
+    let mut body = String::new();
+    // Stray closers (an unbalanced `}`, `\end` or `\right`) are skipped
+    loop {
+        let (row, end) = parser.parse_seq(None);
+        body.push_str(&row);
+        match end {
+            End::Eof => break,
+            End::Right => {
+                parser.parse_atom();
+            }
+            _ => {}
+        }
+    }
+    if display {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><mrow>{}</mrow></math>",
+            body
+        )
+    } else {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow>{}</mrow></math>",
+            body
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// `\name`, or `\` followed by one non-letter (`\{`, `\,`, `\\`...)
+    Command(String),
+    /// Argument of a command that takes raw text (`\text{...}`, `\mathrm{...}`)
+    Text(String, String),
+    /// `\begin{name}` (true) or `\end{name}` (false)
+    Env(bool, String),
+    Open,
+    Close,
+    Sup,
+    Sub,
+    Align,
+    Number(String),
+    Letter(char),
+    Symbol(char),
+}
+
+/// Commands whose braced argument is read as raw text
+const TEXT_COMMANDS: &[&str] = &[
+    "text",
+    "textrm",
+    "textbf",
+    "textit",
+    "mbox",
+    "mathrm",
+    "operatorname",
+    "mathbb",
+    "mathcal",
+    "mathfrak",
+    "mathscr",
+];
+
+fn tokenize(latex: &str) -> Vec<Token> {
+    let chars: Vec<char> = latex.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else {
+                    break;
+                };
+                if !next.is_ascii_alphabetic() {
+                    tokens.push(Token::Command(next.to_string()));
+                    i += 1;
+                    continue;
+                }
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                let takes_raw =
+                    TEXT_COMMANDS.contains(&name.as_str()) || name == "begin" || name == "end";
+                if takes_raw {
+                    let mut j = i;
+                    while j < chars.len() && chars[j].is_whitespace() {
+                        j += 1;
+                    }
+                    if chars.get(j) == Some(&'{') {
+                        let (raw, next) = read_group(&chars, j + 1);
+                        i = next;
+                        tokens.push(match name.as_str() {
+                            "begin" => Token::Env(true, raw.trim().to_string()),
+                            "end" => Token::Env(false, raw.trim().to_string()),
+                            _ => Token::Text(name, raw),
+                        });
+                        continue;
+                    }
+                }
+                tokens.push(Token::Command(name));
+            }
+            '{' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            '}' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            '^' => {
+                tokens.push(Token::Sup);
+                i += 1;
+            }
+            '_' => {
+                tokens.push(Token::Sub);
+                i += 1;
+            }
+            '&' => {
+                tokens.push(Token::Align);
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || (chars[i] == '.'
+                            && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())))
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Number(chars[start..i].iter().collect()));
+            }
+            c if c.is_alphabetic() => {
+                tokens.push(Token::Letter(c));
+                i += 1;
+            }
+            c => {
+                tokens.push(Token::Symbol(c));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+/// Contents of a `{...}` group starting after its `{`, and the index after
+/// its closing `}`
+fn read_group(chars: &[char], start: usize) -> (String, usize) {
+    let mut depth = 0;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return (chars[start..i].iter().collect(), i + 1),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    (chars[start..].iter().collect(), chars.len())
+}
+
+/// What stopped `Parser::parse_seq`
+#[derive(Debug, PartialEq)]
+enum End {
+    Eof,
+    /// `}`, or the closing bracket asked for
+    Close,
+    /// `&` between table cells
+    Align,
+    /// `\\` between table rows
+    NewRow,
+    /// `\right` (left in place for `\left`)
+    Right,
+    /// `\end{...}`
+    Env,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    display: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Nodes up to the end of the current group, or up to `closer` when given
+    fn parse_seq(&mut self, closer: Option<char>) -> (String, End) {
+        let mut out = String::new();
+        loop {
+            let end = match self.peek() {
+                None => Some(End::Eof),
+                Some(Token::Close) => Some(End::Close),
+                Some(Token::Align) => Some(End::Align),
+                Some(Token::Command(c)) if c == "\\" => Some(End::NewRow),
+                Some(Token::Command(c)) if c == "right" => {
+                    return (out, End::Right);
+                }
+                Some(Token::Env(false, _)) => Some(End::Env),
+                Some(Token::Symbol(c)) if Some(*c) == closer => Some(End::Close),
+                _ => None,
+            };
+            if let Some(end) = end {
+                self.pos += 1;
+                return (out, end);
+            }
+            out.push_str(&self.parse_scripted());
+        }
+    }
+
+    /// An atom with its `^` / `_` scripts
+    fn parse_scripted(&mut self) -> String {
+        let (base, movable) = match self.peek() {
+            Some(Token::Sup) | Some(Token::Sub) => ("<mrow></mrow>".to_string(), false),
+            _ => self.parse_atom(),
+        };
+        let mut sup = None;
+        let mut sub = None;
+        loop {
+            match self.peek() {
+                Some(Token::Sup) if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.parse_arg());
+                }
+                Some(Token::Sub) if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.parse_arg());
+                }
+                _ => break,
+            }
+        }
+        let under_over = movable && self.display;
+        match (sub, sup) {
+            (None, None) => base,
+            (Some(sub), None) if under_over => format!("<munder>{}{}</munder>", base, sub),
+            (None, Some(sup)) if under_over => format!("<mover>{}{}</mover>", base, sup),
+            (Some(sub), Some(sup)) if under_over => {
+                format!("<munderover>{}{}{}</munderover>", base, sub, sup)
+            }
+            (Some(sub), None) => format!("<msub>{}{}</msub>", base, sub),
+            (None, Some(sup)) => format!("<msup>{}{}</msup>", base, sup),
+            (Some(sub), Some(sup)) => format!("<msubsup>{}{}{}</msubsup>", base, sub, sup),
+        }
+    }
+
+    /// One argument: a group, or a single token (`x^10` raises only the 1)
+    fn parse_arg(&mut self) -> String {
+        if let Some(Token::Number(n)) = self.tokens.get_mut(self.pos) {
+            if n.chars().count() > 1 {
+                let first = n.remove(0);
+                return format!("<mn>{}</mn>", first);
+            }
+        }
+        match self.peek() {
+            None => "<mrow></mrow>".to_string(),
+            _ => self.parse_atom().0,
+        }
+    }
+
+    /// One node, and whether its scripts go under and over it in display
+    /// mode (`\sum`, `\lim`...)
+    fn parse_atom(&mut self) -> (String, bool) {
+        let Some(token) = self.next() else {
+            return (String::new(), false);
+        };
+        let node = match token {
+            Token::Open => {
+                let (inner, _) = self.parse_seq(None);
+                format!("<mrow>{}</mrow>", inner)
+            }
+            Token::Number(n) => format!("<mn>{}</mn>", n),
+            Token::Letter(c) => format!("<mi>{}</mi>", escape(&c.to_string())),
+            Token::Symbol(c) => symbol_node(c),
+            Token::Text(command, raw) => text_node(&command, &raw),
+            Token::Env(true, name) => self.parse_env(&name),
+            Token::Command(name) => return self.parse_command(&name),
+            // Stray closers and separators are skipped
+            Token::Close | Token::Align | Token::Sup | Token::Sub | Token::Env(false, _) => {
+                String::new()
+            }
+        };
+        (node, false)
+    }
+
+    fn parse_command(&mut self, name: &str) -> (String, bool) {
+        let node = match name {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.parse_arg();
+                let den = self.parse_arg();
+                format!("<mfrac>{}{}</mfrac>", num, den)
+            }
+            "binom" => {
+                let n = self.parse_arg();
+                let k = self.parse_arg();
+                format!(
+                    "<mrow><mo>(</mo><mfrac linethickness=\"0\">{}{}</mfrac><mo>)</mo></mrow>",
+                    n, k
+                )
+            }
+            "sqrt" => {
+                if self.peek() == Some(&Token::Symbol('[')) {
+                    self.pos += 1;
+                    let (index, _) = self.parse_seq(Some(']'));
+                    let radicand = self.parse_arg();
+                    format!("<mroot>{}<mrow>{}</mrow></mroot>", radicand, index)
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.parse_arg())
+                }
+            }
+            "left" => {
+                let open = self.parse_delimiter();
+                let (inner, end) = self.parse_seq(None);
+                let close = if end == End::Right {
+                    self.pos += 1;
+                    self.parse_delimiter()
+                } else {
+                    String::new()
+                };
+                format!("<mrow>{}{}{}</mrow>", open, inner, close)
+            }
+            "mathbf" | "boldsymbol" | "bm" => {
+                format!(
+                    "<mrow style=\"font-weight: bold\">{}</mrow>",
+                    self.parse_arg()
+                )
+            }
+            "mathit" | "displaystyle" | "textstyle" | "limits" | "nolimits" | "big" | "Big"
+            | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" | "biggl" | "biggr" | "!" => {
+                String::new()
+            }
+            "hat" | "widehat" => self.accent("^"),
+            "bar" | "overline" => self.accent("\u{203E}"),
+            "vec" | "overrightarrow" => self.accent("\u{2192}"),
+            "dot" => self.accent("\u{02D9}"),
+            "ddot" => self.accent("\u{00A8}"),
+            "tilde" | "widetilde" => self.accent("~"),
+            "underline" => format!(
+                "<munder accentunder=\"true\">{}<mo>_</mo></munder>",
+                self.parse_arg()
+            ),
+            "," => space("0.167em"),
+            ":" | ">" => space("0.222em"),
+            ";" => space("0.278em"),
+            " " => space("0.25em"),
+            "quad" => space("1em"),
+            "qquad" => space("2em"),
+            "\\" => String::new(),
+            // Reached only without a matching `\left`
+            "right" => {
+                let _ = self.parse_delimiter();
+                String::new()
+            }
+            _ => {
+                if let Some(name) = FUNCTIONS.iter().find(|f| **f == name) {
+                    let movable = MOVABLE_LIMITS.contains(name);
+                    return (format!("<mi>{}</mi>", name), movable);
+                }
+                if let Some((_, symbol, kind)) = SYMBOLS.iter().find(|(n, _, _)| *n == name) {
+                    let node = match kind {
+                        Kind::Ident => format!("<mi>{}</mi>", symbol),
+                        Kind::Op => format!("<mo>{}</mo>", escape(symbol)),
+                        Kind::LargeOp => format!("<mo largeop=\"true\">{}</mo>", symbol),
+                    };
+                    return (node, MOVABLE_LIMITS.contains(&name));
+                }
+                if name.chars().count() == 1 {
+                    // \{ \} \% \$ \& \# \_ \|
+                    let c = name.chars().next().unwrap_or(' ');
+                    let c = if c == '|' { '\u{2016}' } else { c };
+                    format!("<mo>{}</mo>", escape(&c.to_string()))
+                } else {
+                    format!("<mtext>\\{}</mtext>", escape(name))
+                }
+            }
+        };
+        (node, false)
+    }
+
+    fn accent(&mut self, mark: &str) -> String {
+        format!(
+            "<mover accent=\"true\">{}<mo>{}</mo></mover>",
+            self.parse_arg(),
+            mark
+        )
+    }
+
+    /// The delimiter after `\left` / `\right` (`.` is none)
+    fn parse_delimiter(&mut self) -> String {
+        let delim = match self.next() {
+            Some(Token::Symbol('.')) | None => return String::new(),
+            Some(Token::Symbol(c)) => c.to_string(),
+            Some(Token::Command(name)) => match name.as_str() {
+                "{" | "lbrace" => "{".to_string(),
+                "}" | "rbrace" => "}".to_string(),
+                "|" | "Vert" => "\u{2016}".to_string(),
+                other => match SYMBOLS.iter().find(|(n, _, _)| *n == other) {
+                    Some((_, symbol, _)) => symbol.to_string(),
+                    None => return String::new(),
+                },
+            },
+            Some(_) => return String::new(),
+        };
+        format!("<mo stretchy=\"true\">{}</mo>", escape(&delim))
+    }
+
+    /// A `\begin{name}` environment: rows split by `\\`, cells by `&`
+    fn parse_env(&mut self, name: &str) -> String {
+        if name == "array" && self.peek() == Some(&Token::Open) {
+            // Column spec ({cc|l}): the layout is left to the renderer
+            self.pos += 1;
+            let _ = self.parse_seq(None);
+        }
+        let mut rows: Vec<Vec<String>> = vec![Vec::new()];
+        loop {
+            let (cell, end) = self.parse_seq(None);
+            if let Some(row) = rows.last_mut() {
+                row.push(cell);
+            }
+            match end {
+                End::Align => {}
+                End::NewRow => rows.push(Vec::new()),
+                End::Right => {
+                    // A `\right` without its `\left`
+                    self.parse_atom();
+                }
+                End::Eof | End::Close | End::Env => break,
+            }
+        }
+        // A trailing `\\` leaves an empty last row
+        if rows.len() > 1 && rows.last().is_some_and(|r| r.iter().all(String::is_empty)) {
+            rows.pop();
+        }
+
+        let column_align = match name {
+            "cases" => " columnalign=\"left left\"",
+            "aligned" | "align" | "align*" | "split" | "gathered" => " columnalign=\"right left\"",
+            _ => "",
+        };
+        let mut table = format!("<mtable{}>", column_align);
+        for row in rows {
+            table.push_str("<mtr>");
+            for cell in row {
+                table.push_str(&format!("<mtd>{}</mtd>", cell));
+            }
+            table.push_str("</mtr>");
+        }
+        table.push_str("</mtable>");
+
+        let (open, close) = match name {
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("\u{2016}", "\u{2016}"),
+            "cases" => ("{", ""),
+            _ => return table,
+        };
+        let close = if close.is_empty() {
+            String::new()
+        } else {
+            format!("<mo stretchy=\"true\">{}</mo>", close)
+        };
+        format!(
+            "<mrow><mo stretchy=\"true\">{}</mo>{}{}</mrow>",
+            open, table, close
+        )
+    }
+}
+
+fn symbol_node(c: char) -> String {
+    match c {
+        '-' => "<mo>\u{2212}</mo>".to_string(),
+        '*' => "<mo>\u{2217}</mo>".to_string(),
+        '\'' => "<mo>\u{2032}</mo>".to_string(),
+        '~' => space("0.25em"),
+        c => format!("<mo>{}</mo>", escape(&c.to_string())),
+    }
+}
+
+fn text_node(command: &str, raw: &str) -> String {
+    match command {
+        "mathrm" | "operatorname" => {
+            format!("<mi mathvariant=\"normal\">{}</mi>", escape(raw.trim()))
+        }
+        "mathbb" => format!("<mi>{}</mi>", escape(&map_letters(raw, double_struck))),
+        "mathcal" | "mathscr" => format!("<mi>{}</mi>", escape(&map_letters(raw, script))),
+        "mathfrak" => format!("<mi>{}</mi>", escape(raw.trim())),
+        "textbf" => format!("<mtext style=\"font-weight: bold\">{}</mtext>", escape(raw)),
+        "textit" => format!(
+            "<mtext style=\"font-style: italic\">{}</mtext>",
+            escape(raw)
+        ),
+        _ => format!("<mtext>{}</mtext>", escape(raw)),
+    }
+}
+
+fn space(width: &str) -> String {
+    format!("<mspace width=\"{}\"></mspace>", width)
+}
+
+fn map_letters(raw: &str, map: fn(char) -> Option<char>) -> String {
+    raw.trim().chars().map(|c| map(c).unwrap_or(c)).collect()
+}
+
+/// Double-struck capitals, for `\mathbb` (ℝ, ℕ...)
+fn double_struck(c: char) -> Option<char> {
+    match c {
+        'C' => Some('\u{2102}'),
+        'H' => Some('\u{210D}'),
+        'N' => Some('\u{2115}'),
+        'P' => Some('\u{2119}'),
+        'Q' => Some('\u{211A}'),
+        'R' => Some('\u{211D}'),
+        'Z' => Some('\u{2124}'),
+        'A'..='Z' => char::from_u32(0x1D538 + (c as u32 - 'A' as u32)),
+        _ => None,
+    }
+}
+
+/// Script capitals, for `\mathcal`
+fn script(c: char) -> Option<char> {
+    match c {
+        'B' => Some('\u{212C}'),
+        'E' => Some('\u{2130}'),
+        'F' => Some('\u{2131}'),
+        'H' => Some('\u{210B}'),
+        'I' => Some('\u{2110}'),
+        'L' => Some('\u{2112}'),
+        'M' => Some('\u{2133}'),
+        'R' => Some('\u{211B}'),
+        'A'..='Z' => char::from_u32(0x1D49C + (c as u32 - 'A' as u32)),
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Named functions, set upright
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "det", "dim", "gcd", "deg", "arg", "ker", "hom", "Pr", "lim",
+    "liminf", "limsup", "max", "min", "sup", "inf", "argmax", "argmin",
+];
+
+/// Operators whose limits go under and over them in display mode
+const MOVABLE_LIMITS: &[&str] = &[
+    "sum",
+    "prod",
+    "coprod",
+    "bigcup",
+    "bigcap",
+    "bigoplus",
+    "bigotimes",
+    "lim",
+    "liminf",
+    "limsup",
+    "max",
+    "min",
+    "sup",
+    "inf",
+    "argmax",
+    "argmin",
+];
+
+enum Kind {
+    Ident,
+    Op,
+    LargeOp,
+}
+
+const SYMBOLS: &[(&str, &str, Kind)] = &[
+    // Greek
+    ("alpha", "\u{03B1}", Kind::Ident),
+    ("beta", "\u{03B2}", Kind::Ident),
+    ("gamma", "\u{03B3}", Kind::Ident),
+    ("delta", "\u{03B4}", Kind::Ident),
+    ("epsilon", "\u{03F5}", Kind::Ident),
+    ("varepsilon", "\u{03B5}", Kind::Ident),
+    ("zeta", "\u{03B6}", Kind::Ident),
+    ("eta", "\u{03B7}", Kind::Ident),
+    ("theta", "\u{03B8}", Kind::Ident),
+    ("vartheta", "\u{03D1}", Kind::Ident),
+    ("iota", "\u{03B9}", Kind::Ident),
+    ("kappa", "\u{03BA}", Kind::Ident),
+    ("lambda", "\u{03BB}", Kind::Ident),
+    ("mu", "\u{03BC}", Kind::Ident),
+    ("nu", "\u{03BD}", Kind::Ident),
+    ("xi", "\u{03BE}", Kind::Ident),
+    ("pi", "\u{03C0}", Kind::Ident),
+    ("varpi", "\u{03D6}", Kind::Ident),
+    ("rho", "\u{03C1}", Kind::Ident),
+    ("varrho", "\u{03F1}", Kind::Ident),
+    ("sigma", "\u{03C3}", Kind::Ident),
+    ("varsigma", "\u{03C2}", Kind::Ident),
+    ("tau", "\u{03C4}", Kind::Ident),
+    ("upsilon", "\u{03C5}", Kind::Ident),
+    ("phi", "\u{03D5}", Kind::Ident),
+    ("varphi", "\u{03C6}", Kind::Ident),
+    ("chi", "\u{03C7}", Kind::Ident),
+    ("psi", "\u{03C8}", Kind::Ident),
+    ("omega", "\u{03C9}", Kind::Ident),
+    ("Gamma", "\u{0393}", Kind::Ident),
+    ("Delta", "\u{0394}", Kind::Ident),
+    ("Theta", "\u{0398}", Kind::Ident),
+    ("Lambda", "\u{039B}", Kind::Ident),
+    ("Xi", "\u{039E}", Kind::Ident),
+    ("Pi", "\u{03A0}", Kind::Ident),
+    ("Sigma", "\u{03A3}", Kind::Ident),
+    ("Upsilon", "\u{03A5}", Kind::Ident),
+    ("Phi", "\u{03A6}", Kind::Ident),
+    ("Psi", "\u{03A8}", Kind::Ident),
+    ("Omega", "\u{03A9}", Kind::Ident),
+    // Letter-like
+    ("infty", "\u{221E}", Kind::Ident),
+    ("partial", "\u{2202}", Kind::Ident),
+    ("nabla", "\u{2207}", Kind::Ident),
+    ("hbar", "\u{210F}", Kind::Ident),
+    ("ell", "\u{2113}", Kind::Ident),
+    ("emptyset", "\u{2205}", Kind::Ident),
+    ("varnothing", "\u{2205}", Kind::Ident),
+    ("Re", "\u{211C}", Kind::Ident),
+    ("Im", "\u{2111}", Kind::Ident),
+    ("aleph", "\u{2135}", Kind::Ident),
+    // Large operators
+    ("sum", "\u{2211}", Kind::LargeOp),
+    ("prod", "\u{220F}", Kind::LargeOp),
+    ("coprod", "\u{2210}", Kind::LargeOp),
+    ("int", "\u{222B}", Kind::LargeOp),
+    ("iint", "\u{222C}", Kind::LargeOp),
+    ("iiint", "\u{222D}", Kind::LargeOp),
+    ("oint", "\u{222E}", Kind::LargeOp),
+    ("bigcup", "\u{22C3}", Kind::LargeOp),
+    ("bigcap", "\u{22C2}", Kind::LargeOp),
+    ("bigoplus", "\u{2A01}", Kind::LargeOp),
+    ("bigotimes", "\u{2A02}", Kind::LargeOp),
+    // Binary operators
+    ("pm", "\u{00B1}", Kind::Op),
+    ("mp", "\u{2213}", Kind::Op),
+    ("times", "\u{00D7}", Kind::Op),
+    ("div", "\u{00F7}", Kind::Op),
+    ("cdot", "\u{22C5}", Kind::Op),
+    ("ast", "\u{2217}", Kind::Op),
+    ("star", "\u{22C6}", Kind::Op),
+    ("circ", "\u{2218}", Kind::Op),
+    ("bullet", "\u{2219}", Kind::Op),
+    ("oplus", "\u{2295}", Kind::Op),
+    ("otimes", "\u{2297}", Kind::Op),
+    ("cup", "\u{222A}", Kind::Op),
+    ("cap", "\u{2229}", Kind::Op),
+    ("setminus", "\u{2216}", Kind::Op),
+    ("wedge", "\u{2227}", Kind::Op),
+    ("land", "\u{2227}", Kind::Op),
+    ("vee", "\u{2228}", Kind::Op),
+    ("lor", "\u{2228}", Kind::Op),
+    ("neg", "\u{00AC}", Kind::Op),
+    ("lnot", "\u{00AC}", Kind::Op),
+    // Relations
+    ("leq", "\u{2264}", Kind::Op),
+    ("le", "\u{2264}", Kind::Op),
+    ("geq", "\u{2265}", Kind::Op),
+    ("ge", "\u{2265}", Kind::Op),
+    ("neq", "\u{2260}", Kind::Op),
+    ("ne", "\u{2260}", Kind::Op),
+    ("lt", "<", Kind::Op),
+    ("gt", ">", Kind::Op),
+    ("ll", "\u{226A}", Kind::Op),
+    ("gg", "\u{226B}", Kind::Op),
+    ("approx", "\u{2248}", Kind::Op),
+    ("equiv", "\u{2261}", Kind::Op),
+    ("sim", "\u{223C}", Kind::Op),
+    ("simeq", "\u{2243}", Kind::Op),
+    ("cong", "\u{2245}", Kind::Op),
+    ("propto", "\u{221D}", Kind::Op),
+    ("in", "\u{2208}", Kind::Op),
+    ("notin", "\u{2209}", Kind::Op),
+    ("ni", "\u{220B}", Kind::Op),
+    ("subset", "\u{2282}", Kind::Op),
+    ("supset", "\u{2283}", Kind::Op),
+    ("subseteq", "\u{2286}", Kind::Op),
+    ("supseteq", "\u{2287}", Kind::Op),
+    ("perp", "\u{22A5}", Kind::Op),
+    ("parallel", "\u{2225}", Kind::Op),
+    ("mid", "\u{2223}", Kind::Op),
+    ("forall", "\u{2200}", Kind::Op),
+    ("exists", "\u{2203}", Kind::Op),
+    ("nexists", "\u{2204}", Kind::Op),
+    ("therefore", "\u{2234}", Kind::Op),
+    ("because", "\u{2235}", Kind::Op),
+    // Arrows
+    ("to", "\u{2192}", Kind::Op),
+    ("rightarrow", "\u{2192}", Kind::Op),
+    ("leftarrow", "\u{2190}", Kind::Op),
+    ("gets", "\u{2190}", Kind::Op),
+    ("leftrightarrow", "\u{2194}", Kind::Op),
+    ("Rightarrow", "\u{21D2}", Kind::Op),
+    ("implies", "\u{21D2}", Kind::Op),
+    ("Leftarrow", "\u{21D0}", Kind::Op),
+    ("Leftrightarrow", "\u{21D4}", Kind::Op),
+    ("iff", "\u{21D4}", Kind::Op),
+    ("mapsto", "\u{21A6}", Kind::Op),
+    ("uparrow", "\u{2191}", Kind::Op),
+    ("downarrow", "\u{2193}", Kind::Op),
+    ("longrightarrow", "\u{27F6}", Kind::Op),
+    // Dots and delimiters
+    ("ldots", "\u{2026}", Kind::Op),
+    ("dots", "\u{2026}", Kind::Op),
+    ("cdots", "\u{22EF}", Kind::Op),
+    ("vdots", "\u{22EE}", Kind::Op),
+    ("ddots", "\u{22F1}", Kind::Op),
+    ("langle", "\u{27E8}", Kind::Op),
+    ("rangle", "\u{27E9}", Kind::Op),
+    ("lfloor", "\u{230A}", Kind::Op),
+    ("rfloor", "\u{230B}", Kind::Op),
+    ("lceil", "\u{2308}", Kind::Op),
+    ("rceil", "\u{2309}", Kind::Op),
+    ("lvert", "|", Kind::Op),
+    ("rvert", "|", Kind::Op),
+    ("vert", "|", Kind::Op),
+    ("lVert", "\u{2016}", Kind::Op),
+    ("rVert", "\u{2016}", Kind::Op),
+    ("angle", "\u{2220}", Kind::Op),
+    ("degree", "\u{00B0}", Kind::Op),
+    ("prime", "\u{2032}", Kind::Op),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inline(latex: &str) -> String {
+        let mathml = latex_to_mathml(latex, false);
+        mathml
+            .strip_prefix("<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow>")
+            .and_then(|m| m.strip_suffix("</mrow></math>"))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_latex_to_mathml() {
+        assert_eq!(
+            inline(r"\frac{a+1}{2}"),
+            "<mfrac><mrow><mi>a</mi><mo>+</mo><mn>1</mn></mrow><mrow><mn>2</mn></mrow></mfrac>"
+        );
+        assert_eq!(
+            inline("x_i^{10}"),
+            "<msubsup><mi>x</mi><mi>i</mi><mrow><mn>10</mn></mrow></msubsup>"
+        );
+        // Without braces only the first digit is raised
+        assert_eq!(
+            inline("e^10"),
+            "<msup><mi>e</mi><mn>1</mn></msup><mn>0</mn>"
+        );
+        assert_eq!(
+            inline(r"\sqrt[3]{\alpha}"),
+            "<mroot><mrow><mi>\u{03B1}</mi></mrow><mrow><mn>3</mn></mrow></mroot>"
+        );
+        assert_eq!(inline(r"\text{if } x"), "<mtext>if </mtext><mi>x</mi>");
+        assert_eq!(inline(r"\mathbb{R}"), "<mi>\u{211D}</mi>");
+        assert_eq!(
+            inline(r"\left( x \right)"),
+            "<mrow><mo stretchy=\"true\">(</mo><mi>x</mi><mo stretchy=\"true\">)</mo></mrow>"
+        );
+
+        // Limits go under and over only in display mode
+        assert!(inline(r"\sum_{i=1}^n i").starts_with("<msubsup><mo largeop"));
+        assert!(latex_to_mathml(r"\sum_{i=1}^n i", true).contains("<munderover><mo largeop"));
+
+        assert_eq!(
+            inline(r"\begin{pmatrix} 1 & 0 \\ 0 & 1 \\ \end{pmatrix}"),
+            "<mrow><mo stretchy=\"true\">(</mo><mtable>\
+             <mtr><mtd><mn>1</mn></mtd><mtd><mn>0</mn></mtd></mtr>\
+             <mtr><mtd><mn>0</mn></mtd><mtd><mn>1</mn></mtd></mtr>\
+             </mtable><mo stretchy=\"true\">)</mo></mrow>"
+        );
+    }
+
+    #[test]
+    fn test_latex_to_mathml_is_escaped_and_lenient() {
+        assert_eq!(inline("a < b"), "<mi>a</mi><mo>&lt;</mo><mi>b</mi>");
+        assert_eq!(inline(r"\text{<script>}"), "<mtext>&lt;script&gt;</mtext>");
+        // Unknown commands show their name; unbalanced input doesn't fail
+        assert_eq!(inline(r"\foo x"), "<mtext>\\foo</mtext><mi>x</mi>");
+        assert_eq!(
+            inline("x}^"),
+            "<mi>x</mi><msup><mrow></mrow><mrow></mrow></msup>"
+        );
+        assert_eq!(inline(r"a \right) b"), "<mi>a</mi><mi>b</mi>");
+        assert_eq!(
+            inline(r"\frac{1"),
+            "<mfrac><mrow><mn>1</mn></mrow><mrow></mrow></mfrac>"
+        );
+    }
+}
@@ -1,31 +1,45 @@
 //! Message display components with Markdown rendering
 
 use crate::app::AppState;
+use crate::inference::reasoning::{default_reasoning_tags, matching_close, ReasoningTag};
+use crate::agent::PermissionLevel;
+use crate::types::message::{ToolCallRecord, ToolOutput};
+use crate::ui::chat::highlight::highlight_code;
+use crate::ui::chat::math::latex_to_mathml;
+use chrono::{DateTime, Local, Utc};
 use dioxus::prelude::*;
+use uuid::Uuid;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum MessageRole {
     User,
     Assistant,
     System,
+    Tool,
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct Message {
+    /// Same as the stored message's, also the bubble's DOM id (`msg-<id>`)
+    pub id: Uuid,
     pub role: MessageRole,
     pub content: String,
+    pub timestamp: DateTime<Utc>,
 }
 
 // Convert storage Message to UI Message
 impl From<crate::types::message::Message> for Message {
     fn from(msg: crate::types::message::Message) -> Self {
         Message {
+            id: msg.id,
             role: match msg.role {
                 crate::types::message::Role::User => MessageRole::User,
                 crate::types::message::Role::Assistant => MessageRole::Assistant,
                 crate::types::message::Role::System => MessageRole::System,
+                crate::types::message::Role::Tool => MessageRole::Tool,
             },
             content: msg.content,
+            timestamp: msg.timestamp,
         }
     }
 }
@@ -33,17 +47,31 @@ impl From<crate::types::message::Message> for Message {
 // Convert UI Message to storage Message
 impl From<Message> for crate::types::message::Message {
     fn from(msg: Message) -> Self {
-        crate::types::message::Message::new(
+        let mut stored = crate::types::message::Message::new(
             match msg.role {
                 MessageRole::User => crate::types::message::Role::User,
                 MessageRole::Assistant => crate::types::message::Role::Assistant,
                 MessageRole::System => crate::types::message::Role::System,
+                MessageRole::Tool => crate::types::message::Role::Tool,
             },
             msg.content,
-        )
+        );
+        stored.id = msg.id;
+        stored.timestamp = msg.timestamp;
+        stored
     }
 }
 
+/// Length to cut `messages` to before regenerating the assistant message at
+/// `index`: right after the user message it answers, so the tool calls and
+/// notes of that run go as well. `None` when no user message precedes it.
+pub fn regenerate_cut(messages: &[Message], index: usize) -> Option<usize> {
+    messages[..index.min(messages.len())]
+        .iter()
+        .rposition(|m| m.role == MessageRole::User)
+        .map(|i| i + 1)
+}
+
 // Content parts for parsed message content
 #[derive(Clone, PartialEq, Debug)]
 enum ContentPart {
@@ -53,77 +81,101 @@ enum ContentPart {
 }
 
 /// Parse thinking blocks from message content.
-/// Supports both <think>...</think> and <thinking>...</thinking> tags.
-/// Incomplete tags are rendered as live streaming blocks.
+/// Any of the reasoning `tags` (<think>...</think>, <thinking>...</thinking>,
+/// the loaded model's own markers...) opens a block; blocks may follow one
+/// another and be nested inside one another.
+/// An unterminated block is rendered as a live streaming block, and a tag cut
+/// in half at the end of a streamed chunk is hidden until it completes.
+/// A closing tag with no opening tag before it (chat templates that open the
+/// block in the prompt) closes a block that started at the top of the message.
 /// Also strips <request>...</request> tags (rendered as normal text).
-fn parse_thinking_blocks(content: &str) -> Vec<ContentPart> {
+fn parse_thinking_blocks(content: &str, tags: &[ReasoningTag]) -> Vec<ContentPart> {
     // First: strip <request>...</request> tags, keeping inner content as normal text
     let cleaned = strip_xml_tags(content, "request");
 
     let mut parts = Vec::new();
     let mut remaining = cleaned.as_str();
 
-    loop {
-        // Find the earliest opening tag: <think> or <thinking>
-        let think_pos = remaining.find("<think>");
-        let thinking_pos = remaining.find("<thinking>");
-
-        let (start, open_tag, close_tag) = match (think_pos, thinking_pos) {
-            (Some(a), Some(b)) => {
-                if a <= b {
-                    (a, "<think>", "</think>")
-                } else {
-                    (b, "<thinking>", "</thinking>")
-                }
-            }
-            (Some(a), None) => (a, "<think>", "</think>"),
-            (None, Some(b)) => (b, "<thinking>", "</thinking>"),
-            (None, None) => break,
-        };
+    if let Some((end, close_tag)) = leading_close_tag(remaining, tags) {
+        let think_content = &remaining[..end];
+        if !think_content.trim().is_empty() {
+            parts.push(ContentPart::Thinking(think_content.to_string()));
+        }
+        remaining = &remaining[end + close_tag.len()..];
+    }
 
+    // Find the earliest opening tag
+    while let Some((start, open_tag, close_tag)) = tags
+        .iter()
+        .filter_map(|tag| {
+            remaining
+                .find(&tag.open)
+                .map(|pos| (pos, tag.open.as_str(), tag.close.as_str()))
+        })
+        .min_by_key(|(pos, _, _)| *pos)
+    {
         // Text before the tag
-        if start > 0 {
-            let text = remaining[..start].to_string();
-            if !text.trim().is_empty() {
-                parts.push(ContentPart::Text(text));
-            }
+        let text = &remaining[..start];
+        if !text.trim().is_empty() {
+            parts.push(ContentPart::Text(text.to_string()));
         }
 
-        let open_len = open_tag.len();
-        let close_len = close_tag.len();
-
-        if let Some(end_offset) = remaining[start..].find(close_tag) {
-            // Closed thinking block
-            let think_start = start + open_len;
-            let think_end = start + end_offset;
-            let think_content = remaining[think_start..think_end].to_string();
-            if !think_content.trim().is_empty() {
-                parts.push(ContentPart::Thinking(think_content));
+        let body = &remaining[start + open_tag.len()..];
+        match matching_close(body, open_tag, close_tag) {
+            Some(end) => {
+                let think_content = &body[..end];
+                if !think_content.trim().is_empty() {
+                    parts.push(ContentPart::Thinking(think_content.to_string()));
+                }
+                remaining = &body[end + close_tag.len()..];
             }
-            remaining = &remaining[think_end + close_len..];
-        } else {
-            // STREAMING: open tag without closing -> live thinking block
-            let think_start = start + open_len;
-            if think_start <= remaining.len() {
-                let think_content = remaining[think_start..].to_string();
-                parts.push(ContentPart::ThinkingStreaming(think_content));
+            None => {
+                // STREAMING: open tag without closing -> live thinking block
+                let think_content = strip_partial_tag(body, close_tag);
+                parts.push(ContentPart::ThinkingStreaming(think_content.to_string()));
+                remaining = "";
             }
-            remaining = "";
-            break;
         }
     }
 
-    if !remaining.is_empty() {
+    let remaining = tags
+        .iter()
+        .map(|tag| strip_partial_tag(remaining, &tag.open))
+        .min_by_key(|text| text.len())
+        .unwrap_or(remaining);
+    if parts.is_empty() || !remaining.trim().is_empty() {
         parts.push(ContentPart::Text(remaining.to_string()));
     }
 
-    if parts.is_empty() {
-        parts.push(ContentPart::Text(content.to_string()));
-    }
-
     parts
 }
 
+/// A closing tag that appears before any opening tag, with its position
+fn leading_close_tag<'t>(content: &str, tags: &'t [ReasoningTag]) -> Option<(usize, &'t str)> {
+    let first_open = tags
+        .iter()
+        .filter_map(|tag| content.find(&tag.open))
+        .min()
+        .unwrap_or(content.len());
+    tags.iter()
+        .filter_map(|tag| {
+            content
+                .find(&tag.close)
+                .map(|pos| (pos, tag.close.as_str()))
+        })
+        .filter(|(pos, _)| *pos < first_open)
+        .min_by_key(|(pos, _)| *pos)
+}
+
+/// Drop the start of `tag` (at least `<` and one more character) left
+/// dangling at the end of `text` by a chunk boundary.
+fn strip_partial_tag<'a>(text: &'a str, tag: &str) -> &'a str {
+    (2..tag.len())
+        .rev()
+        .find(|&len| text.ends_with(&tag[..len]))
+        .map_or(text, |len| &text[..text.len() - len])
+}
+
 /// Strip XML-like tags, keeping the inner content as plain text.
 /// e.g. strip_xml_tags("Hello <request>world</request>!", "request") -> "Hello world!"
 fn strip_xml_tags(content: &str, tag: &str) -> String {
@@ -141,7 +193,8 @@ fn strip_xml_tags(content: &str, tag: &str) -> String {
 fn ThinkingBlock(content: String) -> Element {
     let app_state = use_context::<AppState>();
     let is_en = app_state.settings.read().language == "en";
-    let mut is_expanded = use_signal(|| false);
+    // Starts as the setting says, then each block toggles on its own
+    let mut is_expanded = use_signal(|| app_state.settings.read().expand_thinking);
 
     let chevron_class = if is_expanded() {
         "thinking-chevron expanded"
@@ -244,11 +297,17 @@ fn ThinkingBlockStreaming(content: String) -> Element {
 
 /// Markdown content renderer
 #[component]
-fn MarkdownContent(content: String) -> Element {
+fn MarkdownContent(
+    content: String,
+    /// Blink a cursor after the last block, while tokens still arrive
+    #[props(default)]
+    streaming: bool,
+) -> Element {
     let blocks = parse_markdown_blocks(&content);
 
     rsx! {
-        div { class: "markdown-content space-y-3",
+        div {
+            class: if streaming { "markdown-content streaming space-y-3" } else { "markdown-content space-y-3" },
             for block in blocks {
                 {render_block(block)}
             }
@@ -266,25 +325,99 @@ enum MarkdownBlock {
     OrderedList(Vec<String>),
     HorizontalRule,
     Blockquote(String),
-    Table(Vec<Vec<String>>, Vec<String>), // (rows, headers)
+    Table {
+        headers: Vec<String>,
+        alignments: Vec<ColumnAlign>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// Alignment of a table column, from the colons of its separator cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColumnAlign {
+    /// `---`
+    Default,
+    /// `:---`
+    Left,
+    /// `:---:`
+    Center,
+    /// `---:`
+    Right,
+}
+
+impl ColumnAlign {
+    fn class(self) -> &'static str {
+        match self {
+            ColumnAlign::Default | ColumnAlign::Left => "text-left",
+            ColumnAlign::Center => "text-center",
+            ColumnAlign::Right => "text-right",
+        }
+    }
 }
 
-/// Parse a table row into cells
+/// Parse a table row into cells. The outer pipes are optional; `\|` and
+/// pipes inside `code spans` stay in the cell.
 fn parse_table_row(line: &str) -> Vec<String> {
-    line.trim()
-        .trim_matches('|')
-        .split('|')
-        .map(|cell| cell.trim().to_string())
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = match trimmed.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => trimmed,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_code = false;
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '`' => {
+                in_code = !in_code;
+                cell.push(c);
+            }
+            '|' if !in_code => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Column alignments if `line` is a table separator (`|:---|:---:|---:|`)
+fn parse_table_alignments(line: &str) -> Option<Vec<ColumnAlign>> {
+    if !line.contains('-') || !line.contains(['|', ':']) {
+        return None;
+    }
+    parse_table_row(line)
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => ColumnAlign::Center,
+                (true, false) => ColumnAlign::Left,
+                (false, true) => ColumnAlign::Right,
+                (false, false) => ColumnAlign::Default,
+            })
+        })
         .collect()
 }
 
-/// Check if a line is a table separator (|---|---|)
-fn is_table_separator(line: &str) -> bool {
-    let trimmed = line.trim().trim_matches('|');
-    trimmed.split('|').all(|cell| {
-        let c = cell.trim();
-        c.chars().all(|ch| ch == '-' || ch == ':' || ch == ' ') && c.contains('-')
-    })
+/// A GFM table starts with a header row followed by a separator row with as
+/// many cells. Returns the column alignments when `lines[i]` starts one.
+fn table_start(lines: &[&str], i: usize) -> Option<Vec<ColumnAlign>> {
+    let header = lines[i].trim();
+    if !header.contains('|') {
+        return None;
+    }
+    let alignments = parse_table_alignments(lines.get(i + 1)?)?;
+    (alignments.len() == parse_table_row(header).len()).then_some(alignments)
 }
 
 fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
@@ -336,6 +469,37 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
             continue;
         }
 
+        // Math block \[...\]
+        if let Some(first_line_content) = trimmed.strip_prefix("\\[") {
+            let first_line_content = first_line_content.trim();
+            if let Some(math) = first_line_content.strip_suffix("\\]") {
+                blocks.push(MarkdownBlock::MathBlock(math.trim().to_string()));
+                i += 1;
+                continue;
+            }
+
+            let mut math_lines = Vec::new();
+            if !first_line_content.is_empty() {
+                math_lines.push(first_line_content.to_string());
+            }
+            i += 1;
+            while i < lines.len() {
+                let l = lines[i].trim();
+                if let Some(end) = l.find("\\]") {
+                    let before_end = l[..end].trim();
+                    if !before_end.is_empty() {
+                        math_lines.push(before_end.to_string());
+                    }
+                    i += 1;
+                    break;
+                }
+                math_lines.push(lines[i].to_string());
+                i += 1;
+            }
+            blocks.push(MarkdownBlock::MathBlock(math_lines.join("\n")));
+            continue;
+        }
+
         // Code block ```
         if trimmed.starts_with("```") {
             let lang = trimmed.trim_start_matches('`').to_string();
@@ -379,38 +543,25 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
             continue;
         }
 
-        // Table (lines starting with |)
-        if trimmed.starts_with('|') && trimmed.ends_with('|') {
-            let mut table_lines: Vec<&str> = Vec::new();
-            while i < lines.len() {
-                let l = lines[i].trim();
-                if l.starts_with('|') && l.ends_with('|') {
-                    table_lines.push(l);
-                    i += 1;
-                } else {
-                    break;
-                }
-            }
-
-            if table_lines.len() >= 2 {
-                // Parse header row
-                let headers: Vec<String> = parse_table_row(table_lines[0]);
-
-                // Skip separator row (|---|---|)
-                let data_start = if table_lines.len() > 1 && is_table_separator(table_lines[1]) {
-                    2
-                } else {
-                    1
-                };
-
-                // Parse data rows
-                let rows: Vec<Vec<String>> = table_lines[data_start..]
-                    .iter()
-                    .map(|line| parse_table_row(line))
-                    .collect();
-
-                blocks.push(MarkdownBlock::Table(rows, headers));
+        // Table: header row, separator row, then rows until a blank line
+        // or a line without any pipe
+        if let Some(alignments) = table_start(&lines, i) {
+            let headers = parse_table_row(trimmed);
+            let columns = headers.len();
+            i += 2;
+            let mut rows = Vec::new();
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                // Rows are cut or padded to the header's width
+                let mut row = parse_table_row(lines[i]);
+                row.resize(columns, String::new());
+                rows.push(row);
+                i += 1;
             }
+            blocks.push(MarkdownBlock::Table {
+                headers,
+                alignments,
+                rows,
+            });
             continue;
         }
 
@@ -483,6 +634,9 @@ fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
                 || t.starts_with("- ")
                 || t.starts_with("* ")
                 || t.starts_with("> ")
+                || t.starts_with("$$")
+                || t.starts_with("\\[")
+                || (!para_lines.is_empty() && table_start(&lines, i).is_some())
             {
                 break;
             }
@@ -519,20 +673,7 @@ fn render_block(block: MarkdownBlock) -> Element {
             }
         }
         MarkdownBlock::CodeBlock(lang, code) => rsx! {
-            div { class: "my-3 rounded-xl overflow-hidden border border-[var(--border-subtle)]",
-                style: "background: #121110;",
-                if !lang.is_empty() {
-                    div { class: "code-header",
-                        span { "{lang}" }
-                    }
-                }
-                pre { class: "p-4 overflow-x-auto",
-                    code { class: "text-sm font-mono leading-relaxed",
-                        style: "color: #E8E2DB;",
-                        "{code}"
-                    }
-                }
-            }
+            CodeView { lang, code }
         },
         MarkdownBlock::UnorderedList(items) => rsx! {
             ul { class: "space-y-1.5 pl-1",
@@ -559,11 +700,7 @@ fn render_block(block: MarkdownBlock) -> Element {
             }
         },
         MarkdownBlock::MathBlock(math) => rsx! {
-            div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
-                pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
-                    "{math}"
-                }
-            }
+            MathView { latex: math, display: true }
         },
         MarkdownBlock::HorizontalRule => rsx! {
             hr { class: "border-none h-px bg-[var(--border-subtle)] my-6" }
@@ -575,14 +712,19 @@ fn render_block(block: MarkdownBlock) -> Element {
                 }
             }
         },
-        MarkdownBlock::Table(rows, headers) => rsx! {
-            div { class: "my-4 overflow-x-auto rounded-xl border border-[var(--border-subtle)]",
-                table { class: "w-full text-sm",
+        MarkdownBlock::Table {
+            headers,
+            alignments,
+            rows,
+        } => rsx! {
+            // Wide tables scroll sideways instead of squeezing the columns
+            div { class: "markdown-table my-4 overflow-x-auto max-w-full rounded-xl border border-[var(--border-subtle)]",
+                table { class: "min-w-full text-sm",
                     thead { class: "bg-[var(--bg-tertiary)]",
                         tr {
-                            for header in headers.iter() {
+                            for (header, align) in headers.iter().zip(alignments.iter()) {
                                 th {
-                                    class: "px-4 py-3 text-left font-semibold text-[var(--text-primary)] border-b border-[var(--border-subtle)]",
+                                    class: "px-4 py-3 {align.class()} font-semibold text-[var(--text-primary)] border-b border-[var(--border-subtle)]",
                                     {render_inline(header)}
                                 }
                             }
@@ -592,9 +734,9 @@ fn render_block(block: MarkdownBlock) -> Element {
                         for (row_idx, row) in rows.iter().enumerate() {
                             tr {
                                 class: if row_idx % 2 == 0 { "bg-[var(--bg-secondary)]" } else { "bg-[var(--bg-primary)]" },
-                                for cell in row.iter() {
+                                for (cell, align) in row.iter().zip(alignments.iter()) {
                                     td {
-                                        class: "px-4 py-2.5 text-[var(--text-secondary)] border-b border-[var(--border-subtle)]/50",
+                                        class: "px-4 py-2.5 {align.class()} text-[var(--text-secondary)] border-b border-[var(--border-subtle)]/50",
                                         {render_inline(cell)}
                                     }
                                 }
@@ -616,7 +758,7 @@ fn render_inline(text: &str) -> Element {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 enum InlineSegment {
     Text(String),
     Bold(String),
@@ -655,21 +797,42 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
             }
         }
 
+        // Escaped dollar \$
+        if chars[i] == '\\' && chars.get(i + 1) == Some(&'$') {
+            current_text.push('$');
+            i += 2;
+            continue;
+        }
+
+        // Inline math \(...\)
+        if chars[i] == '\\' && chars.get(i + 1) == Some(&'(') {
+            let close = (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == '\\' && chars[j + 1] == ')');
+            if let Some(end) = close {
+                if !current_text.is_empty() {
+                    segments.push(InlineSegment::Text(current_text.clone()));
+                    current_text.clear();
+                }
+                let math: String = chars[i + 2..end].iter().collect();
+                segments.push(InlineSegment::InlineMath(math));
+                i = end + 2;
+                continue;
+            }
+        }
+
         // Inline math $...$
         if chars[i] == '$' && !matches!(chars.get(i + 1), Some('$')) {
-            if let Some(close_offset) = chars[i + 1..].iter().position(|&c| c == '$') {
+            if let Some(end) = inline_math_end(&chars, i) {
                 if !current_text.is_empty() {
                     segments.push(InlineSegment::Text(current_text.clone()));
                     current_text.clear();
                 }
-                let start = i + 1;
-                let end = i + 1 + close_offset;
-                let math: String = chars[start..end].iter().collect();
+                let math: String = chars[i + 1..end].iter().collect();
                 segments.push(InlineSegment::InlineMath(math));
                 i = end + 1;
                 continue;
             } else {
-                // Unclosed dollar, treat as normal text
+                // Unclosed dollar or an amount, treat as normal text
                 current_text.push('$');
                 i += 1;
                 continue;
@@ -740,6 +903,17 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
             }
         }
 
+        // Bare URL
+        if let Some(end) = bare_url_end(&chars, i) {
+            if !current_text.is_empty() {
+                segments.push(InlineSegment::Text(current_text.clone()));
+                current_text.clear();
+            }
+            segments.push(bare_url_link(&chars[i..end]));
+            i = end;
+            continue;
+        }
+
         // Link [text](url)
         if chars[i] == '[' {
             let bracket_start = i;
@@ -781,6 +955,112 @@ fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
     segments
 }
 
+/// Plain text with its bare URLs as links, for messages not rendered as
+/// markdown
+fn linkify(text: &str) -> Vec<InlineSegment> {
+    let mut segments = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    let mut current_text = String::new();
+
+    while i < chars.len() {
+        if let Some(end) = bare_url_end(&chars, i) {
+            if !current_text.is_empty() {
+                segments.push(InlineSegment::Text(current_text.clone()));
+                current_text.clear();
+            }
+            segments.push(bare_url_link(&chars[i..end]));
+            i = end;
+            continue;
+        }
+        current_text.push(chars[i]);
+        i += 1;
+    }
+
+    if !current_text.is_empty() {
+        segments.push(InlineSegment::Text(current_text));
+    }
+
+    segments
+}
+
+/// End of the `http(s)://` or `www.` URL starting at `start`, if one does.
+/// The URL must start a word and stops at whitespace; trailing punctuation
+/// and closing brackets it doesn't open are left to the sentence, so
+/// "(see https://a.b/c)." links only `https://a.b/c`.
+fn bare_url_end(chars: &[char], start: usize) -> Option<usize> {
+    if start > 0 && (chars[start - 1].is_alphanumeric() || "/.@:-_".contains(chars[start - 1])) {
+        return None;
+    }
+    let prefix_len = ["https://", "http://", "www."].iter().find_map(|prefix| {
+        let len = prefix.chars().count();
+        let head: String = chars.get(start..start + len)?.iter().collect();
+        head.eq_ignore_ascii_case(prefix).then_some(len)
+    })?;
+
+    let mut end = start;
+    while end < chars.len() && !chars[end].is_whitespace() && !"<>\"`".contains(chars[end]) {
+        end += 1;
+    }
+    while end > start + prefix_len {
+        let last = chars[end - 1];
+        let unbalanced = |open: char, close: char| {
+            last == close && {
+                let url = &chars[start..end];
+                url.iter().filter(|&&c| c == open).count()
+                    < url.iter().filter(|&&c| c == close).count()
+            }
+        };
+        if ".,:;!?'*_".contains(last) || unbalanced('(', ')') || unbalanced('[', ']') {
+            end -= 1;
+        } else {
+            break;
+        }
+    }
+
+    // Nothing after the prefix, or no host ("www." alone, "http://")
+    let host = &chars[start + prefix_len..end];
+    if host.is_empty() || host[0] == '/' || (prefix_len == 4 && !host.contains(&'.')) {
+        return None;
+    }
+    Some(end)
+}
+
+/// Link for a bare URL, `www.` ones opened over https
+fn bare_url_link(url: &[char]) -> InlineSegment {
+    let text: String = url.iter().collect();
+    let href = if text.to_ascii_lowercase().starts_with("www.") {
+        format!("https://{}", text)
+    } else {
+        text.clone()
+    };
+    InlineSegment::Link(text, href)
+}
+
+/// Index of the `$` closing the inline math opened at `open`, if it is math.
+/// As in Pandoc, the opening `$` must be followed by a non-space and the
+/// next unescaped `$` preceded by a non-space and not followed by a digit,
+/// so amounts stay text: in "from $5 to $10" neither dollar is math.
+fn inline_math_end(chars: &[char], open: usize) -> Option<usize> {
+    if !matches!(chars.get(open + 1), Some(c) if !c.is_whitespace()) {
+        return None;
+    }
+    let mut j = open + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '\\' => j += 1,
+            '$' => {
+                let closes = !chars[j - 1].is_whitespace()
+                    && !chars.get(j + 1).is_some_and(|c| c.is_ascii_digit());
+                return closes.then_some(j);
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
 fn render_segment(segment: InlineSegment) -> Element {
     match segment {
         InlineSegment::Text(text) => rsx! { "{text}" },
@@ -801,16 +1081,91 @@ fn render_segment(segment: InlineSegment) -> Element {
                 href: "{url}",
                 target: "_blank",
                 rel: "noopener noreferrer",
-                class: "text-[var(--accent-primary)] hover:underline",
+                class: "text-[var(--accent-primary)] hover:underline break-all",
+                title: "{url}",
                 "{text}"
             }
         },
         InlineSegment::InlineMath(math) => rsx! {
-            code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{math}" }
+            MathView { latex: math, display: false }
         },
     }
 }
 
+/// LaTeX rendered as MathML, or shown as its source when math rendering is
+/// turned off in the settings
+/// Fenced code block, highlighted for its language hint in the app theme
+#[component]
+fn CodeView(lang: String, code: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let dark = app_state.settings.read().theme != "light";
+    let highlighted = highlight_code(&code, &lang, dark);
+    let (background, foreground) = if dark {
+        ("#121110", "#E8E2DB")
+    } else {
+        ("#FBFAF8", "#2A2520")
+    };
+
+    rsx! {
+        div { class: "my-3 rounded-xl overflow-hidden border border-[var(--border-subtle)]",
+            style: "background: {background};",
+            div { class: "code-header group",
+                span { "{lang}" }
+                CopyButton { text: code.clone() }
+            }
+            pre { class: "p-4 overflow-x-auto",
+                if let Some(html) = highlighted {
+                    code { class: "text-[0.9em] font-mono leading-relaxed",
+                        style: "color: {foreground};",
+                        dangerous_inner_html: html,
+                    }
+                } else {
+                    code { class: "text-[0.9em] font-mono leading-relaxed",
+                        style: "color: {foreground};",
+                        "{code}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MathView(latex: String, display: bool) -> Element {
+    let app_state = use_context::<AppState>();
+    let render = app_state.settings.read().render_math;
+
+    if display {
+        rsx! {
+            div { class: "my-4 p-4 rounded-xl bg-[var(--bg-tertiary)]/50 border border-[var(--border-subtle)] overflow-x-auto",
+                if render {
+                    div {
+                        class: "text-[var(--text-primary)]",
+                        title: "{latex}",
+                        dangerous_inner_html: latex_to_mathml(&latex, true),
+                    }
+                } else {
+                    pre { class: "font-mono text-sm text-[var(--accent-primary)] text-center whitespace-pre-wrap",
+                        "{latex}"
+                    }
+                }
+            }
+        }
+    } else if render {
+        rsx! {
+            span {
+                class: "text-[var(--text-primary)]",
+                title: "{latex}",
+                dangerous_inner_html: latex_to_mathml(&latex, false),
+            }
+        }
+    } else {
+        rsx! {
+            code { class: "px-1.5 py-0.5 rounded-md bg-[var(--accent-primary)]/10 text-[var(--accent-primary)] font-mono text-[0.9em] italic", "{latex}" }
+        }
+    }
+}
+
 /// Check if content is a tool-related message
 fn is_tool_message(content: &str) -> Option<ToolMessageType> {
     let trimmed = content.trim();
@@ -1023,15 +1378,234 @@ fn ToolCard(message_type: ToolMessageType, content: String) -> Element {
     }
 }
 
+/// Most matches listed under a search call
+const MAX_SHOWN_MATCHES: usize = 50;
+
+/// The parameter that says what a call acted on (path, command, query...),
+/// shown next to the tool name
+fn params_summary(params: &serde_json::Value) -> Option<String> {
+    let value = params
+        .as_object()?
+        .values()
+        .find_map(|value| value.as_str().filter(|s| !s.trim().is_empty()))?;
+    let line = value.lines().next().unwrap_or_default();
+    Some(if line.chars().count() > 60 || line.len() < value.len() {
+        format!("{}…", line.chars().take(60).collect::<String>())
+    } else {
+        line.to_string()
+    })
+}
+
+/// A recorded tool call: tool name, what it acted on and its outcome, with
+/// the parameters and the result (diff, matches) folded underneath
 #[component]
-pub fn MessageBubble(message: Message) -> Element {
+fn ToolCallBubble(content: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut is_expanded = use_signal(|| false);
+
+    let Some(record) = ToolCallRecord::from_content(&content) else {
+        return rsx! {
+            MarkdownContent { content: content }
+        };
+    };
+
+    let accent_var = if record.success {
+        "var(--success)"
+    } else {
+        "var(--error)"
+    };
+    // Calls the user is asked to approve stand out
+    let approval_level = app_state
+        .agent
+        .tool_registry
+        .get(&record.tool)
+        .filter(|tool| tool.requires_approval())
+        .map(|tool| PermissionLevel::for_tool(tool.as_ref()));
+    let background = if approval_level.is_some() {
+        "var(--warning-bg)"
+    } else {
+        "rgba(var(--accent-rgb), 0.03)"
+    };
+    let summary = params_summary(&record.params);
+    let params = serde_json::to_string_pretty(&record.params).unwrap_or_default();
+    let duration = format!("{:.1}s", record.duration_ms as f64 / 1000.0);
+    let chevron_class = if is_expanded() {
+        "thinking-chevron expanded"
+    } else {
+        "thinking-chevron"
+    };
+
+    rsx! {
+        div {
+            class: "animate-fade-in",
+            style: format!(
+                "margin: 0.35rem 0; padding: 0.4rem 0.5rem; border-left: 2px solid {}; background: linear-gradient(90deg, {} 0%, transparent 100%); border-radius: 0 8px 8px 0;",
+                accent_var, background
+            ),
+
+            // Tool name, target, permission and outcome
+            div {
+                class: "flex items-center gap-2 cursor-pointer",
+                onclick: move |_| is_expanded.set(!is_expanded()),
+                svg {
+                    class: "{chevron_class}",
+                    width: "10",
+                    height: "10",
+                    view_box: "0 0 24 24",
+                    fill: "none",
+                    stroke: "currentColor",
+                    stroke_width: "2.5",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    polyline { points: "9 18 15 12 9 6" }
+                }
+                span {
+                    class: "font-mono text-xs font-medium",
+                    style: format!("color: {};", accent_var),
+                    "{record.tool}"
+                }
+                if let Some(summary) = summary {
+                    span {
+                        class: "font-mono text-[11px] truncate min-w-0",
+                        style: "color: var(--text-tertiary);",
+                        "{summary}"
+                    }
+                }
+                div { class: "flex-1" }
+                if let Some(level) = approval_level {
+                    span {
+                        class: "flex-shrink-0 font-mono text-[10px]",
+                        style: "color: var(--warning);",
+                        title: "{level.label()}",
+                        "{level.icon()} {level}"
+                    }
+                }
+                span {
+                    class: "flex-shrink-0 font-mono text-[10px]",
+                    style: format!("color: {};", accent_var),
+                    if record.success { "✓ {duration}" } else { "✗ {duration}" }
+                }
+            }
+
+            if is_expanded() {
+                // Parameters
+                pre {
+                    class: "font-mono text-[11px] whitespace-pre-wrap mt-1 max-h-32 overflow-y-auto",
+                    style: "color: var(--text-tertiary);",
+                    "{params}"
+                }
+                // Result
+                pre {
+                    class: "text-xs leading-relaxed whitespace-pre-wrap mt-1 max-h-80 overflow-y-auto",
+                    style: "color: var(--text-secondary);",
+                    "{record.result}"
+                }
+                match record.output {
+                    Some(ToolOutput::Diff(diff)) => rsx! {
+                        div { class: "markdown-content mt-1",
+                            CodeView { lang: "diff".to_string(), code: diff }
+                        }
+                    },
+                    Some(ToolOutput::Matches { matches, total }) => rsx! {
+                        div { class: "mt-1 max-h-80 overflow-y-auto font-mono text-[11px]",
+                            for m in matches.into_iter().take(MAX_SHOWN_MATCHES) {
+                                div { class: "flex gap-2",
+                                    span {
+                                        class: "flex-shrink-0",
+                                        style: "color: var(--text-tertiary);",
+                                        "{m.file}:{m.line_number}"
+                                    }
+                                    span {
+                                        class: "truncate min-w-0",
+                                        style: "color: var(--text-secondary);",
+                                        "{m.content}"
+                                    }
+                                }
+                            }
+                            if total > MAX_SHOWN_MATCHES {
+                                div {
+                                    style: "color: var(--text-tertiary);",
+                                    if is_en {
+                                        "… {total - MAX_SHOWN_MATCHES} more"
+                                    } else {
+                                        "… {total - MAX_SHOWN_MATCHES} de plus"
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
+
+/// Whether a generation in progress writes into `message`: an assistant
+/// answer, not a tool card
+pub fn streams_into(message: &Message) -> bool {
+    message.role == MessageRole::Assistant && is_tool_message(&message.content).is_none()
+}
+
+#[component]
+pub fn MessageBubble(
+    message: Message,
+    /// The answer is still being generated into this message
+    #[props(default)]
+    streaming: bool,
+    /// Re-run the answer (assistant messages)
+    on_regenerate: Option<EventHandler<()>>,
+    /// Load the message back into the input, dropping it and what follows
+    /// (user messages)
+    on_edit: Option<EventHandler<()>>,
+) -> Element {
+    let app_state = use_context::<AppState>();
     let is_user = message.role == MessageRole::User;
+    let is_en = app_state.settings.read().language == "en";
+
+    if message.role == MessageRole::Tool {
+        return rsx! {
+            div { class: "message-layout", id: "msg-{message.id}",
+                ToolCallBubble { content: message.content.clone() }
+            }
+        };
+    }
+
+    // Scaffolding the app adds for the model (reflection prompts, summaries):
+    // centered and muted, apart from the conversation
+    if message.role == MessageRole::System {
+        return rsx! {
+            div { class: "message-layout animate-fade-in", id: "msg-{message.id}",
+                div { class: "message-system flex items-start gap-2 mx-auto mb-4 px-3 py-2 max-w-[85%]",
+                    svg {
+                        class: "flex-shrink-0 mt-0.5",
+                        width: "12",
+                        height: "12",
+                        view_box: "0 0 24 24",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: "2",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        circle { cx: "12", cy: "12", r: "3" }
+                        path { d: "M12 1v4M12 19v4M4.2 4.2l2.9 2.9M16.9 16.9l2.9 2.9M1 12h4M19 12h4M4.2 19.8l2.9-2.9M16.9 7.1l2.9-2.9" }
+                    }
+                    div {
+                        class: "text-xs leading-relaxed whitespace-pre-wrap max-h-40 overflow-y-auto",
+                        title: if is_en { "Added by the app for the model" } else { "Ajouté par l'application pour le modèle" },
+                        "{message.content}"
+                    }
+                }
+            }
+        };
+    }
 
     // Check if this is a tool-related message
     if !is_user {
         if let Some(tool_type) = is_tool_message(&message.content) {
             return rsx! {
-                div { class: "message-layout",
+                div { class: "message-layout", id: "msg-{message.id}",
                     ToolCard {
                         message_type: tool_type,
                         content: message.content.clone()
@@ -1041,8 +1615,22 @@ pub fn MessageBubble(message: Message) -> Element {
         }
     }
 
+    // What "Copy" puts on the clipboard: the answer's markdown, without
+    // the reasoning
+    let mut copy_text = String::new();
     let content_parts = if !is_user {
-        parse_thinking_blocks(&message.content)
+        let mut tags = default_reasoning_tags();
+        tags.extend(app_state.reasoning_tags.read().iter().cloned());
+        let parts = parse_thinking_blocks(&message.content, &tags);
+        for part in &parts {
+            if let ContentPart::Text(text) = part {
+                if !copy_text.is_empty() {
+                    copy_text.push_str("\n\n");
+                }
+                copy_text.push_str(text.trim());
+            }
+        }
+        parts
     } else {
         vec![ContentPart::Text(message.content.clone())]
     };
@@ -1050,22 +1638,38 @@ pub fn MessageBubble(message: Message) -> Element {
     if is_user {
         // User message — right-aligned, accent-tinted glass
         rsx! {
-            div { class: "message-layout animate-fade-in-up",
+            div { class: "message-layout animate-fade-in-up group", id: "msg-{message.id}",
                 div { class: "flex justify-end mb-4",
                     div {
                         class: "message-user px-4 py-3 max-w-[85%]",
                         div {
-                            class: "text-[15px] leading-relaxed text-[var(--text-primary)]",
-                            "{message.content}"
+                            class: "text-chat leading-relaxed text-[var(--text-primary)]",
+                            {linkify(&message.content).into_iter().map(render_segment)}
                         }
                     }
                 }
+                div { class: "flex justify-end items-start gap-2",
+                    CopyButton { text: message.content.clone() }
+                    if let Some(on_edit) = on_edit {
+                        MessageAction {
+                            label: if is_en { "Edit" } else { "Modifier" },
+                            onclick: on_edit,
+                        }
+                    }
+                    MessageTime { timestamp: message.timestamp, align_right: true }
+                }
             }
         }
     } else {
+        // The cursor follows the answer text, a live thinking block has its
+        // own indicator
+        let cursor_part = match content_parts.last() {
+            Some(ContentPart::Text(_)) if streaming => content_parts.len() - 1,
+            _ => usize::MAX,
+        };
         // Assistant message — with small avatar, no bubble
         rsx! {
-            div { class: "message-layout animate-fade-in-up",
+            div { class: "message-layout animate-fade-in-up group", id: "msg-{message.id}",
                 div { class: "flex items-start gap-3 mb-4",
                     // LocalClaw avatar — small circle with gradient
                     div {
@@ -1073,7 +1677,7 @@ pub fn MessageBubble(message: Message) -> Element {
                         style: "background: var(--accent-primary); box-shadow: 0 4px 12px -4px var(--accent-glow);",
                         svg {
                             class: "w-3 h-3",
-                            style: "color: #F2EDE7;",
+                            style: "color: var(--accent-text);",
                             view_box: "0 0 24 24",
                             fill: "none",
                             stroke: "currentColor",
@@ -1087,7 +1691,7 @@ pub fn MessageBubble(message: Message) -> Element {
                     // Content
                     div {
                         class: "flex-1 min-w-0",
-                        for part in content_parts {
+                        for (i, part) in content_parts.into_iter().enumerate() {
                             match part {
                                 ContentPart::Thinking(text) => rsx! {
                                     ThinkingBlock { content: text }
@@ -1096,13 +1700,485 @@ pub fn MessageBubble(message: Message) -> Element {
                                     ThinkingBlockStreaming { content: text }
                                 },
                                 ContentPart::Text(text) => rsx! {
-                                    MarkdownContent { content: text }
+                                    MarkdownContent { content: text, streaming: i == cursor_part }
                                 },
                             }
                         }
+                        div { class: "flex items-start gap-2",
+                            MessageTime { timestamp: message.timestamp, align_right: false }
+                            if !copy_text.is_empty() {
+                                CopyButton { text: copy_text }
+                            }
+                            if let Some(on_regenerate) = on_regenerate {
+                                MessageAction {
+                                    label: if is_en { "Regenerate" } else { "Régénérer" },
+                                    onclick: on_regenerate,
+                                }
+                            }
+                        }
                     }
                 }
             }
         }
     }
 }
+
+/// Small text button under a message, shown while the message is hovered
+#[component]
+fn MessageAction(label: String, onclick: EventHandler<()>) -> Element {
+    rsx! {
+        button {
+            class: "text-[10px] mt-1 opacity-0 group-hover:opacity-100 transition-opacity hover:text-[var(--accent-primary)]",
+            style: "color: var(--text-tertiary);",
+            onclick: move |_| onclick.call(()),
+            "{label}"
+        }
+    }
+}
+
+/// Hover "Copy" button putting `text` on the clipboard, showing "Copied!"
+/// for a moment afterwards
+#[component]
+fn CopyButton(text: String) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut copied = use_signal(|| false);
+
+    let label = match (copied(), is_en) {
+        (true, true) => "Copied!",
+        (true, false) => "Copié !",
+        (false, true) => "Copy",
+        (false, false) => "Copier",
+    };
+
+    rsx! {
+        button {
+            class: if copied() {
+                "text-[10px] mt-1 transition-opacity text-[var(--accent-primary)]"
+            } else {
+                "text-[10px] mt-1 opacity-0 group-hover:opacity-100 transition-opacity hover:text-[var(--accent-primary)]"
+            },
+            style: if !copied() { "color: var(--text-tertiary);" },
+            onclick: move |_| {
+                match crate::system::clipboard::copy_text(&text) {
+                    Ok(()) => {
+                        copied.set(true);
+                        spawn(async move {
+                            tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+                            copied.set(false);
+                        });
+                    }
+                    Err(e) => tracing::warn!("Failed to copy to the clipboard: {}", e),
+                }
+            },
+            "{label}"
+        }
+    }
+}
+
+/// Small send time under a message, with the full date on hover
+#[component]
+fn MessageTime(timestamp: DateTime<Utc>, align_right: bool) -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    if !app_state.settings.read().show_timestamps {
+        return rsx! {};
+    }
+
+    let label = format_message_time(timestamp, Utc::now(), is_en);
+    let full = timestamp
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S (UTC%:z)")
+        .to_string();
+    let align = if align_right {
+        "text-right -mt-3 mb-3"
+    } else {
+        "mt-1"
+    };
+
+    rsx! {
+        div {
+            class: "text-[10px] select-none {align}",
+            style: "color: var(--text-tertiary);",
+            title: "{full}",
+            "{label}"
+        }
+    }
+}
+
+/// "just now" / "5 min ago" within the hour, the time of day for today,
+/// and the date for anything older
+pub fn format_message_time(timestamp: DateTime<Utc>, now: DateTime<Utc>, is_en: bool) -> String {
+    let elapsed = now.signed_duration_since(timestamp);
+    let local = timestamp.with_timezone(&Local);
+    if elapsed.num_seconds() < 60 {
+        if is_en { "just now" } else { "à l'instant" }.to_string()
+    } else if elapsed.num_minutes() < 60 {
+        if is_en {
+            format!("{} min ago", elapsed.num_minutes())
+        } else {
+            format!("il y a {} min", elapsed.num_minutes())
+        }
+    } else if local.date_naive() == now.with_timezone(&Local).date_naive() {
+        local.format("%H:%M").to_string()
+    } else if is_en {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        local.format("%d/%m/%Y %H:%M").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thinking(text: &str) -> ContentPart {
+        ContentPart::Thinking(text.to_string())
+    }
+
+    fn text(text: &str) -> ContentPart {
+        ContentPart::Text(text.to_string())
+    }
+
+    fn parse(content: &str) -> Vec<ContentPart> {
+        parse_thinking_blocks(content, &default_reasoning_tags())
+    }
+
+    #[test]
+    fn test_inline_math_detection() {
+        use InlineSegment::{Code, InlineMath, Text};
+        let s = |s: &str| s.to_string();
+
+        assert_eq!(
+            parse_inline_markdown(r"Area is $\pi r^2$."),
+            vec![Text(s("Area is ")), InlineMath(s(r"\pi r^2")), Text(s("."))]
+        );
+        // Amounts are not math, escaped dollars and code spans neither
+        assert_eq!(
+            parse_inline_markdown("from $5 to $10"),
+            vec![Text(s("from $5 to $10"))]
+        );
+        assert_eq!(
+            parse_inline_markdown("costs $5 and $x$"),
+            vec![Text(s("costs $5 and ")), InlineMath(s("x"))]
+        );
+        assert_eq!(
+            parse_inline_markdown(r"\$x\$ and `$y$`"),
+            vec![Text(s("$x$ and ")), Code(s("$y$"))]
+        );
+        assert_eq!(
+            parse_inline_markdown(r"so \(a+b\) holds"),
+            vec![Text(s("so ")), InlineMath(s("a+b")), Text(s(" holds"))]
+        );
+
+        let blocks = parse_markdown_blocks("Then\n\\[\nx^2\n\\]\ndone");
+        assert!(matches!(&blocks[1], MarkdownBlock::MathBlock(m) if m == "x^2"));
+        assert_eq!(blocks.len(), 3);
+    }
+
+    #[test]
+    fn test_bare_urls() {
+        use InlineSegment::{Code, Link, Text};
+        let s = |s: &str| s.to_string();
+        let link = |url: &str| Link(s(url), s(url));
+
+        assert_eq!(
+            parse_inline_markdown("See https://docs.rs/regex, or (http://a.io/x_(y))."),
+            vec![
+                Text(s("See ")),
+                link("https://docs.rs/regex"),
+                Text(s(", or (")),
+                link("http://a.io/x_(y)"),
+                Text(s(")."))
+            ]
+        );
+        assert_eq!(
+            parse_inline_markdown("Go to www.rust-lang.org!"),
+            vec![
+                Text(s("Go to ")),
+                Link(s("www.rust-lang.org"), s("https://www.rust-lang.org")),
+                Text(s("!"))
+            ]
+        );
+        // Code spans, markdown links and words that only contain a URL-like
+        // part are left alone
+        assert_eq!(
+            parse_inline_markdown("`curl https://x.io` [site](https://y.io)"),
+            vec![
+                Code(s("curl https://x.io")),
+                Text(s(" ")),
+                Link(s("site"), s("https://y.io"))
+            ]
+        );
+        assert_eq!(
+            linkify("mailto:me@www.x.io, https:// and www. alone"),
+            vec![Text(s("mailto:me@www.x.io, https:// and www. alone"))]
+        );
+        // Fenced code stays a code block
+        let blocks = parse_markdown_blocks("```\nhttps://x.io\n```");
+        assert!(matches!(&blocks[0], MarkdownBlock::CodeBlock(_, code) if code == "https://x.io"));
+    }
+
+    #[test]
+    fn test_gfm_tables() {
+        let blocks = parse_markdown_blocks(
+            "Results:\n| Name | Qty | Price |\n|:-----|:---:|------:|\n| `a|b` | 2 | 1.50 |\n| c \\| d | 3 |\n\nAfter",
+        );
+        assert!(matches!(&blocks[0], MarkdownBlock::Paragraph(p) if p == "Results:"));
+        let MarkdownBlock::Table {
+            headers,
+            alignments,
+            rows,
+        } = &blocks[1]
+        else {
+            panic!("expected a table, got {:?}", blocks[1]);
+        };
+        assert_eq!(headers, &["Name", "Qty", "Price"]);
+        assert_eq!(
+            alignments,
+            &[ColumnAlign::Left, ColumnAlign::Center, ColumnAlign::Right]
+        );
+        assert_eq!(rows[0], ["`a|b`", "2", "1.50"]);
+        // Escaped pipe kept, missing cell padded
+        assert_eq!(rows[1], ["c | d", "3", ""]);
+        assert!(matches!(&blocks[2], MarkdownBlock::Paragraph(p) if p == "After"));
+
+        // Outer pipes are optional, but the separator must match the header
+        let blocks = parse_markdown_blocks("a | b\n--- | ---\n1 | 2");
+        assert!(matches!(&blocks[0], MarkdownBlock::Table { rows, .. } if rows.len() == 1));
+        let blocks = parse_markdown_blocks("| a | b |\n|---|\n| 1 | 2 |");
+        assert!(!matches!(blocks[0], MarkdownBlock::Table { .. }));
+    }
+
+    #[test]
+    fn test_system_message_round_trip() {
+        let stored = crate::types::message::Message::new(
+            crate::types::message::Role::System,
+            "📋 Summary of the earlier conversation",
+        );
+        let shown: Message = stored.clone().into();
+        assert_eq!(shown.role, MessageRole::System);
+        assert_eq!(shown.id, stored.id);
+        assert_eq!(crate::types::message::Message::from(shown), stored);
+    }
+
+    #[test]
+    fn test_regenerate_cut() {
+        let msg = |role: MessageRole| Message {
+            id: Uuid::new_v4(),
+            role,
+            content: String::new(),
+            timestamp: Utc::now(),
+        };
+        let messages = vec![
+            msg(MessageRole::User),
+            msg(MessageRole::Assistant),
+            msg(MessageRole::User),
+            msg(MessageRole::Tool),
+            msg(MessageRole::System),
+            msg(MessageRole::Assistant),
+        ];
+        assert_eq!(regenerate_cut(&messages, 5), Some(3));
+        assert_eq!(regenerate_cut(&messages, 1), Some(1));
+        assert_eq!(regenerate_cut(&messages, 0), None);
+    }
+
+    #[test]
+    fn test_streams_into() {
+        let msg = |role: MessageRole, content: &str| Message {
+            id: Uuid::new_v4(),
+            role,
+            content: content.to_string(),
+            timestamp: Utc::now(),
+        };
+        assert!(streams_into(&msg(MessageRole::Assistant, "")));
+        assert!(streams_into(&msg(MessageRole::Assistant, "<think>Let me")));
+        assert!(!streams_into(&msg(
+            MessageRole::Assistant,
+            "✅ `file_read` (0.1s): ok"
+        )));
+        assert!(!streams_into(&msg(MessageRole::Tool, "")));
+        assert!(!streams_into(&msg(MessageRole::User, "")));
+    }
+
+    #[test]
+    fn test_params_summary() {
+        assert_eq!(
+            params_summary(&serde_json::json!({ "path": "src/main.rs", "limit": 10 })),
+            Some("src/main.rs".to_string())
+        );
+        assert_eq!(
+            params_summary(&serde_json::json!({ "command": "cargo build\ncargo test" })),
+            Some("cargo build…".to_string())
+        );
+        assert_eq!(params_summary(&serde_json::json!({ "limit": 10 })), None);
+        assert_eq!(params_summary(&serde_json::json!(null)), None);
+    }
+
+    #[test]
+    fn test_unterminated_think_is_streaming() {
+        assert_eq!(
+            parse("Hello <think>weighing options"),
+            vec![
+                text("Hello "),
+                ContentPart::ThinkingStreaming("weighing options".into())
+            ]
+        );
+        // Neither half of a tag split across chunks is shown raw
+        assert_eq!(
+            parse("<think>almost done</thi"),
+            vec![ContentPart::ThinkingStreaming("almost done".into())]
+        );
+        assert_eq!(parse("Sure. <thin"), vec![text("Sure. ")]);
+    }
+
+    #[test]
+    fn test_streamed_prefixes_never_show_tags() {
+        for tag in default_reasoning_tags() {
+            let full = format!(
+                "Intro {open}step one {open}aside{close} step two{close}The answer.",
+                open = tag.open,
+                close = tag.close
+            );
+            let answer_start = full.find("The answer").unwrap();
+            for (end, _) in full.char_indices().skip(1) {
+                let prefix = &full[..end];
+                let parts = parse(prefix);
+                for part in &parts {
+                    let shown = match part {
+                        ContentPart::Text(text) => text,
+                        ContentPart::Thinking(text) | ContentPart::ThinkingStreaming(text) => {
+                            // Nested tags are part of the reasoning, but the
+                            // outer ones never are
+                            assert!(!text.starts_with(&tag.open[..2]), "{:?}", prefix);
+                            continue;
+                        }
+                    };
+                    assert!(
+                        !shown.contains(&tag.open[..2]),
+                        "{:?} -> {:?}",
+                        prefix,
+                        parts
+                    );
+                    assert!(!shown.contains("</"), "{:?} -> {:?}", prefix, parts);
+                    assert!(!shown.contains("step"), "{:?} -> {:?}", prefix, parts);
+                }
+                // The reasoning is live until the outer block closes
+                let streaming = matches!(parts.last(), Some(ContentPart::ThinkingStreaming(_)));
+                let opened = prefix.len() >= "Intro ".len() + tag.open.len();
+                assert_eq!(streaming, opened && end < answer_start, "{:?}", prefix);
+            }
+        }
+    }
+
+    #[test]
+    fn test_consecutive_think_blocks() {
+        assert_eq!(
+            parse("<think>first</think>A<thinking>second</thinking>B"),
+            vec![thinking("first"), text("A"), thinking("second"), text("B")]
+        );
+        assert_eq!(
+            parse("<think>one</think>\n<think>two</think>"),
+            vec![thinking("one"), thinking("two")]
+        );
+    }
+
+    #[test]
+    fn test_interleaved_reasoning_and_answer() {
+        assert_eq!(
+            parse("Intro<think>a</think>B<think>c <think>d</think></think>E<think>f"),
+            vec![
+                text("Intro"),
+                thinking("a"),
+                text("B"),
+                thinking("c <think>d</think>"),
+                text("E"),
+                ContentPart::ThinkingStreaming("f".to_string()),
+            ]
+        );
+        // A block of another style nested inside stays part of the outer one
+        assert_eq!(
+            parse("<thinking>x <think>y</think> z</thinking>answer"),
+            vec![thinking("x <think>y</think> z"), text("answer")]
+        );
+    }
+
+    #[test]
+    fn test_think_block_without_surrounding_text() {
+        assert_eq!(
+            parse("<think>just thoughts</think>\n"),
+            vec![thinking("just thoughts")]
+        );
+        assert_eq!(parse("<think></think>"), vec![text("")]);
+    }
+
+    #[test]
+    fn test_nested_and_orphan_tags() {
+        assert_eq!(
+            parse("<think>outer <think>inner</think> back</think>answer"),
+            vec![thinking("outer <think>inner</think> back"), text("answer")]
+        );
+        assert_eq!(
+            parse("reasoning from the template</think>answer"),
+            vec![thinking("reasoning from the template"), text("answer")]
+        );
+        assert_eq!(parse("plain text"), vec![text("plain text")]);
+    }
+
+    #[test]
+    fn test_reasoning_tag_styles() {
+        assert_eq!(
+            parse("<thinking>plan</thinking>Done."),
+            vec![thinking("plan"), text("Done.")]
+        );
+        assert_eq!(
+            parse("Intro <reasoning>why</reasoning> then <|thinking|>how<|/thinking|> end"),
+            vec![
+                text("Intro "),
+                thinking("why"),
+                text(" then "),
+                thinking("how"),
+                text(" end")
+            ]
+        );
+        assert_eq!(
+            parse("<|thinking|>still going"),
+            vec![ContentPart::ThinkingStreaming("still going".into())]
+        );
+
+        // Markers declared by the model's chat template
+        let mut tags = default_reasoning_tags();
+        tags.push(ReasoningTag::new(
+            "<|begin_of_thought|>",
+            "<|end_of_thought|>",
+        ));
+        assert_eq!(
+            parse_thinking_blocks("<|begin_of_thought|>hmm<|end_of_thought|>Yes", &tags),
+            vec![thinking("hmm"), text("Yes")]
+        );
+        assert_eq!(
+            parse("<|begin_of_thought|>hmm<|end_of_thought|>Yes"),
+            vec![text("<|begin_of_thought|>hmm<|end_of_thought|>Yes")]
+        );
+    }
+
+    #[test]
+    fn test_format_message_time() {
+        let now = Local::now().with_timezone(&Utc);
+        assert_eq!(format_message_time(now, now, true), "just now");
+        assert_eq!(
+            format_message_time(now - chrono::Duration::minutes(5), now, false),
+            "il y a 5 min"
+        );
+        let old = now - chrono::Duration::days(40);
+        let local = old.with_timezone(&Local);
+        assert_eq!(
+            format_message_time(old, now, true),
+            local.format("%Y-%m-%d %H:%M").to_string()
+        );
+        assert_eq!(
+            format_message_time(old, now, false),
+            local.format("%d/%m/%Y %H:%M").to_string()
+        );
+    }
+}
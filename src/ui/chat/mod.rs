@@ -3,35 +3,43 @@
 //! Contains the main chat view, message display, and input components.
 //! Implements an advanced agentic loop inspired by Claude Code and OpenCode.
 
+pub mod context_meter;
+pub mod highlight;
 pub mod input;
+pub mod math;
 pub mod message;
 
 use dioxus::prelude::*;
 use input::ChatInput;
-use message::{Message, MessageBubble, MessageRole};
+use message::{streams_into, Message, MessageBubble, MessageRole};
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use crate::agent::{
     extract_tool_call,
     format_tool_result_for_system,
-    get_tool_permission,
-    PermissionRequest,
-    PermissionResult,
-    PermissionDecision,
+    ApprovalHandler,
+    AutoApprove,
+    PermissionLevel,
+    PermissionPrompt,
     AgentContext,
     AgentState,
 };
 use crate::agent::loop_runner::ToolHistoryEntry;
-use crate::agent::tools::ToolResult;
+use crate::agent::tools::{execute_with_timeout, ToolContext, ToolError, ToolResult};
+use tokio_util::sync::CancellationToken;
 use crate::agent::prompts::build_agent_system_prompt;
 use crate::agent::prompts::build_reflection_prompt;
 use crate::agent::prompts::build_context_compression_prompt;
 use crate::agent::prompts::build_title_generation_prompt;
 use crate::app::{AppState, ModelState};
 use crate::inference::engine::GenerationParams;
+use crate::inference::reasoning::{default_reasoning_tags, strip_reasoning};
 use crate::inference::streaming::StreamToken;
-use crate::storage::conversations::save_conversation;
-use crate::types::message::{Message as StorageMessage, Role as StorageRole};
+use crate::storage::conversations::{list_conversations, save_conversation};
+use crate::types::message::{
+    Message as StorageMessage, Role as StorageRole, ToolCallRecord, ToolOutput,
+};
 use chrono::Utc;
 use uuid::Uuid;
 use std::time::Instant;
@@ -86,6 +94,43 @@ fn is_garbage_text(content: &str) -> bool {
     false
 }
 
+/// Partial results shown under a running tool's indicator
+const MAX_LIVE_LINES: usize = 20;
+
+/// One line per item of a tool's progress update (`Tool::execute_streaming`):
+/// `file:line  text` for search matches
+fn progress_lines(update: &serde_json::Value) -> Vec<String> {
+    update["matches"]
+        .as_array()
+        .map(|matches| {
+            matches
+                .iter()
+                .map(|m| {
+                    format!(
+                        "`{}:{}`  {}",
+                        m["file"].as_str().unwrap_or(""),
+                        m["line_number"],
+                        crate::truncate_str(m["content"].as_str().unwrap_or(""), 120)
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The indicator followed by the latest partial results
+fn live_progress(indicator: &str, lines: &[String]) -> String {
+    let shown = &lines[lines.len().saturating_sub(MAX_LIVE_LINES)..];
+    let mut content = format!("{}\n\n", indicator);
+    if shown.len() < lines.len() {
+        content.push_str(&format!("… {} de plus\n", lines.len() - shown.len()));
+    }
+    for line in shown {
+        content.push_str(&format!("- {}\n", line));
+    }
+    content
+}
+
 /// Estimate token count from message content (~4 chars per token)
 #[allow(dead_code)]
 fn estimate_tokens(messages: &[Message]) -> usize {
@@ -296,6 +341,8 @@ pub fn apply_hierarchical_compression(
                 // Build new message list with summary
                 let summary_msg = Message {
                     role: MessageRole::System,
+                    timestamp: Utc::now(),
+                    id: Uuid::new_v4(),
                     content: format!(
                         "[{} messages compressed via incremental summarization]",
                         middle_count
@@ -354,6 +401,8 @@ pub fn apply_hierarchical_compression(
             if !anchor_content.is_empty() {
                 messages.push(Message {
                     role: MessageRole::System,
+                    timestamp: Utc::now(),
+                    id: Uuid::new_v4(),
                     content: format!(
                         "[{} previous messages archived - critical context preserved]{}",
                         msg_count.saturating_sub(keep_recent),
@@ -421,33 +470,23 @@ pub fn ChatView() -> Element {
         });
     }
 
-    // Handler for sending a message
-    let handle_send = {
+    // Starts a run over the current history (send, regenerate). Sampling
+    // settings are read from the settings at the start of every run.
+    let start_generation = {
         let mut messages = messages.clone();
-        let _is_generating = is_generating.clone();
         let mut app_state = app_state.clone();
-        move |text: String| {
-            if !matches!(*app_state.model_state.read(), ModelState::Loaded(_)) {
-                messages.write().push(Message {
-                    role: MessageRole::Assistant,
-                    content: "Model not loaded. Please select and load a model first.".to_string(),
-                });
-                return;
-            }
-
-            // Add user message immediately
-            messages.write().push(Message {
-                role: MessageRole::User,
-                content: text,
-            });
-
+        move || {
             // Add empty assistant message to stream into
             messages.write().push(Message {
                 role: MessageRole::Assistant,
+                timestamp: Utc::now(),
+                id: Uuid::new_v4(),
                 content: String::new(),
             });
 
             app_state.stop_signal.store(false, Ordering::Relaxed);
+            let tool_cancel = CancellationToken::new();
+            *app_state.tool_cancel.lock().unwrap() = tool_cancel.clone();
             app_state.is_generating.set(true);
 
             let mut messages = messages.clone();
@@ -459,14 +498,15 @@ pub fn ChatView() -> Element {
                 let mut agent_ctx = AgentContext::new();
                 agent_ctx.state = AgentState::Analyzing;
                 
-                let (params, base_system_prompt, tools_enabled, tool_timeout_secs, max_iterations) = {
+                let (params, base_system_prompt, tools_enabled, tool_timeout_secs, tool_output_max_kb, max_iterations) = {
                     let settings = app_state.settings.read();
                     let params = GenerationParams {
                         max_tokens: settings.max_tokens,
                         temperature: settings.temperature,
                         top_k: settings.top_k,
                         top_p: settings.top_p,
-                        repeat_penalty: 1.1,
+                        min_p: settings.min_p,
+                        repeat_penalty: settings.repeat_penalty,
                         seed: 0,
                         max_context_size: settings.context_size,
                     };
@@ -475,7 +515,8 @@ pub fn ChatView() -> Element {
                         params,
                         settings.system_prompt.clone(),
                         app_state.agent.config.enable_tools,
-                        app_state.agent.config.tool_timeout_secs,
+                        settings.tool_timeout_secs,
+                        settings.tool_output_max_kb,
                         app_state.agent.config.loop_config.max_iterations,
                     )
                 };
@@ -506,6 +547,8 @@ pub fn ChatView() -> Element {
                         let mut msgs = messages.write();
                         msgs.push(Message {
                             role: MessageRole::Assistant,
+                            timestamp: Utc::now(),
+                            id: Uuid::new_v4(),
                             content: "⚠️ J'ai détecté que je répète les mêmes actions. Laisse-moi reformuler ma réponse.".to_string(),
                         });
                         break;
@@ -516,6 +559,8 @@ pub fn ChatView() -> Element {
                         let mut msgs = messages.write();
                         msgs.push(Message {
                             role: MessageRole::Assistant,
+                            timestamp: Utc::now(),
+                            id: Uuid::new_v4(),
                             content: "⏱️ Temps d'exécution maximal atteint. Voici ce que j'ai trouvé jusqu'à présent.".to_string(),
                         });
                         break;
@@ -531,6 +576,9 @@ pub fn ChatView() -> Element {
                         {
                             history.pop();
                         }
+                        // Tool records are for the transcript: the model gets
+                        // each result from the system message that follows it
+                        history.retain(|m| m.role != MessageRole::Tool);
 
                         // Keep more history for better context
                         let max_history = 40usize;
@@ -602,6 +650,8 @@ pub fn ChatView() -> Element {
                             // Notify user
                             messages.write().push(Message {
                                 role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: format!(
                                     "💾 Hierarchical compression applied (tier: {}, ~{} chars saved).",
                                     tier.name(),
@@ -625,6 +675,8 @@ pub fn ChatView() -> Element {
                                 agent_ctx.consecutive_errors += 1;
                                 messages.write().push(Message {
                                     role: MessageRole::Assistant,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: format!("❌ Erreur de génération: {e}"),
                                 });
                                 if agent_ctx.consecutive_errors >= 3 {
@@ -765,6 +817,8 @@ pub fn ChatView() -> Element {
                             // Notify user
                             messages.write().push(Message {
                                 role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: format!(
                                     "💾 Post-truncation compression applied (tier: {}, ~{} chars saved).",
                                     tier.name(),
@@ -838,6 +892,7 @@ pub fn ChatView() -> Element {
                                             MessageRole::User => "U",
                                             MessageRole::Assistant => "A",
                                             MessageRole::System => "S",
+                                            MessageRole::Tool => "T",
                                         };
                                         let content = if m.content.len() > 200 {
                                             format!("{}...", &m.content[..200])
@@ -894,6 +949,8 @@ pub fn ChatView() -> Element {
                                 
                                 msgs.push(Message {
                                     role: MessageRole::System,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: format!("📋 {}", summary),
                                 });
                                 
@@ -905,6 +962,8 @@ pub fn ChatView() -> Element {
                                 
                                 msgs.push(Message {
                                     role: MessageRole::Assistant,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: String::new(),
                                 });
                             }
@@ -916,6 +975,13 @@ pub fn ChatView() -> Element {
                         }
                     }
 
+                    // Stopped by the user: keep the partial answer as it is,
+                    // without acting on a half-written tool call
+                    if app_state.stop_signal.load(Ordering::Relaxed) {
+                        tracing::info!("Generation stopped by user");
+                        break;
+                    }
+
                     // Check if stream ended with errors
                     let last_content = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
                     let had_stream_error = last_content.contains("❌ Erreur:");
@@ -925,10 +991,14 @@ pub fn ChatView() -> Element {
                         if agent_ctx.consecutive_errors < 3 {
                             messages.write().push(Message {
                                 role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: "Une erreur est survenue pendant la génération. Reformule ta réponse ou essaie une approche différente.".to_string(),
                             });
                             messages.write().push(Message {
                                 role: MessageRole::Assistant,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: String::new(),
                             });
                             continue;
@@ -975,10 +1045,14 @@ pub fn ChatView() -> Element {
                                 agent_ctx.consecutive_errors += 1;
                                 messages.write().push(Message {
                                     role: MessageRole::System,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: "Le format JSON de l'appel d'outil était invalide. Rappel: utilise exactement ce format sans texte avant ni après:\n```json\n{\"tool\": \"nom_outil\", \"params\": {...}}\n```\nRéessaie avec le bon format.".to_string(),
                                 });
                                 messages.write().push(Message {
                                     role: MessageRole::Assistant,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: String::new(),
                                 });
                                 continue;
@@ -1002,8 +1076,54 @@ pub fn ChatView() -> Element {
                         }
                     }
 
-                    // Permission check
-                    let permission_level = get_tool_permission(&tool_call.tool);
+                    // A disabled tool is refused before the user is asked about it
+                    let tool = match app_state.agent.tool_registry.get_enabled(&tool_call.tool) {
+                        Ok(tool) => tool,
+                        Err(e) => {
+                            agent_ctx.consecutive_errors += 1;
+                            let disabled = matches!(e, ToolError::Disabled(_));
+                            let mut msgs = messages.write();
+                            if let Some(last) = msgs.last_mut() {
+                                last.content = if disabled {
+                                    format!("🚫 Outil désactivé: `{}`.", tool_call.tool)
+                                } else {
+                                    format!("❌ Outil introuvable: `{}`.", tool_call.tool)
+                                };
+                            }
+                            // Let the LLM try a different tool
+                            let available_tools: Vec<String> = app_state.agent.tool_registry.list_tools().iter().map(|t| t.name.clone()).collect();
+                            let reason = if disabled {
+                                "est désactivé dans les paramètres"
+                            } else {
+                                "n'existe pas"
+                            };
+                            msgs.push(Message {
+                                role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
+                                content: format!(
+                                    "L'outil `{}` {}. Voici les outils disponibles: {}. Utilise un des outils existants ou réponds directement.",
+                                    tool_call.tool,
+                                    reason,
+                                    available_tools.join(", ")
+                                ),
+                            });
+                            msgs.push(Message {
+                                role: MessageRole::Assistant,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
+                                content: String::new(),
+                            });
+                            if agent_ctx.consecutive_errors >= 3 {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+
+                    // Tools that require approval wait in the executor for
+                    // the permission dialog, unless the settings approve them
+                    let permission_level = PermissionLevel::for_tool(tool.as_ref());
                     let target = tool_call
                         .params
                         .get("path")
@@ -1015,89 +1135,92 @@ pub fn ChatView() -> Element {
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| tool_call.params.to_string());
 
-                    let permission_request = PermissionRequest {
-                        id: Uuid::new_v4(),
-                        tool_name: tool_call.tool.clone(),
-                        operation: "execute".to_string(),
-                        target: target.clone(),
-                        level: permission_level,
-                        params: tool_call.params.clone(),
-                        timestamp: Utc::now(),
-                    };
-
-                    // Check auto-approve settings before asking user
                     // Internal safe tools are always auto-approved
                     let is_internal_safe_tool = matches!(tool_call.tool.as_str(),
                         "skill_create" | "skill_invoke" | "skill_list" | "think" | "todo_write"
                     );
-                    let auto_approved = {
+                    // A destructive command is confirmed whatever the settings say
+                    let confirmation = tool.confirmation_reason(&tool_call.params);
+                    let auto_approved = confirmation.is_none() && {
                         let settings = app_state.settings.read();
                         settings.auto_approve_all_tools
                             || settings.tool_allowlist.contains(&tool_call.tool)
                             || is_internal_safe_tool
                     };
                     tracing::info!("Tool {} permission check: level={:?}, auto_approved={}", tool_call.tool, permission_level, auto_approved);
-
-                    let permission_result = if auto_approved {
-                        PermissionResult::Approved
+                    let approval: Arc<dyn ApprovalHandler> = if auto_approved {
+                        Arc::new(AutoApprove)
                     } else {
-                        app_state
-                            .agent
-                            .permission_manager
-                            .request_permission(permission_request.clone())
-                            .await
+                        Arc::new(PermissionPrompt {
+                            manager: app_state.agent.permission_manager.clone(),
+                            level: permission_level,
+                        })
                     };
 
-                    let approved = match permission_result {
-                        PermissionResult::Approved => true,
-                        PermissionResult::Pending => {
-                            agent_ctx.state = AgentState::WaitingForUser;
-                            tracing::info!("Waiting for user approval for tool: {}", tool_call.tool);
-                            {
+                    let indicator = messages.read().last().map(|m| m.content.clone()).unwrap_or_default();
+                    if (tool.requires_approval() || confirmation.is_some()) && !auto_approved {
+                        agent_ctx.state = AgentState::WaitingForUser;
+                        let mut msgs = messages.write();
+                        if let Some(last) = msgs.last_mut() {
+                            last.content = format!(
+                                "⏳ Autorisation requise pour `{}` ({}).\nCible: {}",
+                                tool_call.tool,
+                                confirmation.as_deref().unwrap_or(permission_level.label()),
+                                target
+                            );
+                        }
+                    }
+
+                    tracing::info!("Executing tool: {} with timeout {}s", tool_call.tool, tool_timeout_secs);
+                    let start_time = Instant::now();
+                    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+                    let tool_ctx = ToolContext::default()
+                        .with_timeout(std::time::Duration::from_secs(tool_timeout_secs))
+                        .with_max_output_bytes(tool_output_max_kb as usize * 1024)
+                        .with_cancellation(tool_cancel.clone())
+                        .with_audit_log(app_state.agent.audit_log.clone())
+                        .with_approval(approval)
+                        .with_progress(progress_tx);
+                    // Partial results (search matches) fill in under the
+                    // indicator while the tool runs
+                    let mut live_lines: Vec<String> = Vec::new();
+                    let execution = execute_with_timeout(tool.as_ref(), tool_call.params.clone(), &tool_ctx);
+                    tokio::pin!(execution);
+                    let outcome = loop {
+                        tokio::select! {
+                            outcome = &mut execution => break outcome,
+                            Some(update) = progress_rx.recv() => {
+                                live_lines.extend(progress_lines(&update));
                                 let mut msgs = messages.write();
                                 if let Some(last) = msgs.last_mut() {
-                                    last.content = format!(
-                                        "⏳ Autorisation requise pour `{}` ({}).\nCible: {}",
-                                        tool_call.tool,
-                                        permission_level.label(),
-                                        target
-                                    );
+                                    last.content = live_progress(&indicator, &live_lines);
                                 }
                             }
-
-                            match app_state
-                                .agent
-                                .permission_manager
-                                .wait_for_decision(
-                                    permission_request.id,
-                                    std::time::Duration::from_secs(120),
-                                )
-                                .await
-                            {
-                                Some(PermissionDecision::Approved) => true,
-                                Some(PermissionDecision::Denied) => {
-                                    let mut msgs = messages.write();
-                                    if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "🚫 Permission refusée pour `{}`.",
-                                            tool_call.tool
-                                        );
-                                    }
-                                    false
-                                }
-                                None => {
-                                    let mut msgs = messages.write();
-                                    if let Some(last) = msgs.last_mut() {
-                                        last.content = format!(
-                                            "⏱️ Délai expiré pour `{}`.",
-                                            tool_call.tool
-                                        );
-                                    }
-                                    false
-                                }
+                        }
+                    };
+                    let tool_result: Result<ToolResult, ToolError> = match outcome {
+                        Ok(result) => Ok(result),
+                        Err(ToolError::Cancelled) => {
+                            // Stopped by the user: drop the call without recording it
+                            tracing::info!("Tool {} cancelled by user", tool_call.tool);
+                            let mut msgs = messages.write();
+                            if let Some(last) = msgs.last_mut() {
+                                last.content = format!("⏹️ `{}` interrompu.", tool_call.tool);
                             }
+                            break;
                         }
-                        PermissionResult::Denied => {
+                        Err(ToolError::Denied(reason)) => {
+                            tracing::info!("Tool {} not approved: {}", tool_call.tool, reason);
+                            // Record denied permission in context and try alternative
+                            agent_ctx.tool_history.push(ToolHistoryEntry {
+                                tool_name: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                result: None,
+                                error: Some("Permission denied".to_string()),
+                                error_code: Some("denied".to_string()),
+                                timestamp: Utc::now().timestamp() as u64,
+                                duration_ms: 0,
+                            });
                             let mut msgs = messages.write();
                             if let Some(last) = msgs.last_mut() {
                                 last.content = format!(
@@ -1105,77 +1228,25 @@ pub fn ChatView() -> Element {
                                     tool_call.tool
                                 );
                             }
-                            false
-                        }
-                    };
-
-                    if !approved {
-                        // Record denied permission in context and try alternative
-                        agent_ctx.tool_history.push(ToolHistoryEntry {
-                            tool_name: tool_call.tool.clone(),
-                            params: tool_call.params.clone(),
-                            result: None,
-                            error: Some("Permission denied".to_string()),
-                            timestamp: Utc::now().timestamp() as u64,
-                            duration_ms: 0,
-                        });
-                        
-                        // Add message to help LLM find alternative
-                        messages.write().push(Message {
-                            role: MessageRole::System,
-                            content: format!(
-                                "L'outil {} a été refusé. Essaie une autre approche ou réponds avec les informations disponibles.",
-                                tool_call.tool
-                            ),
-                        });
-                        messages.write().push(Message {
-                            role: MessageRole::Assistant,
-                            content: String::new(),
-                        });
-                        continue;
-                    }
-
-                    // Execute tool
-                    let tool = match app_state.agent.tool_registry.get(&tool_call.tool) {
-                        Some(tool) => tool,
-                        None => {
-                            agent_ctx.consecutive_errors += 1;
-                            let mut msgs = messages.write();
-                            if let Some(last) = msgs.last_mut() {
-                                last.content = format!("❌ Outil introuvable: `{}`.", tool_call.tool);
-                            }
-                            // Let the LLM try a different tool
-                            let available_tools: Vec<String> = app_state.agent.tool_registry.list_tools().iter().map(|t| t.name.clone()).collect();
+                            // Add message to help LLM find alternative
                             msgs.push(Message {
                                 role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: format!(
-                                    "L'outil `{}` n'existe pas. Voici les outils disponibles: {}. Utilise un des outils existants ou réponds directement.",
-                                    tool_call.tool,
-                                    available_tools.join(", ")
+                                    "L'outil {} a été refusé. Essaie une autre approche ou réponds avec les informations disponibles.",
+                                    tool_call.tool
                                 ),
                             });
                             msgs.push(Message {
                                 role: MessageRole::Assistant,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: String::new(),
                             });
-                            if agent_ctx.consecutive_errors >= 3 {
-                                break;
-                            }
                             continue;
                         }
-                    };
-
-                    tracing::info!("Executing tool: {} with timeout {}s", tool_call.tool, tool_timeout_secs);
-                    let start_time = Instant::now();
-                    let tool_result: Result<ToolResult, String> = match tokio::time::timeout(
-                        std::time::Duration::from_secs(tool_timeout_secs),
-                        tool.execute(tool_call.params.clone()),
-                    )
-                    .await
-                    {
-                        Ok(Ok(result)) => Ok(result),
-                        Ok(Err(e)) => Err(e.to_string()),
-                        Err(_) => Err("Timeout dépassé".to_string()),
+                        Err(e) => Err(e),
                     };
                     let duration_ms = start_time.elapsed().as_millis() as u64;
 
@@ -1193,26 +1264,31 @@ pub fn ChatView() -> Element {
                                 params: tool_call.params.clone(),
                                 result: Some(result.clone()),
                                 error: None,
+                                error_code: None,
                                 timestamp: Utc::now().timestamp() as u64,
                                 duration_ms,
                             });
 
-                            // Show result summary (safe truncation)
-                            let result_preview = if result.message.len() > 200 {
-                                let safe = crate::truncate_str(&result.message, 200);
+                            // Record the call in the transcript (safe truncation)
+                            let result_text = if result.message.len() > 4000 {
+                                let safe = crate::truncate_str(&result.message, 4000);
                                 format!("{}...", safe)
                             } else {
                                 result.message.clone()
                             };
-                            
+                            let record = ToolCallRecord {
+                                tool: tool_call.tool.clone(),
+                                params: tool_call.params.clone(),
+                                success: result.success,
+                                result: result_text,
+                                duration_ms,
+                                output: ToolOutput::from_data(&result.data),
+                            };
                             messages.write().push(Message {
-                                role: MessageRole::Assistant,
-                                content: format!(
-                                    "✅ `{}` ({:.1}s): {}",
-                                    tool_call.tool,
-                                    duration_ms as f64 / 1000.0,
-                                    result_preview
-                                ),
+                                role: MessageRole::Tool,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
+                                content: record.to_content(),
                             });
 
                             // Inject tool result for LLM (capped to prevent context overflow)
@@ -1225,6 +1301,8 @@ pub fn ChatView() -> Element {
                             };
                             messages.write().push(Message {
                                 role: MessageRole::System,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: tool_result_text,
                             });
 
@@ -1232,6 +1310,8 @@ pub fn ChatView() -> Element {
                             agent_ctx.state = AgentState::Reflecting;
                             messages.write().push(Message {
                                 role: MessageRole::Assistant,
+                                timestamp: Utc::now(),
+                                id: Uuid::new_v4(),
                                 content: String::new(),
                             });
                         }
@@ -1242,7 +1322,8 @@ pub fn ChatView() -> Element {
                                 tool_name: tool_call.tool.clone(),
                                 params: tool_call.params.clone(),
                                 result: None,
-                                error: Some(e.clone()),
+                                error: Some(e.to_string()),
+                                error_code: Some(e.code().to_string()),
                                 timestamp: Utc::now().timestamp() as u64,
                                 duration_ms,
                             });
@@ -1254,6 +1335,8 @@ pub fn ChatView() -> Element {
                                 "❌ Erreur `{}`: {}",
                                 tool_call.tool, e
                             );
+                            // The code lets the model tell a missing file from a stale hash
+                            let error_for_model = format!("[{}] {}", e.code(), e);
                             
                             let mut msgs = messages.write();
                             if let Some(last) = msgs.last_mut() {
@@ -1264,10 +1347,18 @@ pub fn ChatView() -> Element {
                             if agent_ctx.consecutive_errors < 4 {
                                 msgs.push(Message {
                                     role: MessageRole::System,
-                                    content: build_reflection_prompt(&tool_call.tool, &e, false),
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
+                                    content: build_reflection_prompt(
+                                        &tool_call.tool,
+                                        &error_for_model,
+                                        false,
+                                    ),
                                 });
                                 msgs.push(Message {
                                     role: MessageRole::Assistant,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: String::new(),
                                 });
                                 agent_ctx.state = AgentState::Reflecting;
@@ -1275,6 +1366,8 @@ pub fn ChatView() -> Element {
                                 // Too many errors — add a final message explaining the situation
                                 msgs.push(Message {
                                     role: MessageRole::System,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: format!(
                                         "Trop d'erreurs consécutives ({}). Arrête d'utiliser des outils et donne une réponse finale à l'utilisateur en expliquant ce que tu as essayé et ce qui n'a pas marché. Propose des solutions alternatives si possible.",
                                         agent_ctx.consecutive_errors
@@ -1282,6 +1375,8 @@ pub fn ChatView() -> Element {
                                 });
                                 msgs.push(Message {
                                     role: MessageRole::Assistant,
+                                    timestamp: Utc::now(),
+                                    id: Uuid::new_v4(),
                                     content: String::new(),
                                 });
                                 // One last generation attempt for the final message
@@ -1324,9 +1419,16 @@ pub fn ChatView() -> Element {
                             .map(|m| m.content.clone())
                             .unwrap_or_default();
                         
+                        // Reasoning is neither summarized nor kept in the title
+                        let mut reasoning_tags = default_reasoning_tags();
+                        reasoning_tags.extend(app_state.reasoning_tags.read().iter().cloned());
                         let first_assistant_msg = msgs.iter()
                             .find(|m| m.role == MessageRole::Assistant)
-                            .map(|m| m.content.clone())
+                            .map(|m| {
+                                strip_reasoning(&m.content, &reasoning_tags)
+                                    .trim()
+                                    .to_string()
+                            })
                             .unwrap_or_default();
                         
                         // Only generate if we have both messages
@@ -1339,6 +1441,7 @@ pub fn ChatView() -> Element {
                                 temperature: 0.3,
                                 top_k: 40,
                                 top_p: 0.9,
+                                min_p: 0.05,
                                 repeat_penalty: 1.1,
                                 seed: 0,
                                 max_context_size: 2048,
@@ -1360,13 +1463,8 @@ pub fn ChatView() -> Element {
                                             StreamToken::Error(_) => break,
                                         }
                                     }
-                                    // Clean up the title (remove thinking tags, quotes if present, trim)
-                                    let cleaned = text
-                                        .replace("<think>", "")
-                                        .replace("</thinking>", "")
-                                        .replace("<thinking>", "")
-                                        .replace("</think>", "")
-                                        .replace("<think>", "")
+                                    // Clean up the title (drop the reasoning, quotes if present, trim)
+                                    let cleaned = strip_reasoning(&text, &reasoning_tags)
                                         .replace("```", "")
                                         .replace("\n", " ")
                                         .replace("  ", " ");
@@ -1401,49 +1499,168 @@ pub fn ChatView() -> Element {
                         .cloned()
                         .map(|m| m.into())
                         .collect();
+                    let model = match &*app_state.model_state.read() {
+                        ModelState::Loaded(path) => std::path::Path::new(path)
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned()),
+                        _ => None,
+                    };
                     
                     let mut conv_write = app_state.current_conversation.write();
                     if let Some(ref mut conv) = *conv_write {
                         conv.messages = storage_messages;
+                        if model.is_some() {
+                            conv.model = model;
+                        }
+                        conv.updated_at = Utc::now();
                         if let Err(e) = save_conversation(conv) {
                             tracing::error!("Failed to save conversation: {}", e);
                         }
                     }
                 }
+                // Move it to the top of the sidebar, with its new title
+                if let Ok(conversations) = list_conversations() {
+                    app_state.conversations.set(conversations);
+                }
             });
         }
     };
 
+    // Handler for sending a message
+    let handle_send = {
+        let mut messages = messages;
+        let app_state = app_state.clone();
+        let mut start_generation = start_generation.clone();
+        move |text: String| {
+            if !matches!(*app_state.model_state.read(), ModelState::Loaded(_)) {
+                messages.write().push(Message {
+                    role: MessageRole::Assistant,
+                    timestamp: Utc::now(),
+                    id: Uuid::new_v4(),
+                    content: "Model not loaded. Please select and load a model first.".to_string(),
+                });
+                return;
+            }
+
+            // Add user message immediately
+            messages.write().push(Message {
+                role: MessageRole::User,
+                timestamp: Utc::now(),
+                id: Uuid::new_v4(),
+                content: text,
+            });
+
+            start_generation();
+        }
+    };
+
+    // Handler for regenerating an assistant message: drops it (and what
+    // follows) and answers the user message before it again
+    let handle_regenerate = {
+        let mut app_state = app_state.clone();
+        let mut start_generation = start_generation.clone();
+        use_callback(move |id: Uuid| {
+            if *app_state.is_generating.read()
+                || !matches!(*app_state.model_state.read(), ModelState::Loaded(_))
+            {
+                return;
+            }
+            if app_state.rewind_to_prompt(id) {
+                start_generation();
+            }
+        })
+    };
+
+    // Handler for editing a user message: puts its text back in the input
+    // and drops it and everything after it
+    let handle_edit = {
+        let mut app_state = app_state.clone();
+        use_callback(move |id: Uuid| {
+            if !*app_state.is_generating.read() {
+                app_state.edit_message(id);
+            }
+        })
+    };
+
     // Handler for stopping generation
     let handle_stop = {
-        let mut app_state = app_state.clone();
+        let app_state = app_state.clone();
         move |_| {
+            // The generation task winds down and clears `is_generating`
+            // itself, so a new prompt can't start while it still runs
             app_state.stop_signal.store(true, Ordering::Relaxed);
-            app_state.is_generating.set(false);
+            app_state.tool_cancel.lock().unwrap().cancel();
         }
     };
 
+    let show_system_messages = app_state.settings.read().show_system_messages;
+
+    // Bring a search result into view once its conversation has loaded
+    {
+        let mut scroll_to_message = app_state.scroll_to_message;
+        use_effect(move || {
+            let Some(id) = scroll_to_message() else {
+                return;
+            };
+            if !messages.read().iter().any(|m| m.id == id) {
+                return;
+            }
+            scroll_to_message.set(None);
+            document::eval(&format!(
+                "requestAnimationFrame(() => {{
+                    const el = document.getElementById('msg-{id}');
+                    if (!el) return;
+                    el.scrollIntoView({{ block: 'center', behavior: 'smooth' }});
+                    el.classList.add('message-found');
+                    setTimeout(() => el.classList.remove('message-found'), 2000);
+                }});"
+            ));
+        });
+    }
+
+    // The answer being generated, rendered live with a cursor
+    let streaming_idx = {
+        let msgs = messages.read();
+        msgs.last()
+            .filter(|msg| is_generating() && streams_into(msg))
+            .map(|_| msgs.len() - 1)
+    };
+
     rsx! {
-        div { class: "flex flex-col flex-1 min-h-0 relative",
+        div { class: "chat-root flex flex-col flex-1 min-h-0 relative",
             
             // Messages Area — narrower for readability
             div { class: "flex-1 min-h-0 overflow-y-auto px-4 py-4 custom-scrollbar scroll-smooth",
                 div { class: "max-w-3xl mx-auto w-full flex flex-col gap-1 pb-4",
                     // Message List
                     for (idx, msg) in messages.read().iter().enumerate() {
-                        if msg.role != MessageRole::System {
-                            MessageBubble { key: "{idx}", message: msg.clone() }
+                        if msg.role != MessageRole::System || show_system_messages {
+                            {
+                                let id = msg.id;
+                                rsx! {
+                                    MessageBubble {
+                                        key: "{id}",
+                                        message: msg.clone(),
+                                        streaming: streaming_idx == Some(idx),
+                                        on_regenerate: (msg.role == MessageRole::Assistant && !is_generating())
+                                            .then(|| EventHandler::new(move |_| handle_regenerate.call(id))),
+                                        on_edit: (msg.role == MessageRole::User && !is_generating())
+                                            .then(|| EventHandler::new(move |_| handle_edit.call(id))),
+                                    }
+                                }
+                            }
                         }
                     }
                     
-                    // Typing / Generating Indicator — softer dots
-                    if is_generating() {
+                    // Typing / Generating Indicator — softer dots, while no
+                    // answer is streaming (tools running, prompt processing)
+                    if is_generating() && streaming_idx.is_none() {
                         div { class: "message-layout",
                             div { class: "flex items-center gap-3 py-2 animate-fade-in",
                                 div {
                                     class: "w-6 h-6 rounded-full flex items-center justify-center",
                                     style: "background: var(--accent-primary); opacity: 0.7;",
-                                    div { class: "w-2 h-2 rounded-full animate-pulse", style: "background: #F2EDE7;" }
+                                    div { class: "w-2 h-2 rounded-full animate-pulse", style: "background: var(--accent-text);" }
                                 }
                                 div { class: "flex items-center gap-1.5",
                                     div { class: "w-1.5 h-1.5 rounded-full bg-[var(--accent-primary)] opacity-60 animate-bounce" }
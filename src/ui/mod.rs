@@ -2,6 +2,7 @@
 //!
 //! This module contains all user interface components built with Dioxus.
 
+pub mod accent;
 pub mod chat;
 pub mod components;
 pub mod help;
@@ -22,6 +23,38 @@ pub fn t<'a>(app_state: &AppState, fr: &'a str, en: &'a str) -> &'a str {
     if app_state.settings.read().language == "en" { en } else { fr }
 }
 
+/// Chat text size for the `font_size` setting
+pub fn chat_font_size(font_size: &str) -> &'static str {
+    match font_size {
+        "small" => "0.875rem",
+        "large" => "1.0625rem",
+        _ => "0.9375rem",
+    }
+}
+
+/// Chat typeface stack for the `font_family` setting. "system" is the app
+/// font; "mono" is the code block stack, so code reads like the text around it.
+pub fn chat_font_family(font_family: &str) -> &'static str {
+    match font_family {
+        "sans" => "\"Segoe UI\", Roboto, \"Helvetica Neue\", Arial, sans-serif",
+        "serif" => "'Source Serif 4', Georgia, Cambria, \"Times New Roman\", serif",
+        "mono" => "'JetBrains Mono', 'Fira Code', 'Cascadia Code', 'Consolas', monospace",
+        _ => "'Inter', -apple-system, BlinkMacSystemFont, \"Segoe UI\", Roboto, \"Helvetica Neue\", Arial, sans-serif",
+    }
+}
+
+/// Inline CSS variables for the theme wrapper: the chat text size and
+/// typeface and the accent color. Read from the settings signal, so a change
+/// restyles the open chat at once.
+fn root_style(settings: &crate::storage::settings::AppSettings) -> String {
+    format!(
+        "--chat-font-size: {}; --chat-font-family: {}; {}",
+        chat_font_size(&settings.font_size),
+        chat_font_family(&settings.font_family),
+        accent::accent_style(&settings.accent_color)
+    )
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum MainView {
     Chat,
@@ -36,12 +69,12 @@ fn HeaderModelPicker() -> Element {
     let is_en = app_state.settings.read().language == "en";
     let mut dropdown_open = use_signal(|| false);
     let mut models = use_signal(Vec::new);
-    let models_directory = app_state.settings.read().models_directory.clone();
+    let settings = app_state.settings;
+    let models_directory = use_memo(move || settings.read().models_directory.clone());
 
-    // Scan models on mount
-    let models_directory_clone = models_directory.clone();
+    // Scan models on mount and whenever the models directory changes
     use_effect(move || {
-        let found = scan_models_directory(&models_directory_clone).unwrap_or_default();
+        let found = scan_models_directory(&models_directory.read()).unwrap_or_default();
         models.set(found);
     });
 
@@ -77,25 +110,8 @@ fn HeaderModelPicker() -> Element {
     // Handle load
     let app_state_load = app_state.clone();
     let handle_load = move |path: String| {
-        let mut app_state = app_state_load.clone();
         dropdown_open.set(false);
-        app_state.model_state.set(ModelState::Loading);
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        spawn(app_state_load.clone().switch_model(path));
     };
 
     // Handle unload
@@ -107,6 +123,8 @@ fn HeaderModelPicker() -> Element {
             let mut engine = app_state.engine.lock().await;
             engine.unload_model();
         });
+        app_state.vram_baseline_mb.set(None);
+        app_state.loaded_model.set(None);
         app_state.model_state.set(ModelState::NotLoaded);
     };
 
@@ -185,7 +203,10 @@ fn HeaderModelPicker() -> Element {
                             {
                                 let path_str = model.path.to_string_lossy().to_string();
                                 let filename = model.filename.clone();
-                                let size = model.size_string();
+                                let size = match model.quantization() {
+                                    Some(quantization) => format!("{} · {}", quantization, model.size_string()),
+                                    None => model.size_string(),
+                                };
                                 let is_current = match &model_state {
                                     ModelState::Loaded(p) => *p == path_str,
                                     _ => false,
@@ -314,12 +335,14 @@ pub fn Layout() -> Element {
     
     // Get theme from settings
     let theme_str = app_state.settings.read().theme.clone();
+    let root_style = root_style(&app_state.settings.read());
     let is_en = app_state.settings.read().language == "en";
 
     rsx! {
         // Theme wrapper
         div {
             "data-theme": "{theme_str}",
+            style: "{root_style}",
             class: "relative flex h-screen w-screen bg-[var(--bg-primary)] text-[var(--text-primary)] overflow-hidden",
 
             // Inline CSS
@@ -645,7 +668,7 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
                             style: "background: var(--accent-primary);",
                             svg {
                                 class: "w-4 h-4",
-                                style: "color: #F2EDE7;",
+                                style: "color: var(--accent-text);",
                                 view_box: "0 0 24 24",
                                 fill: "none",
                                 stroke: "currentColor",
@@ -668,3 +691,33 @@ fn WelcomeScreen(on_prompt_click: EventHandler<String>) -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::settings::AppSettings;
+
+    #[test]
+    fn test_root_style_follows_settings() {
+        let mut settings = AppSettings::default();
+        assert_eq!(
+            root_style(&settings),
+            format!(
+                "--chat-font-size: 0.9375rem; --chat-font-family: {}; ",
+                chat_font_family("system")
+            )
+        );
+
+        settings.font_size = "large".to_string();
+        settings.accent_color = "#3B5BDB".to_string();
+        let style = root_style(&settings);
+        assert!(style.starts_with("--chat-font-size: 1.0625rem;"));
+        assert!(style.contains("--accent-primary: #3B5BDB;"));
+
+        settings.font_size = "small".to_string();
+        assert!(root_style(&settings).starts_with("--chat-font-size: 0.875rem;"));
+
+        settings.font_family = "mono".to_string();
+        assert!(root_style(&settings).contains("--chat-font-family: 'JetBrains Mono',"));
+    }
+}
@@ -1,5 +1,10 @@
+use crate::agent::{set_locale, Locale};
 use crate::app::AppState;
-use crate::storage::settings::{default_system_prompt_for_lang, save_settings};
+use crate::storage::settings::{
+    default_system_prompt_for_lang, normalize_hex_color, save_settings,
+};
+use crate::ui::accent::ACCENT_PRESETS;
+use crate::ui::{chat_font_family, chat_font_size};
 use dioxus::prelude::*;
 
 pub fn AppearanceSettings() -> Element {
@@ -16,7 +21,22 @@ pub fn AppearanceSettings() -> Element {
     };
     let mut app_state_theme = app_state.clone();
     let mut app_state_font_size = app_state.clone();
+    let font_family = settings.font_family.clone();
+    let mut app_state_font_family = app_state.clone();
+    let mut app_state_math = app_state.clone();
+    let render_math = settings.render_math;
+    let show_timestamps = settings.show_timestamps;
+    let mut app_state_timestamps = app_state.clone();
+    let expand_thinking = settings.expand_thinking;
+    let mut app_state_thinking = app_state.clone();
+    let show_system_messages = settings.show_system_messages;
+    let mut app_state_system = app_state.clone();
     let mut app_state_lang = app_state.clone();
+    let accent_color = settings.accent_color.clone();
+    let mut custom_accent = use_signal(|| accent_color.clone());
+    let custom_accent_valid = normalize_hex_color(&custom_accent()).is_some();
+    let mut app_state_accent = app_state.clone();
+    let mut app_state_custom_accent = app_state.clone();
 
     rsx! {
         div {
@@ -38,7 +58,7 @@ pub fn AppearanceSettings() -> Element {
                     }
                     div {
                         class: "text-xs text-[var(--text-tertiary)] mb-4",
-                        if is_fr { "Change la langue de l'interface et des réponses de l'IA" } else { "Changes the UI language and AI responses" }
+                        if is_fr { "Change la langue de l'interface, des messages des outils et des réponses de l'IA" } else { "Changes the UI language, tool messages and AI responses" }
                     }
 
                     div { class: "grid grid-cols-2 gap-3",
@@ -49,7 +69,10 @@ pub fn AppearanceSettings() -> Element {
                                     move |_| {
                                         let mut settings = app_state_lang.settings.write();
                                         settings.language = code.clone();
-                                        settings.system_prompt = default_system_prompt_for_lang(&code);
+                                        set_locale(Locale::from_code(&code));
+                                        if !settings.system_prompt_custom {
+                                            settings.system_prompt = default_system_prompt_for_lang(&code);
+                                        }
                                         if let Err(error) = save_settings(&settings) {
                                             tracing::error!("Failed to save settings: {}", error);
                                         }
@@ -108,6 +131,208 @@ pub fn AppearanceSettings() -> Element {
                 }
             }
 
+            // Accent Color Card
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_fr { "Couleur d'accent" } else { "Accent color" }
+                }
+                div { class: "text-xs text-[var(--text-tertiary)] mb-4",
+                    if is_fr {
+                        "Boutons, liens et sélections. Le texte posé dessus passe en clair ou en foncé pour rester lisible."
+                    } else {
+                        "Buttons, links and selections. Text on top switches between light and dark to stay legible."
+                    }
+                }
+
+                div { class: "flex flex-wrap gap-2 mb-4",
+                    for (hex, name_en, name_fr) in ACCENT_PRESETS.iter().copied() {
+                        button {
+                            title: if is_fr { "{name_fr}" } else { "{name_en}" },
+                            class: "w-8 h-8 rounded-full border-2 transition-all cursor-pointer",
+                            style: if accent_color == hex {
+                                "background: {hex}; border-color: var(--text-primary);"
+                            } else {
+                                "background: {hex}; border-color: transparent;"
+                            },
+                            onclick: move |_| {
+                                custom_accent.set(hex.to_string());
+                                let mut settings = app_state_accent.settings.write();
+                                settings.accent_color = hex.to_string();
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                        }
+                    }
+                }
+
+                div { class: "flex items-center gap-3",
+                    span { class: "text-sm text-[var(--text-secondary)]",
+                        if is_fr { "Personnalisée" } else { "Custom" }
+                    }
+                    input {
+                        r#type: "text",
+                        value: "{custom_accent}",
+                        maxlength: "7",
+                        placeholder: "#8B2635",
+                        oninput: move |e| {
+                            let value = e.value();
+                            custom_accent.set(value.clone());
+                            if let Some(hex) = normalize_hex_color(&value) {
+                                let mut settings = app_state_custom_accent.settings.write();
+                                settings.accent_color = hex;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            }
+                        },
+                        class: "w-32 py-2 px-3 rounded-lg bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono uppercase",
+                        style: if !custom_accent_valid { "border-color: var(--error);" },
+                    }
+                    // Preview of text on the accent
+                    span {
+                        class: "px-3 py-1.5 rounded-lg text-xs font-semibold",
+                        style: "background: var(--accent-primary); color: var(--accent-text);",
+                        "Aa"
+                    }
+                }
+            }
+
+            // Timestamps Card
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
+                    if is_fr { "Messages" } else { "Messages" }
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_fr { "Afficher l'heure" } else { "Show timestamps" }
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_fr {
+                                "Heure d'envoi sous chaque message, la date complete au survol"
+                            } else {
+                                "Send time under each message, with the full date on hover"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_timestamps.settings.write();
+                            settings.show_timestamps = !show_timestamps;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: if show_timestamps { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between mt-4",
+
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_fr { "Deplier les reflexions" } else { "Expand thinking" }
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_fr {
+                                "Les blocs de reflexion s'affichent ouverts, chacun reste repliable"
+                            } else {
+                                "Thinking blocks start open, each can still be collapsed"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_thinking.settings.write();
+                            settings.expand_thinking = !expand_thinking;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: if expand_thinking { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between mt-4",
+
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_fr { "Afficher les messages systeme" } else { "Show system messages" }
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_fr {
+                                "Consignes et resumes ajoutes par l'application pour le modele"
+                            } else {
+                                "Instructions and summaries the app adds for the model"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_system.settings.write();
+                            settings.show_system_messages = !show_system_messages;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: if show_system_messages { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+            }
+
+            // Math Card
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-5 text-[var(--text-primary)]",
+                    if is_fr { "Mathematiques" } else { "Math" }
+                }
+
+                div {
+                    class: "flex items-center justify-between",
+
+                    div {
+                        div { class: "text-sm font-medium text-[var(--text-primary)]",
+                            if is_fr { "Afficher les formules" } else { "Render formulas" }
+                        }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if is_fr {
+                                "Afficher le LaTeX ($...$, $$...$$) en notation mathematique plutot qu'en source"
+                            } else {
+                                "Show LaTeX ($...$, $$...$$) as math notation instead of source"
+                            }
+                        }
+                    }
+                    button {
+                        onclick: move |_| {
+                            let mut settings = app_state_math.settings.write();
+                            settings.render_math = !render_math;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: if render_math { "toggle-switch active" } else { "toggle-switch" },
+                        div { class: "toggle-switch-knob" }
+                    }
+                }
+            }
+
             // Font Size Card — glass with selection cards
             div {
                 class: "p-5 rounded-2xl glass-md",
@@ -146,18 +371,55 @@ pub fn AppearanceSettings() -> Element {
                                 div { class: "text-sm font-medium", "{size}" }
                                 div {
                                     class: "text-[var(--text-tertiary)] mt-1",
-                                    style: match *size {
-                                        "Small" => "font-size: 0.75rem;",
-                                        "Medium" => "font-size: 0.875rem;",
-                                        "Large" => "font-size: 1rem;",
-                                        _ => ""
-                                    },
+                                    style: "font-size: {chat_font_size(&size.to_lowercase())};",
                                     "Aa"
                                 }
                             }
                         }
                     }
                 }
+
+                div { class: "mt-6",
+                    div { class: "text-sm font-medium text-[var(--text-primary)] mb-1",
+                        if is_fr { "Police" } else { "Font" }
+                    }
+                    div { class: "text-xs text-[var(--text-tertiary)] mb-4",
+                        if is_fr {
+                            "Police du chat ; la monospace est celle des blocs de code"
+                        } else {
+                            "Typeface of the chat; monospace matches the code blocks"
+                        }
+                    }
+
+                    select {
+                        class: "w-full px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{font_family}",
+                        onchange: move |e: Event<FormData>| {
+                            let mut settings = app_state_font_family.settings.write();
+                            settings.font_family = e.value();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        for (id, label_en, label_fr) in [
+                            ("system", "System default", "Par défaut"),
+                            ("sans", "Sans-serif", "Sans empattement"),
+                            ("serif", "Serif", "Avec empattement"),
+                            ("mono", "Monospace", "Monospace"),
+                        ] {
+                            option { value: "{id}", if is_fr { "{label_fr}" } else { "{label_en}" } }
+                        }
+                    }
+                    div {
+                        class: "text-[var(--text-secondary)] mt-3",
+                        style: "font-family: {chat_font_family(&font_family)}; font-size: {chat_font_size(&font_size)};",
+                        if is_fr {
+                            "Portez ce vieux whisky au juge blond qui fume."
+                        } else {
+                            "The quick brown fox jumps over the lazy dog."
+                        }
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,169 @@
+use crate::app::AppState;
+use crate::storage::settings::{export_settings, import_settings, AppSettings};
+use crate::ui::settings::inference::SettingsCard;
+use dioxus::prelude::*;
+
+/// Outcome of the last action, shown under the buttons
+#[derive(Clone, PartialEq)]
+enum Status {
+    Done(String),
+    Failed(String),
+}
+
+/// Reset, export and import all settings
+pub fn BackupSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let mut status = use_signal(|| None::<Status>);
+    // Reset asks for a second click
+    let mut confirm_reset = use_signal(|| false);
+
+    let card_title = if is_en {
+        "Settings backup"
+    } else {
+        "Sauvegarde des réglages"
+    };
+    let mut app_state_reset = app_state.clone();
+    let app_state_export = app_state.clone();
+    let app_state_import = app_state.clone();
+
+    let button_class = "px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all";
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            SettingsCard { title: card_title,
+                p { class: "text-xs text-[var(--text-tertiary)]",
+                    if is_en {
+                        "Export the settings to a JSON file to move them to another machine, or start over from the defaults. Tool approval is not imported: this machine's choice is kept."
+                    } else {
+                        "Exporter les réglages dans un fichier JSON pour les reprendre sur une autre machine, ou repartir des valeurs par défaut. L'approbation des outils n'est pas importée : le choix de cette machine est conservé."
+                    }
+                }
+
+                div { class: "flex flex-wrap gap-2",
+                    button {
+                        class: "{button_class}",
+                        onclick: move |_| {
+                            let settings = app_state_export.settings.read().clone();
+                            spawn(async move {
+                                let Some(file) = rfd::AsyncFileDialog::new()
+                                    .set_file_name("clawrs-settings.json")
+                                    .add_filter("JSON", &["json"])
+                                    .save_file()
+                                    .await
+                                else {
+                                    return;
+                                };
+                                let path = file.path().to_path_buf();
+                                status.set(Some(match export_settings(&settings, &path) {
+                                    Ok(()) => Status::Done(if is_en {
+                                        format!("Exported to {}", path.display())
+                                    } else {
+                                        format!("Exporté dans {}", path.display())
+                                    }),
+                                    Err(error) => Status::Failed(if is_en {
+                                        format!("Export failed: {}", error)
+                                    } else {
+                                        format!("Échec de l'export : {}", error)
+                                    }),
+                                }));
+                            });
+                        },
+                        if is_en { "Export..." } else { "Exporter..." }
+                    }
+                    button {
+                        class: "{button_class}",
+                        onclick: move |_| {
+                            let mut app_state = app_state_import.clone();
+                            spawn(async move {
+                                let Some(file) = rfd::AsyncFileDialog::new()
+                                    .add_filter("JSON", &["json"])
+                                    .pick_file()
+                                    .await
+                                else {
+                                    return;
+                                };
+                                let path = file.path().to_path_buf();
+                                let current = app_state.settings.read().clone();
+                                let imported = match import_settings(&path, &current) {
+                                    Ok(imported) => imported,
+                                    Err(error) => {
+                                        status.set(Some(Status::Failed(if is_en {
+                                            format!("Cannot import {}: {}", path.display(), error)
+                                        } else {
+                                            format!("Import impossible de {} : {}", path.display(), error)
+                                        })));
+                                        return;
+                                    }
+                                };
+                                if let Err(error) = app_state.replace_settings(imported.settings) {
+                                    status.set(Some(Status::Failed(if is_en {
+                                        format!("Imported, but saving failed: {}", error)
+                                    } else {
+                                        format!("Importé, mais l'enregistrement a échoué : {}", error)
+                                    })));
+                                    return;
+                                }
+                                status.set(Some(Status::Done(match (imported.missing_models_directory, is_en) {
+                                    (None, true) => "Settings imported.".to_string(),
+                                    (None, false) => "Réglages importés.".to_string(),
+                                    (Some(dir), true) => format!(
+                                        "Settings imported. The models folder {} does not exist here, the current one was kept.",
+                                        dir.display()
+                                    ),
+                                    (Some(dir), false) => format!(
+                                        "Réglages importés. Le dossier des modèles {} n'existe pas ici, l'actuel a été conservé.",
+                                        dir.display()
+                                    ),
+                                })));
+                            });
+                        },
+                        if is_en { "Import..." } else { "Importer..." }
+                    }
+                    button {
+                        class: "{button_class}",
+                        style: if confirm_reset() { "border-color: var(--error); color: var(--text-error);" },
+                        onclick: move |_| {
+                            if !confirm_reset() {
+                                confirm_reset.set(true);
+                                return;
+                            }
+                            confirm_reset.set(false);
+                            status.set(Some(match app_state_reset.replace_settings(AppSettings::default()) {
+                                Ok(()) => Status::Done(if is_en {
+                                    "Settings reset to defaults.".to_string()
+                                } else {
+                                    "Réglages remis par défaut.".to_string()
+                                }),
+                                Err(error) => Status::Failed(if is_en {
+                                    format!("Reset failed: {}", error)
+                                } else {
+                                    format!("Échec de la réinitialisation : {}", error)
+                                }),
+                            }));
+                        },
+                        onmouseleave: move |_| confirm_reset.set(false),
+                        match (confirm_reset(), is_en) {
+                            (true, true) => "Click again to reset everything",
+                            (true, false) => "Cliquer à nouveau pour tout réinitialiser",
+                            (false, true) => "Reset to defaults",
+                            (false, false) => "Réinitialiser",
+                        }
+                    }
+                }
+
+                match status() {
+                    Some(Status::Done(message)) => rsx! {
+                        p { class: "text-xs text-[var(--text-success)]", "{message}" }
+                    },
+                    Some(Status::Failed(message)) => rsx! {
+                        p { class: "text-xs text-[var(--text-error)]", "{message}" }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -1,46 +1,119 @@
-use crate::app::AppState;
-use crate::storage::settings::save_settings;
+use crate::app::{AppState, ModelState};
+use crate::storage::models::scan_models_directory;
+use crate::storage::settings::{
+    save_settings, MAX_CONTEXT_SIZE, MAX_GPU_LAYERS, MIN_CONTEXT_SIZE,
+};
 use crate::system::gpu::{detect_gpu, GpuInfo};
-use crate::system::resources::{get_resource_usage, ResourceUsage};
+use crate::system::resources::{get_resource_usage, logical_cores, physical_cores, ResourceUsage};
 use dioxus::prelude::*;
 use std::process::Command;
+use std::time::Duration;
+
+/// How often the GPU and RAM figures are refreshed while the pane is open
+const USAGE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Context window presets, in tokens, with their labels
+const CONTEXT_PRESETS: &[(u32, &str)] = &[
+    (2048, "2K"),
+    (4096, "4K"),
+    (8192, "8K"),
+    (16384, "16K"),
+    (32768, "32K"),
+];
+
+/// Outcome of the last folder picked with "Browse...", shown under the input
+#[derive(Clone, PartialEq)]
+enum DirNotice {
+    /// Kept, but worth knowing (no models in it yet)
+    Warning(String),
+    /// Not kept, or not saved
+    Error(String),
+}
 
 pub fn HardwareSettings() -> Element {
     let app_state = use_context::<AppState>();
     let settings = app_state.settings.read().clone();
     let gpu_layers = settings.gpu_layers;
+    let cpu_threads = settings.cpu_threads;
+    let context_size = settings.context_size;
+    let max_threads = logical_cores();
+    // Spawns a query process on some platforms, so only once per mount
+    let core_count = use_hook(physical_cores);
     let models_dir = settings.models_directory.to_string_lossy().to_string();
     let models_dir_path = settings.models_directory.clone();
     let auto_load_model = settings.auto_load_model;
     let last_model_path = settings.last_model_path.clone();
+    let is_en = settings.language == "en";
     let mut app_state_gpu_layers = app_state.clone();
+    let mut app_state_gpu_auto = app_state.clone();
+    let mut app_state_cpu_threads = app_state.clone();
+    let mut app_state_context_preset = app_state.clone();
+    let mut app_state_context_custom = app_state.clone();
+    let mut custom_context = use_signal(move || {
+        !CONTEXT_PRESETS
+            .iter()
+            .any(|(size, _)| *size == context_size)
+    });
+    // KV cache of the loaded model at the chosen context, and at its training length
+    let kv_estimate = app_state.loaded_model.read().as_ref().map(|info| {
+        let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+        (gb(info.kv_cache_bytes(context_size)), info.context_length)
+    });
     let mut app_state_auto_load = app_state.clone();
+    let app_state_models_dir = app_state.clone();
+    // Problem with the last folder picked with "Browse..."
+    let mut models_dir_notice = use_signal(|| None::<DirNotice>);
 
-    let gpu_info = use_signal(GpuInfo::default);
-    let ram_usage = use_signal(ResourceUsage::default);
-    let info_loaded = use_signal(|| false);
+    let mut gpu_info = use_signal(GpuInfo::default);
+    let mut ram_usage = use_signal(ResourceUsage::default);
+    let mut info_loaded = use_signal(|| false);
+    // Highest VRAM use (MB) seen during the current or last generation
+    let mut vram_peak_mb = use_signal(|| None::<u64>);
+    let is_generating = app_state.is_generating;
 
-    {
-        let mut gpu_info = gpu_info.clone();
-        let mut ram_usage = ram_usage.clone();
-        let mut info_loaded = info_loaded.clone();
-        use_effect(move || {
-            if !info_loaded() {
-                gpu_info.set(detect_gpu());
-                ram_usage.set(get_resource_usage());
+    // Re-read usage until the pane unmounts, which drops this future, so
+    // VRAM can be watched while dragging the GPU layers slider
+    use_future(move || async move {
+        let mut was_generating = false;
+        loop {
+            let probe = tokio::task::spawn_blocking(|| (detect_gpu(), get_resource_usage())).await;
+            if let Ok((gpu, ram)) = probe {
+                let generating = *is_generating.peek();
+                if generating && !was_generating {
+                    vram_peak_mb.set(None);
+                }
+                if generating && gpu.vram_usage_available {
+                    let peak = vram_peak_mb.peek().unwrap_or(0).max(gpu.vram_used_mb);
+                    vram_peak_mb.set(Some(peak));
+                }
+                was_generating = generating;
+                gpu_info.set(gpu);
+                ram_usage.set(ram);
                 info_loaded.set(true);
             }
-        });
-    }
+            tokio::time::sleep(USAGE_POLL_INTERVAL).await;
+        }
+    });
 
     let gpu_snapshot = gpu_info.read().clone();
     let ram_snapshot = ram_usage.read().clone();
 
-    let gpu_name = if gpu_snapshot.is_available && !gpu_snapshot.name.is_empty() {
+    let gpu_detected = gpu_snapshot.is_available && !gpu_snapshot.name.is_empty();
+    let gpu_name = if gpu_detected {
         gpu_snapshot.name.clone()
+    } else if !info_loaded() {
+        if is_en {
+            "Detecting GPU…"
+        } else {
+            "Détection du GPU…"
+        }
+        .to_string()
+    } else if is_en {
+        "CPU only".to_string()
     } else {
-        "GPU non detecte".to_string()
+        "CPU uniquement".to_string()
     };
+    let gpu_backend = gpu_snapshot.backend.label();
 
     let vram_total_mb = gpu_snapshot.vram_total_mb;
     let vram_used_mb = gpu_snapshot.vram_used_mb;
@@ -53,6 +126,36 @@ pub fn HardwareSettings() -> Element {
     } else {
         0.0
     };
+    // What the loaded model added on top of the VRAM in use before loading it
+    let model_loaded = matches!(*app_state.model_state.read(), ModelState::Loaded(_));
+    let vram_baseline_mb = *app_state.vram_baseline_mb.read();
+    let model_vram_gb = match vram_baseline_mb {
+        Some(baseline) if model_loaded && vram_usage_available => {
+            Some(vram_used_mb.saturating_sub(baseline) as f64 / 1024.0)
+        }
+        _ => None,
+    };
+    // Idle is the usage before the model was loaded; the peak is what a
+    // generation needed, to size the GPU layers against the total
+    let vram_idle_gb = vram_baseline_mb
+        .filter(|_| model_loaded && vram_usage_available)
+        .map(|mb| mb as f64 / 1024.0);
+    let vram_peak_gb = vram_peak_mb().filter(|_| vram_usage_available).map(|mb| mb as f64 / 1024.0);
+    // Layers of the loaded model that fit in the VRAM free without it, as
+    // (fit, total, free GB)
+    let gpu_layers_estimate = app_state.loaded_model.read().as_ref().and_then(|info| {
+        if !gpu_detected || vram_total_mb == 0 || info.n_layer == 0 {
+            return None;
+        }
+        let in_use_mb = match (vram_usage_available, model_loaded) {
+            (true, true) => vram_baseline_mb.unwrap_or(0),
+            (true, false) => vram_used_mb,
+            (false, _) => 0,
+        };
+        let free_mb = vram_total_mb.saturating_sub(in_use_mb);
+        let fit = info.gpu_layers_that_fit(context_size, free_mb * 1024 * 1024);
+        Some((fit, info.n_layer, free_mb as f64 / 1024.0))
+    });
 
     let ram_total_mb = ram_snapshot.ram_total_mb;
     let ram_used_mb = ram_snapshot.ram_used_mb;
@@ -91,33 +194,95 @@ pub fn HardwareSettings() -> Element {
 
                     div { class: "flex-1",
                         div { class: "font-semibold text-[var(--text-primary)]", "{gpu_name}" }
+                        div { class: "text-xs text-[var(--text-tertiary)] mt-0.5",
+                            if gpu_detected {
+                                "{gpu_backend}"
+                            } else if info_loaded() {
+                                if is_en {
+                                    "No GPU backend found (NVIDIA, AMD, Metal): models run on the CPU"
+                                } else {
+                                    "Aucun backend GPU trouvé (NVIDIA, AMD, Metal) : les modèles tournent sur le CPU"
+                                }
+                            }
+                        }
 
-                        div { class: "mt-3 space-y-2",
-                            if vram_total_mb == 0 {
-                                p { class: "text-xs text-[var(--text-tertiary)]", "VRAM indisponible" }
-                            } else if vram_usage_available {
+                        // Without a GPU the whole model runs from system RAM
+                        if !gpu_detected && info_loaded() {
+                            div { class: "mt-3 space-y-2",
                                 div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM utilisee" }
-                                    span { class: "font-mono", "{vram_used_gb:.1} / {vram_total_gb:.1} GB" }
+                                    span { if is_en { "Processor" } else { "Processeur" } }
+                                    span { class: "font-mono",
+                                        if is_en {
+                                            "{core_count} cores, {max_threads} threads"
+                                        } else {
+                                            "{core_count} cœurs, {max_threads} threads"
+                                        }
+                                    }
                                 }
-                                div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM restante" }
-                                    span { class: "font-mono", "{vram_free_gb:.1} GB" }
+                                p { class: "text-xs text-[var(--text-tertiary)]",
+                                    if is_en {
+                                        "Models are loaded into system RAM and the GPU layers setting has no effect."
+                                    } else {
+                                        "Les modèles sont chargés en RAM et le réglage des couches GPU est sans effet."
+                                    }
                                 }
-                                // Progress Bar — accent gradient
-                                div {
-                                    class: "w-full rounded-full h-1.5 overflow-hidden bg-white/[0.06]",
+                            }
+                        }
+
+                        if gpu_detected {
+                            div { class: "mt-3 space-y-2",
+                                if vram_total_mb == 0 {
+                                    p { class: "text-xs text-[var(--text-tertiary)]", "VRAM indisponible" }
+                                } else if vram_usage_available {
+                                    div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                        span { "VRAM utilisee" }
+                                        span { class: "font-mono", "{vram_used_gb:.1} / {vram_total_gb:.1} GB" }
+                                    }
+                                    div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                        span { "VRAM restante" }
+                                        span { class: "font-mono", "{vram_free_gb:.1} GB" }
+                                    }
+                                    if let Some(model_gb) = model_vram_gb {
+                                        div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                            span { if is_en { "Used by the loaded model" } else { "Utilisee par le modele charge" } }
+                                            span { class: "font-mono", "+{model_gb:.1} GB" }
+                                        }
+                                    }
+                                    if let Some(idle_gb) = vram_idle_gb {
+                                        div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                            span { if is_en { "Idle (before loading the model)" } else { "Au repos (avant le chargement du modele)" } }
+                                            span { class: "font-mono", "{idle_gb:.1} GB" }
+                                        }
+                                    }
+                                    if let Some(peak_gb) = vram_peak_gb {
+                                        div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                            span {
+                                                if is_generating() {
+                                                    if is_en { "Peak while generating" } else { "Pic pendant la generation" }
+                                                } else if is_en {
+                                                    "Peak during the last generation"
+                                                } else {
+                                                    "Pic pendant la derniere generation"
+                                                }
+                                            }
+                                            span { class: "font-mono", "{peak_gb:.1} / {vram_total_gb:.1} GB" }
+                                        }
+                                    }
+                                    // Progress Bar — accent gradient
                                     div {
-                                        class: "h-1.5 rounded-full transition-all",
-                                        style: "width: {vram_percent}%; background: var(--accent-gradient);"
+                                        class: "w-full rounded-full h-1.5 overflow-hidden bg-white/[0.06]",
+                                        div {
+                                            class: "h-1.5 rounded-full transition-all",
+                                            style: "width: {vram_percent}%; background: var(--accent-gradient);"
+                                        }
                                     }
+                                } else {
+                                    div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
+                                        span { "VRAM totale" }
+                                        span { class: "font-mono", "{vram_total_gb:.1} GB" }
+                                    }
+                                    p { class: "text-xs text-[var(--text-tertiary)]", "Utilisation VRAM indisponible" }
                                 }
-                            } else {
-                                div { class: "flex justify-between text-xs text-[var(--text-secondary)]",
-                                    span { "VRAM totale" }
-                                    span { class: "font-mono", "{vram_total_gb:.1} GB" }
-                                }
-                                p { class: "text-xs text-[var(--text-tertiary)]", "Utilisation VRAM indisponible" }
                             }
                         }
                     }
@@ -198,15 +363,33 @@ pub fn HardwareSettings() -> Element {
                 div { class: "mb-6",
                     div { class: "flex justify-between items-center mb-2",
                         label { class: "text-sm font-medium text-[var(--text-primary)]", "GPU Layers" }
-                        span {
-                            class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
-                            "{gpu_layers}"
+                        div { class: "flex items-center gap-2",
+                            button {
+                                class: "text-xs px-2 py-1 rounded-lg bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] hover:bg-white/[0.08] transition-colors disabled:opacity-50",
+                                disabled: gpu_layers_estimate.is_none(),
+                                title: if is_en { "Offload as many layers as fit in the free VRAM" } else { "Décharger autant de couches que la VRAM libre le permet" },
+                                onclick: move |_| {
+                                    let Some((fit, total, _)) = gpu_layers_estimate else {
+                                        return;
+                                    };
+                                    let mut settings = app_state_gpu_auto.settings.write();
+                                    settings.gpu_layers = if fit >= total { MAX_GPU_LAYERS } else { fit };
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                "Auto"
+                            }
+                            span {
+                                class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
+                                "{gpu_layers}"
+                            }
                         }
                     }
                     input {
                         r#type: "range",
                         min: "0",
-                        max: "99",
+                        max: "{MAX_GPU_LAYERS}",
                         value: "{gpu_layers}",
                         oninput: move |e| {
                             let value = e.value().parse().unwrap_or(0);
@@ -221,6 +404,127 @@ pub fn HardwareSettings() -> Element {
                     p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
                         "Layers to offload to GPU. Higher values need more VRAM."
                     }
+                    if gpu_detected {
+                        p { class: "text-xs text-[var(--text-tertiary)] mt-1",
+                            match gpu_layers_estimate {
+                                Some((fit, total, free_gb)) if is_en => format!(
+                                    "~{fit}/{total} layers fit in {free_gb:.1} GB free VRAM with a {context_size}-token context. Applies the next time the model is loaded."
+                                ),
+                                Some((fit, total, free_gb)) => format!(
+                                    "~{fit}/{total} couches tiennent dans {free_gb:.1} Go de VRAM libre avec un contexte de {context_size} tokens. Appliqué au prochain chargement du modèle."
+                                ),
+                                None if is_en => "Load a model to estimate how many layers fit.".to_string(),
+                                None => "Chargez un modèle pour estimer combien de couches tiennent.".to_string(),
+                            }
+                        }
+                    }
+                }
+
+                // CPU Threads Control
+                div { class: "mb-6",
+                    div { class: "flex justify-between items-center mb-2",
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", "CPU Threads" }
+                        span {
+                            class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
+                            "{cpu_threads}"
+                        }
+                    }
+                    input {
+                        r#type: "range",
+                        min: "1",
+                        max: "{max_threads}",
+                        value: "{cpu_threads}",
+                        oninput: move |e| {
+                            let value: u32 = e.value().parse().unwrap_or(1);
+                            let mut settings = app_state_cpu_threads.settings.write();
+                            settings.cpu_threads = value.clamp(1, max_threads as u32);
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "w-full",
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        if is_en {
+                            "Threads for the layers run on the CPU ({core_count} physical cores). Going past the core count usually slows generation down. Applies on the next model load."
+                        } else {
+                            "Threads pour les couches exécutées sur le CPU ({core_count} cœurs physiques). Dépasser le nombre de cœurs ralentit généralement la génération. Appliqué au prochain chargement du modèle."
+                        }
+                    }
+                }
+
+                // Context Length Control
+                div { class: "mb-6",
+                    div { class: "flex justify-between items-center mb-2",
+                        label { class: "text-sm font-medium text-[var(--text-primary)]", "Context Length" }
+                        span {
+                            class: "text-xs font-mono px-2 py-1 rounded-lg bg-white/[0.04] text-[var(--text-secondary)] border border-[var(--border-subtle)]",
+                            "{context_size}"
+                        }
+                    }
+                    div { class: "flex gap-2",
+                        select {
+                            value: if custom_context() { "custom".to_string() } else { context_size.to_string() },
+                            onchange: move |e| {
+                                let value = e.value();
+                                if value == "custom" {
+                                    custom_context.set(true);
+                                    return;
+                                }
+                                custom_context.set(false);
+                                let mut settings = app_state_context_preset.settings.write();
+                                settings.context_size = value.parse().unwrap_or(8192);
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                            class: "flex-1 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm appearance-none cursor-pointer",
+                            for (size, label) in CONTEXT_PRESETS.iter().copied() {
+                                option { value: "{size}", "{label}" }
+                            }
+                            option { value: "custom", if is_en { "Custom" } else { "Personnalisé" } }
+                        }
+                        if custom_context() {
+                            input {
+                                r#type: "number",
+                                min: "{MIN_CONTEXT_SIZE}",
+                                max: "{MAX_CONTEXT_SIZE}",
+                                step: "256",
+                                value: "{context_size}",
+                                onchange: move |e| {
+                                    let Ok(value) = e.value().parse::<u32>() else {
+                                        return;
+                                    };
+                                    let mut settings = app_state_context_custom.settings.write();
+                                    // Same bounds and 256-token steps as AppSettings::validate
+                                    settings.context_size = value.clamp(MIN_CONTEXT_SIZE, MAX_CONTEXT_SIZE) / 256 * 256;
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                    }
+                                },
+                                class: "w-32 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono",
+                            }
+                        }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
+                        match kv_estimate {
+                            Some((kv_gb, trained)) if is_en => format!(
+                                "KV cache for the loaded model: about {kv_gb:.2} GB more memory (model trained on {trained} tokens)."
+                            ),
+                            Some((kv_gb, trained)) => format!(
+                                "Cache KV du modèle chargé : environ {kv_gb:.2} Go de mémoire en plus (modèle entraîné sur {trained} tokens)."
+                            ),
+                            None if is_en => "Load a model to estimate the memory its KV cache needs.".to_string(),
+                            None => "Chargez un modèle pour estimer la mémoire de son cache KV.".to_string(),
+                        }
+                    }
+                    p { class: "text-xs text-[var(--text-tertiary)] mt-1",
+                        if is_en {
+                            "Longer contexts remember more of the conversation but are slower and may not fit in VRAM. Used from the next message; a smaller value frees memory once the model is reloaded."
+                        } else {
+                            "Un contexte plus long retient plus de la conversation mais est plus lent et peut dépasser la VRAM. Utilisé dès le prochain message ; une valeur plus petite libère la mémoire au rechargement du modèle."
+                        }
+                    }
                 }
 
                 // Models Directory Input
@@ -233,6 +537,53 @@ pub fn HardwareSettings() -> Element {
                             value: "{models_dir}",
                             class: "flex-1 py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-secondary)] text-sm cursor-not-allowed",
                         }
+                        button {
+                            class: "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors",
+                            onclick: move |_| {
+                                let current = app_state_models_dir.settings.read().models_directory.clone();
+                                let mut app_state = app_state_models_dir.clone();
+                                spawn(async move {
+                                    let Some(folder) = rfd::AsyncFileDialog::new()
+                                        .set_directory(&current)
+                                        .pick_folder()
+                                        .await
+                                    else {
+                                        return;
+                                    };
+                                    let dir = folder.path().to_path_buf();
+                                    if let Err(error) = std::fs::read_dir(&dir) {
+                                        tracing::warn!("Models directory {} is not readable: {}", dir.display(), error);
+                                        models_dir_notice.set(Some(DirNotice::Error(if is_en {
+                                            format!("Cannot read {}: {}", dir.display(), error)
+                                        } else {
+                                            format!("Impossible de lire {} : {}", dir.display(), error)
+                                        })));
+                                        return;
+                                    }
+
+                                    let model_count = scan_models_directory(&dir).map(|m| m.len()).unwrap_or(0);
+                                    models_dir_notice.set((model_count == 0).then(|| {
+                                        DirNotice::Warning(if is_en {
+                                            "This folder contains no .gguf models yet.".to_string()
+                                        } else {
+                                            "Ce dossier ne contient encore aucun modèle .gguf.".to_string()
+                                        })
+                                    }));
+                                    // Model pickers rescan when the setting changes
+                                    let mut settings = app_state.settings.write();
+                                    settings.models_directory = dir;
+                                    if let Err(error) = save_settings(&settings) {
+                                        tracing::error!("Failed to save settings: {}", error);
+                                        models_dir_notice.set(Some(DirNotice::Error(if is_en {
+                                            format!("Folder selected, but saving the settings failed: {}", error)
+                                        } else {
+                                            format!("Dossier choisi, mais l'enregistrement des réglages a échoué : {}", error)
+                                        })));
+                                    }
+                                });
+                            },
+                            if is_en { "Browse..." } else { "Parcourir..." }
+                        }
                         button {
                             class: "px-4 py-2.5 rounded-xl bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] text-sm font-medium hover:bg-white/[0.08] transition-colors",
                             onclick: move |_| {
@@ -249,11 +600,24 @@ pub fn HardwareSettings() -> Element {
                                     tracing::error!("Failed to open models directory: {}", error);
                                 }
                             },
-                            "Open"
+                            if is_en { "Open" } else { "Ouvrir" }
                         }
                     }
                     p { class: "text-xs text-[var(--text-tertiary)] mt-1.5",
-                        "Location where model files (.gguf) are stored."
+                        if is_en {
+                            "Location where model files (.gguf) are stored."
+                        } else {
+                            "Dossier où sont rangés les modèles (.gguf)."
+                        }
+                    }
+                    match models_dir_notice() {
+                        Some(DirNotice::Warning(message)) => rsx! {
+                            p { class: "text-xs text-warning mt-1.5", "{message}" }
+                        },
+                        Some(DirNotice::Error(message)) => rsx! {
+                            p { class: "text-xs text-error mt-1.5", "{message}" }
+                        },
+                        None => rsx! {},
                     }
                 }
             }
@@ -1,16 +1,26 @@
 #![allow(non_snake_case)]
 
 pub mod appearance;
+pub mod backup;
 pub mod hardware;
 pub mod inference;
+pub mod sampling;
+pub mod system_prompt;
+pub mod tool_access;
+pub mod tool_audit;
 pub mod tools;
 pub mod skills;
 pub mod mcp;
 
 use crate::app::AppState;
 use crate::ui::settings::appearance::AppearanceSettings;
+use crate::ui::settings::backup::BackupSettings;
 use crate::ui::settings::hardware::HardwareSettings;
 use crate::ui::settings::inference::InferenceSettings;
+use crate::ui::settings::sampling::SamplingSettings;
+use crate::ui::settings::system_prompt::SystemPromptSettings;
+use crate::ui::settings::tool_access::ToolAccessSettings;
+use crate::ui::settings::tool_audit::ToolAuditSettings;
 use crate::ui::settings::tools::ToolsSettings;
 use crate::ui::settings::skills::SkillsSettings;
 use crate::ui::settings::mcp::McpSettings;
@@ -19,11 +29,16 @@ use dioxus::prelude::*;
 #[derive(PartialEq, Clone, Copy)]
 enum SettingsTab {
     Inference,
+    Sampling,
+    SystemPrompt,
     Hardware,
     Tools,
+    ToolAccess,
+    ToolAudit,
     Skills,
     Mcp,
     Appearance,
+    Backup,
 }
 
 pub fn Settings() -> Element {
@@ -52,6 +67,16 @@ pub fn Settings() -> Element {
                             onclick: move |_| active_tab.set(SettingsTab::Inference),
                             label: if is_en { "Inference" } else { "Inference" },
                         }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Sampling,
+                            onclick: move |_| active_tab.set(SettingsTab::Sampling),
+                            label: if is_en { "Sampling" } else { "Échantillonnage" },
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::SystemPrompt,
+                            onclick: move |_| active_tab.set(SettingsTab::SystemPrompt),
+                            label: if is_en { "System prompt" } else { "Prompt système" },
+                        }
                         TabButton {
                             active: active_tab() == SettingsTab::Hardware,
                             onclick: move |_| active_tab.set(SettingsTab::Hardware),
@@ -62,6 +87,16 @@ pub fn Settings() -> Element {
                             onclick: move |_| active_tab.set(SettingsTab::Tools),
                             label: if is_en { "Tools" } else { "Outils" },
                         }
+                        TabButton {
+                            active: active_tab() == SettingsTab::ToolAccess,
+                            onclick: move |_| active_tab.set(SettingsTab::ToolAccess),
+                            label: if is_en { "Access" } else { "Accès" },
+                        }
+                        TabButton {
+                            active: active_tab() == SettingsTab::ToolAudit,
+                            onclick: move |_| active_tab.set(SettingsTab::ToolAudit),
+                            label: if is_en { "Audit" } else { "Journal" },
+                        }
                         TabButton {
                             active: active_tab() == SettingsTab::Skills,
                             onclick: move |_| active_tab.set(SettingsTab::Skills),
@@ -77,6 +112,11 @@ pub fn Settings() -> Element {
                             onclick: move |_| active_tab.set(SettingsTab::Appearance),
                             label: if is_en { "Appearance" } else { "Apparence" },
                         }
+                        TabButton {
+                            active: active_tab() == SettingsTab::Backup,
+                            onclick: move |_| active_tab.set(SettingsTab::Backup),
+                            label: if is_en { "Backup" } else { "Sauvegarde" },
+                        }
                     }
                 }
             }
@@ -86,11 +126,16 @@ pub fn Settings() -> Element {
                 class: "flex-1 overflow-y-auto p-6 scrollbar-thin",
                 match active_tab() {
                     SettingsTab::Inference => rsx! { InferenceSettings {} },
+                    SettingsTab::Sampling => rsx! { SamplingSettings {} },
+                    SettingsTab::SystemPrompt => rsx! { SystemPromptSettings {} },
                     SettingsTab::Hardware => rsx! { HardwareSettings {} },
                     SettingsTab::Tools => rsx! { ToolsSettings {} },
+                    SettingsTab::ToolAccess => rsx! { ToolAccessSettings {} },
+                    SettingsTab::ToolAudit => rsx! { ToolAuditSettings {} },
                     SettingsTab::Skills => rsx! { SkillsSettings {} },
                     SettingsTab::Mcp => rsx! { McpSettings {} },
                     SettingsTab::Appearance => rsx! { AppearanceSettings {} },
+                    SettingsTab::Backup => rsx! { BackupSettings {} },
                 }
             }
         }
@@ -0,0 +1,202 @@
+use crate::app::AppState;
+use crate::storage::settings::{save_settings, AppSettings};
+use crate::ui::settings::inference::{SettingsCard, SettingsNumber, SettingsSlider};
+use dioxus::prelude::*;
+
+/// How the next token is picked. Read from the settings at every
+/// generation, so changes apply to the next message without a reload.
+pub fn SamplingSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let model_defaults = app_state
+        .loaded_model
+        .read()
+        .as_ref()
+        .map(|info| info.sampling_defaults.clone());
+    let has_model_defaults = model_defaults.as_ref().is_some_and(|d| {
+        d.temperature.is_some()
+            || d.top_k.is_some()
+            || d.top_p.is_some()
+            || d.min_p.is_some()
+            || d.repeat_penalty.is_some()
+    });
+
+    let card_title = if is_en {
+        "Sampling"
+    } else {
+        "Échantillonnage"
+    };
+    let temperature_help = if is_en {
+        "Randomness. 0 always picks the most likely token."
+    } else {
+        "Part d'aléatoire. 0 choisit toujours le token le plus probable."
+    };
+    let top_p_help = if is_en {
+        "Keeps the most likely tokens until their probabilities add up to P."
+    } else {
+        "Garde les tokens les plus probables jusqu'à ce que leurs probabilités atteignent P."
+    };
+    let top_k_help = if is_en {
+        "Only the K most likely tokens can be picked."
+    } else {
+        "Seuls les K tokens les plus probables peuvent être choisis."
+    };
+    let min_p_help = if is_en {
+        "Drops tokens less likely than this fraction of the top token. 0 turns it off."
+    } else {
+        "Écarte les tokens moins probables que cette fraction du meilleur. 0 le désactive."
+    };
+    let repeat_help = if is_en {
+        "Discourages repeating recent tokens. 1 turns it off."
+    } else {
+        "Décourage la répétition des tokens récents. 1 la désactive."
+    };
+
+    let mut app_state_temperature = app_state.clone();
+    let mut app_state_top_p = app_state.clone();
+    let mut app_state_top_k = app_state.clone();
+    let mut app_state_min_p = app_state.clone();
+    let mut app_state_repeat = app_state.clone();
+    let mut app_state_reset = app_state.clone();
+    let mut app_state_reset_app = app_state.clone();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            SettingsCard { title: card_title,
+                SettingsSlider {
+                    label: "Temperature",
+                    value: settings.temperature,
+                    min: 0.0,
+                    max: 2.0,
+                    step: 0.05,
+                    description: temperature_help,
+                    on_change: move |value| {
+                        let mut settings = app_state_temperature.settings.write();
+                        settings.temperature = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsSlider {
+                    label: "Top P",
+                    value: settings.top_p,
+                    min: 0.0,
+                    max: 1.0,
+                    step: 0.05,
+                    description: top_p_help,
+                    on_change: move |value| {
+                        let mut settings = app_state_top_p.settings.write();
+                        settings.top_p = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsNumber {
+                    label: "Top K",
+                    value: settings.top_k as f64,
+                    min: 0.0,
+                    max: 100.0,
+                    description: top_k_help,
+                    on_change: move |value: f64| {
+                        let mut settings = app_state_top_k.settings.write();
+                        settings.top_k = value.clamp(0.0, 100.0).round() as u32;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsSlider {
+                    label: "Min P",
+                    value: settings.min_p,
+                    min: 0.0,
+                    max: 0.5,
+                    step: 0.01,
+                    description: min_p_help,
+                    on_change: move |value| {
+                        let mut settings = app_state_min_p.settings.write();
+                        settings.min_p = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                SettingsSlider {
+                    label: "Repeat Penalty",
+                    value: settings.repeat_penalty,
+                    min: 1.0,
+                    max: 2.0,
+                    step: 0.05,
+                    description: repeat_help,
+                    on_change: move |value| {
+                        let mut settings = app_state_repeat.settings.write();
+                        settings.repeat_penalty = value;
+                        if let Err(error) = save_settings(&settings) {
+                            tracing::error!("Failed to save settings: {}", error);
+                        }
+                    }
+                }
+
+                div { class: "flex items-center justify-between gap-4",
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if has_model_defaults {
+                            if is_en {
+                                "The loaded model recommends its own values; the rest fall back to the app defaults."
+                            } else {
+                                "Le modèle chargé recommande ses propres valeurs ; les autres reprennent celles de l'application."
+                            }
+                        } else if is_en {
+                            "The loaded model recommends no values: the app defaults are used."
+                        } else {
+                            "Le modèle ne recommande aucune valeur : celles de l'application sont utilisées."
+                        }
+                    }
+                    button {
+                        class: "flex-shrink-0 px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all",
+                        onclick: move |_| {
+                            let app_defaults = AppSettings::default();
+                            let model = model_defaults.clone().unwrap_or_default();
+                            let mut settings = app_state_reset.settings.write();
+                            settings.temperature = model.temperature.unwrap_or(app_defaults.temperature);
+                            settings.top_p = model.top_p.unwrap_or(app_defaults.top_p);
+                            settings.top_k = model.top_k.unwrap_or(app_defaults.top_k);
+                            settings.min_p = model.min_p.unwrap_or(app_defaults.min_p);
+                            settings.repeat_penalty = model.repeat_penalty.unwrap_or(app_defaults.repeat_penalty);
+                            settings.validate();
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        if is_en { "Reset to model defaults" } else { "Valeurs par défaut du modèle" }
+                    }
+                    if has_model_defaults {
+                        button {
+                            class: "flex-shrink-0 px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all",
+                            onclick: move |_| {
+                                let app_defaults = AppSettings::default();
+                                let mut settings = app_state_reset_app.settings.write();
+                                settings.temperature = app_defaults.temperature;
+                                settings.top_p = app_defaults.top_p;
+                                settings.top_k = app_defaults.top_k;
+                                settings.min_p = app_defaults.min_p;
+                                settings.repeat_penalty = app_defaults.repeat_penalty;
+                                if let Err(error) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", error);
+                                }
+                            },
+                            if is_en { "Reset to app defaults" } else { "Valeurs par défaut de l'application" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
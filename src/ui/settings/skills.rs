@@ -25,7 +25,7 @@ pub fn SkillsSettings() -> Element {
                 }
 
                 button {
-                    class: "px-4 py-2 bg-[var(--accent-primary)] hover:bg-[var(--accent-hover)] text-white rounded-lg text-sm font-medium transition-colors flex items-center gap-2",
+                    class: "px-4 py-2 bg-[var(--accent-primary)] hover:bg-[var(--accent-hover)] text-[var(--accent-text)] rounded-lg text-sm font-medium transition-colors flex items-center gap-2",
                     onclick: move |_| {
                          // Redirect logic (placeholder)
                     },
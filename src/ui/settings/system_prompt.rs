@@ -0,0 +1,147 @@
+use crate::app::AppState;
+use crate::storage::settings::{
+    default_system_prompt_for_lang, response_language_instruction, save_settings,
+};
+use crate::types::message::{Message as StorageMessage, Role as StorageRole};
+use crate::ui::settings::inference::SettingsCard;
+use dioxus::prelude::*;
+
+/// Built-in templates in dropdown order, with their labels (en, fr)
+const TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "default",
+        "App default (tools and environment)",
+        "Par défaut (outils et environnement)",
+    ),
+    ("coding", "Coding assistant", "Assistant de code"),
+    ("concise", "Concise", "Concis"),
+    ("creative", "Creative", "Créatif"),
+];
+
+/// The prompt a template fills in, answering in `lang`
+fn template_prompt(id: &str, lang: &str) -> String {
+    let body = match id {
+        "coding" => {
+            "You are clawRS, a coding assistant running locally on the user's machine.\n\
+             Read the relevant code before changing it, follow the conventions the project already uses \
+             and keep changes small. Put code in fenced blocks with a language tag. \
+             When a request is ambiguous, say what you assumed."
+        }
+        "concise" => {
+            "You are clawRS, an assistant running locally on the user's machine.\n\
+             Answer in as few words as the question allows: no preamble, no restating the question, \
+             no closing summary. Use a list only when the answer is a list."
+        }
+        "creative" => {
+            "You are clawRS, a creative writing partner running locally on the user's machine.\n\
+             Offer vivid, original ideas and varied phrasing. Build on the user's drafts rather than \
+             replacing them, and ask about tone or audience when it changes the result."
+        }
+        _ => return default_system_prompt_for_lang(lang),
+    };
+    format!("{}\n{}", body, response_language_instruction(lang))
+}
+
+/// Edit the system prompt sent at the start of every conversation
+pub fn SystemPromptSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let lang = settings.language.clone();
+    let system_prompt = settings.system_prompt.clone();
+    // Counted by the loaded model, like the chat's context meter
+    let app_state_tokens = app_state.clone();
+    let prompt_tokens = use_resource(move || {
+        let app_state = app_state_tokens.clone();
+        let prompt = app_state.settings.read().system_prompt.clone();
+        async move {
+            app_state
+                .count_tokens(&[StorageMessage::new(StorageRole::System, prompt)])
+                .await
+        }
+    });
+    let tokens = prompt_tokens().unwrap_or(system_prompt.chars().count() / 4);
+    let context_share = tokens as f32 * 100.0 / settings.context_size.max(1) as f32;
+    let selected = TEMPLATES
+        .iter()
+        .map(|(id, _, _)| *id)
+        .find(|id| template_prompt(id, &lang) == system_prompt)
+        .unwrap_or("custom");
+
+    let card_title = if is_en {
+        "System prompt"
+    } else {
+        "Prompt système"
+    };
+
+    let mut app_state_template = app_state.clone();
+    let mut app_state_prompt = app_state.clone();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            SettingsCard { title: card_title,
+                div { class: "space-y-2",
+                    label { class: "text-sm font-medium text-[var(--text-primary)]",
+                        if is_en { "Template" } else { "Modèle" }
+                    }
+                    select {
+                        class: "w-full px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{selected}",
+                        onchange: move |e: Event<FormData>| {
+                            let id = e.value();
+                            if id == "custom" {
+                                return;
+                            }
+                            let mut settings = app_state_template.settings.write();
+                            settings.system_prompt = template_prompt(&id, &settings.language);
+                            // The default keeps following the code and the language
+                            settings.system_prompt_custom = id != "default";
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        for (id, label_en, label_fr) in TEMPLATES.iter().copied() {
+                            option { value: "{id}", if is_en { "{label_en}" } else { "{label_fr}" } }
+                        }
+                        option { value: "custom", if is_en { "Custom" } else { "Personnalisé" } }
+                    }
+                }
+
+                div { class: "space-y-2",
+                    textarea {
+                        value: "{system_prompt}",
+                        oninput: move |e| {
+                            let value = e.value();
+                            let mut settings = app_state_prompt.settings.write();
+                            settings.system_prompt = value;
+                            settings.system_prompt_custom = true;
+                            if let Err(error) = save_settings(&settings) {
+                                tracing::error!("Failed to save settings: {}", error);
+                            }
+                        },
+                        class: "w-full py-2.5 px-3 rounded-xl bg-white/[0.03] border border-[var(--border-subtle)] text-[var(--text-primary)] focus:border-[var(--accent-primary)] transition-all outline-none text-sm font-mono h-80 resize-y",
+                        placeholder: if is_en { "Enter system prompt..." } else { "Saisir le prompt système..." },
+                    }
+                    div { class: "flex items-center justify-between gap-4 text-xs text-[var(--text-tertiary)]",
+                        span {
+                            if is_en {
+                                "Sent first in every conversation. Tool instructions are added after it when tools are on."
+                            } else {
+                                "Envoyé en premier dans chaque conversation. Les instructions des outils s'y ajoutent quand ils sont actifs."
+                            }
+                        }
+                        span { class: "flex-shrink-0 font-mono",
+                            if is_en {
+                                "~{tokens} tokens ({context_share:.1}% of context)"
+                            } else {
+                                "~{tokens} tokens ({context_share:.1}% du contexte)"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::agent::tools::{ToolCategory, ToolInfo, ToolRegistry};
+use crate::app::AppState;
+use crate::storage::settings::{save_settings, AppSettings};
+use dioxus::prelude::*;
+
+/// Categories in display order, with their labels (en, fr)
+const CATEGORIES: &[(ToolCategory, &str, &str)] = &[
+    (ToolCategory::ReadOnly, "Read only", "Lecture seule"),
+    (
+        ToolCategory::Mutating,
+        "Modifies files",
+        "Modifie des fichiers",
+    ),
+    (ToolCategory::Network, "Network", "Réseau"),
+    (
+        ToolCategory::Shell,
+        "Runs commands",
+        "Exécute des commandes",
+    ),
+];
+
+/// Turn tools on or off. A disabled tool is hidden from the model and fails
+/// with `ToolError::Disabled` if it is called anyway.
+pub fn ToolAccessSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let settings = app_state.settings.read().clone();
+    let is_en = settings.language == "en";
+    let disabled = settings.disabled_tools.clone();
+    let tools = app_state.agent.tool_registry.list();
+    let disabled_count = tools.iter().filter(|t| disabled.contains(&t.name)).count();
+
+    let mut app_state_read_only = app_state.clone();
+    let mut app_state_all = app_state.clone();
+    let read_only_names: Vec<String> = tools
+        .iter()
+        .filter(|t| matches!(t.category, ToolCategory::Mutating | ToolCategory::Shell))
+        .map(|t| t.name.clone())
+        .collect();
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            // Presets
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Tool access" } else { "Accès aux outils" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    if is_en {
+                        "Disabled tools are never offered to the AI and cannot run, even when it asks for them."
+                    } else {
+                        "Les outils désactivés ne sont jamais proposés à l'IA et ne peuvent pas s'exécuter, même si elle les demande."
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-between gap-4",
+                    span {
+                        class: "text-sm text-[var(--text-secondary)]",
+                        if is_en {
+                            "{tools.len() - disabled_count} of {tools.len()} tools enabled"
+                        } else {
+                            "{tools.len() - disabled_count} outils activés sur {tools.len()}"
+                        }
+                    }
+                    div {
+                        class: "flex gap-2",
+                        button {
+                            class: "px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all",
+                            onclick: move |_| {
+                                let mut settings = app_state_read_only.settings.write();
+                                for name in &read_only_names {
+                                    if !settings.disabled_tools.contains(name) {
+                                        settings.disabled_tools.push(name.clone());
+                                    }
+                                }
+                                apply_disabled_tools(&app_state_read_only.agent.tool_registry, &settings);
+                            },
+                            if is_en { "Read-only session" } else { "Session en lecture seule" }
+                        }
+                        button {
+                            class: "px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all",
+                            onclick: move |_| {
+                                let mut settings = app_state_all.settings.write();
+                                settings.disabled_tools.clear();
+                                apply_disabled_tools(&app_state_all.agent.tool_registry, &settings);
+                            },
+                            if is_en { "Enable all" } else { "Tout activer" }
+                        }
+                    }
+                }
+            }
+
+            // One card per category
+            for (category, label_en, label_fr) in CATEGORIES.iter().copied() {
+                {
+                    let in_category: Vec<ToolInfo> = tools
+                        .iter()
+                        .filter(|t| t.category == category)
+                        .cloned()
+                        .collect();
+                    rsx! {
+                        if !in_category.is_empty() {
+                            div {
+                                class: "p-5 rounded-2xl glass-md",
+
+                                h3 {
+                                    class: "text-base font-semibold mb-4 text-[var(--text-primary)]",
+                                    if is_en { "{label_en}" } else { "{label_fr}" }
+                                }
+
+                                div {
+                                    class: "space-y-3",
+                                    for tool in in_category {
+                                        ToolToggle {
+                                            enabled: !disabled.contains(&tool.name),
+                                            name: tool.name.clone(),
+                                            summary: summary(&tool.description),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One tool with its on/off switch
+#[component]
+fn ToolToggle(name: String, summary: String, enabled: bool) -> Element {
+    let mut app_state = use_context::<AppState>();
+
+    rsx! {
+        div {
+            class: "flex items-center justify-between gap-4",
+
+            div {
+                class: "min-w-0",
+                div { class: "text-sm font-mono text-[var(--text-primary)]", "{name}" }
+                div { class: "text-xs text-[var(--text-tertiary)] mt-0.5 truncate", "{summary}" }
+            }
+            button {
+                onclick: move |_| {
+                    let mut settings = app_state.settings.write();
+                    if enabled {
+                        settings.disabled_tools.push(name.clone());
+                    } else {
+                        settings.disabled_tools.retain(|t| t != &name);
+                    }
+                    apply_disabled_tools(&app_state.agent.tool_registry, &settings);
+                },
+                class: if enabled { "toggle-switch active flex-shrink-0" } else { "toggle-switch flex-shrink-0" },
+                div { class: "toggle-switch-knob" }
+            }
+        }
+    }
+}
+
+/// Save `settings` and switch the live registry over to its disabled tools
+fn apply_disabled_tools(registry: &ToolRegistry, settings: &AppSettings) {
+    registry.set_disabled(&settings.disabled_tools);
+    if let Err(e) = save_settings(settings) {
+        tracing::error!("Failed to save settings: {}", e);
+    }
+}
+
+/// First sentence of a tool description
+fn summary(description: &str) -> String {
+    let first_line = description.lines().next().unwrap_or_default();
+    match first_line.find(". ") {
+        Some(end) => first_line[..=end].to_string(),
+        None => first_line.to_string(),
+    }
+}
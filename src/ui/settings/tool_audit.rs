@@ -0,0 +1,158 @@
+use crate::app::AppState;
+use crate::storage::audit::AuditEntry;
+use crate::ui::settings::inference::SettingsCard;
+use chrono::Local;
+use dioxus::prelude::*;
+
+/// Calls listed, newest first
+const RECENT_CALLS: usize = 200;
+
+/// Recent tool calls from the audit log, with their parameters and outcome
+pub fn ToolAuditSettings() -> Element {
+    let app_state = use_context::<AppState>();
+    let is_en = app_state.settings.read().language == "en";
+    let audit_log = app_state.agent.audit_log.clone();
+    // Bumped to read the log again
+    let mut reload = use_signal(|| 0u32);
+    // Clear asks for a second click
+    let mut confirm_clear = use_signal(|| false);
+    let mut clear_error = use_signal(|| None::<String>);
+
+    let log_for_entries = audit_log.clone();
+    let recent = use_memo(move || {
+        reload();
+        match &log_for_entries {
+            Some(log) => log.recent(RECENT_CALLS).map_err(|e| e.to_string()),
+            None => Ok(Vec::new()),
+        }
+    });
+    let (entries, read_error) = match recent() {
+        Ok(entries) => (entries, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    let error = clear_error().or(read_error);
+    let has_entries = !entries.is_empty();
+    let log_path = audit_log
+        .as_ref()
+        .map(|log| log.path().display().to_string());
+    let log_for_clear = audit_log.clone();
+
+    let card_title = if is_en {
+        "Tool calls"
+    } else {
+        "Appels d'outils"
+    };
+    let button_class = "px-3 py-1.5 rounded-lg text-xs font-medium border border-[var(--border-subtle)] text-[var(--text-secondary)] hover:border-[var(--border-medium)] hover:bg-white/[0.04] transition-all";
+
+    rsx! {
+        div {
+            class: "space-y-6 max-w-3xl mx-auto animate-fade-in-up pb-8",
+
+            SettingsCard { title: card_title,
+                p { class: "text-xs text-[var(--text-tertiary)] mb-3",
+                    if is_en {
+                        "Every tool the agent ran, with its parameters and result. Long values are truncated in the log."
+                    } else {
+                        "Chaque outil lancé par l'agent, avec ses paramètres et son résultat. Les valeurs longues sont tronquées dans le journal."
+                    }
+                }
+                if let Some(path) = log_path {
+                    p { class: "text-xs font-mono text-[var(--text-tertiary)] mb-3 break-all", "{path}" }
+                } else {
+                    p { class: "text-xs text-[var(--text-error)] mb-3",
+                        if is_en {
+                            "The data folder is unavailable, so tool calls are not recorded."
+                        } else {
+                            "Le dossier de données est indisponible, les appels d'outils ne sont pas enregistrés."
+                        }
+                    }
+                }
+
+                div { class: "flex flex-wrap gap-2 mb-4",
+                    button {
+                        class: "{button_class}",
+                        onclick: move |_| {
+                            clear_error.set(None);
+                            reload += 1;
+                        },
+                        if is_en { "Refresh" } else { "Actualiser" }
+                    }
+                    button {
+                        class: "{button_class}",
+                        style: if confirm_clear() { "border-color: var(--error); color: var(--text-error);" },
+                        disabled: !has_entries,
+                        onclick: move |_| {
+                            if !confirm_clear() {
+                                confirm_clear.set(true);
+                                return;
+                            }
+                            confirm_clear.set(false);
+                            if let Some(log) = &log_for_clear {
+                                clear_error.set(log.clear().err().map(|e| e.to_string()));
+                            }
+                            reload += 1;
+                        },
+                        onmouseleave: move |_| confirm_clear.set(false),
+                        match (confirm_clear(), is_en) {
+                            (true, true) => "Click again to clear the log",
+                            (true, false) => "Cliquer à nouveau pour vider le journal",
+                            (false, true) => "Clear",
+                            (false, false) => "Vider",
+                        }
+                    }
+                }
+
+                if let Some(message) = error {
+                    p { class: "text-xs text-[var(--text-error)] mb-3", "{message}" }
+                }
+
+                if !has_entries {
+                    p { class: "text-xs text-[var(--text-tertiary)]",
+                        if is_en { "No tool calls recorded yet." } else { "Aucun appel d'outil enregistré." }
+                    }
+                } else {
+                    div { class: "space-y-2",
+                        for (index, entry) in entries.into_iter().enumerate() {
+                            AuditRow { key: "{index}", entry }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AuditRow(entry: AuditEntry) -> Element {
+    let mut expanded = use_signal(|| false);
+    let time = entry
+        .timestamp
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let params = serde_json::to_string(&entry.params).unwrap_or_default();
+    let params_pretty = serde_json::to_string_pretty(&entry.params).unwrap_or_default();
+    let (mark, mark_class) = if entry.success {
+        ("✓", "text-[var(--text-success)]")
+    } else {
+        ("✗", "text-[var(--text-error)]")
+    };
+
+    rsx! {
+        div {
+            class: "rounded-xl px-3 py-2 bg-white/[0.02] border border-[var(--border-subtle)] cursor-pointer",
+            onclick: move |_| expanded.toggle(),
+            div { class: "flex items-center gap-2 text-xs",
+                span { class: "{mark_class}", "{mark}" }
+                span { class: "font-mono font-medium text-[var(--text-primary)]", "{entry.tool}" }
+                span { class: "flex-1 truncate font-mono text-[var(--text-tertiary)]", "{params}" }
+                span { class: "font-mono text-[var(--text-tertiary)]", "{entry.duration_ms} ms" }
+                span { class: "text-[var(--text-tertiary)]", "{time}" }
+            }
+            if expanded() {
+                pre { class: "mt-2 text-xs font-mono whitespace-pre-wrap break-all text-[var(--text-secondary)]", "{params_pretty}" }
+                p { class: "mt-2 text-xs whitespace-pre-wrap break-words text-[var(--text-secondary)]", "{entry.message}" }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::agent::get_tool_permission;
+use crate::agent::PermissionLevel;
 use crate::app::AppState;
 use crate::storage::settings::save_settings;
 use dioxus::prelude::*;
@@ -15,6 +15,8 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
             "glob",
             "file_info",
             "file_search",
+            "directory_tree",
+            "directory_list",
         ],
         "📂",
         "safe",
@@ -25,9 +27,11 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
             "file_write",
             "file_edit",
             "file_create",
+            "file_append",
             "file_delete",
             "file_move",
             "file_copy",
+            "file_chmod",
             "directory_create",
         ],
         "📝",
@@ -35,7 +39,7 @@ const TOOL_GROUPS: &[(&str, &[&str], &str, &str)] = &[
     ),
     (
         "Shell / Bash",
-        &["bash", "bash_background", "command"],
+        &["bash", "bash_background", "shell_exec", "command"],
         "⚡",
         "dangerous",
     ),
@@ -104,6 +108,8 @@ pub fn ToolsSettings() -> Element {
     let allowlist = settings.tool_allowlist.clone();
 
     let mut app_state_toggle = app_state.clone();
+    let mut app_state_timeout = app_state.clone();
+    let mut app_state_output = app_state.clone();
     let mut app_state_group = app_state.clone();
     let mut app_state_tool = app_state.clone();
 
@@ -230,6 +236,81 @@ pub fn ToolsSettings() -> Element {
                 }
             }
 
+            // Tool timeout
+            div {
+                class: "p-5 rounded-2xl glass-md",
+
+                h3 {
+                    class: "text-base font-semibold mb-1 text-[var(--text-primary)]",
+                    if is_en { "Tool Limits" } else { "Limites des outils" }
+                }
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mb-5",
+                    if is_en {
+                        "A tool call still running after this many seconds is abandoned. Files being written are left untouched."
+                    } else {
+                        "Un appel d'outil encore en cours après ce nombre de secondes est abandonné. Les fichiers en cours d'écriture restent intacts."
+                    }
+                }
+
+                div {
+                    class: "flex items-center gap-4",
+                    label {
+                        class: "text-sm text-[var(--text-secondary)] w-32",
+                        if is_en { "Seconds" } else { "Secondes" }
+                    }
+                    input {
+                        r#type: "number",
+                        min: "1",
+                        max: "3600",
+                        class: "w-28 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{settings.tool_timeout_secs}",
+                        onchange: move |e: Event<FormData>| {
+                            if let Ok(secs) = e.value().trim().parse::<u64>() {
+                                let mut settings = app_state_timeout.settings.write();
+                                settings.tool_timeout_secs = secs.clamp(1, 3600);
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            }
+                        },
+                    }
+                }
+
+                p {
+                    class: "text-xs text-[var(--text-tertiary)] mt-4 mb-3",
+                    if is_en {
+                        "Results larger than this are cut down before the AI sees them, keeping their beginning and end."
+                    } else {
+                        "Les résultats plus gros sont réduits avant d'être transmis à l'IA, en gardant leur début et leur fin."
+                    }
+                }
+
+                div {
+                    class: "flex items-center gap-4",
+                    label {
+                        class: "text-sm text-[var(--text-secondary)] w-32",
+                        if is_en { "Max result (KB)" } else { "Résultat max (Ko)" }
+                    }
+                    input {
+                        r#type: "number",
+                        min: "4",
+                        max: "1024",
+                        class: "w-28 px-3 py-2 rounded-lg text-sm text-[var(--text-primary)] bg-[var(--bg-secondary)] border border-[var(--border-subtle)] focus:outline-none focus:border-[var(--accent-primary)]",
+                        value: "{settings.tool_output_max_kb}",
+                        onchange: move |e: Event<FormData>| {
+                            if let Ok(kb) = e.value().trim().parse::<u32>() {
+                                let mut settings = app_state_output.settings.write();
+                                settings.tool_output_max_kb = kb.clamp(4, 1024);
+                                if let Err(e) = save_settings(&settings) {
+                                    tracing::error!("Failed to save settings: {}", e);
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+
             // Allowlist — per-group and per-tool toggles
             if !auto_approve {
                 div {
@@ -329,7 +410,7 @@ pub fn ToolsSettings() -> Element {
                                                     if all_in_allowlist {
                                                         svg {
                                                             class: "w-3 h-3",
-                                                            style: "color: #F2EDE7;",
+                                                            style: "color: var(--accent-text);",
                                                             view_box: "0 0 24 24",
                                                             fill: "none",
                                                             stroke: "currentColor",
@@ -355,7 +436,11 @@ pub fn ToolsSettings() -> Element {
                                                     {
                                                         let tool = tool_name.to_string();
                                                         let is_allowed = allowlist.contains(&tool);
-                                                        let perm = get_tool_permission(tool_name);
+                                                        let perm = app_state_tool
+                                                            .agent
+                                                            .tool_registry
+                                                            .get(tool_name)
+                                                            .map(|t| PermissionLevel::for_tool(t.as_ref()));
                                                         let tool_cb_style = if is_allowed {
                                                             "background: var(--accent-primary); border-color: var(--accent-primary);"
                                                         } else {
@@ -387,9 +472,11 @@ pub fn ToolsSettings() -> Element {
                                                                         class: "text-xs font-mono text-[var(--text-secondary)]",
                                                                         "{tool_name}"
                                                                     }
-                                                                    span {
-                                                                        class: "text-[9px] text-[var(--text-tertiary)]",
-                                                                        "({perm})",
+                                                                    if let Some(perm) = perm {
+                                                                        span {
+                                                                            class: "text-[9px] text-[var(--text-tertiary)]",
+                                                                            "({perm})",
+                                                                        }
                                                                     }
                                                                 }
 
@@ -399,7 +486,7 @@ pub fn ToolsSettings() -> Element {
                                                                     if is_allowed {
                                                                         svg {
                                                                             class: "w-2.5 h-2.5",
-                                                                            style: "color: #F2EDE7;",
+                                                                            style: "color: var(--accent-text);",
                                                                             view_box: "0 0 24 24",
                                                                             fill: "none",
                                                                             stroke: "currentColor",
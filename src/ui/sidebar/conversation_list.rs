@@ -1,9 +1,13 @@
 use dioxus::prelude::*;
 
+use chrono::Utc;
+
 use crate::app::AppState;
 use crate::storage::conversations::{
-    delete_conversation, list_conversations, save_conversation, Conversation,
+    delete_conversation, list_conversations, rename_conversation, save_conversation, Conversation,
 };
+use crate::storage::search::ConversationIndex;
+use crate::ui::chat::message::format_message_time;
 
 #[component]
 pub fn ConversationList() -> Element {
@@ -39,21 +43,113 @@ pub fn ConversationList() -> Element {
         .read()
         .as_ref()
         .map(|conv| conv.id.clone());
+    let is_en = app_state.settings.read().language == "en";
+    let now = Utc::now();
+    // Conversation whose title is being edited, and the text typed so far
+    let mut renaming = use_signal(|| None::<String>);
+    let mut draft = use_signal(String::new);
+    // Delete asks for a second click
+    let mut confirm_delete = use_signal(|| None::<String>);
+    // Search over every saved message, rebuilt when the list changes
+    let mut query = use_signal(String::new);
+    let conversations_signal = app_state.conversations;
+    let index = use_memo(move || ConversationIndex::build(&conversations_signal.read()));
+    let hits = (!query().trim().is_empty()).then(|| index.read().search(&query()));
+
+    // Save the typed title; an empty one keeps the old title
+    let mut commit_rename = {
+        let mut current_conversation_signal = app_state.current_conversation;
+        let mut conversations_signal = app_state.conversations;
+        move |id: String| {
+            renaming.set(None);
+            let title = draft().trim().to_string();
+            if title.is_empty() {
+                return;
+            }
+            if let Err(e) = rename_conversation(&id, &title) {
+                tracing::error!("Failed to rename conversation: {}", e);
+                return;
+            }
+            // The open conversation is saved from memory, keep it in step
+            if let Some(conv) = current_conversation_signal
+                .write()
+                .as_mut()
+                .filter(|conv| conv.id == id)
+            {
+                conv.title = title;
+            }
+            if let Ok(conversations) = list_conversations() {
+                conversations_signal.set(conversations);
+            }
+        }
+    };
 
     rsx! {
         div {
             class: "flex-1 overflow-y-auto p-2 space-y-1 scrollbar-thin",
 
-            if conversations.is_empty() {
+            if !conversations.is_empty() {
+                div { class: "px-1 pb-2",
+                    input {
+                        class: "w-full px-3 py-1.5 rounded-lg text-xs bg-white/[0.04] border border-[var(--border-subtle)] text-[var(--text-primary)] placeholder-[var(--text-tertiary)] outline-none focus:border-[var(--accent-primary)] transition-all",
+                        r#type: "search",
+                        placeholder: if is_en { "Search messages..." } else { "Rechercher dans les messages..." },
+                        value: "{query}",
+                        oninput: move |evt| query.set(evt.value()),
+                        onkeydown: move |evt: KeyboardEvent| {
+                            if evt.key() == Key::Escape {
+                                query.set(String::new());
+                            }
+                        },
+                    }
+                }
+            }
+
+            if let Some(hits) = hits {
+                if hits.is_empty() {
+                    div { class: "px-3 py-4 text-xs text-center text-[var(--text-tertiary)]",
+                        if is_en { "No message matches" } else { "Aucun message ne correspond" }
+                    }
+                }
+                for hit in hits {
+                    {
+                        let conversation = conversations.iter().find(|c| c.id == hit.conversation_id).cloned();
+                        let is_selected = selected_id.as_deref() == Some(hit.conversation_id.as_str());
+                        let mut current_conversation_signal = app_state.current_conversation;
+                        let mut scroll_to_message = app_state.scroll_to_message;
+                        rsx! {
+                            div {
+                                key: "{hit.conversation_id}",
+                                class: if is_selected {
+                                    "px-3 py-2 rounded-lg bg-white/[0.08] cursor-pointer transition-all"
+                                } else {
+                                    "px-3 py-2 rounded-lg hover:bg-white/[0.05] cursor-pointer transition-all"
+                                },
+                                onclick: move |_| {
+                                    if let Some(conversation) = conversation.clone() {
+                                        scroll_to_message.set(Some(hit.message_id));
+                                        current_conversation_signal.set(Some(conversation));
+                                    }
+                                },
+                                div { class: "flex items-center gap-2",
+                                    div { class: "truncate flex-1 text-sm text-[var(--text-primary)]", "{hit.title}" }
+                                    span { class: "flex-shrink-0 text-[10px] font-mono text-[var(--text-tertiary)]", "{hit.matches}" }
+                                }
+                                div { class: "text-[11px] text-[var(--text-tertiary)] line-clamp-2", "{hit.snippet}" }
+                            }
+                        }
+                    }
+                }
+            } else if conversations.is_empty() {
                 div {
                     class: "flex flex-col items-center justify-center py-10 text-[var(--text-tertiary)] gap-2 opacity-50",
                     svg { width: "24", height: "24", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "1.5", stroke_dasharray: "4 4", circle { cx: "12", cy: "12", r: "10" } }
-                    span { class: "text-xs font-medium", "No recent chats" }
+                    span { class: "text-xs font-medium", if is_en { "No recent chats" } else { "Aucune conversation" } }
                 }
             } else {
                 div {
                     class: "text-[10px] uppercase tracking-widest text-[var(--text-tertiary)] font-semibold px-3 py-2 select-none opacity-60",
-                    "Recent"
+                    if is_en { "Recent" } else { "Récentes" }
                 }
 
                 {conversations.into_iter().map(|conversation| {
@@ -61,6 +157,9 @@ pub fn ConversationList() -> Element {
                         .as_ref()
                         .map(|id| id == &conversation.id)
                         .unwrap_or(false);
+                    let is_renaming = renaming.read().as_deref() == Some(conversation.id.as_str());
+                    let is_confirming = confirm_delete.read().as_deref() == Some(conversation.id.as_str());
+                    let updated = format_message_time(conversation.updated_at, now, is_en);
 
                     let row_class = if is_selected {
                         "group flex items-center gap-2.5 px-3 py-2 rounded-lg bg-white/[0.08] border-l-2 border-[var(--accent-primary)] text-[var(--text-primary)] cursor-pointer transition-all"
@@ -70,6 +169,12 @@ pub fn ConversationList() -> Element {
 
                     let conversation_for_select = conversation.clone();
                     let conversation_id = conversation.id.clone();
+                    let rename_id = conversation.id.clone();
+                    let edit_id = conversation.id.clone();
+                    let edit_title = conversation.title.clone();
+                    let confirm_id = conversation.id.clone();
+                    let commit_key_id = conversation.id.clone();
+                    let commit_blur_id = conversation.id.clone();
                     let mut current_conversation_signal = app_state.current_conversation.clone();
                     let mut conversations_signal = app_state.conversations.clone();
 
@@ -99,43 +204,111 @@ pub fn ConversationList() -> Element {
                                     }
                                 }
 
-                                // Title
-                                div {
-                                    class: "truncate flex-1 text-sm",
-                                    "{conversation.title}"
-                                }
+                                if is_renaming {
+                                    input {
+                                        class: "flex-1 min-w-0 text-sm bg-transparent outline-none border-b border-[var(--accent-primary)] text-[var(--text-primary)]",
+                                        value: "{draft}",
+                                        onmounted: move |evt| async move {
+                                            let _ = evt.set_focus(true).await;
+                                        },
+                                        onclick: move |evt| evt.stop_propagation(),
+                                        oninput: move |evt| draft.set(evt.value()),
+                                        onkeydown: move |evt: KeyboardEvent| match evt.key() {
+                                            Key::Enter => commit_rename(commit_key_id.clone()),
+                                            Key::Escape => renaming.set(None),
+                                            _ => {}
+                                        },
+                                        onblur: move |_| {
+                                            // Enter and Escape already closed the editor
+                                            if renaming.peek().as_deref() == Some(commit_blur_id.as_str()) {
+                                                commit_rename(commit_blur_id.clone());
+                                            }
+                                        },
+                                    }
+                                } else {
+                                    // Title and last activity; double-click to rename
+                                    div {
+                                        class: "flex-1 min-w-0",
+                                        ondoubleclick: move |evt| {
+                                            evt.stop_propagation();
+                                            draft.set(edit_title.clone());
+                                            renaming.set(Some(edit_id.clone()));
+                                        },
+                                        div { class: "truncate text-sm", "{conversation.title}" }
+                                        div { class: "text-[10px] text-[var(--text-tertiary)]", "{updated}" }
+                                    }
 
-                                button {
-                                    class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]",
-                                    title: if app_state.settings.read().language == "en" { "Delete conversation" } else { "Supprimer la conversation" },
-                                    onclick: move |evt| {
-                                        evt.stop_propagation();
-                                        if let Err(e) = delete_conversation(&conversation_id) {
-                                            tracing::error!("Failed to delete conversation: {}", e);
+                                    button {
+                                        class: "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-primary)]",
+                                        title: if is_en { "Rename conversation" } else { "Renommer la conversation" },
+                                        onclick: {
+                                            let title = conversation.title.clone();
+                                            move |evt: MouseEvent| {
+                                                evt.stop_propagation();
+                                                draft.set(title.clone());
+                                                renaming.set(Some(rename_id.clone()));
+                                            }
+                                        },
+                                        svg {
+                                            width: "12",
+                                            height: "12",
+                                            view_box: "0 0 24 24",
+                                            fill: "none",
+                                            stroke: "currentColor",
+                                            stroke_width: "2",
+                                            stroke_linecap: "round",
+                                            stroke_linejoin: "round",
+                                            path { d: "M12 20h9" }
+                                            path { d: "M16.5 3.5a2.1 2.1 0 0 1 3 3L7 19l-4 1 1-4Z" }
                                         }
-                                        let should_clear = current_conversation_signal
-                                            .read()
-                                            .as_ref()
-                                            .map(|conv| conv.id == conversation_id)
-                                            .unwrap_or(false);
-                                        if should_clear {
-                                            current_conversation_signal.set(None);
-                                        }
-                                        if let Ok(conversations) = list_conversations() {
-                                            conversations_signal.set(conversations);
+                                    }
+
+                                    button {
+                                        class: if is_confirming {
+                                            "p-1 rounded-md text-[10px] font-medium text-[var(--text-error)] hover:bg-white/[0.08]"
+                                        } else {
+                                            "opacity-0 group-hover:opacity-100 transition-opacity p-1 rounded-md hover:bg-white/[0.08] text-[var(--text-tertiary)] hover:text-[var(--text-error)]"
+                                        },
+                                        title: if is_en { "Delete conversation" } else { "Supprimer la conversation" },
+                                        onmouseleave: move |_| confirm_delete.set(None),
+                                        onclick: move |evt| {
+                                            evt.stop_propagation();
+                                            if confirm_delete.peek().as_deref() != Some(confirm_id.as_str()) {
+                                                confirm_delete.set(Some(confirm_id.clone()));
+                                                return;
+                                            }
+                                            confirm_delete.set(None);
+                                            if let Err(e) = delete_conversation(&conversation_id) {
+                                                tracing::error!("Failed to delete conversation: {}", e);
+                                            }
+                                            let should_clear = current_conversation_signal
+                                                .read()
+                                                .as_ref()
+                                                .map(|conv| conv.id == conversation_id)
+                                                .unwrap_or(false);
+                                            if should_clear {
+                                                current_conversation_signal.set(None);
+                                            }
+                                            if let Ok(conversations) = list_conversations() {
+                                                conversations_signal.set(conversations);
+                                            }
+                                        },
+                                        if is_confirming {
+                                            if is_en { "Delete?" } else { "Supprimer ?" }
+                                        } else {
+                                            svg {
+                                                width: "12",
+                                                height: "12",
+                                                view_box: "0 0 24 24",
+                                                fill: "none",
+                                                stroke: "currentColor",
+                                                stroke_width: "2",
+                                                stroke_linecap: "round",
+                                                stroke_linejoin: "round",
+                                                line { x1: "18", y1: "6", x2: "6", y2: "18" }
+                                                line { x1: "6", y1: "6", x2: "18", y2: "18" }
+                                            }
                                         }
-                                    },
-                                    svg {
-                                        width: "12",
-                                        height: "12",
-                                        view_box: "0 0 24 24",
-                                        fill: "none",
-                                        stroke: "currentColor",
-                                        stroke_width: "2",
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        line { x1: "18", y1: "6", x2: "6", y2: "18" }
-                                        line { x1: "6", y1: "6", x2: "18", y2: "18" }
                                     }
                                 }
                             }
@@ -48,7 +48,7 @@ pub fn Sidebar(on_settings_click: EventHandler<MouseEvent>, on_new_chat: EventHa
                 button {
                     onclick: handle_new,
                     class: "w-full flex items-center justify-center gap-2 px-4 py-2.5 text-sm font-semibold rounded-xl transition-all hover:scale-[1.02] active:scale-[0.98]",
-                    style: "background: var(--accent-primary); color: #F2EDE7; box-shadow: 0 2px 8px -2px rgba(42,107,124,0.25);",
+                    style: "background: var(--accent-primary); color: var(--accent-text); box-shadow: 0 2px 8px -2px rgba(42,107,124,0.25);",
                     
                     svg {
                         class: "w-4 h-4",
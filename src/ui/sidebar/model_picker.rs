@@ -8,7 +8,9 @@ use crate::ui::components::loading::Spinner;
 #[component]
 pub fn ModelPicker() -> Element {
     let app_state = use_context::<AppState>();
-    let models_directory = app_state.settings.read().models_directory.clone();
+    // Rescans below follow the setting, e.g. after picking another folder
+    let settings = app_state.settings;
+    let models_directory = use_memo(move || settings.read().models_directory.clone());
     
     let mut models = use_signal(Vec::new);
     let mut selected_model_path = use_signal(|| None::<String>);
@@ -21,13 +23,20 @@ pub fn ModelPicker() -> Element {
     let mut download_error = use_signal(|| None::<String>);
     let mut download_success = use_signal(|| false);
     
-    let models_directory_clone = models_directory.clone();
     use_effect(move || {
-        let found_models = scan_models_directory(&models_directory_clone).unwrap_or_default();
-        if selected_model_path.read().is_none() {
-            if let Some(first_model) = found_models.first() {
-                let path_str = first_model.path.to_string_lossy().to_string();
-                tracing::debug!("Pre-selecting first model: {}", path_str);
+        let found_models = scan_models_directory(&models_directory.read()).unwrap_or_default();
+        if selected_model_path.peek().is_none() {
+            // The model in use, else the last one loaded, else the first found
+            let loaded = match &*app_state.model_state.peek() {
+                ModelState::Loaded(path) => Some(path.clone()),
+                _ => None,
+            };
+            let preferred = loaded.or_else(|| settings.peek().last_model_path.clone());
+            let path_str = preferred
+                .filter(|path| found_models.iter().any(|m| m.path.to_string_lossy() == *path))
+                .or_else(|| found_models.first().map(|m| m.path.to_string_lossy().to_string()));
+            if let Some(path_str) = path_str {
+                tracing::debug!("Pre-selecting model: {}", path_str);
                 selected_model_path.set(Some(path_str));
             }
         }
@@ -35,31 +44,14 @@ pub fn ModelPicker() -> Element {
     });
 
     // Handlers
+    // Also switches from the loaded model to the selected one
     let app_state_for_load = app_state.clone();
     let selected_model_path_for_load = selected_model_path.clone();
     let handle_load = move |_| {
-        let mut app_state = app_state_for_load.clone();
-        app_state.model_state.set(ModelState::Loading);
-        let path = selected_model_path_for_load
-            .read()
-            .clone()
-            .unwrap_or_default();
-        let gpu_layers = app_state.settings.read().gpu_layers;
-        spawn(async move {
-            let result = {
-                let mut engine = app_state.engine.lock().await;
-                if !engine.is_initialized() {
-                    if let Err(e) = engine.init() {
-                        return app_state.model_state.set(ModelState::Error(e.to_string()));
-                    }
-                }
-                engine.load_model_async(&path, gpu_layers).await
-            };
-            match result {
-                Ok(_info) => app_state.model_state.set(ModelState::Loaded(path)),
-                Err(e) => app_state.model_state.set(ModelState::Error(e.to_string())),
-            }
-        });
+        let Some(path) = selected_model_path_for_load.read().clone() else {
+            return;
+        };
+        spawn(app_state_for_load.clone().switch_model(path));
     };
 
     let app_state_for_unload = app_state.clone();
@@ -69,6 +61,8 @@ pub fn ModelPicker() -> Element {
             let mut engine = app_state.engine.lock().await;
             engine.unload_model();
         });
+        app_state.vram_baseline_mb.set(None);
+        app_state.loaded_model.set(None);
         app_state.model_state.set(ModelState::NotLoaded);
     };
 
@@ -99,7 +93,7 @@ pub fn ModelPicker() -> Element {
         let mut download_error_inner = download_error.clone();
         let mut download_success_inner = download_success.clone();
         let mut models_inner = models.clone();
-        let models_directory_inner = models_directory.clone();
+        let models_directory_inner = models_directory();
         let mut download_url_inner = download_url.clone();
         
         spawn(async move {
@@ -170,7 +164,7 @@ pub fn ModelPicker() -> Element {
                     
                     // Model Selector — custom dropdown
                     {
-                        let is_disabled = matches!(*app_state.model_state.read(), ModelState::Loading | ModelState::Loaded(_));
+                        let is_disabled = matches!(*app_state.model_state.read(), ModelState::Loading);
                         let selected_name = {
                             let sel = selected_model_path.read();
                             let mods = models.read();
@@ -221,7 +215,10 @@ pub fn ModelPicker() -> Element {
                                                     let path_str = model.path.to_string_lossy().to_string();
                                                     let is_selected = selected_model_path.read().as_ref().map_or(false, |p| *p == path_str);
                                                     let filename = model.filename.clone();
-                                                    let size = model.size_string();
+                                                    let size = match model.quantization() {
+                                                        Some(quantization) => format!("{} · {}", quantization, model.size_string()),
+                                                        None => model.size_string(),
+                                                    };
 
                                                     rsx! {
                                                         button {
@@ -264,7 +261,13 @@ pub fn ModelPicker() -> Element {
                     if let Some(path) = selected_model_path.read().as_ref() {
                         if let Some(model) = models.read().iter().find(|m| m.path.to_string_lossy() == *path) {
                             div {
-                                class: "flex justify-end",
+                                class: "flex justify-end gap-1",
+                                if let Some(quantization) = model.quantization() {
+                                    span {
+                                        class: "px-2 py-0.5 rounded-md text-[10px] font-mono bg-white/[0.03] text-[var(--text-tertiary)] border border-[var(--border-subtle)]",
+                                        "{quantization}"
+                                    }
+                                }
                                 span {
                                     class: "px-2 py-0.5 rounded-md text-[10px] font-mono bg-white/[0.03] text-[var(--text-tertiary)] border border-[var(--border-subtle)]",
                                     "{model.size_string()}"
@@ -306,7 +309,27 @@ pub fn ModelPicker() -> Element {
                                 div { class: "loading-bar" }
                             }
                         },
-                        ModelState::Loaded(_) => rsx! {
+                        ModelState::Loaded(ref loaded_path) => rsx! {
+                            if selected_model_path.read().as_ref().is_some_and(|p| p != loaded_path) {
+                                button {
+                                    onclick: handle_load,
+                                    class: "w-full flex items-center justify-center gap-2 bg-white/[0.03] border border-[var(--border-subtle)] hover:border-[var(--accent-primary)] hover:text-[var(--accent-primary)] text-[var(--text-secondary)] text-sm font-medium py-2.5 rounded-xl transition-all active:scale-[0.98]",
+                                    svg {
+                                        class: "w-4 h-4",
+                                        view_box: "0 0 24 24",
+                                        fill: "none",
+                                        stroke: "currentColor",
+                                        stroke_width: "2",
+                                        stroke_linecap: "round",
+                                        stroke_linejoin: "round",
+                                        polyline { points: "17 1 21 5 17 9" }
+                                        path { d: "M3 11V9a4 4 0 0 1 4-4h14" }
+                                        polyline { points: "7 23 3 19 7 15" }
+                                        path { d: "M21 13v2a4 4 0 0 1-4 4H3" }
+                                    }
+                                    if app_state.settings.read().language == "en" { "Switch to this model" } else { "Passer a ce modele" }
+                                }
+                            }
                             div {
                                 class: "flex items-center gap-2",
                                 div {
@@ -339,6 +362,11 @@ pub fn ModelPicker() -> Element {
                                 class: "w-full p-2 bg-[var(--bg-error-subtle)] border border-[var(--border-error-subtle)] rounded-xl text-xs text-[var(--text-error)]",
                                 "{msg}"
                             }
+                            button {
+                                onclick: handle_load,
+                                class: "w-full flex items-center justify-center gap-2 bg-white/[0.03] border border-[var(--border-subtle)] hover:border-[var(--accent-primary)] hover:text-[var(--accent-primary)] text-[var(--text-secondary)] text-sm font-medium py-2.5 rounded-xl transition-all active:scale-[0.98]",
+                                if app_state.settings.read().language == "en" { "Load selected model" } else { "Charger le modele choisi" }
+                            }
                         }
                     }
                 }